@@ -2,6 +2,51 @@
 
 use crate::co::*;
 
+const_bitflag! { ACE_FLAG: u8;
+	/// [`ACE_HEADER`](https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-ace_header)
+	/// `AceFlags` (`u8`), used in [`Ace`](crate::Ace).
+	///
+	/// Originally has `ACE` suffix.
+	=>
+	OBJECT_INHERIT 0x01
+	CONTAINER_INHERIT 0x02
+	NO_PROPAGATE_INHERIT 0x04
+	INHERIT_ONLY 0x08
+	INHERITED 0x10
+	SUCCESSFUL_ACCESS 0x40
+	FAILED_ACCESS 0x80
+}
+
+const_ordinary! { ACE_TYPE: u8;
+	/// [`ACE_HEADER`](https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-ace_header)
+	/// `AceType` (`u8`), used in [`Ace`](crate::Ace).
+	///
+	/// Originally has `ACE_TYPE` suffix.
+	=>
+	ACCESS_ALLOWED 0x00
+	ACCESS_DENIED 0x01
+	SYSTEM_AUDIT 0x02
+	SYSTEM_ALARM 0x03
+	ACCESS_ALLOWED_COMPOUND 0x04
+	ACCESS_ALLOWED_OBJECT 0x05
+	ACCESS_DENIED_OBJECT 0x06
+	SYSTEM_AUDIT_OBJECT 0x07
+	SYSTEM_ALARM_OBJECT 0x08
+	ACCESS_ALLOWED_CALLBACK 0x09
+	ACCESS_DENIED_CALLBACK 0x0a
+	ACCESS_ALLOWED_CALLBACK_OBJECT 0x0b
+	ACCESS_DENIED_CALLBACK_OBJECT 0x0c
+	SYSTEM_AUDIT_CALLBACK 0x0d
+	SYSTEM_ALARM_CALLBACK 0x0e
+	SYSTEM_AUDIT_CALLBACK_OBJECT 0x0f
+	SYSTEM_ALARM_CALLBACK_OBJECT 0x10
+	SYSTEM_MANDATORY_LABEL 0x11
+	SYSTEM_RESOURCE_ATTRIBUTE 0x12
+	SYSTEM_SCOPED_POLICY_ID 0x13
+	SYSTEM_PROCESS_TRUST_LABEL 0x14
+	SYSTEM_ACCESS_FILTER 0x15
+}
+
 const_ordinary! { EVENTLOG: u16;
 	/// [`HEVENTLOG::ReportEvent`](crate::prelude::advapi_Heventlog::ReportEvent)
 	/// `event_type` [`u16`].
@@ -14,6 +59,18 @@ const_ordinary! { EVENTLOG: u16;
 	WARNING_TYPE 0x0002
 }
 
+const_bitflag! { EVENTLOG_READ: u32;
+	/// [`HEVENTLOG::ReadEventLog`](crate::prelude::advapi_Heventlog::ReadEventLog)
+	/// `flags` (`u32`).
+	///
+	/// Originally has `EVENTLOG` prefix.
+	=>
+	SEQUENTIAL_READ 0x0001
+	SEEK_READ 0x0002
+	FORWARDS_READ 0x0004
+	BACKWARDS_READ 0x0008
+}
+
 const_bitflag! { KEY: u32;
 	/// [Registry access rights](https://learn.microsoft.com/en-us/windows/win32/sysinfo/registry-key-security-and-access-rights)
 	/// (`u32`).
@@ -66,6 +123,20 @@ const_ordinary! { REG_DISPOSITION: u32;
 	OPENED_EXISTING_KEY 0x0000_0002
 }
 
+const_bitflag! { REG_NOTIFY: u32;
+	/// [`HKEY::RegNotifyChangeKeyValue`](crate::prelude::advapi_Hkey::RegNotifyChangeKeyValue)
+	/// `notify_filter` (`u32`).
+	///
+	/// Originally has `REG_NOTIFY_CHANGE` prefix, with `REG_NOTIFY_THREAD_AGNOSTIC`
+	/// renamed to `THREAD_AGNOSTIC`.
+	=>
+	NAME 0x0000_0001
+	ATTRIBUTES 0x0000_0002
+	LAST_SET 0x0000_0004
+	SECURITY 0x0000_0008
+	THREAD_AGNOSTIC 0x1000_0000
+}
+
 const_bitflag! { REG_OPTION: u32;
 	/// [`HKEY::RegOpenKeyEx`](crate::prelude::advapi_Hkey::RegOpenKeyEx)
 	/// `options` (`u32`).
@@ -297,6 +368,18 @@ const_bitflag! { RRF: u32;
 	ZEROONFAILURE 0x2000_0000
 }
 
+const_ordinary! { SC_ACTION: u32;
+	/// [`SC_ACTION_TYPE`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/ne-winsvc-sc_action_type)
+	/// enumeration (`u32`).
+	///
+	/// Originally has `SC_ACTION` prefix.
+	=>
+	NONE 0
+	RESTART 1
+	REBOOT 2
+	RUN_COMMAND 3
+}
+
 const_bitflag! { SC_MANAGER: u32;
 	/// Service Control Manager access rights
 	/// [`flags`](https://learn.microsoft.com/en-us/windows/win32/services/service-security-and-access-rights)
@@ -316,6 +399,46 @@ const_bitflag! { SC_MANAGER: u32;
 	GENERIC_ALL Self::ALL_ACCESS.0
 }
 
+const_bitflag! { SE_GROUP: u32;
+	/// Group
+	/// [attributes](https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-token_groups)
+	/// (`u32`).
+	///
+	/// Originally has `SE_GROUP` prefix.
+	=>
+	MANDATORY 0x0000_0001
+	ENABLED_BY_DEFAULT 0x0000_0002
+	ENABLED 0x0000_0004
+	OWNER 0x0000_0008
+	USE_FOR_DENY_ONLY 0x0000_0010
+	INTEGRITY 0x0000_0020
+	INTEGRITY_ENABLED 0x0000_0040
+	RESOURCE 0x2000_0000
+	LOGON_ID 0xc000_0000
+}
+
+const_ordinary! { SE_OBJECT_TYPE: u32;
+	/// [`SE_OBJECT_TYPE`](https://learn.microsoft.com/en-us/windows/win32/api/accctrl/ne-accctrl-se_object_type)
+	/// enumeration (`u32`).
+	///
+	/// Originally has `SE` prefix.
+	=>
+	UNKNOWN_OBJECT_TYPE 0
+	FILE_OBJECT 1
+	SERVICE 2
+	PRINTER 3
+	REGISTRY_KEY 4
+	LMSHARE 5
+	KERNEL_OBJECT 6
+	WINDOW_OBJECT 7
+	DS_OBJECT 8
+	DS_OBJECT_ALL 9
+	PROVIDER_DEFINED_OBJECT 10
+	WMIGUID_OBJECT 11
+	REGISTRY_WOW64_32KEY 12
+	REGISTRY_WOW64_64KEY 13
+}
+
 const_str! { SE_PRIV;
 	/// [Privilege constants](https://learn.microsoft.com/en-us/windows/win32/secauthz/privilege-constants)
 	/// (`&'static str`).
@@ -429,6 +552,24 @@ const_bitflag! { SERVICE_CONTROL: u32;
 	USERMODEREBOOT 0x0000_0040
 }
 
+const_ordinary! { SERVICE_CONFIG: u32;
+	/// [`HSERVICE::QueryServiceConfig2`](crate::prelude::advapi_Hservice::QueryServiceConfig2)
+	/// `info_level` (`u32`).
+	///
+	/// Originally has `SERVICE_CONFIG` prefix.
+	=>
+	DESCRIPTION 1
+	FAILURE_ACTIONS 2
+	DELAYED_AUTO_START_INFO 3
+	FAILURE_ACTIONS_FLAG 4
+	SERVICE_SID_INFO 5
+	REQUIRED_PRIVILEGES_INFO 6
+	PRESHUTDOWN_INFO 7
+	TRIGGER_INFO 8
+	PREFERRED_NODE 9
+	LAUNCH_PROTECTED 12
+}
+
 const_ordinary! { SERVICE_ERROR: u32;
 	/// [`HSC::CreateService`](crate::prelude::advapi_Hsc::CreateService)
 	/// `error_control` (`u32`).
@@ -439,6 +580,19 @@ const_ordinary! { SERVICE_ERROR: u32;
 	SEVERE 0x0000_0002
 }
 
+const_ordinary! { SERVICE_ENUM_STATE: u32;
+	/// [`HSC::EnumServicesStatusEx`](crate::prelude::advapi_Hsc::EnumServicesStatusEx)
+	/// and
+	/// [`HSERVICE::EnumDependentServices`](crate::prelude::advapi_Hservice::EnumDependentServices)
+	/// `service_state` (`u32`).
+	///
+	/// Originally has `SERVICE` prefix.
+	=>
+	ACTIVE 0x0000_0001
+	INACTIVE 0x0000_0002
+	STATE_ALL 0x0000_0003
+}
+
 const_ordinary! { SERVICE_START: u32;
 	/// [`HSC::CreateService`](crate::prelude::advapi_Hsc::CreateService)
 	/// `start_type` (`u32`).
@@ -465,6 +619,51 @@ const_ordinary! { SERVICE_STATE: u32;
 	STOPPED 0x0000_0001
 }
 
+const_bitflag! { SERVICE_STOP_REASON: u32;
+	/// [Service stop reason](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/ns-winsvc-service_control_status_reason_paramsw)
+	/// codes (`u32`), used in
+	/// [`HSERVICE::ControlServiceEx`](crate::prelude::advapi_Hservice::ControlServiceEx).
+	///
+	/// A reason is a combination of a `FLAG`, a `MAJOR` and a `MINOR` value.
+	///
+	/// Originally has `SERVICE_STOP_REASON` prefix.
+	=>
+	FLAG_UNPLANNED 0x1000_0000
+	FLAG_CUSTOM 0x2000_0000
+	FLAG_PLANNED 0x4000_0000
+
+	MAJOR_OTHER 0x0001_0000
+	MAJOR_HARDWARE 0x0002_0000
+	MAJOR_OPERATINGSYSTEM 0x0003_0000
+	MAJOR_SOFTWARE 0x0004_0000
+	MAJOR_APPLICATION 0x0005_0000
+	MAJOR_NONE 0x0006_0000
+
+	MINOR_OTHER 0x0000_0001
+	MINOR_MAINTENANCE 0x0000_0002
+	MINOR_INSTALLATION 0x0000_0003
+	MINOR_UPGRADE 0x0000_0004
+	MINOR_RECONFIG 0x0000_0005
+	MINOR_HUNG 0x0000_0006
+	MINOR_UNSTABLE 0x0000_0007
+	MINOR_DISK 0x0000_0008
+	MINOR_NETWORKCARD 0x0000_0009
+	MINOR_ENVIRONMENT 0x0000_000a
+	MINOR_HARDWARE_DRIVER 0x0000_000b
+	MINOR_OTHERDRIVER 0x0000_000c
+	MINOR_SERVICEPACK 0x0000_000d
+	MINOR_SOFTWARE_UPDATE 0x0000_000e
+	MINOR_SECURITYFIX 0x0000_000f
+	MINOR_SECURITY 0x0000_0010
+	MINOR_NETWORK_CONNECTIVITY 0x0000_0011
+	MINOR_WMI 0x0000_0012
+	MINOR_SERVICEPACK_UNINSTALL 0x0000_0013
+	MINOR_SOFTWARE_UPDATE_UNINSTALL 0x0000_0014
+	MINOR_SECURITYFIX_UNINSTALL 0x0000_0015
+	MINOR_MMC 0x0000_0016
+	MINOR_NONE 0x0000_0017
+}
+
 const_ordinary! { SERVICE_TYPE: u32;
 	/// [`HSC::CreateService`](crate::prelude::advapi_Hsc::CreateService)
 	/// `service_type` (`u32`).
@@ -480,6 +679,9 @@ const_ordinary! { SERVICE_TYPE: u32;
 
 	WIN32_OWN_PROCESS_INTERACTIVE Self::WIN32_OWN_PROCESS.0 | 0x0000_0100
 	WIN32_SHARE_PROCESS_INTERACTIVE Self::WIN32_SHARE_PROCESS.0 | 0x0000_0100
+
+	DRIVER Self::KERNEL_DRIVER.0 | Self::FILE_SYSTEM_DRIVER.0 | Self::RECOGNIZER_DRIVER.0
+	WIN32 Self::WIN32_OWN_PROCESS.0 | Self::WIN32_SHARE_PROCESS.0
 }
 
 const_ordinary! { SID_NAME_USE: u32;
@@ -16,7 +16,7 @@ pub enum DisabPriv<'a> {
 ///
 /// This is a high-level abstraction over the [`co::REG`](crate::co::REG)
 /// constants, plus the value they carry.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum RegistryValue {
 	/// Binary value, defined as [`REG::BINARY`](crate::co::REG::BINARY).
 	Binary(Vec<u8>),
@@ -173,6 +173,87 @@ impl RegistryValue {
 	}
 }
 
+/// Configuration of a service, returned by
+/// [`HSERVICE::QueryServiceConfig`](crate::prelude::advapi_Hservice::QueryServiceConfig).
+///
+/// This is an owned version of the
+/// [`QUERY_SERVICE_CONFIG`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/ns-winsvc-query_service_configw)
+/// struct.
+#[derive(Clone, Debug)]
+pub struct SvcConfig {
+	pub service_type: co::SERVICE_TYPE,
+	pub start_type: co::SERVICE_START,
+	pub error_control: co::SERVICE_ERROR,
+	pub binary_path_name: String,
+	pub load_order_group: String,
+	pub tag_id: u32,
+	/// Names of services or load order groups – the latter prefixed with `+` –
+	/// which must start before this service.
+	pub dependencies: Vec<String>,
+	pub service_start_name: String,
+	pub display_name: String,
+}
+
+/// Changes to the configuration of a service, used in
+/// [`HSERVICE::ChangeServiceConfig`](crate::prelude::advapi_Hservice::ChangeServiceConfig).
+///
+/// Fields informed as `None` are left unchanged.
+#[derive(Clone, Debug, Default)]
+pub struct SvcConfigChange {
+	pub service_type: Option<co::SERVICE_TYPE>,
+	pub start_type: Option<co::SERVICE_START>,
+	pub error_control: Option<co::SERVICE_ERROR>,
+	pub binary_path_name: Option<String>,
+	/// Load order group; an empty string removes the service from its group.
+	pub load_order_group: Option<String>,
+	/// Names of services or load order groups – the latter prefixed with `+` –
+	/// which must start before this service; an empty `Vec` removes all
+	/// dependencies.
+	pub dependencies: Option<Vec<String>>,
+	pub service_start_name: Option<String>,
+	pub password: Option<String>,
+	pub display_name: Option<String>,
+}
+
+/// Optional service configuration for:
+///
+/// * [`HSERVICE::ChangeServiceConfig2`](crate::prelude::advapi_Hservice::ChangeServiceConfig2);
+/// * [`HSERVICE::QueryServiceConfig2`](crate::prelude::advapi_Hservice::QueryServiceConfig2).
+///
+/// The enum values match those in
+/// [`co::SERVICE_CONFIG`](crate::co::SERVICE_CONFIG) constant type.
+#[derive(Clone, Debug)]
+pub enum SvcConfig2 {
+	/// Service description. When changing, `None` leaves the description
+	/// unchanged, and an empty string removes it.
+	Description(Option<String>),
+	/// Actions taken when the service fails.
+	FailureActions(SvcFailureActions),
+	/// Whether an auto-start service is started after a short delay.
+	DelayedAutoStart(bool),
+	/// Whether the failure actions also run when the service stops with a
+	/// non-zero exit code, not only when it crashes.
+	FailureActionsFlag(bool),
+	/// Time the service has to perform its pre-shutdown tasks, in
+	/// milliseconds.
+	PreshutdownTimeout(u32),
+}
+
+impl SvcConfig2 {
+	/// Returns the correspondent [`co::SERVICE_CONFIG`](crate::co::SERVICE_CONFIG)
+	/// constant.
+	#[must_use]
+	pub const fn info_level(&self) -> co::SERVICE_CONFIG {
+		match self {
+			Self::Description(_) => co::SERVICE_CONFIG::DESCRIPTION,
+			Self::FailureActions(_) => co::SERVICE_CONFIG::FAILURE_ACTIONS,
+			Self::DelayedAutoStart(_) => co::SERVICE_CONFIG::DELAYED_AUTO_START_INFO,
+			Self::FailureActionsFlag(_) => co::SERVICE_CONFIG::FAILURE_ACTIONS_FLAG,
+			Self::PreshutdownTimeout(_) => co::SERVICE_CONFIG::PRESHUTDOWN_INFO,
+		}
+	}
+}
+
 /// Notification content for
 /// [`HSERVICESTATUS::RegisterServiceCtrlHandlerEx`](crate::prelude::advapi_Hservicestatus::RegisterServiceCtrlHandlerEx)
 /// callback, describing [`co::SERVICE_CONTROL`](crate::co::SERVICE_CONTROL).
@@ -325,6 +406,30 @@ impl<'a> SvcCtlPowerEvent<'a> {
 	}
 }
 
+/// Actions taken by the Service Control Manager when a service fails, used
+/// in [`SvcConfig2::FailureActions`](crate::SvcConfig2::FailureActions).
+///
+/// This is an owned version of the
+/// [`SERVICE_FAILURE_ACTIONS`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/ns-winsvc-service_failure_actionsw)
+/// struct.
+#[derive(Clone, Debug, Default)]
+pub struct SvcFailureActions {
+	/// Time after which the failure count is reset to zero, in seconds, or
+	/// `None` if it's never reset.
+	pub reset_period: Option<u32>,
+	/// Message broadcast before rebooting. When changing, `None` leaves it
+	/// unchanged, and an empty string removes it.
+	pub reboot_msg: Option<String>,
+	/// Command line run by [`co::SC_ACTION::RUN_COMMAND`](crate::co::SC_ACTION::RUN_COMMAND).
+	/// When changing, `None` leaves it unchanged, and an empty string removes
+	/// it.
+	pub command: Option<String>,
+	/// The actions, one for each subsequent failure; the last one is repeated
+	/// for further failures. When changing, an empty `Vec` removes all
+	/// actions.
+	pub actions: Vec<SC_ACTION>,
+}
+
 /// Variant parameter for:
 ///
 /// * [`HACCESSTOKEN::GetTokenInformation`](crate::prelude::advapi_Haccesstoken::GetTokenInformation).
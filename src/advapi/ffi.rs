@@ -1,11 +1,19 @@
 use crate::kernel::ffi_types::*;
 
 extern_sys! { "advapi32";
+	AccessCheck(PCVOID, HANDLE, u32, PCVOID, PVOID, *mut u32, *mut u32, *mut BOOL) -> BOOL
 	AdjustTokenPrivileges(HANDLE, BOOL, PCVOID, u32, PVOID, *mut u32) -> BOOL
 	AllocateAndInitializeSid(PCVOID, u8, u32, u32, u32, u32, u32, u32, u32, u32, *mut u8) -> BOOL
+	BackupEventLogW(HANDLE, PCSTR) -> BOOL
+	ChangeServiceConfig2W(HANDLE, u32, PCVOID) -> BOOL
+	ChangeServiceConfigW(HANDLE, u32, u32, u32, PCSTR, PCSTR, *mut u32, PCSTR, PCSTR, PCSTR, PCSTR) -> BOOL
 	CheckTokenCapability(HANDLE, PCVOID, *mut BOOL) -> BOOL
 	CheckTokenMembership(HANDLE, PCVOID, *mut BOOL) -> BOOL
+	ClearEventLogW(HANDLE, PCSTR) -> BOOL
+	CloseEventLog(HANDLE) -> BOOL
 	CloseServiceHandle(HANDLE) -> BOOL
+	ControlService(HANDLE, u32, PVOID) -> BOOL
+	ControlServiceExW(HANDLE, u32, u32, PVOID) -> BOOL
 	ConvertSidToStringSidW(PCVOID, *mut PSTR) -> BOOL
 	ConvertStringSidToSidW(PCSTR, *mut *mut u8) -> BOOL
 	CopySid(u32, PVOID, PCVOID) -> BOOL
@@ -17,11 +25,18 @@ extern_sys! { "advapi32";
 	DuplicateToken(HANDLE, u32, *mut HANDLE) -> BOOL
 	EncryptFileW(PCSTR) -> BOOL
 	EncryptionDisable(PCSTR, BOOL) -> BOOL
+	EnumDependentServicesW(HANDLE, u32, PVOID, u32, *mut u32, *mut u32) -> BOOL
+	EnumServicesStatusExW(HANDLE, u32, u32, u32, PVOID, u32, *mut u32, *mut u32, *mut u32, PCSTR) -> BOOL
 	EqualDomainSid(PVOID, PVOID, *mut BOOL) -> BOOL
 	EqualPrefixSid(PVOID, PVOID) -> BOOL
 	EqualSid(PVOID, PVOID) -> BOOL
 	FreeSid(PVOID)
 	GetLengthSid(PVOID) -> u32
+	GetNamedSecurityInfoW(PCSTR, u32, u32, *mut PVOID, *mut PVOID, *mut PVOID, *mut PVOID, *mut PVOID) -> u32
+	GetNumberOfEventLogRecords(HANDLE, *mut u32) -> BOOL
+	GetOldestEventLogRecord(HANDLE, *mut u32) -> BOOL
+	GetSecurityDescriptorLength(PCVOID) -> u32
+	GetSecurityInfo(HANDLE, u32, u32, *mut PVOID, *mut PVOID, *mut PVOID, *mut PVOID, *mut PVOID) -> u32
 	GetSidLengthRequired(u8) -> u32
 	GetTokenInformation(HANDLE, u32, PCVOID, u32, *mut u32) -> BOOL
 	GetUserNameW(PSTR, *mut u32) -> BOOL
@@ -38,10 +53,16 @@ extern_sys! { "advapi32";
 	LookupAccountSidW(PCSTR, PCVOID, PSTR, *mut u32, PSTR, *mut u32, *mut u32) -> BOOL
 	LookupPrivilegeNameW(PCSTR, PCVOID, PSTR, *mut u32) -> BOOL
 	LookupPrivilegeValueW(PCSTR, PCSTR, PVOID) -> BOOL
+	OpenBackupEventLogW(PCSTR, PCSTR) -> HANDLE
+	OpenEventLogW(PCSTR, PCSTR) -> HANDLE
 	OpenProcessToken(HANDLE, u32, *mut HANDLE) -> BOOL
 	OpenSCManagerW(PCSTR, PCSTR, u32) -> HANDLE
 	OpenServiceW(HANDLE, PCSTR, u32) -> HANDLE
 	OpenThreadToken(HANDLE, u32, BOOL, *mut HANDLE) -> BOOL
+	QueryServiceConfig2W(HANDLE, u32, PVOID, u32, *mut u32) -> BOOL
+	QueryServiceConfigW(HANDLE, PVOID, u32, *mut u32) -> BOOL
+	QueryServiceStatusEx(HANDLE, u32, PVOID, u32, *mut u32) -> BOOL
+	ReadEventLogW(HANDLE, u32, u32, PVOID, u32, *mut u32, *mut u32) -> BOOL
 	RegCloseKey(HANDLE) -> i32
 	RegConnectRegistryW(PCSTR, HANDLE, *mut HANDLE) -> i32
 	RegCopyTreeW(HANDLE, PCSTR, HANDLE) -> i32
@@ -63,6 +84,7 @@ extern_sys! { "advapi32";
 	RegisterEventSourceW(PCSTR, PCSTR) -> HANDLE
 	RegisterServiceCtrlHandlerExW(PCSTR, PFUNC, PVOID) -> HANDLE
 	RegLoadKeyW(HANDLE, PCSTR, PCSTR) -> i32
+	RegNotifyChangeKeyValue(HANDLE, BOOL, u32, HANDLE, BOOL) -> i32
 	RegOpenCurrentUser(u32, *mut HANDLE) -> i32
 	RegOpenKeyExW(HANDLE, PCSTR, u32, u32, *mut HANDLE) -> i32
 	RegOpenKeyTransactedW(HANDLE, PCSTR, u32, u32, *mut HANDLE, HANDLE, PVOID) -> i32
@@ -79,7 +101,11 @@ extern_sys! { "advapi32";
 	RegSetValueExW(HANDLE, PCSTR, u32, u32, *const u8, u32) -> i32
 	RegUnLoadKeyW(HANDLE, PCSTR) -> i32
 	ReportEventW(HANDLE, u16, u16, u32, PCVOID, u16, u32, *const PCSTR, PCVOID) -> BOOL
+	SetNamedSecurityInfoW(PSTR, u32, u32, PVOID, PVOID, PVOID, PVOID) -> u32
+	SetSecurityInfo(HANDLE, u32, u32, PVOID, PVOID, PVOID, PVOID) -> u32
 	SetServiceStatus(HANDLE, PCVOID) -> BOOL
+	StartServiceCtrlDispatcherW(PCVOID) -> BOOL
+	StartServiceW(HANDLE, u32, *const PCSTR) -> BOOL
 }
 
 extern_sys! { "ktmw32";
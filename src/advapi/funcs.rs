@@ -437,6 +437,54 @@ pub fn GetLengthSid(sid: &SID) -> u32 {
 	unsafe { ffi::GetLengthSid(sid as *const _ as _) }
 }
 
+/// [`GetNamedSecurityInfo`](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-getnamedsecurityinfow)
+/// function.
+///
+/// Returns an owned [`SecurityDescriptor`](crate::SecurityDescriptor) with the
+/// parts given in `security_info`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*, co};
+///
+/// let sd = w::GetNamedSecurityInfo(
+///     "C:\\Temp",
+///     co::SE_OBJECT_TYPE::FILE_OBJECT,
+///     co::SECURITY_INFORMATION::OWNER | co::SECURITY_INFORMATION::DACL,
+/// )?;
+/// println!("{sd}");
+/// # w::SysResult::Ok(())
+/// ```
+///
+/// # Related functions
+///
+/// * [`HFILE::GetSecurityInfo`](crate::prelude::advapi_Hfile::GetSecurityInfo)
+/// * [`HKEY::GetSecurityInfo`](crate::prelude::advapi_Hkey::GetSecurityInfo)
+/// * [`HPROCESS::GetSecurityInfo`](crate::prelude::advapi_Hprocess::GetSecurityInfo)
+/// * [`HSERVICE::GetSecurityInfo`](crate::prelude::advapi_Hservice::GetSecurityInfo)
+/// * [`SetNamedSecurityInfo`](crate::SetNamedSecurityInfo)
+#[must_use]
+pub fn GetNamedSecurityInfo(
+	object_name: &str,
+	object_type: co::SE_OBJECT_TYPE,
+	security_info: co::SECURITY_INFORMATION,
+) -> SysResult<SecurityDescriptor>
+{
+	get_security_info(|psd| unsafe {
+		ffi::GetNamedSecurityInfoW(
+			WString::from_str(object_name).as_ptr(),
+			object_type.raw(),
+			security_info.raw(),
+			std::ptr::null_mut(),
+			std::ptr::null_mut(),
+			std::ptr::null_mut(),
+			std::ptr::null_mut(),
+			psd,
+		)
+	})
+}
+
 /// [`GetSidLengthRequired`](https://learn.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsidlengthrequired)
 /// function.
 ///
@@ -884,3 +932,75 @@ pub fn RegDisablePredefinedCache() -> SysResult<()> {
 pub fn RegDisablePredefinedCacheEx() -> SysResult<()> {
 	error_to_sysresult(unsafe { ffi::RegDisablePredefinedCacheEx() })
 }
+
+/// [`SetNamedSecurityInfo`](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-setnamedsecurityinfow)
+/// function.
+///
+/// Only the parts of `sd` given in `security_info` are set. If the DACL or the
+/// SACL of `sd` is protected, the corresponding `PROTECTED` flag is
+/// automatically added to `security_info`.
+///
+/// Since a null DACL grants full access to everyone, if `security_info`
+/// has [`co::SECURITY_INFORMATION::DACL`](crate::co::SECURITY_INFORMATION::DACL)
+/// the DACL of `sd` must be present, otherwise
+/// [`co::ERROR::INVALID_ACL`](crate::co::ERROR::INVALID_ACL) is returned.
+///
+/// # Examples
+///
+/// Granting read access to the users on a data directory, inherited by its
+/// files and subdirectories:
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*, co};
+///
+/// let path = "C:\\ProgramData\\My Company";
+///
+/// let mut sd = w::GetNamedSecurityInfo(
+///     path,
+///     co::SE_OBJECT_TYPE::FILE_OBJECT,
+///     co::SECURITY_INFORMATION::DACL,
+/// )?;
+///
+/// let users = w::Sid::well_known(co::WELL_KNOWN_SID_TYPE::BuiltinUsers, None)?;
+/// sd.grant_access(
+///     &users,
+///     co::GENERIC::READ.raw(),
+///     co::ACE_FLAG::CONTAINER_INHERIT | co::ACE_FLAG::OBJECT_INHERIT,
+/// );
+///
+/// w::SetNamedSecurityInfo(
+///     path,
+///     co::SE_OBJECT_TYPE::FILE_OBJECT,
+///     co::SECURITY_INFORMATION::DACL,
+///     &sd,
+/// )?;
+/// # w::SysResult::Ok(())
+/// ```
+///
+/// # Related functions
+///
+/// * [`GetNamedSecurityInfo`](crate::GetNamedSecurityInfo)
+/// * [`HFILE::SetSecurityInfo`](crate::prelude::advapi_Hfile::SetSecurityInfo)
+/// * [`HKEY::SetSecurityInfo`](crate::prelude::advapi_Hkey::SetSecurityInfo)
+/// * [`HPROCESS::SetSecurityInfo`](crate::prelude::advapi_Hprocess::SetSecurityInfo)
+/// * [`HSERVICE::SetSecurityInfo`](crate::prelude::advapi_Hservice::SetSecurityInfo)
+pub fn SetNamedSecurityInfo(
+	object_name: &str,
+	object_type: co::SE_OBJECT_TYPE,
+	security_info: co::SECURITY_INFORMATION,
+	sd: &SecurityDescriptor,
+) -> SysResult<()>
+{
+	let mut wname = WString::from_str(object_name);
+	set_security_info(security_info, sd, |si, owner, group, dacl, sacl| unsafe {
+		ffi::SetNamedSecurityInfoW(
+			wname.as_mut_ptr(),
+			object_type.raw(),
+			si,
+			owner,
+			group,
+			dacl,
+			sacl,
+		)
+	})
+}
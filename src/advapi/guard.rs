@@ -22,6 +22,14 @@ handle_guard! { CloseServiceHandleSvcGuard: HSERVICE;
 	/// when the object goes out of scope.
 }
 
+handle_guard! { CloseEventLogGuard: HEVENTLOG;
+	ffi::CloseEventLog;
+	/// RAII implementation for [`HEVENTLOG`](crate::HEVENTLOG) which
+	/// automatically calls
+	/// [`CloseEventLog`](https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-closeeventlog)
+	/// when the object goes out of scope.
+}
+
 handle_guard! { DeregisterEventSourceGuard: HEVENTLOG;
 	ffi::DeregisterEventSource;
 	/// RAII implementation for [`HEVENTLOG`](crate::HEVENTLOG) which
@@ -257,6 +265,60 @@ impl<'a> TokenGroupsGuard<'a> {
 	}
 }
 
+/// RAII implementation which enables a privilege of an
+/// [`HACCESSTOKEN`](crate::HACCESSTOKEN), returned by
+/// [`HACCESSTOKEN::enable_privilege`](crate::prelude::advapi_Haccesstoken::enable_privilege),
+/// which automatically restores the previous state of the privilege when the
+/// object goes out of scope.
+pub struct PrivilegeGuard<'a> {
+	htoken: HACCESSTOKEN,
+	prev_state: Option<LUID_AND_ATTRIBUTES>,
+	_htoken: PhantomData<&'a HACCESSTOKEN>,
+}
+
+impl<'a> Drop for PrivilegeGuard<'a> {
+	fn drop(&mut self) {
+		if let Some(prev_state) = self.prev_state {
+			if let Ok(privs) = TOKEN_PRIVILEGES::new(&[prev_state]) {
+				unsafe {
+					ffi::AdjustTokenPrivileges( // ignore errors
+						self.htoken.ptr(),
+						0,
+						&*privs as *const _ as _,
+						0,
+						std::ptr::null_mut(),
+						std::ptr::null_mut(),
+					);
+				}
+			}
+		}
+	}
+}
+
+impl<'a> PrivilegeGuard<'a> {
+	/// Constructs the guard by taking a copy of the token handle, and the
+	/// previous state of the privilege, if it was changed.
+	///
+	/// # Safety
+	///
+	/// Be sure the handle outlives the guard.
+	#[must_use]
+	pub(in crate::advapi) const unsafe fn new(
+		htoken: HACCESSTOKEN,
+		prev_state: Option<LUID_AND_ATTRIBUTES>,
+	) -> Self
+	{
+		Self { htoken, prev_state, _htoken: PhantomData }
+	}
+
+	/// Returns `true` if the privilege was disabled before, and will be
+	/// disabled again when the guard is dropped.
+	#[must_use]
+	pub const fn was_disabled(&self) -> bool {
+		self.prev_state.is_some()
+	}
+}
+
 /// RAII implementation for [`TOKEN_PRIVILEGES`](crate::TOKEN_PRIVILEGES) which
 /// manages the allocated memory.
 pub struct TokenPrivilegesGuard {
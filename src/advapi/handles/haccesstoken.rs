@@ -1,6 +1,6 @@
 #![allow(non_camel_case_types, non_snake_case)]
 
-use crate::advapi::ffi;
+use crate::advapi::{ffi, privs::*};
 use crate::co;
 use crate::decl::*;
 use crate::guard::*;
@@ -24,6 +24,94 @@ impl advapi_Haccesstoken for HACCESSTOKEN {}
 /// use winsafe::prelude::*;
 /// ```
 pub trait advapi_Haccesstoken: Handle {
+	/// [`AccessCheck`](https://learn.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-accesscheck)
+	/// function.
+	///
+	/// Returns the granted access rights, or
+	/// [`co::ERROR::ACCESS_DENIED`](crate::co::ERROR::ACCESS_DENIED) if the
+	/// access is denied. The token must be an impersonation token, which can be
+	/// obtained with
+	/// [`HACCESSTOKEN::DuplicateToken`](crate::prelude::advapi_Haccesstoken::DuplicateToken).
+	///
+	/// The generic rights in `desired_access` and in the ACEs of the DACL are
+	/// mapped with `generic_mapping` before the call, since the system doesn't
+	/// accept them in the former and doesn't map them in the latter.
+	///
+	/// To evaluate the access without calling the system, see
+	/// [`AccessEvaluator`](crate::AccessEvaluator).
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let htoken = w::HPROCESS::GetCurrentProcess()
+	///     .OpenProcessToken(co::TOKEN::DUPLICATE | co::TOKEN::QUERY)?
+	///     .DuplicateToken(co::SECURITY_IMPERSONATION::Impersonation)?;
+	///
+	/// let sd = w::GetNamedSecurityInfo(
+	///     "C:\\Temp",
+	///     co::SE_OBJECT_TYPE::FILE_OBJECT,
+	///     co::SECURITY_INFORMATION::OWNER
+	///         | co::SECURITY_INFORMATION::GROUP
+	///         | co::SECURITY_INFORMATION::DACL,
+	/// )?;
+	///
+	/// match htoken.AccessCheck(&sd, co::GENERIC::WRITE.raw(), &w::GENERIC_MAPPING::FILE) {
+	///     Ok(granted) => println!("Granted: {:#x}", granted),
+	///     Err(co::ERROR::ACCESS_DENIED) => println!("Denied"),
+	///     Err(e) => return Err(e),
+	/// }
+	/// # w::SysResult::Ok(())
+	/// ```
+	fn AccessCheck(&self,
+		sd: &SecurityDescriptor,
+		desired_access: u32,
+		generic_mapping: &GENERIC_MAPPING,
+	) -> SysResult<u32>
+	{
+		let mut sd = sd.clone();
+		if let Some(dacl) = &mut sd.dacl {
+			for ace in dacl.iter_mut() {
+				ace.access_mask = generic_mapping.map(ace.access_mask);
+			}
+		}
+
+		let sd_buf = aligned_sd_buf(&sd); // the system also requires owner and group
+
+		let desired_access = generic_mapping.map(desired_access);
+		let mut privs_buf = vec![0u32; 64]; // PRIVILEGE_SET, grown if needed
+		loop {
+			let mut privs_sz = (privs_buf.len() * std::mem::size_of::<u32>()) as u32;
+			let mut granted = u32::default();
+			let mut status: BOOL = 0;
+
+			match bool_to_sysresult(
+				unsafe {
+					ffi::AccessCheck(
+						sd_buf.as_ptr() as _,
+						self.ptr(),
+						desired_access,
+						generic_mapping as *const _ as _,
+						privs_buf.as_mut_ptr() as _,
+						&mut privs_sz,
+						&mut granted,
+						&mut status,
+					)
+				},
+			) {
+				Ok(_) => return match status {
+					0 => Err(GetLastError()),
+					_ => Ok(granted),
+				},
+				Err(co::ERROR::INSUFFICIENT_BUFFER) => {
+					privs_buf.resize((privs_sz as usize).div_ceil(4), 0);
+				},
+				Err(e) => return Err(e),
+			}
+		}
+	}
+
 	/// [`AdjustTokenPrivileges`](https://learn.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-adjusttokenprivileges)
 	/// function.
 	///
@@ -302,6 +390,61 @@ pub trait advapi_Haccesstoken: Handle {
 		}
 	}
 
+	/// Enables a privilege of the token, returning a guard which restores the
+	/// previous state of the privilege when dropped.
+	///
+	/// The token must have been opened with
+	/// [`co::TOKEN::ADJUST_PRIVILEGES`](crate::co::TOKEN::ADJUST_PRIVILEGES)
+	/// and [`co::TOKEN::QUERY`](crate::co::TOKEN::QUERY). If the token doesn't
+	/// have the privilege,
+	/// [`co::ERROR::NOT_ALL_ASSIGNED`](crate::co::ERROR::NOT_ALL_ASSIGNED) is
+	/// returned.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let htoken = w::HPROCESS::GetCurrentProcess()
+	///     .OpenProcessToken(co::TOKEN::ADJUST_PRIVILEGES | co::TOKEN::QUERY)?;
+	///
+	/// {
+	///     let _backup = htoken.enable_privilege(co::SE_PRIV::BACKUP_NAME)?;
+	///     // ... read the files ...
+	/// } // privilege restored here
+	/// # w::SysResult::Ok(())
+	/// ```
+	fn enable_privilege(&self, privilege: co::SE_PRIV) -> SysResult<PrivilegeGuard<'_>> {
+		let luid = LookupPrivilegeValue(None, privilege)?;
+		let new_state = TOKEN_PRIVILEGES::new(&[
+			LUID_AND_ATTRIBUTES::new(luid, co::SE_PRIV_ATTR::ENABLED),
+		])?;
+		let mut prev_state = TOKEN_PRIVILEGES::new(&[ // room for one entry
+			LUID_AND_ATTRIBUTES::new(luid, co::SE_PRIV_ATTR::NoValue),
+		])?;
+		let mut prev_sz = std::mem::size_of::<TOKEN_PRIVILEGES>() as u32;
+
+		bool_to_sysresult(
+			unsafe {
+				ffi::AdjustTokenPrivileges(
+					self.ptr(),
+					0,
+					&*new_state as *const _ as _,
+					prev_sz,
+					&mut *prev_state as *mut _ as _,
+					&mut prev_sz,
+				)
+			},
+		)?;
+		if GetLastError() == co::ERROR::NOT_ALL_ASSIGNED {
+			return Err(co::ERROR::NOT_ALL_ASSIGNED); // the token doesn't have the privilege
+		}
+
+		// If the privilege was already enabled, nothing is returned.
+		let prev_state = prev_state.Privileges().first().copied();
+		Ok(unsafe { PrivilegeGuard::new(HACCESSTOKEN::from_ptr(self.ptr()), prev_state) })
+	}
+
 	/// [`ImpersonateLoggedOnUser`](https://learn.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-impersonateloggedonuser)
 	/// function.
 	fn ImpersonateLoggedOnUser(&self) -> SysResult<()> {
@@ -320,4 +463,28 @@ pub trait advapi_Haccesstoken: Handle {
 			_ => Ok(true),
 		}
 	}
+
+	/// Reads the user, groups, privileges, integrity level, elevation type and
+	/// linked token into a [`TokenSnapshot`](crate::TokenSnapshot).
+	///
+	/// The token must have been opened with
+	/// [`co::TOKEN::QUERY`](crate::co::TOKEN::QUERY).
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let nfo = w::HPROCESS::GetCurrentProcess()
+	///     .OpenProcessToken(co::TOKEN::QUERY)?
+	///     .snapshot()?;
+	///
+	/// let admins = w::Sid::well_known(co::WELL_KNOWN_SID_TYPE::BuiltinAdministrators, None)?;
+	/// println!("Admin: {}, elevated: {}", nfo.is_member(&admins), nfo.is_elevated());
+	/// # w::SysResult::Ok(())
+	/// ```
+	#[must_use]
+	fn snapshot(&self) -> SysResult<TokenSnapshot> {
+		TokenSnapshot::read(unsafe { &HACCESSTOKEN::from_ptr(self.ptr()) })
+	}
 }
@@ -1,6 +1,6 @@
 #![allow(non_camel_case_types, non_snake_case)]
 
-use crate::advapi::ffi;
+use crate::advapi::{ffi, iterators::*};
 use crate::co;
 use crate::decl::*;
 use crate::guard::*;
@@ -24,6 +24,143 @@ impl advapi_Heventlog for HEVENTLOG {}
 /// use winsafe::prelude::*;
 /// ```
 pub trait advapi_Heventlog: Handle {
+	/// [`BackupEventLog`](https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-backupeventlogw)
+	/// function.
+	fn BackupEventLog(&self, backup_file_name: &str) -> SysResult<()> {
+		bool_to_sysresult(
+			unsafe {
+				ffi::BackupEventLogW(
+					self.ptr(),
+					WString::from_str(backup_file_name).as_ptr(),
+				)
+			},
+		)
+	}
+
+	/// [`ClearEventLog`](https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-cleareventlogw)
+	/// function.
+	///
+	/// If `backup_file_name` is informed, the log is saved to this file before
+	/// being cleared.
+	fn ClearEventLog(&self, backup_file_name: Option<&str>) -> SysResult<()> {
+		bool_to_sysresult(
+			unsafe {
+				ffi::ClearEventLogW(
+					self.ptr(),
+					WString::from_opt_str(backup_file_name).as_ptr(),
+				)
+			},
+		)
+	}
+
+	/// [`GetNumberOfEventLogRecords`](https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-getnumberofeventlogrecords)
+	/// function.
+	#[must_use]
+	fn GetNumberOfEventLogRecords(&self) -> SysResult<u32> {
+		let mut num = u32::default();
+		bool_to_sysresult(
+			unsafe { ffi::GetNumberOfEventLogRecords(self.ptr(), &mut num) },
+		).map(|_| num)
+	}
+
+	/// [`GetOldestEventLogRecord`](https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-getoldesteventlogrecord)
+	/// function.
+	#[must_use]
+	fn GetOldestEventLogRecord(&self) -> SysResult<u32> {
+		let mut num = u32::default();
+		bool_to_sysresult(
+			unsafe { ffi::GetOldestEventLogRecord(self.ptr(), &mut num) },
+		).map(|_| num)
+	}
+
+	/// [`OpenBackupEventLog`](https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-openbackupeventlogw)
+	/// function.
+	#[must_use]
+	fn OpenBackupEventLog(
+		unc_server_name: Option<&str>,
+		file_name: &str,
+	) -> SysResult<CloseEventLogGuard>
+	{
+		unsafe {
+			ptr_to_sysresult_handle(
+				ffi::OpenBackupEventLogW(
+					WString::from_opt_str(unc_server_name).as_ptr(),
+					WString::from_str(file_name).as_ptr(),
+				),
+			).map(|h| CloseEventLogGuard::new(h))
+		}
+	}
+
+	/// [`OpenEventLog`](https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-openeventlogw)
+	/// function.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*};
+	///
+	/// let hlog = w::HEVENTLOG::OpenEventLog(None, "Application")?;
+	/// println!("{} records", hlog.GetNumberOfEventLogRecords()?);
+	/// # w::SysResult::Ok(())
+	/// ```
+	#[must_use]
+	fn OpenEventLog(
+		unc_server_name: Option<&str>,
+		source_name: &str,
+	) -> SysResult<CloseEventLogGuard>
+	{
+		unsafe {
+			ptr_to_sysresult_handle(
+				ffi::OpenEventLogW(
+					WString::from_opt_str(unc_server_name).as_ptr(),
+					WString::from_str(source_name).as_ptr(),
+				),
+			).map(|h| CloseEventLogGuard::new(h))
+		}
+	}
+
+	/// [`ReadEventLog`](https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-readeventlogw)
+	/// function.
+	///
+	/// Returns an iterator over the records, which are read in batches and
+	/// parsed into [`EventLogRecord`](crate::EventLogRecord) objects.
+	///
+	/// `flags` must contain either
+	/// [`co::EVENTLOG_READ::SEQUENTIAL_READ`](crate::co::EVENTLOG_READ::SEQUENTIAL_READ)
+	/// or [`co::EVENTLOG_READ::SEEK_READ`](crate::co::EVENTLOG_READ::SEEK_READ),
+	/// plus the direction. `record_offset` is used only with `SEEK_READ`; after
+	/// the first batch, the remaining records are read sequentially.
+	///
+	/// # Examples
+	///
+	/// Listing the errors of the application log, newest first:
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hlog = w::HEVENTLOG::OpenEventLog(None, "Application")?;
+	///
+	/// for rec in hlog.ReadEventLog(
+	///     co::EVENTLOG_READ::SEQUENTIAL_READ | co::EVENTLOG_READ::BACKWARDS_READ,
+	///     0,
+	/// ) {
+	///     let rec = rec?;
+	///     if rec.event_type == co::EVENTLOG::ERROR_TYPE {
+	///         println!("{} {} {:?}",
+	///             rec.source_name, rec.event_code(), rec.strings);
+	///     }
+	/// }
+	/// # w::SysResult::Ok(())
+	/// ```
+	#[must_use]
+	fn ReadEventLog(&self,
+		flags: co::EVENTLOG_READ,
+		record_offset: u32,
+	) -> impl Iterator<Item = SysResult<EventLogRecord>> + '_
+	{
+		HeventlogReadIter::new(self, flags, record_offset)
+	}
+
 	/// [`RegisterEventSource`](https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-registereventsourcew)
 	/// function.
 	#[must_use]
@@ -42,6 +179,46 @@ pub trait advapi_Heventlog: Handle {
 		}
 	}
 
+	/// Writes an event with
+	/// [`HEVENTLOG::ReportEvent`](crate::prelude::advapi_Heventlog::ReportEvent),
+	/// taking the event type, category, identifier, user SID, insertion
+	/// strings and binary data from the [`EventLogRecord`](crate::EventLogRecord).
+	/// The remaining fields are filled by the system.
+	///
+	/// Returns [`co::ERROR::INVALID_SID`](crate::co::ERROR::INVALID_SID) if
+	/// the user SID is malformed.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hsource = w::HEVENTLOG::RegisterEventSource(None, "MyApp")?;
+	///
+	/// hsource.report_record(&w::EventLogRecord {
+	///     event_type: co::EVENTLOG::WARNING_TYPE,
+	///     event_id: 1000,
+	///     strings: vec!["Disk almost full".to_owned()],
+	///     data: 97u32.to_le_bytes().to_vec(),
+	///     ..Default::default()
+	/// })?;
+	/// # w::SysResult::Ok(())
+	/// ```
+	fn report_record(&self, rec: &EventLogRecord) -> SysResult<()> {
+		let sid_guard = rec.user_sid.as_deref()
+			.map(|bytes| Sid::parse(bytes).and_then(|(sid, _)| sid.to_sid_guard()))
+			.transpose()?;
+
+		self.ReportEvent(
+			rec.event_type,
+			rec.event_category,
+			rec.event_id,
+			sid_guard.as_deref(),
+			Some(&rec.strings),
+			Some(&rec.data),
+		)
+	}
+
 	/// [`ReportEvent`](https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-reporteventw)
 	/// function.
 	fn ReportEvent(&self,
@@ -0,0 +1,123 @@
+#![allow(non_camel_case_types, non_snake_case)]
+
+use crate::advapi::{ffi, privs::*};
+use crate::co;
+use crate::decl::*;
+use crate::prelude::*;
+
+impl advapi_Hfile for HFILE {}
+
+/// This trait is enabled with the `advapi` feature, and provides methods for
+/// [`HFILE`](crate::HFILE).
+///
+/// Prefer importing this trait through the prelude:
+///
+/// ```no_run
+/// use winsafe::prelude::*;
+/// ```
+pub trait advapi_Hfile: kernel_Hfile {
+	/// [`GetSecurityInfo`](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-getsecurityinfo)
+	/// function, with
+	/// [`co::SE_OBJECT_TYPE::FILE_OBJECT`](crate::co::SE_OBJECT_TYPE::FILE_OBJECT).
+	///
+	/// Returns an owned [`SecurityDescriptor`](crate::SecurityDescriptor) with
+	/// the parts given in `security_info`.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let (hfile, _) = w::HFILE::CreateFile(
+	///     "C:\\Temp\\test.txt",
+	///     co::GENERIC::READ,
+	///     Some(co::FILE_SHARE::READ),
+	///     None,
+	///     co::DISPOSITION::OPEN_EXISTING,
+	///     co::FILE_ATTRIBUTE::NORMAL,
+	///     None,
+	///     None,
+	///     None,
+	/// )?;
+	///
+	/// let sd = hfile.GetSecurityInfo(co::SECURITY_INFORMATION::OWNER)?;
+	/// println!("{:?}", sd.owner);
+	/// # w::SysResult::Ok(())
+	/// ```
+	///
+	/// # Related functions
+	///
+	/// * [`GetNamedSecurityInfo`](crate::GetNamedSecurityInfo)
+	/// * [`HFILE::SetSecurityInfo`](crate::prelude::advapi_Hfile::SetSecurityInfo)
+	#[must_use]
+	fn GetSecurityInfo(&self,
+		security_info: co::SECURITY_INFORMATION,
+	) -> SysResult<SecurityDescriptor>
+	{
+		get_security_info(|psd| unsafe {
+			ffi::GetSecurityInfo(
+				self.ptr(),
+				co::SE_OBJECT_TYPE::FILE_OBJECT.raw(),
+				security_info.raw(),
+				std::ptr::null_mut(),
+				std::ptr::null_mut(),
+				std::ptr::null_mut(),
+				std::ptr::null_mut(),
+				psd,
+			)
+		})
+	}
+
+	/// [`SetSecurityInfo`](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-setsecurityinfo)
+	/// function, with
+	/// [`co::SE_OBJECT_TYPE::FILE_OBJECT`](crate::co::SE_OBJECT_TYPE::FILE_OBJECT).
+	///
+	/// See [`SetNamedSecurityInfo`](crate::SetNamedSecurityInfo) for how `sd`
+	/// is applied.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let (hfile, _) = w::HFILE::CreateFile(
+	///     "C:\\Temp\\test.txt",
+	///     co::GENERIC::ALL,
+	///     Some(co::FILE_SHARE::READ),
+	///     None,
+	///     co::DISPOSITION::OPEN_EXISTING,
+	///     co::FILE_ATTRIBUTE::NORMAL,
+	///     None,
+	///     None,
+	///     None,
+	/// )?;
+	///
+	/// let mut sd = hfile.GetSecurityInfo(co::SECURITY_INFORMATION::DACL)?;
+	/// let users = w::Sid::well_known(co::WELL_KNOWN_SID_TYPE::BuiltinUsers, None)?;
+	/// sd.revoke_access(&users);
+	/// hfile.SetSecurityInfo(co::SECURITY_INFORMATION::DACL, &sd)?;
+	/// # w::SysResult::Ok(())
+	/// ```
+	///
+	/// # Related functions
+	///
+	/// * [`HFILE::GetSecurityInfo`](crate::prelude::advapi_Hfile::GetSecurityInfo)
+	/// * [`SetNamedSecurityInfo`](crate::SetNamedSecurityInfo)
+	fn SetSecurityInfo(&self,
+		security_info: co::SECURITY_INFORMATION,
+		sd: &SecurityDescriptor,
+	) -> SysResult<()>
+	{
+		set_security_info(security_info, sd, |si, owner, group, dacl, sacl| unsafe {
+			ffi::SetSecurityInfo(
+				self.ptr(),
+				co::SE_OBJECT_TYPE::FILE_OBJECT.raw(),
+				si,
+				owner,
+				group,
+				dacl,
+				sacl,
+			)
+		})
+	}
+}
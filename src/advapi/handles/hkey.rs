@@ -1,6 +1,6 @@
 #![allow(non_camel_case_types, non_snake_case)]
 
-use crate::advapi::{ffi, iterators::*};
+use crate::advapi::{ffi, iterators::*, privs::*};
 use crate::co;
 use crate::decl::*;
 use crate::guard::*;
@@ -46,6 +46,52 @@ pub trait advapi_Hkey: Handle {
 	predef_key!(PERFORMANCE_TEXT, 0x8000_0050);
 	predef_key!(PERFORMANCE_NLSTEXT, 0x8000_0060);
 
+	/// [`GetSecurityInfo`](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-getsecurityinfo)
+	/// function, with
+	/// [`co::SE_OBJECT_TYPE::REGISTRY_KEY`](crate::co::SE_OBJECT_TYPE::REGISTRY_KEY).
+	///
+	/// Returns an owned [`SecurityDescriptor`](crate::SecurityDescriptor) with
+	/// the parts given in `security_info`.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hkey = w::HKEY::CURRENT_USER.RegOpenKeyEx(
+	///     Some("Software\\My Company"),
+	///     co::REG_OPTION::NoValue,
+	///     co::KEY::READ,
+	/// )?;
+	///
+	/// let sd = hkey.GetSecurityInfo(co::SECURITY_INFORMATION::DACL)?;
+	/// println!("{sd}");
+	/// # w::SysResult::Ok(())
+	/// ```
+	///
+	/// # Related functions
+	///
+	/// * [`GetNamedSecurityInfo`](crate::GetNamedSecurityInfo)
+	/// * [`HKEY::SetSecurityInfo`](crate::prelude::advapi_Hkey::SetSecurityInfo)
+	#[must_use]
+	fn GetSecurityInfo(&self,
+		security_info: co::SECURITY_INFORMATION,
+	) -> SysResult<SecurityDescriptor>
+	{
+		get_security_info(|psd| unsafe {
+			ffi::GetSecurityInfo(
+				self.ptr(),
+				co::SE_OBJECT_TYPE::REGISTRY_KEY.raw(),
+				security_info.raw(),
+				std::ptr::null_mut(),
+				std::ptr::null_mut(),
+				std::ptr::null_mut(),
+				std::ptr::null_mut(),
+				psd,
+			)
+		})
+	}
+
 	/// [`RegConnectRegistry`](https://learn.microsoft.com/en-us/windows/win32/api/winreg/nf-winreg-regconnectregistryw)
 	/// function.
 	///
@@ -425,6 +471,31 @@ pub trait advapi_Hkey: Handle {
 		)
 	}
 
+	/// [`RegNotifyChangeKeyValue`](https://learn.microsoft.com/en-us/windows/win32/api/winreg/nf-winreg-regnotifychangekeyvalue)
+	/// function.
+	///
+	/// For a higher-level wrapper, see
+	/// [`RegistryWatch`](crate::RegistryWatch).
+	fn RegNotifyChangeKeyValue(&self,
+		watch_subtree: bool,
+		notify_filter: co::REG_NOTIFY,
+		hevent: Option<&HEVENT>,
+		asynchronous: bool,
+	) -> SysResult<()>
+	{
+		error_to_sysresult(
+			unsafe {
+				ffi::RegNotifyChangeKeyValue(
+					self.ptr(),
+					watch_subtree as _,
+					notify_filter.raw(),
+					hevent.map_or(std::ptr::null_mut(), |h| h.ptr()),
+					asynchronous as _,
+				)
+			},
+		)
+	}
+
 	/// [`RegOpenCurrentUser`](https://learn.microsoft.com/en-us/windows/win32/api/winreg/nf-winreg-regopencurrentuser)
 	/// function.
 	#[must_use]
@@ -924,6 +995,87 @@ pub trait advapi_Hkey: Handle {
 			},
 		)
 	}
+
+	/// [`SetSecurityInfo`](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-setsecurityinfo)
+	/// function, with
+	/// [`co::SE_OBJECT_TYPE::REGISTRY_KEY`](crate::co::SE_OBJECT_TYPE::REGISTRY_KEY).
+	///
+	/// See [`SetNamedSecurityInfo`](crate::SetNamedSecurityInfo) for how `sd`
+	/// is applied.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hkey = w::HKEY::CURRENT_USER.RegOpenKeyEx(
+	///     Some("Software\\My Company"),
+	///     co::REG_OPTION::NoValue,
+	///     co::KEY::ALL_ACCESS,
+	/// )?;
+	///
+	/// let mut sd = hkey.GetSecurityInfo(co::SECURITY_INFORMATION::DACL)?;
+	/// let users = w::Sid::well_known(co::WELL_KNOWN_SID_TYPE::BuiltinUsers, None)?;
+	/// sd.grant_access(&users, co::KEY::READ.raw(), co::ACE_FLAG::CONTAINER_INHERIT);
+	/// hkey.SetSecurityInfo(co::SECURITY_INFORMATION::DACL, &sd)?;
+	/// # w::SysResult::Ok(())
+	/// ```
+	///
+	/// # Related functions
+	///
+	/// * [`HKEY::GetSecurityInfo`](crate::prelude::advapi_Hkey::GetSecurityInfo)
+	/// * [`SetNamedSecurityInfo`](crate::SetNamedSecurityInfo)
+	fn SetSecurityInfo(&self,
+		security_info: co::SECURITY_INFORMATION,
+		sd: &SecurityDescriptor,
+	) -> SysResult<()>
+	{
+		set_security_info(security_info, sd, |si, owner, group, dacl, sacl| unsafe {
+			ffi::SetSecurityInfo(
+				self.ptr(),
+				co::SE_OBJECT_TYPE::REGISTRY_KEY.raw(),
+				si,
+				owner,
+				group,
+				dacl,
+				sacl,
+			)
+		})
+	}
+
+	/// Reads the values and all the subkeys of the key – or of the given
+	/// subkey – into an in-memory [`RegistryTree`](crate::RegistryTree),
+	/// which can be later compared with
+	/// [`RegistryTree::diff`](crate::RegistryTree::diff).
+	///
+	/// Values whose type cannot be represented by
+	/// [`RegistryValue`](crate::RegistryValue), like
+	/// [`co::REG::RESOURCE_LIST`](crate::co::REG::RESOURCE_LIST), are kept
+	/// with their raw bytes, as [`RegFileData::Raw`](crate::RegFileData::Raw).
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*};
+	///
+	/// let before = w::HKEY::CURRENT_USER.snapshot(Some("Software\\My Company"))?;
+	/// // ... run the installer ...
+	/// let after = w::HKEY::CURRENT_USER.snapshot(Some("Software\\My Company"))?;
+	///
+	/// for change in before.diff(&after) {
+	///     println!("{change}");
+	/// }
+	/// # w::SysResult::Ok(())
+	/// ```
+	#[must_use]
+	fn snapshot(&self, sub_key: Option<&str>) -> SysResult<RegistryTree> {
+		match sub_key {
+			Some(sub_key) => RegistryTree::read(
+				&*self.RegOpenKeyEx(Some(sub_key), co::REG_OPTION::NoValue, co::KEY::READ)?,
+			),
+			None => RegistryTree::read(self),
+		}
+	}
 }
 
 impl HKEY {
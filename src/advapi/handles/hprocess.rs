@@ -1,6 +1,6 @@
 #![allow(non_camel_case_types, non_snake_case)]
 
-use crate::advapi::ffi;
+use crate::advapi::{ffi, privs::*};
 use crate::co;
 use crate::decl::*;
 use crate::guard::*;
@@ -18,6 +18,47 @@ impl advapi_Hprocess for HPROCESS {}
 /// use winsafe::prelude::*;
 /// ```
 pub trait advapi_Hprocess: kernel_Hprocess {
+	/// [`GetSecurityInfo`](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-getsecurityinfo)
+	/// function, with
+	/// [`co::SE_OBJECT_TYPE::KERNEL_OBJECT`](crate::co::SE_OBJECT_TYPE::KERNEL_OBJECT).
+	///
+	/// Returns an owned [`SecurityDescriptor`](crate::SecurityDescriptor) with
+	/// the parts given in `security_info`.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let sd = w::HPROCESS::GetCurrentProcess()
+	///     .GetSecurityInfo(co::SECURITY_INFORMATION::OWNER | co::SECURITY_INFORMATION::DACL)?;
+	/// println!("{sd}");
+	/// # w::SysResult::Ok(())
+	/// ```
+	///
+	/// # Related functions
+	///
+	/// * [`GetNamedSecurityInfo`](crate::GetNamedSecurityInfo)
+	/// * [`HPROCESS::SetSecurityInfo`](crate::prelude::advapi_Hprocess::SetSecurityInfo)
+	#[must_use]
+	fn GetSecurityInfo(&self,
+		security_info: co::SECURITY_INFORMATION,
+	) -> SysResult<SecurityDescriptor>
+	{
+		get_security_info(|psd| unsafe {
+			ffi::GetSecurityInfo(
+				self.ptr(),
+				co::SE_OBJECT_TYPE::KERNEL_OBJECT.raw(),
+				security_info.raw(),
+				std::ptr::null_mut(),
+				std::ptr::null_mut(),
+				std::ptr::null_mut(),
+				std::ptr::null_mut(),
+				psd,
+			)
+		})
+	}
+
 	/// [`OpenProcessToken`](https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-openprocesstoken)
 	/// function.
 	///
@@ -46,4 +87,46 @@ pub trait advapi_Hprocess: kernel_Hprocess {
 			).map(|_| CloseHandleGuard::new(handle))
 		}
 	}
+
+	/// [`SetSecurityInfo`](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-setsecurityinfo)
+	/// function, with
+	/// [`co::SE_OBJECT_TYPE::KERNEL_OBJECT`](crate::co::SE_OBJECT_TYPE::KERNEL_OBJECT).
+	///
+	/// See [`SetNamedSecurityInfo`](crate::SetNamedSecurityInfo) for how `sd`
+	/// is applied.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hproc = w::HPROCESS::GetCurrentProcess();
+	/// let mut sd = hproc.GetSecurityInfo(co::SECURITY_INFORMATION::DACL)?;
+	/// let everyone = w::Sid::well_known(co::WELL_KNOWN_SID_TYPE::World, None)?;
+	/// sd.deny_access(&everyone, co::PROCESS::TERMINATE.raw(), co::ACE_FLAG::default());
+	/// hproc.SetSecurityInfo(co::SECURITY_INFORMATION::DACL, &sd)?;
+	/// # w::SysResult::Ok(())
+	/// ```
+	///
+	/// # Related functions
+	///
+	/// * [`HPROCESS::GetSecurityInfo`](crate::prelude::advapi_Hprocess::GetSecurityInfo)
+	/// * [`SetNamedSecurityInfo`](crate::SetNamedSecurityInfo)
+	fn SetSecurityInfo(&self,
+		security_info: co::SECURITY_INFORMATION,
+		sd: &SecurityDescriptor,
+	) -> SysResult<()>
+	{
+		set_security_info(security_info, sd, |si, owner, group, dacl, sacl| unsafe {
+			ffi::SetSecurityInfo(
+				self.ptr(),
+				co::SE_OBJECT_TYPE::KERNEL_OBJECT.raw(),
+				si,
+				owner,
+				group,
+				dacl,
+				sacl,
+			)
+		})
+	}
 }
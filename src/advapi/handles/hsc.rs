@@ -1,6 +1,6 @@
 #![allow(non_camel_case_types, non_snake_case)]
 
-use crate::advapi::ffi;
+use crate::advapi::{ffi, iterators::*};
 use crate::co;
 use crate::decl::*;
 use crate::guard::*;
@@ -68,6 +68,45 @@ pub trait advapi_Hsc: Handle {
 		}
 	}
 
+	/// Returns an iterator over the services, which calls
+	/// [`EnumServicesStatusEx`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/nf-winsvc-enumservicesstatusexw)
+	/// repeatedly, yielding the service name, the display name and the status.
+	///
+	/// If `group_name` is `Some`, only the services in the given load order
+	/// group are returned; an empty string returns the services which don't
+	/// belong to any group.
+	///
+	/// The handle must have
+	/// [`co::SC_MANAGER::ENUMERATE_SERVICE`](crate::co::SC_MANAGER::ENUMERATE_SERVICE)
+	/// access.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hsc = w::HSC::OpenSCManager(None, co::SC_MANAGER::ENUMERATE_SERVICE)?;
+	///
+	/// for svc in hsc.EnumServicesStatusEx(
+	///     co::SERVICE_TYPE::WIN32,
+	///     co::SERVICE_ENUM_STATE::ACTIVE,
+	///     None,
+	/// ) {
+	///     let (name, display_name, status) = svc?;
+	///     println!("{} ({}), PID {}", name, display_name, status.dwProcessId);
+	/// }
+	/// # w::SysResult::Ok(())
+	/// ```
+	#[must_use]
+	fn EnumServicesStatusEx(&self,
+		service_type: co::SERVICE_TYPE,
+		service_state: co::SERVICE_ENUM_STATE,
+		group_name: Option<&str>,
+	) -> impl Iterator<Item = SysResult<(String, String, SERVICE_STATUS_PROCESS)>> + '_
+	{
+		HscServiceIter::new(self, service_type, service_state, group_name)
+	}
+
 	/// [`OpenSCManager`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/nf-winsvc-openscmanagerw)
 	/// function.
 	#[must_use]
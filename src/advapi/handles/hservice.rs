@@ -1,8 +1,9 @@
 #![allow(non_camel_case_types, non_snake_case)]
 
-use crate::advapi::ffi;
+use crate::advapi::{ffi, privs::*};
+use crate::co;
 use crate::decl::*;
-use crate::kernel::privs::*;
+use crate::kernel::{ffi_types::*, privs::*};
 use crate::prelude::*;
 
 impl_handle! { HSERVICE;
@@ -22,9 +23,509 @@ impl advapi_Hservice for HSERVICE {}
 /// use winsafe::prelude::*;
 /// ```
 pub trait advapi_Hservice: Handle {
+	/// [`ChangeServiceConfig`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/nf-winsvc-changeserviceconfigw)
+	/// function.
+	///
+	/// Returns the new tag of the service, if its load order group was changed.
+	///
+	/// # Examples
+	///
+	/// Changing the start type of a service:
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hsc = w::HSC::OpenSCManager(None, co::SC_MANAGER::CONNECT)?;
+	/// let hsvc = hsc.OpenService("MyService", co::SERVICE::CHANGE_CONFIG)?;
+	///
+	/// hsvc.ChangeServiceConfig(&w::SvcConfigChange {
+	///     start_type: Some(co::SERVICE_START::DEMAND_START),
+	///     ..Default::default()
+	/// })?;
+	/// # w::SysResult::Ok(())
+	/// ```
+	fn ChangeServiceConfig(&self, changes: &SvcConfigChange) -> SysResult<Option<u32>> {
+		let str_w = |s: &Option<String>| s.as_deref().map(str_to_wstring_nonnull);
+		let binary_path_name_w = str_w(&changes.binary_path_name);
+		let load_order_group_w = str_w(&changes.load_order_group);
+		let dependencies_w = changes.dependencies.as_ref().map(|v| WString::from_str_vec(v));
+		let service_start_name_w = str_w(&changes.service_start_name);
+		let password_w = str_w(&changes.password);
+		let display_name_w = str_w(&changes.display_name);
+		let opt_ptr = |w: &Option<WString>| w.as_ref().map_or(std::ptr::null(), |w| w.as_ptr());
+
+		let mut tag_id = u32::default();
+		bool_to_sysresult(
+			unsafe {
+				ffi::ChangeServiceConfigW(
+					self.ptr(),
+					changes.service_type.map_or(SERVICE_NO_CHANGE, |t| t.raw()),
+					changes.start_type.map_or(SERVICE_NO_CHANGE, |t| t.raw()),
+					changes.error_control.map_or(SERVICE_NO_CHANGE, |e| e.raw()),
+					opt_ptr(&binary_path_name_w),
+					opt_ptr(&load_order_group_w),
+					load_order_group_w.as_ref().map_or(std::ptr::null_mut(), |_| &mut tag_id),
+					opt_ptr(&dependencies_w),
+					opt_ptr(&service_start_name_w),
+					opt_ptr(&password_w),
+					opt_ptr(&display_name_w),
+				)
+			},
+		).map(|_| load_order_group_w.map(|_| tag_id))
+	}
+
+	/// [`ChangeServiceConfig2`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/nf-winsvc-changeserviceconfig2w)
+	/// function.
+	///
+	/// Setting [`co::SC_ACTION::RESTART`](crate::co::SC_ACTION::RESTART)
+	/// failure actions requires the handle to have
+	/// [`co::SERVICE::START`](crate::co::SERVICE::START) access.
+	///
+	/// # Examples
+	///
+	/// Restarting the service after 5 seconds, if it fails:
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hsc = w::HSC::OpenSCManager(None, co::SC_MANAGER::CONNECT)?;
+	/// let hsvc = hsc.OpenService(
+	///     "MyService",
+	///     co::SERVICE::CHANGE_CONFIG | co::SERVICE::START,
+	/// )?;
+	///
+	/// hsvc.ChangeServiceConfig2(&w::SvcConfig2::Description(
+	///     Some("Does important things.".to_owned()),
+	/// ))?;
+	///
+	/// hsvc.ChangeServiceConfig2(&w::SvcConfig2::FailureActions(
+	///     w::SvcFailureActions {
+	///         reset_period: Some(86_400),
+	///         actions: vec![w::SC_ACTION::new(co::SC_ACTION::RESTART, 5_000)],
+	///         ..Default::default()
+	///     },
+	/// ))?;
+	/// # w::SysResult::Ok(())
+	/// ```
+	fn ChangeServiceConfig2(&self, info: &SvcConfig2) -> SysResult<()> {
+		let call = |info_ptr: PCVOID| bool_to_sysresult(
+			unsafe {
+				ffi::ChangeServiceConfig2W(self.ptr(), info.info_level().raw(), info_ptr)
+			},
+		);
+
+		match info {
+			SvcConfig2::Description(desc) => {
+				let desc_w = desc.as_deref().map(str_to_wstring_nonnull);
+				let desc_ptr: PCSTR = desc_w.as_ref().map_or(std::ptr::null(), |w| w.as_ptr());
+				call(&desc_ptr as *const _ as _) // SERVICE_DESCRIPTION has a single pointer field
+			},
+			SvcConfig2::FailureActions(fa) => {
+				let reboot_msg_w = fa.reboot_msg.as_deref().map(str_to_wstring_nonnull);
+				let command_w = fa.command.as_deref().map(str_to_wstring_nonnull);
+				let raw = SERVICE_FAILURE_ACTIONS {
+					dwResetPeriod: fa.reset_period.unwrap_or(INFINITE),
+					lpRebootMsg: reboot_msg_w.as_ref().map_or(std::ptr::null(), |w| w.as_ptr()),
+					lpCommand: command_w.as_ref().map_or(std::ptr::null(), |w| w.as_ptr()),
+					cActions: fa.actions.len() as _,
+					lpsaActions: fa.actions.as_ptr(), // non-null even if empty, so actions are removed
+				};
+				call(&raw as *const _ as _)
+			},
+			SvcConfig2::DelayedAutoStart(delayed) => {
+				let raw: BOOL = *delayed as _;
+				call(&raw as *const _ as _)
+			},
+			SvcConfig2::FailureActionsFlag(on_non_crash) => {
+				let raw: BOOL = *on_non_crash as _;
+				call(&raw as *const _ as _)
+			},
+			SvcConfig2::PreshutdownTimeout(ms) => call(ms as *const _ as _),
+		}
+	}
+
+	/// [`ControlService`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/nf-winsvc-controlservice)
+	/// function.
+	///
+	/// Returns the latest status of the service.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hsc = w::HSC::OpenSCManager(None, co::SC_MANAGER::CONNECT)?;
+	/// let hsvc = hsc.OpenService("MyService", co::SERVICE::STOP)?;
+	///
+	/// let status = hsvc.ControlService(co::SERVICE_CONTROL::STOP)?;
+	/// println!("{}", status.dwCurrentState);
+	/// # w::SysResult::Ok(())
+	/// ```
+	fn ControlService(&self,
+		control: co::SERVICE_CONTROL,
+	) -> SysResult<SERVICE_STATUS>
+	{
+		let mut status = SERVICE_STATUS::default();
+		bool_to_sysresult(
+			unsafe {
+				ffi::ControlService(self.ptr(), control.raw(), &mut status as *mut _ as _)
+			},
+		).map(|_| status)
+	}
+
+	/// [`ControlServiceEx`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/nf-winsvc-controlserviceexw)
+	/// function, with `SERVICE_CONTROL_STATUS_REASON_INFO` level, which
+	/// records the reason of the control – usually a stop – in the event log.
+	///
+	/// Returns the latest status of the service.
+	fn ControlServiceEx(&self,
+		control: co::SERVICE_CONTROL,
+		reason: co::SERVICE_STOP_REASON,
+		comment: Option<&str>,
+	) -> SysResult<SERVICE_STATUS_PROCESS>
+	{
+		let comment_w = WString::from_opt_str(comment);
+		let mut params = SERVICE_CONTROL_STATUS_REASON_PARAMS {
+			dwReason: reason.raw(),
+			pszComment: comment_w.as_ptr(),
+			ServiceStatus: SERVICE_STATUS_PROCESS::default(),
+		};
+
+		bool_to_sysresult(
+			unsafe {
+				ffi::ControlServiceExW(
+					self.ptr(),
+					control.raw(),
+					SERVICE_CONTROL_STATUS_REASON_INFO,
+					&mut params as *mut _ as _,
+				)
+			},
+		).map(|_| params.ServiceStatus)
+	}
+
 	/// [`DeleteService`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/nf-winsvc-deleteservice)
 	/// function.
 	fn DeleteService(&self) -> SysResult<()> {
 		bool_to_sysresult(unsafe { ffi::DeleteService(self.ptr()) })
 	}
+
+	/// [`EnumDependentServices`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/nf-winsvc-enumdependentservicesw)
+	/// function.
+	///
+	/// Returns the service name, the display name and the status of each
+	/// service which depends on this one, in reverse order of starting.
+	///
+	/// The handle must have
+	/// [`co::SERVICE::ENUMERATE_DEPENDENTS`](crate::co::SERVICE::ENUMERATE_DEPENDENTS)
+	/// access.
+	#[must_use]
+	fn EnumDependentServices(&self,
+		service_state: co::SERVICE_ENUM_STATE,
+	) -> SysResult<Vec<(String, String, SERVICE_STATUS)>>
+	{
+		let mut num_returned = u32::default();
+		let buf = query_svc_buf(|buf, buf_sz, needed| unsafe {
+			ffi::EnumDependentServicesW(
+				self.ptr(), service_state.raw(), buf, buf_sz, needed, &mut num_returned)
+		})?;
+
+		Ok(
+			(0..num_returned as usize)
+				.map(|idx| unsafe {
+					let entry = *(buf.as_ptr() as *const ENUM_SERVICE_STATUS).add(idx);
+					(
+						pcstr_to_opt_string(entry.lpServiceName).unwrap_or_default(),
+						pcstr_to_opt_string(entry.lpDisplayName).unwrap_or_default(),
+						entry.ServiceStatus,
+					)
+				})
+				.collect()
+		)
+	}
+
+	/// [`GetSecurityInfo`](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-getsecurityinfo)
+	/// function, with
+	/// [`co::SE_OBJECT_TYPE::SERVICE`](crate::co::SE_OBJECT_TYPE::SERVICE).
+	///
+	/// Returns an owned [`SecurityDescriptor`](crate::SecurityDescriptor) with
+	/// the parts given in `security_info`.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hsc = w::HSC::OpenSCManager(None, co::SC_MANAGER::CONNECT)?;
+	/// let hsvc = hsc.OpenService("MyService", co::SERVICE::READ_CONTROL)?;
+	///
+	/// let sd = hsvc.GetSecurityInfo(co::SECURITY_INFORMATION::DACL)?;
+	/// println!("{sd}");
+	/// # w::SysResult::Ok(())
+	/// ```
+	///
+	/// # Related functions
+	///
+	/// * [`GetNamedSecurityInfo`](crate::GetNamedSecurityInfo)
+	/// * [`HSERVICE::SetSecurityInfo`](crate::prelude::advapi_Hservice::SetSecurityInfo)
+	#[must_use]
+	fn GetSecurityInfo(&self,
+		security_info: co::SECURITY_INFORMATION,
+	) -> SysResult<SecurityDescriptor>
+	{
+		get_security_info(|psd| unsafe {
+			ffi::GetSecurityInfo(
+				self.ptr(),
+				co::SE_OBJECT_TYPE::SERVICE.raw(),
+				security_info.raw(),
+				std::ptr::null_mut(),
+				std::ptr::null_mut(),
+				std::ptr::null_mut(),
+				std::ptr::null_mut(),
+				psd,
+			)
+		})
+	}
+
+	/// [`QueryServiceConfig`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/nf-winsvc-queryserviceconfigw)
+	/// function.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hsc = w::HSC::OpenSCManager(None, co::SC_MANAGER::CONNECT)?;
+	/// let hsvc = hsc.OpenService("MyService", co::SERVICE::QUERY_CONFIG)?;
+	///
+	/// let config = hsvc.QueryServiceConfig()?;
+	/// println!("{} {}", config.display_name, config.binary_path_name);
+	/// # w::SysResult::Ok(())
+	/// ```
+	#[must_use]
+	fn QueryServiceConfig(&self) -> SysResult<SvcConfig> {
+		let buf = query_svc_buf(|buf, buf_sz, needed| unsafe {
+			ffi::QueryServiceConfigW(self.ptr(), buf, buf_sz, needed)
+		})?;
+		if buf.len() * std::mem::size_of::<u64>() < std::mem::size_of::<QUERY_SERVICE_CONFIG>() {
+			return Err(co::ERROR::INVALID_DATA); // no header was returned
+		}
+
+		unsafe {
+			let raw = *(buf.as_ptr() as *const QUERY_SERVICE_CONFIG);
+			Ok(SvcConfig {
+				service_type: co::SERVICE_TYPE::from_raw(raw.dwServiceType),
+				start_type: co::SERVICE_START::from_raw(raw.dwStartType),
+				error_control: co::SERVICE_ERROR::from_raw(raw.dwErrorControl),
+				binary_path_name: pcstr_to_opt_string(raw.lpBinaryPathName).unwrap_or_default(),
+				load_order_group: pcstr_to_opt_string(raw.lpLoadOrderGroup).unwrap_or_default(),
+				tag_id: raw.dwTagId,
+				dependencies: if raw.lpDependencies.is_null() {
+					Vec::new()
+				} else {
+					parse_multi_z_str(raw.lpDependencies, None)
+				},
+				service_start_name: pcstr_to_opt_string(raw.lpServiceStartName).unwrap_or_default(),
+				display_name: pcstr_to_opt_string(raw.lpDisplayName).unwrap_or_default(),
+			})
+		}
+	}
+
+	/// [`QueryServiceConfig2`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/nf-winsvc-queryserviceconfig2w)
+	/// function.
+	///
+	/// The returned enum variant will correspond to the passed `info_level`.
+	/// Levels not represented by [`SvcConfig2`](crate::SvcConfig2) return
+	/// [`co::ERROR::CALL_NOT_IMPLEMENTED`](crate::co::ERROR::CALL_NOT_IMPLEMENTED).
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hsc = w::HSC::OpenSCManager(None, co::SC_MANAGER::CONNECT)?;
+	/// let hsvc = hsc.OpenService("MyService", co::SERVICE::QUERY_CONFIG)?;
+	///
+	/// let nfo = hsvc.QueryServiceConfig2(co::SERVICE_CONFIG::DESCRIPTION)?;
+	/// let w::SvcConfig2::Description(desc) = nfo else { panic!("never") };
+	/// println!("{}", desc.unwrap_or_default());
+	/// # w::SysResult::Ok(())
+	/// ```
+	#[must_use]
+	fn QueryServiceConfig2(&self,
+		info_level: co::SERVICE_CONFIG,
+	) -> SysResult<SvcConfig2>
+	{
+		match info_level {
+			co::SERVICE_CONFIG::DESCRIPTION
+				| co::SERVICE_CONFIG::FAILURE_ACTIONS
+				| co::SERVICE_CONFIG::DELAYED_AUTO_START_INFO
+				| co::SERVICE_CONFIG::FAILURE_ACTIONS_FLAG
+				| co::SERVICE_CONFIG::PRESHUTDOWN_INFO => {},
+			_ => return Err(co::ERROR::CALL_NOT_IMPLEMENTED),
+		}
+
+		let buf = query_svc_buf(|buf, buf_sz, needed| unsafe {
+			ffi::QueryServiceConfig2W(self.ptr(), info_level.raw(), buf, buf_sz, needed)
+		})?;
+		if buf.is_empty() {
+			return Err(co::ERROR::INVALID_DATA); // no struct was returned
+		}
+		let buf_ptr = buf.as_ptr();
+
+		unsafe {
+			Ok(match info_level {
+				co::SERVICE_CONFIG::DESCRIPTION => SvcConfig2::Description(
+					pcstr_to_opt_string(*(buf_ptr as *const PCSTR)),
+				),
+				co::SERVICE_CONFIG::FAILURE_ACTIONS => {
+					let raw = *(buf_ptr as *const SERVICE_FAILURE_ACTIONS);
+					SvcConfig2::FailureActions(SvcFailureActions {
+						reset_period: match raw.dwResetPeriod {
+							INFINITE => None,
+							secs => Some(secs),
+						},
+						reboot_msg: pcstr_to_opt_string(raw.lpRebootMsg),
+						command: pcstr_to_opt_string(raw.lpCommand),
+						actions: if raw.lpsaActions.is_null() {
+							Vec::new()
+						} else {
+							std::slice::from_raw_parts(raw.lpsaActions, raw.cActions as _).to_vec()
+						},
+					})
+				},
+				co::SERVICE_CONFIG::DELAYED_AUTO_START_INFO => SvcConfig2::DelayedAutoStart(
+					*(buf_ptr as *const BOOL) != 0,
+				),
+				co::SERVICE_CONFIG::FAILURE_ACTIONS_FLAG => SvcConfig2::FailureActionsFlag(
+					*(buf_ptr as *const BOOL) != 0,
+				),
+				_ => SvcConfig2::PreshutdownTimeout(*(buf_ptr as *const u32)),
+			})
+		}
+	}
+
+	/// [`QueryServiceStatusEx`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/nf-winsvc-queryservicestatusex)
+	/// function, with `SC_STATUS_PROCESS_INFO` level.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hsc = w::HSC::OpenSCManager(None, co::SC_MANAGER::CONNECT)?;
+	/// let hsvc = hsc.OpenService("MyService", co::SERVICE::QUERY_STATUS)?;
+	///
+	/// let status = hsvc.QueryServiceStatusEx()?;
+	/// if status.dwCurrentState == co::SERVICE_STATE::RUNNING {
+	///     println!("Running with PID {}.", status.dwProcessId);
+	/// }
+	/// # w::SysResult::Ok(())
+	/// ```
+	#[must_use]
+	fn QueryServiceStatusEx(&self) -> SysResult<SERVICE_STATUS_PROCESS> {
+		let mut status = SERVICE_STATUS_PROCESS::default();
+		let mut needed = u32::default();
+		bool_to_sysresult(
+			unsafe {
+				ffi::QueryServiceStatusEx(
+					self.ptr(),
+					SC_STATUS_PROCESS_INFO,
+					&mut status as *mut _ as _,
+					std::mem::size_of::<SERVICE_STATUS_PROCESS>() as _,
+					&mut needed,
+				)
+			},
+		).map(|_| status)
+	}
+
+	/// [`SetSecurityInfo`](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-setsecurityinfo)
+	/// function, with
+	/// [`co::SE_OBJECT_TYPE::SERVICE`](crate::co::SE_OBJECT_TYPE::SERVICE).
+	///
+	/// See [`SetNamedSecurityInfo`](crate::SetNamedSecurityInfo) for how `sd`
+	/// is applied.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hsc = w::HSC::OpenSCManager(None, co::SC_MANAGER::CONNECT)?;
+	/// let hsvc = hsc.OpenService("MyService", co::SERVICE::READ_CONTROL | co::SERVICE::WRITE_DAC)?;
+	///
+	/// let mut sd = hsvc.GetSecurityInfo(co::SECURITY_INFORMATION::DACL)?;
+	/// let users = w::Sid::well_known(co::WELL_KNOWN_SID_TYPE::AuthenticatedUser, None)?;
+	/// sd.grant_access(&users, (co::SERVICE::START | co::SERVICE::STOP).raw(), co::ACE_FLAG::default());
+	/// hsvc.SetSecurityInfo(co::SECURITY_INFORMATION::DACL, &sd)?;
+	/// # w::SysResult::Ok(())
+	/// ```
+	///
+	/// # Related functions
+	///
+	/// * [`HSERVICE::GetSecurityInfo`](crate::prelude::advapi_Hservice::GetSecurityInfo)
+	/// * [`SetNamedSecurityInfo`](crate::SetNamedSecurityInfo)
+	fn SetSecurityInfo(&self,
+		security_info: co::SECURITY_INFORMATION,
+		sd: &SecurityDescriptor,
+	) -> SysResult<()>
+	{
+		set_security_info(security_info, sd, |si, owner, group, dacl, sacl| unsafe {
+			ffi::SetSecurityInfo(
+				self.ptr(),
+				co::SE_OBJECT_TYPE::SERVICE.raw(),
+				si,
+				owner,
+				group,
+				dacl,
+				sacl,
+			)
+		})
+	}
+
+	/// [`StartService`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/nf-winsvc-startservicew)
+	/// function.
+	///
+	/// The arguments are passed to the service's `ServiceMain` function.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hsc = w::HSC::OpenSCManager(None, co::SC_MANAGER::CONNECT)?;
+	/// let hsvc = hsc.OpenService("MyService", co::SERVICE::START)?;
+	///
+	/// hsvc.StartService(&[] as &[&str])?;
+	/// # w::SysResult::Ok(())
+	/// ```
+	fn StartService(&self, args: &[impl AsRef<str>]) -> SysResult<()> {
+		let (_wstrs, ptrs) = create_wstr_ptr_vecs(Some(args));
+		bool_to_sysresult(
+			unsafe {
+				ffi::StartServiceW(self.ptr(), ptrs.len() as _, vec_ptr(&ptrs))
+			},
+		)
+	}
+}
+
+/// Calls a service query function twice: first to retrieve the needed buffer
+/// size, then to actually fill the buffer, which is 8-byte aligned.
+fn query_svc_buf<F>(mut func: F) -> SysResult<Vec<u64>>
+	where F: FnMut(PVOID, u32, &mut u32) -> BOOL,
+{
+	let mut needed = u32::default();
+	match bool_to_sysresult(func(std::ptr::null_mut(), 0, &mut needed)) {
+		Ok(_) => return Ok(Vec::new()), // nothing to return
+		Err(co::ERROR::INSUFFICIENT_BUFFER | co::ERROR::MORE_DATA) => {},
+		Err(e) => return Err(e),
+	}
+
+	let mut buf = vec![0u64; (needed as usize).div_ceil(std::mem::size_of::<u64>())];
+	bool_to_sysresult(
+		func(
+			buf.as_mut_ptr() as _,
+			(buf.len() * std::mem::size_of::<u64>()) as _,
+			&mut needed,
+		),
+	).map(|_| buf)
 }
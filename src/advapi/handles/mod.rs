@@ -1,5 +1,6 @@
 mod haccesstoken;
 mod heventlog;
+mod hfile;
 mod hkey;
 mod hprocess;
 mod hsc;
@@ -21,6 +22,7 @@ pub mod decl {
 pub mod traits {
 	pub use super::haccesstoken::advapi_Haccesstoken;
 	pub use super::heventlog::advapi_Heventlog;
+	pub use super::hfile::advapi_Hfile;
 	pub use super::hkey::advapi_Hkey;
 	pub use super::hprocess::advapi_Hprocess;
 	pub use super::hsc::advapi_Hsc;
@@ -1,8 +1,105 @@
-use crate::advapi::ffi;
+use crate::advapi::{ffi, privs::*};
 use crate::co;
 use crate::decl::*;
+use crate::kernel::privs::*;
 use crate::prelude::*;
 
+pub(in crate::advapi) struct HeventlogReadIter<'a, H>
+	where H: advapi_Heventlog,
+{
+	heventlog: &'a H,
+	flags: co::EVENTLOG_READ,
+	record_offset: u32,
+	buffer: Vec<u8>,
+	filled: usize,
+	current: usize,
+	finished: bool,
+}
+
+impl<'a, H> Iterator for HeventlogReadIter<'a, H>
+	where H: advapi_Heventlog,
+{
+	type Item = SysResult<EventLogRecord>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if self.current < self.filled {
+				return Some(
+					match EventLogRecord::parse(&self.buffer[self.current..self.filled]) {
+						Ok((rec, len)) => {
+							self.current += len;
+							Ok(rec)
+						},
+						Err(e) => {
+							self.finished = true; // no further iterations will be made
+							self.filled = 0;
+							Err(e)
+						},
+					},
+				);
+			} else if self.finished {
+				return None;
+			}
+
+			let mut bytes_read = u32::default();
+			let mut min_bytes_needed = u32::default();
+			let ret = unsafe {
+				ffi::ReadEventLogW(
+					self.heventlog.ptr(),
+					self.flags.raw(),
+					self.record_offset,
+					self.buffer.as_mut_ptr() as _,
+					self.buffer.len() as _,
+					&mut bytes_read,
+					&mut min_bytes_needed,
+				)
+			};
+
+			self.filled = 0;
+			self.current = 0;
+			match bool_to_sysresult(ret) {
+				Ok(_) => {
+					self.filled = bytes_read as _;
+					if self.flags.has(co::EVENTLOG_READ::SEEK_READ) { // seek only once
+						self.flags = (self.flags & !co::EVENTLOG_READ::SEEK_READ)
+							| co::EVENTLOG_READ::SEQUENTIAL_READ;
+					}
+				},
+				Err(co::ERROR::INSUFFICIENT_BUFFER) => {
+					self.buffer.resize(min_bytes_needed as _, 0);
+				},
+				Err(co::ERROR::HANDLE_EOF) => return None,
+				Err(e) => {
+					self.finished = true; // no further iterations will be made
+					return Some(Err(e));
+				},
+			}
+		}
+	}
+}
+
+impl<'a, H> HeventlogReadIter<'a, H>
+	where H: advapi_Heventlog,
+{
+	#[must_use]
+	pub(in crate::advapi) fn new(
+		heventlog: &'a H,
+		flags: co::EVENTLOG_READ,
+		record_offset: u32,
+	) -> Self
+	{
+		Self {
+			heventlog,
+			flags,
+			record_offset,
+			buffer: vec![0; 0x1_0000], // 64 KB
+			filled: 0,
+			current: 0,
+			finished: false,
+		}
+	}
+}
+
 pub(in crate::advapi) struct HkeyKeyIter<'a, H>
 	where H: advapi_Hkey,
 {
@@ -135,3 +232,100 @@ impl<'a, H> HkeyValueIter<'a, H>
 		})
 	}
 }
+
+pub(in crate::advapi) struct HscServiceIter<'a, H>
+	where H: advapi_Hsc,
+{
+	hsc: &'a H,
+	service_type: co::SERVICE_TYPE,
+	service_state: co::SERVICE_ENUM_STATE,
+	group_name: Option<WString>,
+	buffer: Vec<u64>, // 8-byte aligned, entries contain pointers
+	count: u32,
+	current: u32,
+	resume_handle: u32,
+	finished: bool,
+}
+
+impl<'a, H> Iterator for HscServiceIter<'a, H>
+	where H: advapi_Hsc,
+{
+	type Item = SysResult<(String, String, SERVICE_STATUS_PROCESS)>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if self.current < self.count {
+				let entry = unsafe {
+					*(self.buffer.as_ptr() as *const ENUM_SERVICE_STATUS_PROCESS)
+						.add(self.current as _)
+				};
+				self.current += 1;
+				return Some(Ok(unsafe {
+					(
+						pcstr_to_opt_string(entry.lpServiceName).unwrap_or_default(),
+						pcstr_to_opt_string(entry.lpDisplayName).unwrap_or_default(),
+						entry.ServiceStatusProcess,
+					)
+				}));
+			} else if self.finished {
+				return None;
+			}
+
+			let mut bytes_needed = u32::default();
+			let mut num_returned = u32::default();
+			let ret = unsafe {
+				ffi::EnumServicesStatusExW(
+					self.hsc.ptr(),
+					SC_ENUM_PROCESS_INFO,
+					self.service_type.raw(),
+					self.service_state.raw(),
+					self.buffer.as_mut_ptr() as _,
+					(self.buffer.len() * std::mem::size_of::<u64>()) as _,
+					&mut bytes_needed,
+					&mut num_returned,
+					&mut self.resume_handle,
+					self.group_name.as_ref().map_or(std::ptr::null(), |g| g.as_ptr()),
+				)
+			};
+
+			match bool_to_sysresult(ret) {
+				Ok(_) => self.finished = true, // this is the last batch
+				Err(co::ERROR::MORE_DATA) => if num_returned == 0 { // buffer too small for a single entry
+					self.buffer.resize((bytes_needed as usize).div_ceil(std::mem::size_of::<u64>()), 0);
+				},
+				Err(e) => {
+					self.finished = true; // no further iterations will be made
+					self.count = 0;
+					return Some(Err(e));
+				},
+			}
+			self.count = num_returned;
+			self.current = 0;
+		}
+	}
+}
+
+impl<'a, H> HscServiceIter<'a, H>
+	where H: advapi_Hsc,
+{
+	#[must_use]
+	pub(in crate::advapi) fn new(
+		hsc: &'a H,
+		service_type: co::SERVICE_TYPE,
+		service_state: co::SERVICE_ENUM_STATE,
+		group_name: Option<&str>,
+	) -> Self
+	{
+		Self {
+			hsc,
+			service_type,
+			service_state,
+			group_name: group_name.map(WString::from_str),
+			buffer: vec![0; 0x1_0000 / std::mem::size_of::<u64>()], // 64 KB
+			count: 0,
+			current: 0,
+			resume_handle: 0,
+			finished: false,
+		}
+	}
+}
@@ -8,6 +8,7 @@ mod iterators;
 mod privs;
 mod proc;
 mod structs;
+mod utilities;
 
 pub mod co;
 pub mod guard;
@@ -17,8 +18,10 @@ pub mod decl {
 	pub use super::funcs::*;
 	pub use super::handles::decl::*;
 	pub use super::structs::*;
+	pub use super::utilities::*;
 }
 
 pub mod traits {
 	pub use super::handles::traits::*;
+	pub use super::utilities::traits::*;
 }
@@ -1,5 +1,186 @@
+#![allow(non_snake_case)]
+
+use crate::advapi::ffi;
+use crate::co;
+use crate::decl::*;
+use crate::guard::*;
+use crate::kernel::ffi_types::*;
+use crate::kernel::privs::*;
+use crate::prelude::*;
+
 const_values_num_privs! {
+	SC_ENUM_PROCESS_INFO u32 = 0
+	SC_STATUS_PROCESS_INFO u32 = 0
 	SECURITY_DESCRIPTOR_REVISION u32 = 1
+	SERVICE_CONTROL_STATUS_REASON_INFO u32 = 1
+	SERVICE_NO_CHANGE u32 = 0xffff_ffff
 	SID_HASH_SIZE usize = 32
 	TOKEN_SOURCE_LENGTH usize = 8
 }
+
+/// [`ENUM_SERVICE_STATUSW`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/ns-winsvc-enum_service_statusw)
+/// struct.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(in crate::advapi) struct ENUM_SERVICE_STATUS {
+	pub lpServiceName: PCSTR,
+	pub lpDisplayName: PCSTR,
+	pub ServiceStatus: SERVICE_STATUS,
+}
+
+/// [`ENUM_SERVICE_STATUS_PROCESSW`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/ns-winsvc-enum_service_status_processw)
+/// struct.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(in crate::advapi) struct ENUM_SERVICE_STATUS_PROCESS {
+	pub lpServiceName: PCSTR,
+	pub lpDisplayName: PCSTR,
+	pub ServiceStatusProcess: SERVICE_STATUS_PROCESS,
+}
+
+/// [`QUERY_SERVICE_CONFIGW`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/ns-winsvc-query_service_configw)
+/// struct.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(in crate::advapi) struct QUERY_SERVICE_CONFIG {
+	pub dwServiceType: u32,
+	pub dwStartType: u32,
+	pub dwErrorControl: u32,
+	pub lpBinaryPathName: PCSTR,
+	pub lpLoadOrderGroup: PCSTR,
+	pub dwTagId: u32,
+	pub lpDependencies: PCSTR,
+	pub lpServiceStartName: PCSTR,
+	pub lpDisplayName: PCSTR,
+}
+
+/// [`SERVICE_CONTROL_STATUS_REASON_PARAMSW`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/ns-winsvc-service_control_status_reason_paramsw)
+/// struct.
+#[repr(C)]
+pub(in crate::advapi) struct SERVICE_CONTROL_STATUS_REASON_PARAMS {
+	pub dwReason: u32,
+	pub pszComment: PCSTR,
+	pub ServiceStatus: SERVICE_STATUS_PROCESS,
+}
+
+/// [`SERVICE_FAILURE_ACTIONSW`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/ns-winsvc-service_failure_actionsw)
+/// struct.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(in crate::advapi) struct SERVICE_FAILURE_ACTIONS {
+	pub dwResetPeriod: u32,
+	pub lpRebootMsg: PCSTR,
+	pub lpCommand: PCSTR,
+	pub cActions: u32,
+	pub lpsaActions: *const SC_ACTION,
+}
+
+/// [`SERVICE_TABLE_ENTRY`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/ns-winsvc-service_table_entryw)
+/// struct.
+#[repr(C)]
+pub(in crate::advapi) struct SERVICE_TABLE_ENTRY {
+	pub lpServiceName: PCSTR,
+	pub lpServiceProc: PFUNC,
+}
+
+/// Converts a nullable pointer to a null-terminated string, returning `None`
+/// if the pointer is null.
+#[must_use]
+pub(in crate::advapi) unsafe fn pcstr_to_opt_string(src: PCSTR) -> Option<String> {
+	if src.is_null() {
+		None
+	} else {
+		Some(unsafe { WString::from_wchars_nullt(src) }.to_string())
+	}
+}
+
+/// Converts a string to a [`WString`](crate::WString) whose pointer is never
+/// null, even if the string is empty. This is needed by functions where a null
+/// pointer leaves a value unchanged, while an empty string removes it.
+#[must_use]
+pub(in crate::advapi) fn str_to_wstring_nonnull(s: &str) -> WString {
+	if s.is_empty() {
+		WString::new_alloc_buf(1) // just the terminating null
+	} else {
+		WString::from_str(s)
+	}
+}
+
+/// Calls one of the `Get*SecurityInfo` functions, which receives a pointer to
+/// the self-relative security descriptor allocated by the system, then parses
+/// and frees it.
+pub(in crate::advapi) fn get_security_info<F>(get: F) -> SysResult<SecurityDescriptor>
+	where F: FnOnce(*mut PVOID) -> u32,
+{
+	let mut psd = std::ptr::null_mut() as PVOID;
+	error_to_sysresult(get(&mut psd) as _)?;
+	if psd.is_null() {
+		return Err(co::ERROR::INVALID_SECURITY_DESCR);
+	}
+	let _psd_guard = unsafe { LocalFreeGuard::new(HLOCAL::from_ptr(psd)) };
+
+	let sd_len = unsafe { ffi::GetSecurityDescriptorLength(psd) } as usize;
+	SecurityDescriptor::from_bytes(
+		unsafe { std::slice::from_raw_parts(psd as *const u8, sd_len) },
+	)
+}
+
+/// Serializes the security descriptor in self-relative form into a u32 buffer,
+/// because the system requires the descriptor, its ACLs and its SIDs to be
+/// DWORD-aligned.
+#[must_use]
+pub(in crate::advapi) fn aligned_sd_buf(sd: &SecurityDescriptor) -> Vec<u32> {
+	let sd_bytes = sd.to_bytes();
+	let mut buf = vec![0u32; sd_bytes.len().div_ceil(4)];
+	unsafe {
+		std::ptr::copy_nonoverlapping(
+			sd_bytes.as_ptr(), buf.as_mut_ptr() as *mut u8, sd_bytes.len());
+	}
+	buf
+}
+
+/// Calls one of the `Set*SecurityInfo` functions, which receives the security
+/// information flags, followed by pointers to owner, group, DACL and SACL.
+///
+/// If the DACL or the SACL is being set, and the descriptor has
+/// [`co::SE::DACL_PROTECTED`](crate::co::SE::DACL_PROTECTED) or
+/// [`co::SE::SACL_PROTECTED`](crate::co::SE::SACL_PROTECTED), the
+/// corresponding `PROTECTED` flag is added, otherwise the protection would be
+/// silently lost.
+///
+/// If the DACL is being set, it must be present, because a null DACL grants
+/// full access to everyone.
+pub(in crate::advapi) fn set_security_info<F>(
+	security_info: co::SECURITY_INFORMATION,
+	sd: &SecurityDescriptor,
+	set: F,
+) -> SysResult<()>
+	where F: FnOnce(u32, PVOID, PVOID, PVOID, PVOID) -> u32,
+{
+	if security_info.has(co::SECURITY_INFORMATION::DACL) && sd.dacl.is_none() {
+		return Err(co::ERROR::INVALID_ACL);
+	}
+
+	let mut si = security_info;
+	if si.has(co::SECURITY_INFORMATION::DACL) && sd.control.has(co::SE::DACL_PROTECTED) {
+		si |= co::SECURITY_INFORMATION::PROTECTED_DACL;
+	}
+	if si.has(co::SECURITY_INFORMATION::SACL) && sd.control.has(co::SE::SACL_PROTECTED) {
+		si |= co::SECURITY_INFORMATION::PROTECTED_SACL;
+	}
+
+	let mut buf = aligned_sd_buf(sd);
+	let offsets = [buf[1], buf[2], buf[3], buf[4]].map(u32::from_le); // owner, group, SACL, DACL
+	let pbuf = buf.as_mut_ptr() as *mut u8;
+
+	let ptr_at = |idx: usize| -> PVOID {
+		match offsets[idx] {
+			0 => std::ptr::null_mut(),
+			off => unsafe { pbuf.add(off as _) as _ },
+		}
+	};
+
+	error_to_sysresult(
+		set(si.raw(), ptr_at(0), ptr_at(1), ptr_at(3), ptr_at(2)) as _,
+	)
+}
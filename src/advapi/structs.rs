@@ -98,6 +98,57 @@ pub struct DEV_BROADCAST_VOLUME {
 	pub dbcv_flags: co::DBTF,
 }
 
+/// [`GENERIC_MAPPING`](https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-generic_mapping)
+/// struct.
+///
+/// Maps the [`co::GENERIC`](crate::co::GENERIC) rights to the specific rights
+/// of an object type. The mappings of the most common types are available as
+/// associated constants.
+#[repr(C)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GENERIC_MAPPING {
+	pub GenericRead: u32,
+	pub GenericWrite: u32,
+	pub GenericExecute: u32,
+	pub GenericAll: u32,
+}
+
+impl GENERIC_MAPPING {
+	/// Generic mapping of files and directories.
+	pub const FILE: Self = Self::new(0x0012_0089, 0x0012_0116, 0x0012_00a0, 0x001f_01ff);
+	/// Generic mapping of registry keys.
+	pub const KEY: Self = Self::new(0x0002_0019, 0x0002_0006, 0x0002_0019, 0x000f_003f);
+	/// Generic mapping of services.
+	pub const SERVICE: Self = Self::new(0x0002_008d, 0x0002_0002, 0x0002_0170, 0x000f_01ff);
+
+	/// Constructs a new `GENERIC_MAPPING`.
+	#[must_use]
+	pub const fn new(read: u32, write: u32, execute: u32, all: u32) -> Self {
+		Self {
+			GenericRead: read,
+			GenericWrite: write,
+			GenericExecute: execute,
+			GenericAll: all,
+		}
+	}
+
+	/// Replaces the [`co::GENERIC`](crate::co::GENERIC) bits of the access mask
+	/// with the specific rights they map to, like
+	/// [`MapGenericMask`](https://learn.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-mapgenericmask).
+	#[must_use]
+	pub const fn map(&self, access_mask: u32) -> u32 {
+		let mut mapped = access_mask & !(co::GENERIC::READ.raw()
+			| co::GENERIC::WRITE.raw()
+			| co::GENERIC::EXECUTE.raw()
+			| co::GENERIC::ALL.raw());
+		if access_mask & co::GENERIC::READ.raw() != 0 { mapped |= self.GenericRead; }
+		if access_mask & co::GENERIC::WRITE.raw() != 0 { mapped |= self.GenericWrite; }
+		if access_mask & co::GENERIC::EXECUTE.raw() != 0 { mapped |= self.GenericExecute; }
+		if access_mask & co::GENERIC::ALL.raw() != 0 { mapped |= self.GenericAll; }
+		mapped
+	}
+}
+
 /// [`LUID_AND_ATTRIBUTES`](https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-luid_and_attributes)
 /// struct.
 #[repr(C)]
@@ -115,6 +166,23 @@ impl LUID_AND_ATTRIBUTES {
 	}
 }
 
+/// [`SC_ACTION`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/ns-winsvc-sc_action)
+/// struct.
+#[repr(C)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SC_ACTION {
+	pub Type: co::SC_ACTION,
+	pub Delay: u32,
+}
+
+impl SC_ACTION {
+	/// Constructs a new `SC_ACTION`, with the delay in milliseconds.
+	#[must_use]
+	pub const fn new(action: co::SC_ACTION, delay_ms: u32) -> Self {
+		Self { Type: action, Delay: delay_ms }
+	}
+}
+
 /// [`SERVICE_STATUS`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/ns-winsvc-service_status)
 /// struct.
 #[repr(C)]
@@ -129,6 +197,22 @@ pub struct SERVICE_STATUS {
 	pub dwWaitPoint: u32,
 }
 
+/// [`SERVICE_STATUS_PROCESS`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/ns-winsvc-service_status_process)
+/// struct.
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+pub struct SERVICE_STATUS_PROCESS {
+	pub dwServiceType: co::SERVICE_TYPE,
+	pub dwCurrentState: co::SERVICE_STATE,
+	pub dwControlsAccepted: co::SERVICE_ACCEPT,
+	pub dwWin32ExitCode: u32,
+	pub dwServiceSpecificExitCode: u32,
+	pub dwCheckPoint: u32,
+	pub dwWaitHint: u32,
+	pub dwProcessId: u32,
+	pub dwServiceFlags: u32,
+}
+
 /// [`SERVICE_TIMECHANGE_INFO`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/ns-winsvc-service_timechange_info)
 /// struct.
 #[repr(C)]
@@ -0,0 +1,289 @@
+use crate::co;
+use crate::decl::*;
+
+/// The `OWNER RIGHTS` SID, `S-1-3-4`.
+const OWNER_RIGHTS: [u32; 1] = [4];
+
+/// Evaluates, in pure Rust, the access which would be granted to a security
+/// context by the DACL of a [`SecurityDescriptor`](crate::SecurityDescriptor),
+/// following the same rules of
+/// [`HACCESSTOKEN::AccessCheck`](crate::prelude::advapi_Haccesstoken::AccessCheck):
+///
+/// * the ACEs are evaluated in the order they appear, so in a canonical DACL
+///   the denied ACEs take precedence over the allowed ones;
+/// * inherit-only ACEs are ignored;
+/// * generic rights are mapped to specific rights, both in the requested
+///   access and in the ACEs;
+/// * the owner is implicitly granted `READ_CONTROL` and `WRITE_DAC`, unless
+///   the DACL has ACEs for the `OWNER RIGHTS` SID, which then apply to the
+///   owner instead;
+/// * `ACCESS_SYSTEM_SECURITY` requires
+///   [`co::SE_PRIV::SECURITY_NAME`](crate::co::SE_PRIV::SECURITY_NAME), and
+///   [`co::SE_PRIV::TAKE_OWNERSHIP_NAME`](crate::co::SE_PRIV::TAKE_OWNERSHIP_NAME)
+///   grants `WRITE_OWNER`;
+/// * a null or absent DACL grants all access.
+///
+/// Mandatory integrity labels, restricted SIDs, object ACEs and conditional
+/// ACEs are not evaluated.
+///
+/// # Examples
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*, co};
+///
+/// let sd = w::SecurityDescriptor::from_sddl(
+///     "O:BAD:(D;;SD;;;S-1-5-21-1-2-3-1001)(A;OICI;GA;;;BA)(A;;GR;;;BU)",
+/// )?;
+///
+/// let evaluator = w::AccessEvaluator {
+///     sids: vec![
+///         "S-1-5-21-1-2-3-1001".parse()?,
+///         w::Sid::well_known(co::WELL_KNOWN_SID_TYPE::BuiltinUsers, None)?,
+///     ],
+///     ..Default::default()
+/// };
+///
+/// let mapping = w::GENERIC_MAPPING::FILE;
+/// assert!(evaluator.access_check(&sd, co::GENERIC::READ.raw(), &mapping).is_ok());
+/// assert_eq!(
+///     evaluator.access_check(&sd, co::ACCESS_RIGHTS::DELETE.raw(), &mapping),
+///     Err(co::ERROR::ACCESS_DENIED),
+/// );
+/// # w::SysResult::Ok(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccessEvaluator {
+	/// The SIDs of the user and of its enabled groups, which are matched
+	/// against both allowed and denied ACEs.
+	pub sids: Vec<Sid>,
+	/// Deny-only SIDs, which are matched only against denied ACEs.
+	pub deny_only_sids: Vec<Sid>,
+	/// The enabled privileges.
+	pub privileges: Vec<co::SE_PRIV>,
+}
+
+impl AccessEvaluator {
+	/// Creates a new evaluator with the given user and group SIDs, and no
+	/// privileges.
+	#[must_use]
+	pub fn new(sids: &[Sid]) -> Self {
+		Self {
+			sids: sids.to_vec(),
+			..Default::default()
+		}
+	}
+
+	/// Checks whether all the desired access rights would be granted by the
+	/// security descriptor, returning the granted access rights, with the
+	/// generic rights mapped.
+	///
+	/// If `desired_access` has
+	/// [`co::ACCESS_RIGHTS::MAXIMUM_ALLOWED`](crate::co::ACCESS_RIGHTS::MAXIMUM_ALLOWED),
+	/// all the rights which would be granted are returned.
+	///
+	/// Returns [`co::ERROR::ACCESS_DENIED`](crate::co::ERROR::ACCESS_DENIED) if
+	/// the access is denied, or
+	/// [`co::ERROR::PRIVILEGE_NOT_HELD`](crate::co::ERROR::PRIVILEGE_NOT_HELD)
+	/// if `ACCESS_SYSTEM_SECURITY` is requested without the needed privilege.
+	pub fn access_check(&self,
+		sd: &SecurityDescriptor,
+		desired_access: u32,
+		generic_mapping: &GENERIC_MAPPING,
+	) -> SysResult<u32>
+	{
+		let maximum_allowed = desired_access & co::ACCESS_RIGHTS::MAXIMUM_ALLOWED.raw() != 0;
+		let desired = generic_mapping.map(desired_access)
+			& !co::ACCESS_RIGHTS::MAXIMUM_ALLOWED.raw();
+
+		let mut granted = 0;
+		if desired & co::ACCESS_RIGHTS::ACCESS_SYSTEM_SECURITY.raw() != 0 {
+			if !self.has_privilege(co::SE_PRIV::SECURITY_NAME) {
+				return Err(co::ERROR::PRIVILEGE_NOT_HELD);
+			}
+			granted |= co::ACCESS_RIGHTS::ACCESS_SYSTEM_SECURITY.raw();
+		}
+		if (maximum_allowed || desired & co::ACCESS_RIGHTS::WRITE_OWNER.raw() != 0)
+			&& self.has_privilege(co::SE_PRIV::TAKE_OWNERSHIP_NAME)
+		{
+			granted |= co::ACCESS_RIGHTS::WRITE_OWNER.raw();
+		}
+
+		granted |= match &sd.dacl {
+			None => generic_mapping.GenericAll | desired, // null or absent DACL
+			Some(dacl) => self.eval_dacl(sd.owner.as_ref(), dacl, generic_mapping),
+		};
+
+		if desired & !granted != 0 || (maximum_allowed && granted == 0) {
+			Err(co::ERROR::ACCESS_DENIED)
+		} else if maximum_allowed {
+			Ok(granted)
+		} else {
+			Ok(desired)
+		}
+	}
+
+	/// Returns all the access rights which would be granted by the security
+	/// descriptor, like an [`access_check`](crate::AccessEvaluator::access_check)
+	/// with [`co::ACCESS_RIGHTS::MAXIMUM_ALLOWED`](crate::co::ACCESS_RIGHTS::MAXIMUM_ALLOWED).
+	/// Returns zero if no access is granted.
+	#[must_use]
+	pub fn maximum_allowed(&self,
+		sd: &SecurityDescriptor,
+		generic_mapping: &GENERIC_MAPPING,
+	) -> u32
+	{
+		self.access_check(sd, co::ACCESS_RIGHTS::MAXIMUM_ALLOWED.raw(), generic_mapping)
+			.unwrap_or_default()
+	}
+
+	fn has_privilege(&self, privilege: co::SE_PRIV) -> bool {
+		self.privileges.contains(&privilege)
+	}
+
+	fn eval_dacl(&self,
+		owner: Option<&Sid>,
+		dacl: &[Ace],
+		generic_mapping: &GENERIC_MAPPING,
+	) -> u32
+	{
+		let is_owner = owner.is_some_and(|owner| self.sids.contains(owner));
+		let is_owner_rights = |sid: &Sid| *sid == Sid::new([0, 0, 0, 0, 0, 3], &OWNER_RIGHTS);
+		let active_aces = || dacl.iter().filter(|ace| !ace.is_inherit_only());
+
+		let mut granted = 0;
+		let mut denied = 0;
+
+		if is_owner && !active_aces().any(|ace| is_owner_rights(&ace.sid)) {
+			granted |= co::ACCESS_RIGHTS::READ_CONTROL.raw()
+				| co::ACCESS_RIGHTS::WRITE_DAC.raw();
+		}
+
+		for ace in active_aces() {
+			let applies = |deny: bool| self.sids.contains(&ace.sid)
+				|| (deny && self.deny_only_sids.contains(&ace.sid))
+				|| (is_owner && is_owner_rights(&ace.sid));
+			let mask = generic_mapping.map(ace.access_mask);
+
+			match ace.ace_type {
+				co::ACE_TYPE::ACCESS_ALLOWED if applies(false) => granted |= mask & !denied,
+				co::ACE_TYPE::ACCESS_DENIED if applies(true) => denied |= mask & !granted,
+				_ => {},
+			}
+		}
+
+		granted
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const USER: &str = "S-1-5-21-1-2-3-1001";
+	const MAPPING: GENERIC_MAPPING = GENERIC_MAPPING::FILE;
+
+	fn user() -> AccessEvaluator {
+		AccessEvaluator::new(&[USER.parse().unwrap()])
+	}
+
+	fn parse_sd(sddl: &str) -> SecurityDescriptor {
+		SecurityDescriptor::from_sddl(&sddl.replace("USER", USER)).unwrap()
+	}
+
+	#[test]
+	fn deny_before_allow() {
+		let sd = parse_sd("O:BAD:(D;;SD;;;USER)(A;;GA;;;USER)");
+		assert_eq!(user().access_check(&sd, co::GENERIC::READ.raw(), &MAPPING),
+			Ok(MAPPING.GenericRead));
+		assert_eq!(user().access_check(&sd, co::ACCESS_RIGHTS::DELETE.raw(), &MAPPING),
+			Err(co::ERROR::ACCESS_DENIED));
+
+		// Non-canonical order: the earlier allowed ACE wins.
+		let sd = parse_sd("O:BAD:(A;;GA;;;USER)(D;;SD;;;USER)");
+		assert!(user().access_check(&sd, co::ACCESS_RIGHTS::DELETE.raw(), &MAPPING).is_ok());
+	}
+
+	#[test]
+	fn deny_only_sids() {
+		let mut evaluator = user();
+		evaluator.deny_only_sids.push("S-1-5-32-545".parse().unwrap()); // BU
+		let sd = parse_sd("O:BAD:(D;;SD;;;BU)(A;;GA;;;BU)(A;;GR;;;USER)");
+		assert_eq!(evaluator.maximum_allowed(&sd, &MAPPING), MAPPING.GenericRead);
+	}
+
+	#[test]
+	fn owner_rights() {
+		let rc_wd = co::ACCESS_RIGHTS::READ_CONTROL.raw() | co::ACCESS_RIGHTS::WRITE_DAC.raw();
+
+		let sd_implicit = parse_sd("O:USERD:(A;;GR;;;BA)");
+		assert_eq!(user().maximum_allowed(&sd_implicit, &MAPPING), rc_wd);
+
+		let sd_owner_rights = parse_sd("O:USERD:(A;;RC;;;OW)");
+		assert_eq!(user().maximum_allowed(&sd_owner_rights, &MAPPING),
+			co::ACCESS_RIGHTS::READ_CONTROL.raw());
+		assert_eq!(
+			user().access_check(&sd_owner_rights, co::ACCESS_RIGHTS::WRITE_DAC.raw(), &MAPPING),
+			Err(co::ERROR::ACCESS_DENIED),
+		);
+
+		// OWNER RIGHTS ACEs don't apply to who is not the owner.
+		let sd_not_owner = parse_sd("O:BAD:(A;;RC;;;OW)");
+		assert_eq!(user().maximum_allowed(&sd_not_owner, &MAPPING), 0);
+	}
+
+	#[test]
+	fn inherit_only_aces_skipped() {
+		let sd_io = parse_sd("O:BAD:(A;OICIIO;GA;;;USER)");
+		assert_eq!(user().maximum_allowed(&sd_io, &MAPPING), 0);
+
+		let sd_io_owner_rights = parse_sd("O:USERD:(A;OICIIO;RC;;;OW)");
+		assert_eq!(
+			user().access_check(&sd_io_owner_rights, co::ACCESS_RIGHTS::WRITE_DAC.raw(), &MAPPING),
+			Ok(co::ACCESS_RIGHTS::WRITE_DAC.raw()),
+		);
+	}
+
+	#[test]
+	fn null_and_empty_dacl() {
+		let sd_null = parse_sd("O:BA");
+		assert!(sd_null.dacl.is_none());
+		assert_eq!(user().maximum_allowed(&sd_null, &MAPPING), MAPPING.GenericAll);
+		assert!(user().access_check(&sd_null, co::GENERIC::ALL.raw(), &MAPPING).is_ok());
+
+		let sd_empty = parse_sd("O:BAD:");
+		assert_eq!(sd_empty.dacl, Some(Vec::new()));
+		assert_eq!(user().maximum_allowed(&sd_empty, &MAPPING), 0);
+		assert_eq!(user().access_check(&sd_empty, co::GENERIC::READ.raw(), &MAPPING),
+			Err(co::ERROR::ACCESS_DENIED));
+	}
+
+	#[test]
+	fn maximum_allowed() {
+		let sd = parse_sd("O:BAD:(D;;SD;;;USER)(A;;GR;;;USER)(A;;SD;;;USER)");
+		assert_eq!(
+			user().access_check(&sd, co::ACCESS_RIGHTS::MAXIMUM_ALLOWED.raw(), &MAPPING),
+			Ok(MAPPING.GenericRead),
+		);
+		assert_eq!(
+			user().access_check(&sd,
+				co::ACCESS_RIGHTS::MAXIMUM_ALLOWED.raw() | co::ACCESS_RIGHTS::DELETE.raw(), &MAPPING),
+			Err(co::ERROR::ACCESS_DENIED),
+		);
+		assert_eq!(AccessEvaluator::default().access_check(&sd,
+				co::ACCESS_RIGHTS::MAXIMUM_ALLOWED.raw(), &MAPPING),
+			Err(co::ERROR::ACCESS_DENIED));
+	}
+
+	#[test]
+	fn privileges() {
+		let sd = parse_sd("O:BAD:(A;;GR;;;USER)");
+		let sacl = co::ACCESS_RIGHTS::ACCESS_SYSTEM_SECURITY.raw();
+		assert_eq!(user().access_check(&sd, sacl, &MAPPING), Err(co::ERROR::PRIVILEGE_NOT_HELD));
+
+		let mut evaluator = user();
+		evaluator.privileges = vec![co::SE_PRIV::SECURITY_NAME, co::SE_PRIV::TAKE_OWNERSHIP_NAME];
+		assert_eq!(evaluator.access_check(&sd, sacl, &MAPPING), Ok(sacl));
+		assert_eq!(evaluator.maximum_allowed(&sd, &MAPPING),
+			MAPPING.GenericRead | co::ACCESS_RIGHTS::WRITE_OWNER.raw());
+	}
+}
@@ -0,0 +1,322 @@
+use crate::co;
+use crate::decl::*;
+
+/// Signature of an `EVENTLOGRECORD`, the `LfLe` characters.
+const EVENTLOG_SIGNATURE: u32 = 0x654c_664c;
+
+/// Size of the fixed part of an `EVENTLOGRECORD`.
+const HEADER_LEN: usize = 56;
+
+/// An owned, parsed
+/// [`EVENTLOGRECORD`](https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-eventlogrecord)
+/// struct, with its variable-length data, returned by
+/// [`HEVENTLOG::ReadEventLog`](crate::prelude::advapi_Heventlog::ReadEventLog).
+///
+/// The parsing is made in pure Rust, so records can also be parsed from
+/// captured byte dumps with [`parse`](crate::EventLogRecord::parse) and
+/// [`parse_all`](crate::EventLogRecord::parse_all), and serialized back with
+/// [`to_bytes`](crate::EventLogRecord::to_bytes).
+///
+/// A record can also be written to an event log with
+/// [`HEVENTLOG::report_record`](crate::prelude::advapi_Heventlog::report_record).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventLogRecord {
+	/// The record number, which can be used with
+	/// [`co::EVENTLOG_READ::SEEK_READ`](crate::co::EVENTLOG_READ::SEEK_READ).
+	pub record_number: u32,
+	/// When the event was submitted, in seconds since 1970-01-01 UTC.
+	pub time_generated: u32,
+	/// When the event was written to the log, in seconds since 1970-01-01 UTC.
+	pub time_written: u32,
+	/// The full event identifier, including the severity and facility bits.
+	/// See [`event_code`](crate::EventLogRecord::event_code).
+	pub event_id: u32,
+	/// The event type.
+	pub event_type: co::EVENTLOG,
+	/// The event category, specific to the source.
+	pub event_category: u16,
+	/// The name of the source which logged the event.
+	pub source_name: String,
+	/// The name of the computer where the event was logged.
+	pub computer_name: String,
+	/// The raw bytes of the [`SID`](crate::SID) of the user, if any.
+	pub user_sid: Option<Vec<u8>>,
+	/// The insertion strings, to be merged into the message of the event.
+	pub strings: Vec<String>,
+	/// The event-specific binary data.
+	pub data: Vec<u8>,
+}
+
+impl EventLogRecord {
+	/// Parses a single record at the beginning of the buffer, returning it
+	/// along with its length in bytes, so the next record can be parsed.
+	///
+	/// Returns [`co::ERROR::INVALID_DATA`](crate::co::ERROR::INVALID_DATA) if
+	/// the record is truncated or malformed.
+	pub fn parse(buf: &[u8]) -> SysResult<(Self, usize)> {
+		let dword = |off: usize| read_u32(buf, off);
+		let word = |off: usize| read_u16(buf, off);
+
+		if buf.len() < HEADER_LEN || dword(4)? != EVENTLOG_SIGNATURE {
+			return Err(co::ERROR::INVALID_DATA);
+		}
+		let len = dword(0)? as usize;
+		if len < HEADER_LEN || len > buf.len() || len % 4 != 0 {
+			return Err(co::ERROR::INVALID_DATA);
+		}
+		let rec = &buf[..len];
+
+		let num_strings = word(26)? as usize;
+		let string_offset = dword(36)? as usize;
+		let sid_len = dword(40)? as usize;
+		let sid_offset = dword(44)? as usize;
+		let data_len = dword(48)? as usize;
+		let data_offset = dword(52)? as usize;
+
+		let (source_name, source_len) = read_sz(rec, HEADER_LEN)?;
+		let (computer_name, _) = read_sz(rec, HEADER_LEN + source_len)?;
+
+		let user_sid = match sid_len {
+			0 => None,
+			_ => Some(slice_at(rec, sid_offset, sid_len)?.to_vec()),
+		};
+
+		let mut strings = Vec::with_capacity(num_strings);
+		let mut off = string_offset;
+		for _ in 0..num_strings {
+			let (s, s_len) = read_sz(rec, off)?;
+			strings.push(s);
+			off += s_len;
+		}
+
+		Ok((
+			Self {
+				record_number: dword(8)?,
+				time_generated: dword(12)?,
+				time_written: dword(16)?,
+				event_id: dword(20)?,
+				event_type: unsafe { co::EVENTLOG::from_raw(word(24)?) },
+				event_category: word(28)?,
+				source_name,
+				computer_name,
+				user_sid,
+				strings,
+				data: slice_at(rec, data_offset, data_len)?.to_vec(),
+			},
+			len,
+		))
+	}
+
+	/// Parses all the records in the buffer, which must contain only whole
+	/// records, like the ones returned by
+	/// [`ReadEventLog`](https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-readeventlogw).
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*};
+	///
+	/// let dump = std::fs::read("C:\\Temp\\application.bin")?;
+	/// for rec in w::EventLogRecord::parse_all(&dump)? {
+	///     println!("{} {} {:?}", rec.source_name, rec.event_code(), rec.strings);
+	/// }
+	/// # w::AnyResult::Ok(())
+	/// ```
+	pub fn parse_all(buf: &[u8]) -> SysResult<Vec<Self>> {
+		let mut records = Vec::new();
+		let mut off = 0;
+		while off < buf.len() {
+			let (rec, len) = Self::parse(&buf[off..])?;
+			records.push(rec);
+			off += len;
+		}
+		Ok(records)
+	}
+
+	/// Serializes the record into the binary `EVENTLOGRECORD` layout, which
+	/// can be read back with [`parse`](crate::EventLogRecord::parse).
+	///
+	/// As in the records written by the system, the SID is DWORD-aligned, the
+	/// record is padded to a DWORD boundary, and ends with a copy of its
+	/// length.
+	#[must_use]
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut buf = vec![0u8; HEADER_LEN];
+		write_sz(&mut buf, &self.source_name);
+		write_sz(&mut buf, &self.computer_name);
+		pad_dword(&mut buf);
+
+		let sid_offset = buf.len();
+		let sid = self.user_sid.as_deref().unwrap_or_default();
+		buf.extend_from_slice(sid);
+
+		let string_offset = buf.len();
+		for s in self.strings.iter() {
+			write_sz(&mut buf, s);
+		}
+
+		let data_offset = buf.len();
+		buf.extend_from_slice(&self.data);
+		pad_dword(&mut buf);
+
+		let len = (buf.len() + 4) as u32; // room for the trailing length
+		buf.extend_from_slice(&len.to_le_bytes());
+
+		let mut put = |off: usize, bytes: &[u8]| {
+			buf[off..off + bytes.len()].copy_from_slice(bytes);
+		};
+		put(0, &len.to_le_bytes());
+		put(4, &EVENTLOG_SIGNATURE.to_le_bytes());
+		put(8, &self.record_number.to_le_bytes());
+		put(12, &self.time_generated.to_le_bytes());
+		put(16, &self.time_written.to_le_bytes());
+		put(20, &self.event_id.to_le_bytes());
+		put(24, &self.event_type.raw().to_le_bytes());
+		put(26, &(self.strings.len() as u16).to_le_bytes());
+		put(28, &self.event_category.to_le_bytes());
+		put(36, &(string_offset as u32).to_le_bytes());
+		put(40, &(sid.len() as u32).to_le_bytes());
+		put(44, &(sid_offset as u32).to_le_bytes());
+		put(48, &(self.data.len() as u32).to_le_bytes());
+		put(52, &(data_offset as u32).to_le_bytes());
+		buf
+	}
+
+	/// Returns the low-order word of
+	/// [`event_id`](crate::EventLogRecord::event_id), which is the code shown
+	/// by the Event Viewer.
+	#[must_use]
+	pub const fn event_code(&self) -> u16 {
+		self.event_id as u16
+	}
+}
+
+fn slice_at(buf: &[u8], off: usize, len: usize) -> SysResult<&[u8]> {
+	off.checked_add(len)
+		.and_then(|end| buf.get(off..end))
+		.ok_or(co::ERROR::INVALID_DATA)
+}
+
+fn read_u16(buf: &[u8], off: usize) -> SysResult<u16> {
+	slice_at(buf, off, 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(buf: &[u8], off: usize) -> SysResult<u32> {
+	slice_at(buf, off, 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Appends a null-terminated UTF-16 string.
+fn write_sz(buf: &mut Vec<u8>, s: &str) {
+	for ch in s.encode_utf16().chain(std::iter::once(0)) {
+		buf.extend_from_slice(&ch.to_le_bytes());
+	}
+}
+
+/// Appends zeros until the length is a multiple of 4.
+fn pad_dword(buf: &mut Vec<u8>) {
+	buf.resize(buf.len().next_multiple_of(4), 0);
+}
+
+/// Reads a null-terminated UTF-16 string, returning it along with the number
+/// of bytes consumed, including the terminating null.
+fn read_sz(buf: &[u8], off: usize) -> SysResult<(String, usize)> {
+	let tail = buf.get(off..).ok_or(co::ERROR::INVALID_DATA)?;
+	let wchars = tail.chunks_exact(2)
+		.map(|b| u16::from_le_bytes([b[0], b[1]]))
+		.take_while(|ch| *ch != 0)
+		.collect::<Vec<_>>();
+	let consumed = (wchars.len() + 1) * 2;
+	if consumed > tail.len() {
+		return Err(co::ERROR::INVALID_DATA); // no terminating null
+	}
+	Ok((String::from_utf16_lossy(&wchars), consumed))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A record as returned by `ReadEventLog`: source `MyApp`, computer
+	/// `DESKTOP-1`, user `S-1-5-18`, strings `foo` and `bar`, and 5 bytes of
+	/// data.
+	const DUMP: [u8; 128] = [
+		0x80, 0x00, 0x00, 0x00, 0x4c, 0x66, 0x4c, 0x65, 0x11, 0x00, 0x00, 0x00, 0x00, 0xf1, 0x53, 0x65,
+		0x01, 0xf1, 0x53, 0x65, 0xe8, 0x03, 0x00, 0x40, 0x04, 0x00, 0x02, 0x00, 0x02, 0x00, 0x00, 0x00,
+		0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x58, 0x00, 0x00, 0x00,
+		0x05, 0x00, 0x00, 0x00, 0x74, 0x00, 0x00, 0x00, 0x4d, 0x00, 0x79, 0x00, 0x41, 0x00, 0x70, 0x00,
+		0x70, 0x00, 0x00, 0x00, 0x44, 0x00, 0x45, 0x00, 0x53, 0x00, 0x4b, 0x00, 0x54, 0x00, 0x4f, 0x00,
+		0x50, 0x00, 0x2d, 0x00, 0x31, 0x00, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05,
+		0x12, 0x00, 0x00, 0x00, 0x66, 0x00, 0x6f, 0x00, 0x6f, 0x00, 0x00, 0x00, 0x62, 0x00, 0x61, 0x00,
+		0x72, 0x00, 0x00, 0x00, 0xde, 0xad, 0xbe, 0xef, 0x01, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00,
+	];
+
+	fn expected() -> EventLogRecord {
+		EventLogRecord {
+			record_number: 17,
+			time_generated: 1_700_000_000,
+			time_written: 1_700_000_001,
+			event_id: 0x4000_03e8,
+			event_type: co::EVENTLOG::INFORMATION_TYPE,
+			event_category: 2,
+			source_name: "MyApp".to_owned(),
+			computer_name: "DESKTOP-1".to_owned(),
+			user_sid: Some(vec![1, 1, 0, 0, 0, 0, 0, 5, 18, 0, 0, 0]),
+			strings: vec!["foo".to_owned(), "bar".to_owned()],
+			data: vec![0xde, 0xad, 0xbe, 0xef, 0x01],
+		}
+	}
+
+	#[test]
+	fn parse_dump() {
+		let (rec, len) = EventLogRecord::parse(&DUMP).unwrap();
+		assert_eq!(len, DUMP.len());
+		assert_eq!(rec, expected());
+		assert_eq!(rec.event_code(), 1000);
+	}
+
+	#[test]
+	fn parse_all_dumps() {
+		let mut dumps = DUMP.to_vec();
+		dumps.extend_from_slice(&DUMP);
+		dumps[DUMP.len() + 8] = 18; // record number of the second one
+		let recs = EventLogRecord::parse_all(&dumps).unwrap();
+		assert_eq!(recs.len(), 2);
+		assert_eq!(recs[0].record_number, 17);
+		assert_eq!(recs[1].record_number, 18);
+
+		assert_eq!(EventLogRecord::parse_all(&dumps[..DUMP.len() + 60]), Err(co::ERROR::INVALID_DATA));
+		assert_eq!(EventLogRecord::parse_all(&[]), Ok(Vec::new()));
+	}
+
+	#[test]
+	fn malformed_dumps() {
+		let patched = |off: usize, val: u32| {
+			let mut dump = DUMP;
+			dump[off..off + 4].copy_from_slice(&val.to_le_bytes());
+			EventLogRecord::parse(&dump)
+		};
+		assert_eq!(EventLogRecord::parse(&DUMP[..100]), Err(co::ERROR::INVALID_DATA));
+		assert_eq!(patched(4, 0x1234_5678), Err(co::ERROR::INVALID_DATA)); // signature
+		assert_eq!(patched(0, 256), Err(co::ERROR::INVALID_DATA)); // length past the buffer
+		assert_eq!(patched(0, 127), Err(co::ERROR::INVALID_DATA)); // length not aligned
+		assert_eq!(patched(36, 126), Err(co::ERROR::INVALID_DATA)); // strings past the record
+		assert_eq!(patched(44, 0xffff_fffc), Err(co::ERROR::INVALID_DATA)); // SID offset overflow
+		assert_eq!(patched(48, 64), Err(co::ERROR::INVALID_DATA)); // data past the record
+	}
+
+	#[test]
+	fn write_round_trip() {
+		assert_eq!(expected().to_bytes(), DUMP);
+
+		let rec = EventLogRecord {
+			source_name: "Odd".to_owned(),
+			strings: vec![String::new(), "ação".to_owned()],
+			data: vec![1, 2, 3],
+			..Default::default()
+		};
+		let bytes = rec.to_bytes();
+		assert_eq!(bytes.len() % 4, 0);
+		assert_eq!(bytes[bytes.len() - 4..], bytes[..4]);
+		assert_eq!(EventLogRecord::parse(&bytes).unwrap(), (rec, bytes.len()));
+	}
+}
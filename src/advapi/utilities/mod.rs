@@ -0,0 +1,24 @@
+mod access_evaluator;
+mod event_log_record;
+mod reg_file;
+mod registry_tree;
+mod registry_watch;
+mod sddl;
+mod security_descriptor;
+mod service_host;
+mod sid;
+mod token_snapshot;
+
+pub use access_evaluator::AccessEvaluator;
+pub use event_log_record::EventLogRecord;
+pub use reg_file::{RegFile, RegFileData, RegFileFormat, RegFileKey, RegFileValue};
+pub use registry_tree::{RegistryChange, RegistryTree};
+pub use registry_watch::RegistryWatch;
+pub use security_descriptor::{Ace, SecurityDescriptor};
+pub use service_host::{ServiceHost, ServiceProgress, ServiceStatusMachine};
+pub use sid::Sid;
+pub use token_snapshot::{TokenGroup, TokenPrivilege, TokenSnapshot};
+
+pub(in crate::advapi) mod traits {
+	pub use super::service_host::{ServiceHandler, ServiceStatusSink};
+}
@@ -0,0 +1,866 @@
+use crate::advapi::ffi;
+use crate::co;
+use crate::decl::*;
+use crate::kernel::privs::*;
+use crate::prelude::*;
+
+/// Format of a [`RegFile`](crate::RegFile).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RegFileFormat {
+	/// Old `REGEDIT4` format, whose strings in `hex(N)` values are ANSI, and
+	/// whose files are written as
+	/// [`Encoding::Win1252`](crate::Encoding::Win1252).
+	Regedit4,
+	/// `Windows Registry Editor Version 5.00` format, whose strings in
+	/// `hex(N)` values are UTF-16, and whose files are written as
+	/// [`Encoding::Utf16le`](crate::Encoding::Utf16le) with BOM.
+	Regedit5,
+}
+
+impl RegFileFormat {
+	#[must_use]
+	const fn header(&self) -> &'static str {
+		match self {
+			Self::Regedit4 => "REGEDIT4",
+			Self::Regedit5 => "Windows Registry Editor Version 5.00",
+		}
+	}
+}
+
+/// The data of a [`RegFileValue`](crate::RegFileValue).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegFileData {
+	/// A value whose type is represented by
+	/// [`RegistryValue`](crate::RegistryValue).
+	Value(RegistryValue),
+	/// A value of any other type, like
+	/// [`co::REG::RESOURCE_LIST`](crate::co::REG::RESOURCE_LIST), with its raw
+	/// bytes, written as `hex(N):`.
+	Raw(co::REG, Vec<u8>),
+}
+
+impl From<RegistryValue> for RegFileData {
+	fn from(value: RegistryValue) -> Self {
+		Self::Value(value)
+	}
+}
+
+impl std::fmt::Display for RegFileData {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Value(value) => write!(f, "{}", value),
+			Self::Raw(reg_type, bytes) => write!(
+				f,
+				"[hex({:x})] {}",
+				reg_type.raw(),
+				bytes.iter()
+					.map(|n| format!("{:02x}", *n))
+					.collect::<Vec<_>>()
+					.join(" "),
+			),
+		}
+	}
+}
+
+/// A value entry of a [`RegFileKey`](crate::RegFileKey).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegFileValue {
+	/// Name of the value, or `None` for the default value, written as `@`.
+	pub name: Option<String>,
+	/// The data of the value, or `None` if the value is to be deleted, written
+	/// as `"name"=-`.
+	pub value: Option<RegFileData>,
+}
+
+/// A key entry of a [`RegFile`](crate::RegFile).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegFileKey {
+	/// Full path of the key, starting with the root key name, like
+	/// `HKEY_CURRENT_USER\Software\My Company`.
+	pub path: String,
+	/// Whether the key and all its subkeys are to be deleted, written as
+	/// `[-path]`. In this case, the values are ignored.
+	pub delete: bool,
+	/// The values of the key.
+	pub values: Vec<RegFileValue>,
+}
+
+/// Contents of a `.reg` file, as written and read by the Windows Registry
+/// Editor, either in the `REGEDIT4` or in the
+/// `Windows Registry Editor Version 5.00` format.
+///
+/// Parsing and serializing are implemented in pure Rust; only
+/// [`import`](crate::RegFile::import) and [`export`](crate::RegFile::export)
+/// touch the registry.
+///
+/// The root key names can be written in full, like `HKEY_LOCAL_MACHINE`, or
+/// abbreviated, like `HKLM`.
+///
+/// # Examples
+///
+/// Exporting a key and its subkeys to a file:
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*};
+///
+/// let reg = w::RegFile::export("HKEY_CURRENT_USER\\Control Panel\\Mouse")?;
+/// let f = w::File::open("C:\\Temp\\mouse.reg", w::FileAccess::OpenOrCreateRW)?;
+/// f.erase_and_write(&reg.to_bytes()?)?;
+/// # w::SysResult::Ok(())
+/// ```
+///
+/// Parsing and applying a file:
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*};
+///
+/// let f = w::FileMapped::open(
+///     "C:\\Temp\\settings.reg",
+///     w::FileAccess::ExistingReadOnly,
+/// )?;
+/// let reg = w::RegFile::parse_bytes(f.as_slice())?;
+///
+/// for key in reg.keys.iter() {
+///     println!("{} ({} values)", key.path, key.values.len());
+/// }
+/// reg.import()?;
+/// # w::SysResult::Ok(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegFile {
+	/// The file format.
+	pub format: RegFileFormat,
+	/// The keys, in file order.
+	pub keys: Vec<RegFileKey>,
+}
+
+impl RegFile {
+	/// Creates a new, empty file.
+	#[must_use]
+	pub const fn new(format: RegFileFormat) -> Self {
+		Self { format, keys: Vec::new() }
+	}
+
+	/// Parses the text of a `.reg` file.
+	///
+	/// Values whose type cannot be represented by
+	/// [`RegistryValue`](crate::RegistryValue) are kept as
+	/// [`RegFileData::Raw`](crate::RegFileData::Raw); malformed text returns
+	/// [`co::ERROR::INVALID_DATA`](crate::co::ERROR::INVALID_DATA).
+	#[must_use]
+	pub fn parse(text: &str) -> SysResult<Self> {
+		let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+		let mut lines = logical_lines(text).into_iter()
+			.map(|line| line.trim().to_owned())
+			.filter(|line| !line.is_empty() && !line.starts_with(';'));
+
+		let format = match lines.next().as_deref() {
+			Some(h) if h == RegFileFormat::Regedit4.header() => RegFileFormat::Regedit4,
+			Some(h) if h == RegFileFormat::Regedit5.header() => RegFileFormat::Regedit5,
+			_ => return Err(co::ERROR::INVALID_DATA),
+		};
+		let mut new_self = Self::new(format);
+
+		for line in lines {
+			if let Some(inner) = line.strip_prefix('[') {
+				let inner = inner.strip_suffix(']').ok_or(co::ERROR::INVALID_DATA)?;
+				let (delete, path) = match inner.strip_prefix('-') {
+					Some(path) => (true, path),
+					None => (false, inner),
+				};
+				if path.is_empty() {
+					return Err(co::ERROR::INVALID_DATA);
+				}
+				new_self.keys.push(RegFileKey {
+					path: path.to_owned(),
+					delete,
+					values: Vec::new(),
+				});
+			} else {
+				let key = new_self.keys.last_mut()
+					.ok_or(co::ERROR::INVALID_DATA)?; // value before any key
+				key.values.push(parse_value_line(&line, format)?);
+			}
+		}
+
+		Ok(new_self)
+	}
+
+	/// Parses the raw contents of a `.reg` file, guessing its encoding with
+	/// [`Encoding::guess`](crate::Encoding::guess).
+	#[must_use]
+	pub fn parse_bytes(data: &[u8]) -> SysResult<Self> {
+		let (encoding, bom_size) = Encoding::guess(data);
+		let wchars = encoding.decode(&data[bom_size..])?;
+		let text = String::from_utf16(&wchars)
+			.map_err(|_| co::ERROR::NO_UNICODE_TRANSLATION)?;
+		Self::parse(&text)
+	}
+
+	/// Serializes the file into text, with `\r\n` line breaks.
+	///
+	/// Returns
+	/// [`co::ERROR::NO_UNICODE_TRANSLATION`](crate::co::ERROR::NO_UNICODE_TRANSLATION)
+	/// if a string in a `hex(N)` value cannot be represented in the
+	/// `REGEDIT4` format.
+	#[must_use]
+	pub fn serialize(&self) -> SysResult<String> {
+		let mut out = String::new();
+		out.push_str(self.format.header());
+		out.push_str("\r\n\r\n");
+
+		for key in self.keys.iter() {
+			out.push('[');
+			if key.delete {
+				out.push('-');
+			}
+			out.push_str(&key.path);
+			out.push_str("]\r\n");
+
+			if !key.delete {
+				for val in key.values.iter() {
+					write_value_line(&mut out, val, self.format)?;
+				}
+			}
+			out.push_str("\r\n");
+		}
+
+		Ok(out)
+	}
+
+	/// Serializes the file into raw bytes, ready to be written to disk:
+	/// [`RegFileFormat::Regedit5`](crate::RegFileFormat::Regedit5) is
+	/// written as UTF-16 LE with BOM, just like the Registry Editor does, and
+	/// [`RegFileFormat::Regedit4`](crate::RegFileFormat::Regedit4) as
+	/// Windows-1252.
+	#[must_use]
+	pub fn to_bytes(&self) -> SysResult<Vec<u8>> {
+		let wchars = self.serialize()?.encode_utf16().collect::<Vec<_>>();
+		let encoding = match self.format {
+			RegFileFormat::Regedit4 => Encoding::Win1252,
+			RegFileFormat::Regedit5 => Encoding::Utf16le,
+		};
+		let mut bytes = encoding.bom().to_vec();
+		bytes.extend_from_slice(&encoding.encode(&wchars)?);
+		Ok(bytes)
+	}
+
+	/// Reads the given key, its values and all its subkeys from the registry,
+	/// in the
+	/// [`RegFileFormat::Regedit5`](crate::RegFileFormat::Regedit5) format.
+	///
+	/// Values whose type cannot be represented by
+	/// [`RegistryValue`](crate::RegistryValue) are read as
+	/// [`RegFileData::Raw`](crate::RegFileData::Raw).
+	///
+	/// The path must start with the root key name, like
+	/// `HKEY_CURRENT_USER\Software\My Company`.
+	#[must_use]
+	pub fn export(key_path: &str) -> SysResult<Self> {
+		let (hroot, sub_key) = split_root(key_path)?;
+		let hkey = hroot.RegOpenKeyEx(
+			if sub_key.is_empty() { None } else { Some(sub_key) },
+			co::REG_OPTION::NoValue,
+			co::KEY::READ,
+		)?;
+		Self::export_hkey(&hkey, key_path)
+	}
+
+	/// Reads the values and all the subkeys of an already opened key, which
+	/// must have [`co::KEY::READ`](crate::co::KEY::READ) access. The key is
+	/// written in the file with the given path.
+	#[must_use]
+	pub fn export_hkey(hkey: &HKEY, key_path: &str) -> SysResult<Self> {
+		let mut new_self = Self::new(RegFileFormat::Regedit5);
+		export_tree(hkey, key_path, &mut new_self.keys)?;
+		Ok(new_self)
+	}
+
+	/// Applies all keys and values to the registry: keys are created if they
+	/// don't exist, values are set, and deletions are performed. Deleting keys
+	/// or values which don't exist is not an error.
+	///
+	/// All key paths must start with a root key name, otherwise
+	/// [`co::ERROR::INVALID_PARAMETER`](crate::co::ERROR::INVALID_PARAMETER)
+	/// is returned before anything is written.
+	pub fn import(&self) -> SysResult<()> {
+		let targets = self.keys.iter()
+			.map(|key| split_root(&key.path).map(|(hroot, sub_key)| (hroot, sub_key, key)))
+			.collect::<SysResult<Vec<_>>>()?;
+		targets.iter()
+			.try_for_each(|(hroot, sub_key, key)| apply_key(hroot, sub_key, key))
+	}
+
+	/// Applies the keys and values to a subtree of the registry, where `hkey`
+	/// is an already opened key, which corresponds to `key_path` in the file.
+	/// The key must have [`co::KEY::ALL_ACCESS`](crate::co::KEY::ALL_ACCESS)
+	/// access.
+	///
+	/// All key paths must be `key_path` itself or below it – compared
+	/// case-insensitively –, otherwise
+	/// [`co::ERROR::INVALID_PARAMETER`](crate::co::ERROR::INVALID_PARAMETER)
+	/// is returned before anything is written.
+	pub fn import_hkey(&self, hkey: &HKEY, key_path: &str) -> SysResult<()> {
+		let targets = self.keys.iter()
+			.map(|key| relative_path(&key.path, key_path)
+				.map(|sub_key| (sub_key, key))
+				.ok_or(co::ERROR::INVALID_PARAMETER))
+			.collect::<SysResult<Vec<_>>>()?;
+		targets.iter()
+			.try_for_each(|(sub_key, key)| apply_key(hkey, sub_key, key))
+	}
+}
+
+/// Splits the text into lines, joining the ones ending with a backslash, which
+/// are continued in the next line.
+fn logical_lines(text: &str) -> Vec<String> {
+	let mut lines = Vec::<String>::new();
+	let mut continued = false;
+
+	for line in text.lines() {
+		let line = line.trim_end();
+		let (content, has_more) = match line.strip_suffix('\\') {
+			Some(content) if !line.trim_start().starts_with(';') => (content, true),
+			_ => (line, false),
+		};
+		match lines.last_mut() {
+			Some(last) if continued => last.push_str(content.trim_start()),
+			_ => lines.push(content.to_owned()),
+		}
+		continued = has_more;
+	}
+	lines
+}
+
+/// Parses a quoted string, returning it unescaped and the text after the
+/// closing quote.
+fn parse_quoted(text: &str) -> SysResult<(String, &str)> {
+	let text = text.strip_prefix('"').ok_or(co::ERROR::INVALID_DATA)?;
+	let mut s = String::new();
+	let mut chars = text.char_indices();
+
+	while let Some((idx, ch)) = chars.next() {
+		match ch {
+			'"' => return Ok((s, &text[idx + 1..])),
+			'\\' => match chars.next() {
+				Some((_, esc)) => s.push(esc), // \\ and \"
+				None => break,
+			},
+			ch => s.push(ch),
+		}
+	}
+	Err(co::ERROR::INVALID_DATA) // no closing quote
+}
+
+fn parse_value_line(line: &str, format: RegFileFormat) -> SysResult<RegFileValue> {
+	let (name, rest) = match line.strip_prefix('@') {
+		Some(rest) => (None, rest),
+		None => parse_quoted(line).map(|(name, rest)| (Some(name), rest))?,
+	};
+	let data = rest.trim_start()
+		.strip_prefix('=')
+		.ok_or(co::ERROR::INVALID_DATA)?
+		.trim();
+
+	let value = if data == "-" {
+		None
+	} else if data.starts_with('"') {
+		let (s, rest) = parse_quoted(data)?;
+		if !rest.trim().is_empty() {
+			return Err(co::ERROR::INVALID_DATA);
+		}
+		Some(RegistryValue::Sz(s).into())
+	} else if let Some(digits) = strip_prefix_ci(data, "dword:") {
+		Some(RegistryValue::Dword(parse_hex_digits(digits, 8)?).into())
+	} else if let Some(bytes) = strip_prefix_ci(data, "hex:") {
+		Some(RegistryValue::Binary(parse_hex_bytes(bytes)?).into())
+	} else if let Some(typed) = strip_prefix_ci(data, "hex(") {
+		let (reg_type, bytes) = typed.split_once("):").ok_or(co::ERROR::INVALID_DATA)?;
+		let reg_type = parse_hex_digits(reg_type, 8)?;
+		Some(typed_value(
+			unsafe { co::REG::from_raw(reg_type) },
+			parse_hex_bytes(bytes)?,
+			format,
+		)?)
+	} else {
+		return Err(co::ERROR::INVALID_DATA);
+	};
+
+	Ok(RegFileValue { name, value })
+}
+
+fn strip_prefix_ci<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+	text.get(..prefix.len())
+		.filter(|head| head.eq_ignore_ascii_case(prefix))
+		.map(|_| &text[prefix.len()..])
+}
+
+/// Parses a hex number of up to `max_digits` digits, without the sign or the
+/// `0x` prefix accepted by `from_str_radix`.
+fn parse_hex_digits(digits: &str, max_digits: usize) -> SysResult<u32> {
+	if digits.is_empty()
+		|| digits.len() > max_digits
+		|| !digits.chars().all(|ch| ch.is_ascii_hexdigit())
+	{
+		return Err(co::ERROR::INVALID_DATA);
+	}
+	u32::from_str_radix(digits, 16).map_err(|_| co::ERROR::INVALID_DATA)
+}
+
+/// Parses comma-separated hex bytes, like `0a,ff,3c`.
+fn parse_hex_bytes(text: &str) -> SysResult<Vec<u8>> {
+	let text = text.trim();
+	if text.is_empty() {
+		return Ok(Vec::new());
+	}
+	text.split(',')
+		.map(|byte| parse_hex_digits(byte.trim(), 2).map(|b| b as u8))
+		.collect()
+}
+
+/// Converts the bytes of a `hex(N)` value into the corresponding
+/// `RegistryValue`, or keeps them raw if the type is not represented.
+fn typed_value(
+	reg_type: co::REG,
+	bytes: Vec<u8>,
+	format: RegFileFormat,
+) -> SysResult<RegFileData>
+{
+	Ok(RegFileData::Value(match reg_type {
+		co::REG::NONE => RegistryValue::None,
+		co::REG::SZ => RegistryValue::Sz(decode_str(&bytes, format)?),
+		co::REG::EXPAND_SZ => RegistryValue::ExpandSz(decode_str(&bytes, format)?),
+		co::REG::BINARY => RegistryValue::Binary(bytes),
+		co::REG::DWORD => RegistryValue::Dword(u32::from_le_bytes(
+			bytes.try_into().map_err(|_| co::ERROR::INVALID_DATA)?,
+		)),
+		co::REG::QWORD => RegistryValue::Qword(u64::from_le_bytes(
+			bytes.try_into().map_err(|_| co::ERROR::INVALID_DATA)?,
+		)),
+		co::REG::MULTI_SZ => {
+			let all = decode_str(&bytes, format)?;
+			RegistryValue::MultiSz(
+				if all.is_empty() {
+					Vec::new()
+				} else {
+					all.split('\0').map(|s| s.to_owned()).collect()
+				},
+			)
+		},
+		_ => return Ok(RegFileData::Raw(reg_type, bytes)), // not representable by RegistryValue
+	}))
+}
+
+/// Decodes the string bytes of a `hex(N)` value, removing the terminating
+/// nulls.
+fn decode_str(bytes: &[u8], format: RegFileFormat) -> SysResult<String> {
+	let wchars = match format {
+		RegFileFormat::Regedit4 => Encoding::Win1252.decode(bytes)?,
+		RegFileFormat::Regedit5 => {
+			if bytes.len() % 2 != 0 {
+				return Err(co::ERROR::INVALID_DATA);
+			}
+			bytes.chunks_exact(2)
+				.map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+				.collect()
+		},
+	};
+	let s = String::from_utf16(&wchars).map_err(|_| co::ERROR::INVALID_DATA)?;
+	Ok(s.trim_end_matches('\0').to_owned())
+}
+
+/// Encodes a string of a `hex(N)` value, including the terminating null.
+fn encode_str(s: &str, format: RegFileFormat) -> SysResult<Vec<u8>> {
+	let wchars = s.encode_utf16()
+		.chain(std::iter::once(0x0000))
+		.collect::<Vec<_>>();
+	match format {
+		RegFileFormat::Regedit4 => Encoding::Win1252.encode(&wchars),
+		RegFileFormat::Regedit5 => Ok(wchars.iter().flat_map(|ch| ch.to_le_bytes()).collect()),
+	}
+}
+
+fn escape_quoted(s: &str) -> String {
+	format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn write_value_line(
+	out: &mut String,
+	val: &RegFileValue,
+	format: RegFileFormat,
+) -> SysResult<()>
+{
+	let mut line = match &val.name {
+		None => "@=".to_owned(),
+		Some(name) => format!("{}=", escape_quoted(name)),
+	};
+
+	match &val.value {
+		None => line.push('-'),
+		Some(RegFileData::Value(value)) => match value {
+			RegistryValue::Sz(s) if !s.chars().any(|ch| ch.is_control()) => {
+				line.push_str(&escape_quoted(s));
+			},
+			RegistryValue::Sz(s) => { // line breaks can't be written in quotes
+				write_hex(&mut line, "hex(1):", &encode_str(s, format)?);
+			},
+			RegistryValue::Dword(n) => line.push_str(&format!("dword:{:08x}", n)),
+			RegistryValue::Binary(b) => write_hex(&mut line, "hex:", b),
+			RegistryValue::ExpandSz(s) => {
+				write_hex(&mut line, "hex(2):", &encode_str(s, format)?);
+			},
+			RegistryValue::MultiSz(v) => {
+				let mut all = v.join("\0");
+				all.push('\0'); // final empty string
+				write_hex(&mut line, "hex(7):", &encode_str(&all, format)?);
+			},
+			RegistryValue::Qword(n) => write_hex(&mut line, "hex(b):", &n.to_le_bytes()),
+			RegistryValue::None => write_hex(&mut line, "hex(0):", &[]),
+		},
+		Some(RegFileData::Raw(reg_type, b)) => {
+			write_hex(&mut line, &format!("hex({:x}):", reg_type.raw()), b);
+		},
+	}
+
+	out.push_str(&line);
+	out.push_str("\r\n");
+	Ok(())
+}
+
+/// Writes comma-separated hex bytes, breaking the lines at 80 columns, just
+/// like the Registry Editor does.
+fn write_hex(line: &mut String, prefix: &str, bytes: &[u8]) {
+	const MAX_COLS: usize = 80;
+	line.push_str(prefix);
+	let mut col = line.chars().count();
+
+	for (idx, byte) in bytes.iter().enumerate() {
+		let is_last = idx == bytes.len() - 1;
+		let chunk_len = if is_last { 2 } else { 3 };
+		if col + chunk_len + 1 > MAX_COLS { // leave room for the backslash
+			line.push_str("\\\r\n  ");
+			col = 2;
+		}
+		line.push_str(&format!("{:02x}", byte));
+		if !is_last {
+			line.push(',');
+		}
+		col += chunk_len;
+	}
+}
+
+/// Splits a full key path into its predefined root key and the subkey path,
+/// which may be empty.
+fn split_root(key_path: &str) -> SysResult<(HKEY, &str)> {
+	let (root, sub_key) = key_path.split_once('\\').unwrap_or((key_path, ""));
+	let hroot = match root.to_ascii_uppercase().as_str() {
+		"HKEY_CLASSES_ROOT" | "HKCR" => HKEY::CLASSES_ROOT,
+		"HKEY_CURRENT_USER" | "HKCU" => HKEY::CURRENT_USER,
+		"HKEY_LOCAL_MACHINE" | "HKLM" => HKEY::LOCAL_MACHINE,
+		"HKEY_USERS" | "HKU" => HKEY::USERS,
+		"HKEY_CURRENT_CONFIG" | "HKCC" => HKEY::CURRENT_CONFIG,
+		_ => return Err(co::ERROR::INVALID_PARAMETER),
+	};
+	Ok((hroot, sub_key.trim_end_matches('\\')))
+}
+
+/// Returns the path relative to the base path, if it's the base itself or
+/// below it.
+fn relative_path<'a>(path: &'a str, base: &str) -> Option<&'a str> {
+	let base = base.trim_end_matches('\\');
+	let head = path.get(..base.len())?;
+	if !head.eq_ignore_ascii_case(base) {
+		return None;
+	}
+	let rest = &path[base.len()..];
+	if rest.is_empty() {
+		Some(rest)
+	} else {
+		rest.strip_prefix('\\').map(|rest| rest.trim_end_matches('\\'))
+	}
+}
+
+fn apply_key(hparent: &HKEY, sub_key: &str, key: &RegFileKey) -> SysResult<()> {
+	if key.delete {
+		if sub_key.is_empty() {
+			return Err(co::ERROR::INVALID_PARAMETER); // won't delete the parent itself
+		}
+		return match hparent.RegDeleteTree(Some(sub_key)) {
+			Ok(_) | Err(co::ERROR::FILE_NOT_FOUND) => Ok(()),
+			Err(e) => Err(e),
+		};
+	}
+
+	let (hkey, _) = hparent.RegCreateKeyEx(
+		sub_key,
+		None,
+		co::REG_OPTION::NON_VOLATILE,
+		co::KEY::QUERY_VALUE | co::KEY::SET_VALUE,
+		None,
+	)?;
+
+	for val in key.values.iter() {
+		match &val.value {
+			Some(RegFileData::Value(data)) => hkey.RegSetValueEx(val.name.as_deref(), data.clone())?,
+			Some(RegFileData::Raw(reg_type, bytes)) => error_to_sysresult(
+				unsafe {
+					ffi::RegSetValueExW(
+						hkey.ptr(),
+						WString::from_opt_str(val.name.as_deref()).as_ptr(),
+						0,
+						reg_type.raw(),
+						vec_ptr(bytes),
+						bytes.len() as _,
+					)
+				},
+			)?,
+			None => match hkey.RegDeleteValue(val.name.as_deref()) {
+				Ok(_) | Err(co::ERROR::FILE_NOT_FOUND) => {},
+				Err(e) => return Err(e),
+			},
+		}
+	}
+	Ok(())
+}
+
+fn export_tree(hkey: &HKEY, key_path: &str, keys: &mut Vec<RegFileKey>) -> SysResult<()> {
+	let mut values = Vec::<RegFileValue>::new();
+	for name_and_type in hkey.RegEnumValue()? {
+		let (name, _) = name_and_type?;
+		let name = if name.is_empty() { None } else { Some(name) }; // default value
+		let value = match hkey.RegQueryValueEx(name.as_deref()) {
+			Ok(value) => RegFileData::Value(value),
+			Err(co::ERROR::CALL_NOT_IMPLEMENTED) => query_raw_value(hkey, name.as_deref())?,
+			Err(e) => return Err(e),
+		};
+		values.push(RegFileValue { name, value: Some(value) });
+	}
+	values.sort_by_key(|val| val.name.is_some()); // default value first, like the Registry Editor
+
+	keys.push(RegFileKey {
+		path: key_path.to_owned(),
+		delete: false,
+		values,
+	});
+
+	let sub_names = hkey.RegEnumKeyEx()?.collect::<SysResult<Vec<_>>>()?;
+	for sub_name in sub_names.iter() {
+		let hsub = hkey.RegOpenKeyEx(
+			Some(sub_name),
+			co::REG_OPTION::NoValue,
+			co::KEY::READ,
+		)?;
+		export_tree(&hsub, &format!("{}\\{}", key_path, sub_name), keys)?;
+	}
+	Ok(())
+}
+
+/// Reads the raw bytes of a value whose type is not represented by
+/// `RegistryValue`.
+pub(in crate::advapi) fn query_raw_value(
+	hkey: &impl advapi_Hkey,
+	value_name: Option<&str>,
+) -> SysResult<RegFileData>
+{
+	let value_name_w = WString::from_opt_str(value_name);
+	loop {
+		let mut data_len = u32::default(); // in bytes
+		error_to_sysresult(
+			unsafe {
+				ffi::RegQueryValueExW(
+					hkey.ptr(),
+					value_name_w.as_ptr(),
+					std::ptr::null_mut(),
+					std::ptr::null_mut(),
+					std::ptr::null_mut(),
+					&mut data_len, // first call to retrieve size only
+				)
+			},
+		)?;
+
+		let mut buf = vec![0u8; data_len as _];
+		let mut data_type = u32::default();
+		match error_to_sysresult(
+			unsafe {
+				ffi::RegQueryValueExW(
+					hkey.ptr(),
+					value_name_w.as_ptr(),
+					std::ptr::null_mut(),
+					&mut data_type,
+					buf.as_mut_ptr(),
+					&mut data_len,
+				)
+			},
+		) {
+			Ok(_) => {
+				buf.truncate(data_len as _); // data length may have shrunk
+				return Ok(RegFileData::Raw(unsafe { co::REG::from_raw(data_type) }, buf));
+			},
+			Err(co::ERROR::MORE_DATA) => continue, // value changed in a concurrent operation; retry
+			Err(e) => return Err(e),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const REGEDIT5: &str = "\u{feff}Windows Registry Editor Version 5.00\r\n\
+		\r\n\
+		; comment\r\n\
+		[HKEY_CURRENT_USER\\Software\\Test]\r\n\
+		@=\"default\"\r\n\
+		\"Path\"=\"C:\\\\dir \\\"quoted\\\"\"\r\n\
+		\"Count\"=dword:0000002a\r\n\
+		\"Bin\"=hex:01,02,\\\r\n\
+		  ff\r\n\
+		\"Expand\"=hex(2):25,00,41,00,25,00,00,00\r\n\
+		\"Multi\"=hex(7):61,00,00,00,62,00,00,00,00,00\r\n\
+		\"Big\"=hex(b):01,00,00,00,00,00,00,00\r\n\
+		\"Resources\"=hex(8):01,00,00,00\r\n\
+		\"Gone\"=-\r\n\
+		\r\n\
+		[-HKEY_CURRENT_USER\\Software\\Test\\Old]\r\n";
+
+	fn value(name: &str, data: impl Into<RegFileData>) -> RegFileValue {
+		RegFileValue { name: Some(name.to_owned()), value: Some(data.into()) }
+	}
+
+	#[test]
+	fn parse_regedit5() {
+		let reg = RegFile::parse(REGEDIT5).unwrap();
+		assert_eq!(reg.format, RegFileFormat::Regedit5);
+		assert_eq!(reg.keys.len(), 2);
+
+		let key = &reg.keys[0];
+		assert_eq!(key.path, "HKEY_CURRENT_USER\\Software\\Test");
+		assert!(!key.delete);
+		assert_eq!(key.values, [
+			RegFileValue { name: None, value: Some(RegistryValue::Sz("default".to_owned()).into()) },
+			value("Path", RegistryValue::Sz("C:\\dir \"quoted\"".to_owned())),
+			value("Count", RegistryValue::Dword(42)),
+			value("Bin", RegistryValue::Binary(vec![0x01, 0x02, 0xff])),
+			value("Expand", RegistryValue::ExpandSz("%A%".to_owned())),
+			value("Multi", RegistryValue::MultiSz(vec!["a".to_owned(), "b".to_owned()])),
+			value("Big", RegistryValue::Qword(1)),
+			value("Resources", RegFileData::Raw(co::REG::RESOURCE_LIST, vec![1, 0, 0, 0])),
+			RegFileValue { name: Some("Gone".to_owned()), value: None },
+		]);
+
+		let key = &reg.keys[1];
+		assert_eq!(key.path, "HKEY_CURRENT_USER\\Software\\Test\\Old");
+		assert!(key.delete);
+		assert!(key.values.is_empty());
+	}
+
+	#[test]
+	fn parse_regedit4() {
+		let reg = RegFile::parse("REGEDIT4\r\n\r\n\
+			[HKEY_CURRENT_USER\\Software\\Test]\r\n\
+			\"Expand\"=hex(2):25,41,25,e9,00\r\n").unwrap();
+		assert_eq!(reg.format, RegFileFormat::Regedit4);
+		assert_eq!(reg.keys[0].values, [
+			value("Expand", RegistryValue::ExpandSz("%A%\u{e9}".to_owned())),
+		]);
+	}
+
+	#[test]
+	fn parse_malformed() {
+		for text in [
+			"",
+			"REGEDIT5\r\n",
+			"Windows Registry Editor Version 5.00\r\n\"a\"=\"value before key\"\r\n",
+			"Windows Registry Editor Version 5.00\r\n[]\r\n",
+			"Windows Registry Editor Version 5.00\r\n[HKCU\\Test\r\n",
+			"Windows Registry Editor Version 5.00\r\n[HKCU\\Test]\r\n\"a\"=dword:123456789\r\n",
+			"Windows Registry Editor Version 5.00\r\n[HKCU\\Test]\r\n\"a\"=hex:1,,2\r\n",
+			"Windows Registry Editor Version 5.00\r\n[HKCU\\Test]\r\n\"a\"=hex(4):01,02\r\n",
+			"Windows Registry Editor Version 5.00\r\n[HKCU\\Test]\r\n\"a\"=dword:+2a\r\n",
+			"Windows Registry Editor Version 5.00\r\n[HKCU\\Test]\r\n\"a\"=hex(+3):01\r\n",
+			"Windows Registry Editor Version 5.00\r\n[HKCU\\Test]\r\n\"a\"=hex:01,+2\r\n",
+			"Windows Registry Editor Version 5.00\r\n[HKCU\\Test]\r\n\"a\"=\"unclosed\r\n",
+			"Windows Registry Editor Version 5.00\r\n[HKCU\\Test]\r\n\"a\"=what\r\n",
+		] {
+			assert_eq!(RegFile::parse(text).unwrap_err(), co::ERROR::INVALID_DATA, "{:?}", text);
+		}
+	}
+
+	#[test]
+	fn serialize() {
+		let mut reg = RegFile::new(RegFileFormat::Regedit5);
+		reg.keys.push(RegFileKey {
+			path: "HKEY_CURRENT_USER\\Software\\Test".to_owned(),
+			delete: false,
+			values: vec![
+				RegFileValue { name: None, value: Some(RegistryValue::Sz("a\"b".to_owned()).into()) },
+				value("Count", RegistryValue::Dword(0x2a)),
+				value("Lines", RegistryValue::Sz("a\nb".to_owned())),
+				value("Resources", RegFileData::Raw(co::REG::FULL_RESOURCE_DESCRIPTOR, vec![0xab])),
+				RegFileValue { name: Some("Gone".to_owned()), value: None },
+			],
+		});
+		reg.keys.push(RegFileKey {
+			path: "HKEY_CURRENT_USER\\Software\\Test\\Old".to_owned(),
+			delete: true,
+			values: vec![value("Ignored", RegistryValue::Dword(1))],
+		});
+
+		assert_eq!(reg.serialize().unwrap(), "Windows Registry Editor Version 5.00\r\n\
+			\r\n\
+			[HKEY_CURRENT_USER\\Software\\Test]\r\n\
+			@=\"a\\\"b\"\r\n\
+			\"Count\"=dword:0000002a\r\n\
+			\"Lines\"=hex(1):61,00,0a,00,62,00,00,00\r\n\
+			\"Resources\"=hex(9):ab\r\n\
+			\"Gone\"=-\r\n\
+			\r\n\
+			[-HKEY_CURRENT_USER\\Software\\Test\\Old]\r\n\
+			\r\n");
+	}
+
+	#[test]
+	fn serialize_wraps_hex() {
+		let mut reg = RegFile::new(RegFileFormat::Regedit5);
+		reg.keys.push(RegFileKey {
+			path: "HKEY_CURRENT_USER\\Software\\Test".to_owned(),
+			delete: false,
+			values: vec![value("Bin", RegistryValue::Binary((0..=255).collect()))],
+		});
+
+		let text = reg.serialize().unwrap();
+		let lines = text.split("\r\n").skip(3).take_while(|l| !l.is_empty()).collect::<Vec<_>>();
+		assert!(lines.len() > 1);
+		for (idx, line) in lines.iter().enumerate() {
+			assert!(line.len() <= 80, "{:?}", line);
+			assert_eq!(line.ends_with('\\'), idx < lines.len() - 1);
+			if idx > 0 {
+				assert!(line.starts_with("  "));
+			}
+		}
+		assert_eq!(RegFile::parse(&text).unwrap(), reg);
+	}
+
+	#[test]
+	fn round_trip() {
+		let reg = RegFile::parse(REGEDIT5).unwrap();
+		assert_eq!(RegFile::parse(&reg.serialize().unwrap()).unwrap(), reg);
+
+		let bytes = reg.to_bytes().unwrap();
+		assert_eq!(&bytes[..2], &[0xff, 0xfe]); // UTF-16 LE BOM
+		assert_eq!(RegFile::parse_bytes(&bytes).unwrap(), reg);
+
+		let reg4 = RegFile { format: RegFileFormat::Regedit4, ..reg };
+		let bytes = reg4.to_bytes().unwrap();
+		assert!(bytes.starts_with(b"REGEDIT4\r\n"));
+		assert_eq!(RegFile::parse_bytes(&bytes).unwrap(), reg4);
+	}
+
+	#[test]
+	fn paths() {
+		assert_eq!(relative_path("HKEY_CURRENT_USER\\Software", "hkey_current_user\\software\\"), Some(""));
+		assert_eq!(relative_path("HKEY_CURRENT_USER\\Software\\A\\B\\", "HKEY_CURRENT_USER\\Software"), Some("A\\B"));
+		assert_eq!(relative_path("HKEY_CURRENT_USER\\SoftwareX", "HKEY_CURRENT_USER\\Software"), None);
+		assert_eq!(relative_path("HKEY_CURRENT_USER", "HKEY_CURRENT_USER\\Software"), None);
+	}
+}
@@ -0,0 +1,410 @@
+use std::collections::BTreeMap;
+
+use crate::advapi::utilities::reg_file::query_raw_value;
+use crate::co;
+use crate::decl::*;
+use crate::prelude::*;
+
+/// In-memory copy of a registry key, with its values and all its subkeys,
+/// usually taken with
+/// [`HKEY::snapshot`](crate::prelude::advapi_Hkey::snapshot).
+///
+/// Since it's a plain Rust structure, a tree can also be built by hand, and
+/// compared with [`diff`](crate::RegistryTree::diff) without touching the
+/// registry.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RegistryTree {
+	/// The values, by name. The default value has an empty name. Values whose
+	/// type cannot be represented by [`RegistryValue`](crate::RegistryValue)
+	/// are kept as [`RegFileData::Raw`](crate::RegFileData::Raw).
+	pub values: BTreeMap<String, RegFileData>,
+	/// The subkeys, by name.
+	pub subkeys: BTreeMap<String, RegistryTree>,
+}
+
+impl RegistryTree {
+	/// Creates a new, empty tree.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the tree of the given subkey path, like `Foo\Bar`, if any. The
+	/// names are compared case-insensitively, like the registry does.
+	#[must_use]
+	pub fn subkey(&self, path: &str) -> Option<&RegistryTree> {
+		path.split('\\')
+			.filter(|name| !name.is_empty())
+			.try_fold(self, |tree, name| find_ci(&tree.subkeys, name))
+	}
+
+	/// Compares this tree, taken before, with another one, taken after,
+	/// returning all added, removed and changed keys and values. Key and value
+	/// names are compared case-insensitively, like the registry does.
+	///
+	/// The key paths in the returned changes are relative to the root of the
+	/// trees, which is an empty path. When a key is added or removed, all its
+	/// values and subkeys are reported too.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*};
+	///
+	/// let before = w::RegistryTree::new();
+	/// let mut after = w::RegistryTree::new();
+	/// after.values.insert("Color".to_owned(), w::RegistryValue::Sz("blue".to_owned()).into());
+	///
+	/// let changes = before.diff(&after);
+	/// assert_eq!(changes, [w::RegistryChange::ValueAdded {
+	///     key: "".to_owned(),
+	///     name: "Color".to_owned(),
+	///     value: w::RegistryValue::Sz("blue".to_owned()).into(),
+	/// }]);
+	/// ```
+	#[must_use]
+	pub fn diff(&self, after: &RegistryTree) -> Vec<RegistryChange> {
+		let mut changes = Vec::new();
+		diff_trees(self, after, "", &mut changes);
+		changes
+	}
+
+	#[must_use]
+	pub(in crate::advapi) fn read(hkey: &impl advapi_Hkey) -> SysResult<Self> {
+		let mut new_self = Self::new();
+
+		for name_and_type in hkey.RegEnumValue()? {
+			let (name, _) = name_and_type?;
+			let value_name = if name.is_empty() { None } else { Some(name.as_str()) }; // default value
+			let value = match hkey.RegQueryValueEx(value_name) {
+				Ok(value) => RegFileData::Value(value),
+				Err(co::ERROR::CALL_NOT_IMPLEMENTED) => query_raw_value(hkey, value_name)?,
+				Err(e) => return Err(e),
+			};
+			new_self.values.insert(name, value);
+		}
+
+		let sub_names = hkey.RegEnumKeyEx()?.collect::<SysResult<Vec<_>>>()?;
+		for sub_name in sub_names.into_iter() {
+			let hsub = hkey.RegOpenKeyEx(
+				Some(&sub_name),
+				co::REG_OPTION::NoValue,
+				co::KEY::READ,
+			)?;
+			new_self.subkeys.insert(sub_name, Self::read(&*hsub)?);
+		}
+
+		Ok(new_self)
+	}
+}
+
+/// A single change returned by
+/// [`RegistryTree::diff`](crate::RegistryTree::diff).
+///
+/// Key paths are relative to the compared trees; the root is an empty path.
+/// The default value has an empty name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegistryChange {
+	/// A key was added.
+	KeyAdded(String),
+	/// A key was removed.
+	KeyRemoved(String),
+	/// A value was added.
+	ValueAdded {
+		key: String,
+		name: String,
+		value: RegFileData,
+	},
+	/// A value was removed.
+	ValueRemoved {
+		key: String,
+		name: String,
+		value: RegFileData,
+	},
+	/// A value had its type or its data changed.
+	ValueChanged {
+		key: String,
+		name: String,
+		before: RegFileData,
+		after: RegFileData,
+	},
+}
+
+impl std::fmt::Display for RegistryChange {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let val_name = |name: &str| if name.is_empty() { "@".to_owned() } else { name.to_owned() };
+		match self {
+			Self::KeyAdded(key) => write!(f, "+ [{}]", key),
+			Self::KeyRemoved(key) => write!(f, "- [{}]", key),
+			Self::ValueAdded { key, name, value } =>
+				write!(f, "+ [{}] {} = {}", key, val_name(name), value),
+			Self::ValueRemoved { key, name, value } =>
+				write!(f, "- [{}] {} = {}", key, val_name(name), value),
+			Self::ValueChanged { key, name, before, after } =>
+				write!(f, "* [{}] {} = {} -> {}", key, val_name(name), before, after),
+		}
+	}
+}
+
+/// Finds an entry by name, case-insensitively.
+fn find_ci<'a, T>(map: &'a BTreeMap<String, T>, name: &str) -> Option<&'a T> {
+	map.get(name).or_else(|| {
+		let name = name.to_lowercase();
+		map.iter()
+			.find(|(k, _)| k.to_lowercase() == name)
+			.map(|(_, v)| v)
+	})
+}
+
+fn join_path(parent: &str, name: &str) -> String {
+	if parent.is_empty() {
+		name.to_owned()
+	} else {
+		format!("{}\\{}", parent, name)
+	}
+}
+
+fn diff_trees(
+	before: &RegistryTree,
+	after: &RegistryTree,
+	path: &str,
+	changes: &mut Vec<RegistryChange>,
+) {
+	for (name, old_val) in before.values.iter() {
+		match find_ci(&after.values, name) {
+			Some(new_val) => if new_val != old_val {
+				changes.push(RegistryChange::ValueChanged {
+					key: path.to_owned(),
+					name: name.clone(),
+					before: old_val.clone(),
+					after: new_val.clone(),
+				});
+			},
+			None => changes.push(RegistryChange::ValueRemoved {
+				key: path.to_owned(),
+				name: name.clone(),
+				value: old_val.clone(),
+			}),
+		}
+	}
+	for (name, new_val) in after.values.iter() {
+		if find_ci(&before.values, name).is_none() {
+			changes.push(RegistryChange::ValueAdded {
+				key: path.to_owned(),
+				name: name.clone(),
+				value: new_val.clone(),
+			});
+		}
+	}
+
+	for (name, old_sub) in before.subkeys.iter() {
+		let sub_path = join_path(path, name);
+		match find_ci(&after.subkeys, name) {
+			Some(new_sub) => diff_trees(old_sub, new_sub, &sub_path, changes),
+			None => whole_tree(old_sub, &sub_path, false, changes),
+		}
+	}
+	for (name, new_sub) in after.subkeys.iter() {
+		if find_ci(&before.subkeys, name).is_none() {
+			whole_tree(new_sub, &join_path(path, name), true, changes);
+		}
+	}
+}
+
+/// Reports a whole key, with its values and subkeys, as added or removed.
+fn whole_tree(
+	tree: &RegistryTree,
+	path: &str,
+	added: bool,
+	changes: &mut Vec<RegistryChange>,
+) {
+	changes.push(if added {
+		RegistryChange::KeyAdded(path.to_owned())
+	} else {
+		RegistryChange::KeyRemoved(path.to_owned())
+	});
+
+	for (name, value) in tree.values.iter() {
+		let (key, name, value) = (path.to_owned(), name.clone(), value.clone());
+		changes.push(if added {
+			RegistryChange::ValueAdded { key, name, value }
+		} else {
+			RegistryChange::ValueRemoved { key, name, value }
+		});
+	}
+	for (name, sub) in tree.subkeys.iter() {
+		whole_tree(sub, &join_path(path, name), added, changes);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn tree(values: &[(&str, RegFileData)], subkeys: &[(&str, RegistryTree)]) -> RegistryTree {
+		RegistryTree {
+			values: values.iter()
+				.map(|(name, value)| (name.to_string(), value.clone()))
+				.collect(),
+			subkeys: subkeys.iter()
+				.map(|(name, sub)| (name.to_string(), sub.clone()))
+				.collect(),
+		}
+	}
+
+	fn sz(s: &str) -> RegFileData {
+		RegistryValue::Sz(s.to_owned()).into()
+	}
+
+	fn dword(n: u32) -> RegFileData {
+		RegistryValue::Dword(n).into()
+	}
+
+	#[test]
+	fn diff_identical() {
+		let t = tree(&[("", sz("default")), ("A", dword(1))], &[
+			("Sub", tree(&[("B", sz("b"))], &[])),
+		]);
+		assert!(t.diff(&t.clone()).is_empty());
+		assert!(RegistryTree::new().diff(&RegistryTree::new()).is_empty());
+	}
+
+	#[test]
+	fn diff_values() {
+		let before = tree(&[
+			("Kept", sz("same")),
+			("Changed", dword(1)),
+			("Retyped", dword(2)),
+			("Removed", sz("x")),
+		], &[]);
+		let after = tree(&[
+			("Kept", sz("same")),
+			("Changed", dword(10)),
+			("Retyped", RegistryValue::Qword(2).into()),
+			("", sz("new default")),
+		], &[]);
+
+		assert_eq!(before.diff(&after), [
+			RegistryChange::ValueChanged {
+				key: "".to_owned(),
+				name: "Changed".to_owned(),
+				before: dword(1),
+				after: dword(10),
+			},
+			RegistryChange::ValueRemoved {
+				key: "".to_owned(),
+				name: "Removed".to_owned(),
+				value: sz("x"),
+			},
+			RegistryChange::ValueChanged {
+				key: "".to_owned(),
+				name: "Retyped".to_owned(),
+				before: dword(2),
+				after: RegistryValue::Qword(2).into(),
+			},
+			RegistryChange::ValueAdded {
+				key: "".to_owned(),
+				name: "".to_owned(),
+				value: sz("new default"),
+			},
+		]);
+	}
+
+	#[test]
+	fn diff_keys() {
+		let before = tree(&[], &[
+			("Old", tree(&[("A", sz("a"))], &[("Deep", tree(&[], &[]))])),
+			("Kept", tree(&[("B", sz("b"))], &[])),
+		]);
+		let after = tree(&[], &[
+			("Kept", tree(&[("B", sz("c"))], &[])),
+			("New", tree(&[("C", sz("c"))], &[])),
+		]);
+
+		assert_eq!(before.diff(&after), [
+			RegistryChange::ValueChanged {
+				key: "Kept".to_owned(),
+				name: "B".to_owned(),
+				before: sz("b"),
+				after: sz("c"),
+			},
+			RegistryChange::KeyRemoved("Old".to_owned()),
+			RegistryChange::ValueRemoved {
+				key: "Old".to_owned(),
+				name: "A".to_owned(),
+				value: sz("a"),
+			},
+			RegistryChange::KeyRemoved("Old\\Deep".to_owned()),
+			RegistryChange::KeyAdded("New".to_owned()),
+			RegistryChange::ValueAdded {
+				key: "New".to_owned(),
+				name: "C".to_owned(),
+				value: sz("c"),
+			},
+		]);
+	}
+
+	#[test]
+	fn diff_raw_values() {
+		let raw = |reg_type: co::REG, bytes: &[u8]| RegFileData::Raw(reg_type, bytes.to_vec());
+		let before = tree(&[
+			("Big", raw(co::REG::DWORD_BIG_ENDIAN, &[0, 0, 0, 1])),
+			("Link", raw(co::REG::LINK, &[0x41, 0])),
+		], &[]);
+		let after = tree(&[
+			("Big", raw(co::REG::DWORD_BIG_ENDIAN, &[0, 0, 0, 2])),
+			("Resources", raw(co::REG::RESOURCE_LIST, &[1, 0, 0, 0])),
+		], &[]);
+
+		assert_eq!(before.diff(&after), [
+			RegistryChange::ValueChanged {
+				key: "".to_owned(),
+				name: "Big".to_owned(),
+				before: raw(co::REG::DWORD_BIG_ENDIAN, &[0, 0, 0, 1]),
+				after: raw(co::REG::DWORD_BIG_ENDIAN, &[0, 0, 0, 2]),
+			},
+			RegistryChange::ValueRemoved {
+				key: "".to_owned(),
+				name: "Link".to_owned(),
+				value: raw(co::REG::LINK, &[0x41, 0]),
+			},
+			RegistryChange::ValueAdded {
+				key: "".to_owned(),
+				name: "Resources".to_owned(),
+				value: raw(co::REG::RESOURCE_LIST, &[1, 0, 0, 0]),
+			},
+		]);
+		assert_eq!(before.diff(&after)[2].to_string(), "+ [] Resources = [hex(8)] 01 00 00 00");
+	}
+
+	#[test]
+	fn diff_case_insensitive() {
+		let before = tree(&[("Value", sz("v"))], &[("Key", tree(&[], &[]))]);
+		let after = tree(&[("VALUE", sz("v"))], &[("KEY", tree(&[], &[]))]);
+		assert!(before.diff(&after).is_empty());
+	}
+
+	#[test]
+	fn subkey() {
+		let t = tree(&[], &[
+			("Foo", tree(&[], &[("Bar", tree(&[("A", sz("a"))], &[]))])),
+		]);
+		assert_eq!(t.subkey("foo\\BAR").unwrap().values["A"], sz("a"));
+		assert_eq!(t.subkey("Foo\\").unwrap(), &t.subkeys["Foo"]);
+		assert_eq!(t.subkey(""), Some(&t));
+		assert!(t.subkey("Foo\\Baz").is_none());
+	}
+
+	#[test]
+	fn display() {
+		assert_eq!(RegistryChange::KeyAdded("A\\B".to_owned()).to_string(), "+ [A\\B]");
+		assert_eq!(
+			RegistryChange::ValueRemoved {
+				key: "A".to_owned(),
+				name: "".to_owned(),
+				value: dword(3),
+			}.to_string(),
+			format!("- [A] @ = {}", dword(3)),
+		);
+	}
+}
@@ -0,0 +1,137 @@
+use std::marker::PhantomData;
+
+use crate::co;
+use crate::decl::*;
+use crate::guard::*;
+use crate::prelude::*;
+
+/// Watches a registry key for changes, by calling
+/// [`HKEY::RegNotifyChangeKeyValue`](crate::prelude::advapi_Hkey::RegNotifyChangeKeyValue)
+/// asynchronously, with an [`HEVENT`](crate::HEVENT) which is signaled when a
+/// change happens.
+///
+/// The notification is automatically registered again after each change is
+/// reported by [`wait`](crate::RegistryWatch::wait). Note that the
+/// notification is tied to the thread which registered it, unless
+/// [`co::REG_NOTIFY::THREAD_AGNOSTIC`](crate::co::REG_NOTIFY::THREAD_AGNOSTIC)
+/// is passed.
+///
+/// # Examples
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*, co};
+///
+/// let hkey = w::HKEY::CURRENT_USER.RegOpenKeyEx(
+///     Some("Software\\My Company"),
+///     co::REG_OPTION::default(),
+///     co::KEY::NOTIFY | co::KEY::READ,
+/// )?;
+///
+/// let watch = w::RegistryWatch::new(
+///     &hkey,
+///     true,
+///     co::REG_NOTIFY::NAME | co::REG_NOTIFY::LAST_SET,
+/// )?;
+///
+/// let mut before = hkey.snapshot(None)?;
+/// loop {
+///     watch.wait(None)?;
+///     let after = hkey.snapshot(None)?;
+///     for change in before.diff(&after) {
+///         println!("{change}");
+///     }
+///     before = after;
+/// }
+/// # w::SysResult::Ok(())
+/// ```
+pub struct RegistryWatch<'a> {
+	hkey: HKEY,
+	hevent: CloseHandleGuard<HEVENT>,
+	watch_subtree: bool,
+	notify_filter: co::REG_NOTIFY,
+	_hkey: PhantomData<&'a HKEY>,
+}
+
+impl<'a> RegistryWatch<'a> {
+	/// Creates a new auto-reset event and registers the notification. The key
+	/// must have been opened with [`co::KEY::NOTIFY`](crate::co::KEY::NOTIFY)
+	/// access.
+	#[must_use]
+	pub fn new(
+		hkey: &'a HKEY,
+		watch_subtree: bool,
+		notify_filter: co::REG_NOTIFY,
+	) -> SysResult<Self>
+	{
+		let new_self = Self {
+			hkey: unsafe { hkey.raw_copy() },
+			hevent: HEVENT::CreateEvent(None, false, false, None)?,
+			watch_subtree,
+			notify_filter,
+			_hkey: PhantomData,
+		};
+		new_self.register()?;
+		Ok(new_self)
+	}
+
+	/// Returns the event which is signaled when a change happens, so it can be
+	/// waited along with other objects.
+	///
+	/// If the event is waited directly, call
+	/// [`register`](crate::RegistryWatch::register) after it's signaled, so
+	/// the notification is registered again. Since the event is auto-reset, it
+	/// is no longer signaled at this point, thus
+	/// [`wait`](crate::RegistryWatch::wait) would not register it.
+	#[must_use]
+	pub fn hevent(&self) -> &HEVENT {
+		&self.hevent
+	}
+
+	/// Blocks until a change happens, or the timeout elapses, in which case
+	/// `false` is returned. If `milliseconds` is `None`, waits forever.
+	///
+	/// When a change happens, the notification is registered again before
+	/// returning `true`. Note that several changes may be reported at once.
+	pub fn wait(&self, milliseconds: Option<u32>) -> SysResult<bool> {
+		match self.hevent.WaitForSingleObject(milliseconds)? {
+			co::WAIT::TIMEOUT => Ok(false),
+			_ => {
+				self.register()?;
+				Ok(true)
+			},
+		}
+	}
+
+	/// Registers the notification again. This is automatically done by
+	/// [`wait`](crate::RegistryWatch::wait), and must be called only when the
+	/// event returned by [`hevent`](crate::RegistryWatch::hevent) is waited
+	/// directly, after it's signaled.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hkey: w::HKEY; // initialized somewhere
+	/// # let hkey = w::HKEY::NULL;
+	///
+	/// let watch = w::RegistryWatch::new(&hkey, true, co::REG_NOTIFY::LAST_SET)?;
+	///
+	/// loop {
+	///     if watch.hevent().WaitForSingleObject(Some(100))? == co::WAIT::OBJECT_0 {
+	///         watch.register()?;
+	///         println!("Key changed.");
+	///     }
+	///     // do other work...
+	/// }
+	/// # w::SysResult::Ok(())
+	/// ```
+	pub fn register(&self) -> SysResult<()> {
+		self.hkey.RegNotifyChangeKeyValue(
+			self.watch_subtree,
+			self.notify_filter,
+			Some(&self.hevent),
+			true,
+		)
+	}
+}
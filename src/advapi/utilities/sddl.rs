@@ -0,0 +1,533 @@
+//! Pure-Rust parser and formatter of the
+//! [SDDL](https://learn.microsoft.com/en-us/windows/win32/secauthz/security-descriptor-string-format)
+//! form of a [`SecurityDescriptor`](crate::SecurityDescriptor).
+
+use crate::co;
+use crate::decl::*;
+use crate::prelude::*;
+
+/// SID aliases which don't depend on the domain, and their SIDs.
+pub(in crate::advapi) const SID_ALIASES: &[(&str, &str)] = &[
+	("AA", "S-1-5-32-579"),
+	("AC", "S-1-15-2-1"),
+	("AN", "S-1-5-7"),
+	("AO", "S-1-5-32-548"),
+	("AS", "S-1-18-1"),
+	("AU", "S-1-5-11"),
+	("BA", "S-1-5-32-544"),
+	("BG", "S-1-5-32-546"),
+	("BO", "S-1-5-32-551"),
+	("BU", "S-1-5-32-545"),
+	("CD", "S-1-5-32-574"),
+	("CG", "S-1-3-1"),
+	("CO", "S-1-3-0"),
+	("CY", "S-1-5-32-569"),
+	("ED", "S-1-5-9"),
+	("ER", "S-1-5-32-573"),
+	("ES", "S-1-5-32-576"),
+	("HA", "S-1-5-32-578"),
+	("HI", "S-1-16-12288"),
+	("IS", "S-1-5-32-568"),
+	("IU", "S-1-5-4"),
+	("LS", "S-1-5-19"),
+	("LU", "S-1-5-32-559"),
+	("LW", "S-1-16-4096"),
+	("ME", "S-1-16-8192"),
+	("MS", "S-1-5-32-577"),
+	("MU", "S-1-5-32-558"),
+	("NO", "S-1-5-32-556"),
+	("NS", "S-1-5-20"),
+	("NU", "S-1-5-2"),
+	("OW", "S-1-3-4"),
+	("PO", "S-1-5-32-550"),
+	("PS", "S-1-5-10"),
+	("PU", "S-1-5-32-547"),
+	("RA", "S-1-5-32-575"),
+	("RC", "S-1-5-12"),
+	("RD", "S-1-5-32-555"),
+	("RE", "S-1-5-32-552"),
+	("RM", "S-1-5-32-580"),
+	("RU", "S-1-5-32-554"),
+	("SI", "S-1-16-16384"),
+	("SO", "S-1-5-32-549"),
+	("SS", "S-1-18-2"),
+	("SU", "S-1-5-6"),
+	("SY", "S-1-5-18"),
+	("WD", "S-1-1-0"),
+	("WR", "S-1-5-33"),
+];
+
+/// Access rights aliases which are written only when matching the whole mask.
+const RIGHTS_WHOLE: &[(&str, u32)] = &[
+	("FA", 0x001f_01ff),
+	("FR", 0x0012_0089),
+	("FW", 0x0012_0116),
+	("FX", 0x0012_00a0),
+	("KA", 0x000f_003f),
+	("KR", 0x0002_0019),
+	("KW", 0x0002_0006),
+	("KX", 0x0002_0019),
+];
+
+/// Access rights aliases which compose a mask, in the order they're written.
+const RIGHTS_BITS: &[(&str, u32)] = &[
+	("GA", 0x1000_0000),
+	("GR", 0x8000_0000),
+	("GW", 0x4000_0000),
+	("GX", 0x2000_0000),
+	("CC", 0x0000_0001),
+	("DC", 0x0000_0002),
+	("LC", 0x0000_0004),
+	("SW", 0x0000_0008),
+	("RP", 0x0000_0010),
+	("WP", 0x0000_0020),
+	("DT", 0x0000_0040),
+	("LO", 0x0000_0080),
+	("CR", 0x0000_0100),
+	("SD", 0x0001_0000),
+	("RC", 0x0002_0000),
+	("WD", 0x0004_0000),
+	("WO", 0x0008_0000),
+];
+
+/// Access rights aliases of mandatory label ACEs.
+const RIGHTS_LABEL: &[(&str, u32)] = &[
+	("NR", 0x0000_0002),
+	("NW", 0x0000_0001),
+	("NX", 0x0000_0004),
+];
+
+const ACE_TYPES: &[(&str, co::ACE_TYPE)] = &[
+	("A", co::ACE_TYPE::ACCESS_ALLOWED),
+	("D", co::ACE_TYPE::ACCESS_DENIED),
+	("AU", co::ACE_TYPE::SYSTEM_AUDIT),
+	("AL", co::ACE_TYPE::SYSTEM_ALARM),
+	("ML", co::ACE_TYPE::SYSTEM_MANDATORY_LABEL),
+];
+
+const ACE_FLAGS: &[(&str, co::ACE_FLAG)] = &[
+	("OI", co::ACE_FLAG::OBJECT_INHERIT),
+	("CI", co::ACE_FLAG::CONTAINER_INHERIT),
+	("NP", co::ACE_FLAG::NO_PROPAGATE_INHERIT),
+	("IO", co::ACE_FLAG::INHERIT_ONLY),
+	("ID", co::ACE_FLAG::INHERITED),
+	("SA", co::ACE_FLAG::SUCCESSFUL_ACCESS),
+	("FA", co::ACE_FLAG::FAILED_ACCESS),
+];
+
+/// Parses an SDDL string.
+pub(in crate::advapi) fn parse(sddl: &str) -> SysResult<SecurityDescriptor> {
+	let mut sd = SecurityDescriptor::new();
+
+	for (tag, value) in split_components(sddl.trim())?.into_iter() {
+		match tag {
+			'O' => sd.owner = Some(parse_sid(value)?),
+			'G' => sd.group = Some(parse_sid(value)?),
+			'D' => {
+				let (control, aces) = parse_acl(value, true)?;
+				sd.control |= control;
+				if aces.is_none() {
+					sd.control |= co::SE::DACL_PRESENT; // null DACL
+				}
+				sd.dacl = aces;
+			},
+			_ => {
+				let (control, aces) = parse_acl(value, false)?;
+				sd.control |= control;
+				if aces.is_none() {
+					sd.control |= co::SE::SACL_PRESENT; // null SACL
+				}
+				sd.sacl = aces;
+			},
+		}
+	}
+	Ok(sd)
+}
+
+/// Formats a security descriptor as an SDDL string. An ACL which is not
+/// present is omitted, while a null ACL is written as `NO_ACCESS_CONTROL`.
+pub(in crate::advapi) fn format(sd: &SecurityDescriptor) -> String {
+	let mut s = String::new();
+	if let Some(owner) = &sd.owner {
+		s.push_str("O:");
+		s.push_str(&format_sid(owner));
+	}
+	if let Some(group) = &sd.group {
+		s.push_str("G:");
+		s.push_str(&format_sid(group));
+	}
+	format_component('D', sd.control, co::SE::DACL_PRESENT, sd.dacl.as_deref(), &mut s);
+	format_component('S', sd.control, co::SE::SACL_PRESENT, sd.sacl.as_deref(), &mut s);
+	s
+}
+
+/// Writes the `D:` or `S:` component, if the ACL is present.
+fn format_component(
+	tag: char,
+	control: co::SE,
+	present: co::SE,
+	aces: Option<&[Ace]>,
+	s: &mut String,
+) {
+	match aces {
+		Some(aces) => {
+			s.push(tag);
+			s.push(':');
+			format_acl(control, aces, tag == 'D', s);
+		},
+		None => if control.has(present) {
+			s.push(tag);
+			s.push(':');
+			format_acl(control, &[], tag == 'D', s);
+			s.push_str("NO_ACCESS_CONTROL"); // null ACL
+		},
+	}
+}
+
+/// Parses a SID alias, or a SID in the `S-1-5-18` form.
+pub(in crate::advapi) fn parse_sid(s: &str) -> SysResult<Sid> {
+	let s = s.trim();
+	match SID_ALIASES.iter().find(|(alias, _)| *alias == s) {
+		Some((_, str_sid)) => str_sid.parse(),
+		None => if s.starts_with("S-") || s.starts_with("s-") {
+			s.parse()
+		} else {
+			Err(co::ERROR::INVALID_DATA) // unknown or domain-dependent alias
+		},
+	}
+}
+
+/// Formats a SID with its alias, if any.
+pub(in crate::advapi) fn format_sid(sid: &Sid) -> String {
+	let str_sid = sid.to_string();
+	SID_ALIASES.iter()
+		.find(|(_, s)| *s == str_sid)
+		.map_or(str_sid, |(alias, _)| (*alias).to_owned())
+}
+
+/// Splits the string into its `O:`, `G:`, `D:` and `S:` components, each of
+/// which can appear only once.
+fn split_components(sddl: &str) -> SysResult<Vec<(char, &str)>> {
+	let bytes = sddl.as_bytes();
+	let is_tag = |idx: usize| {
+		matches!(bytes[idx], b'O' | b'G' | b'D' | b'S')
+			&& bytes.get(idx + 1) == Some(&b':')
+	};
+
+	let mut components = Vec::<(char, &str)>::new();
+	let mut depth = 0;
+	let mut start = None::<(char, usize)>;
+	let mut idx = 0;
+	while idx < bytes.len() {
+		match bytes[idx] {
+			b'(' => depth += 1,
+			b')' => depth -= 1,
+			_ if depth == 0 && is_tag(idx) => {
+				if let Some((tag, from)) = start {
+					components.push((tag, &sddl[from..idx]));
+				}
+				let tag = bytes[idx] as char;
+				if components.iter().any(|(t, _)| *t == tag) {
+					return Err(co::ERROR::INVALID_DATA); // repeated component
+				}
+				start = Some((tag, idx + 2));
+				idx += 2;
+				continue;
+			},
+			_ => if start.is_none() {
+				return Err(co::ERROR::INVALID_DATA); // text before first component
+			},
+		}
+		idx += 1;
+	}
+	if depth != 0 {
+		return Err(co::ERROR::INVALID_DATA);
+	}
+	if let Some((tag, from)) = start {
+		components.push((tag, &sddl[from..]));
+	}
+	Ok(components)
+}
+
+/// Parses the flags and the ACEs of a DACL or SACL.
+fn parse_acl(s: &str, is_dacl: bool) -> SysResult<(co::SE, Option<Vec<Ace>>)> {
+	let (protected, auto_req, auto_inh) = if is_dacl {
+		(co::SE::DACL_PROTECTED, co::SE::DACL_AUTO_INHERIT_REQ, co::SE::DACL_AUTO_INHERITED)
+	} else {
+		(co::SE::SACL_PROTECTED, co::SE::SACL_AUTO_INHERIT_REQ, co::SE::SACL_AUTO_INHERITED)
+	};
+
+	let flags_end = s.find('(').unwrap_or(s.len());
+	let mut flags = &s[..flags_end];
+	let mut control = co::SE::default();
+	let mut null_acl = false;
+	while !flags.is_empty() {
+		if let Some(rest) = flags.strip_prefix("NO_ACCESS_CONTROL") {
+			null_acl = true;
+			flags = rest;
+		} else if let Some(rest) = flags.strip_prefix("AR") {
+			control |= auto_req;
+			flags = rest;
+		} else if let Some(rest) = flags.strip_prefix("AI") {
+			control |= auto_inh;
+			flags = rest;
+		} else if let Some(rest) = flags.strip_prefix('P') {
+			control |= protected;
+			flags = rest;
+		} else {
+			return Err(co::ERROR::INVALID_DATA);
+		}
+	}
+
+	let mut aces = Vec::new();
+	let mut rest = &s[flags_end..];
+	while !rest.is_empty() {
+		let close = match rest.strip_prefix('(') {
+			Some(_) => rest.find(')').ok_or(co::ERROR::INVALID_DATA)?,
+			None => return Err(co::ERROR::INVALID_DATA),
+		};
+		aces.push(parse_ace(&rest[1..close])?);
+		rest = &rest[close + 1..];
+	}
+
+	if null_acl {
+		if !aces.is_empty() {
+			return Err(co::ERROR::INVALID_DATA);
+		}
+		Ok((control, None))
+	} else {
+		Ok((control, Some(aces)))
+	}
+}
+
+/// Parses a single ACE, without the parentheses.
+fn parse_ace(s: &str) -> SysResult<Ace> {
+	let fields = s.split(';').collect::<Vec<_>>();
+	if fields.len() < 6 {
+		return Err(co::ERROR::INVALID_DATA);
+	}
+	if fields.len() > 6 || !fields[3].is_empty() || !fields[4].is_empty() {
+		return Err(co::ERROR::CALL_NOT_IMPLEMENTED); // resource attribute or object ACE
+	}
+
+	let ace_type = match ACE_TYPES.iter().find(|(name, _)| *name == fields[0]) {
+		Some((_, ace_type)) => *ace_type,
+		None => match fields[0] {
+			"OA" | "OD" | "OU" | "OL" | "XA" | "XD" | "XU" | "ZA" | "RA" | "SP"
+				=> return Err(co::ERROR::CALL_NOT_IMPLEMENTED),
+			_ => return Err(co::ERROR::INVALID_DATA),
+		},
+	};
+
+	let mut flags = co::ACE_FLAG::default();
+	for code in pairs(fields[1])?.into_iter() {
+		flags |= ACE_FLAGS.iter()
+			.find(|(name, _)| *name == code)
+			.map(|(_, f)| *f)
+			.ok_or(co::ERROR::INVALID_DATA)?;
+	}
+
+	Ok(Ace {
+		ace_type,
+		flags,
+		access_mask: parse_rights(fields[2])?,
+		sid: parse_sid(fields[5])?,
+	})
+}
+
+fn parse_rights(s: &str) -> SysResult<u32> {
+	if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+		return u32::from_str_radix(hex, 16).map_err(|_| co::ERROR::INVALID_DATA);
+	} else if s.starts_with(|ch: char| ch.is_ascii_digit()) {
+		return s.parse::<u32>().map_err(|_| co::ERROR::INVALID_DATA);
+	}
+
+	let mut mask = 0;
+	for code in pairs(s)?.into_iter() {
+		mask |= RIGHTS_WHOLE.iter()
+			.chain(RIGHTS_BITS.iter())
+			.chain(RIGHTS_LABEL.iter())
+			.find(|(name, _)| *name == code)
+			.map(|(_, m)| *m)
+			.ok_or(co::ERROR::INVALID_DATA)?;
+	}
+	Ok(mask)
+}
+
+/// Splits a string of two-letter codes.
+fn pairs(s: &str) -> SysResult<Vec<&str>> {
+	if s.len() % 2 != 0 || !s.is_ascii() {
+		return Err(co::ERROR::INVALID_DATA);
+	}
+	Ok((0..s.len()).step_by(2).map(|idx| &s[idx..idx + 2]).collect())
+}
+
+fn format_acl(control: co::SE, aces: &[Ace], is_dacl: bool, s: &mut String) {
+	let flags: [(co::SE, &str); 3] = if is_dacl {
+		[
+			(co::SE::DACL_PROTECTED, "P"),
+			(co::SE::DACL_AUTO_INHERIT_REQ, "AR"),
+			(co::SE::DACL_AUTO_INHERITED, "AI"),
+		]
+	} else {
+		[
+			(co::SE::SACL_PROTECTED, "P"),
+			(co::SE::SACL_AUTO_INHERIT_REQ, "AR"),
+			(co::SE::SACL_AUTO_INHERITED, "AI"),
+		]
+	};
+	for (flag, code) in flags.iter() {
+		if control.has(*flag) {
+			s.push_str(code);
+		}
+	}
+
+	for ace in aces.iter() {
+		s.push('(');
+		match ACE_TYPES.iter().find(|(_, t)| *t == ace.ace_type) {
+			Some((name, _)) => s.push_str(name),
+			None => s.push_str(&format!("0x{:x}", ace.ace_type.raw())), // not representable
+		}
+		s.push(';');
+		for (name, flag) in ACE_FLAGS.iter() {
+			if ace.flags.has(*flag) {
+				s.push_str(name);
+			}
+		}
+		s.push(';');
+		s.push_str(&format_rights(ace.access_mask, ace.ace_type));
+		s.push_str(";;;");
+		s.push_str(&format_sid(&ace.sid));
+		s.push(')');
+	}
+}
+
+fn format_rights(mask: u32, ace_type: co::ACE_TYPE) -> String {
+	let bits = if ace_type == co::ACE_TYPE::SYSTEM_MANDATORY_LABEL {
+		RIGHTS_LABEL
+	} else {
+		if let Some((name, _)) = RIGHTS_WHOLE.iter().find(|(_, m)| *m == mask) {
+			return (*name).to_owned();
+		}
+		RIGHTS_BITS
+	};
+
+	let mut s = String::new();
+	let mut remaining = mask;
+	for (name, m) in bits.iter() {
+		if mask & m != 0 {
+			s.push_str(name);
+			remaining &= !m;
+		}
+	}
+	if remaining != 0 || mask == 0 {
+		format!("0x{:x}", mask)
+	} else {
+		s
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn round_trip(sddl: &str) -> String {
+		format(&parse(sddl).unwrap())
+	}
+
+	#[test]
+	fn aliases() {
+		let sd = parse("O:BAG:SYD:(A;;GA;;;S-1-5-32-545)").unwrap();
+		assert_eq!(sd.owner.as_ref().unwrap().to_string(), "S-1-5-32-544");
+		assert_eq!(sd.group.as_ref().unwrap().to_string(), "S-1-5-18");
+		assert_eq!(format(&sd), "O:BAG:SYD:(A;;GA;;;BU)");
+
+		assert_eq!(round_trip("O:S-1-5-21-1-2-3-500D:"), "O:S-1-5-21-1-2-3-500D:");
+		assert_eq!(parse("O:DA").unwrap_err(), co::ERROR::INVALID_DATA); // domain-dependent
+		assert_eq!(parse("O:XX").unwrap_err(), co::ERROR::INVALID_DATA);
+	}
+
+	#[test]
+	fn acl_flags() {
+		let sd = parse("D:PAI(A;OICI;GA;;;SY)S:AI").unwrap();
+		assert_eq!(
+			sd.control,
+			co::SE::DACL_PROTECTED | co::SE::DACL_AUTO_INHERITED | co::SE::SACL_AUTO_INHERITED,
+		);
+		assert_eq!(sd.dacl.as_ref().unwrap()[0].flags,
+			co::ACE_FLAG::OBJECT_INHERIT | co::ACE_FLAG::CONTAINER_INHERIT);
+		assert_eq!(format(&sd), "D:PAI(A;OICI;GA;;;SY)S:AI");
+		assert_eq!(parse("D:X(A;;GA;;;SY)").unwrap_err(), co::ERROR::INVALID_DATA);
+	}
+
+	#[test]
+	fn null_and_empty_dacl() {
+		let sd = parse("D:NO_ACCESS_CONTROL").unwrap();
+		assert_eq!(sd.dacl, None);
+		assert_eq!(sd.control, co::SE::DACL_PRESENT);
+		assert_eq!(format(&sd), "D:NO_ACCESS_CONTROL");
+		assert_eq!(round_trip("D:PNO_ACCESS_CONTROL"), "D:PNO_ACCESS_CONTROL");
+		assert_eq!(round_trip("S:NO_ACCESS_CONTROL"), "S:NO_ACCESS_CONTROL");
+
+		// Not present at all: owner-only or SACL-only descriptors have no D:.
+		assert_eq!(format(&SecurityDescriptor::new()), "");
+		assert_eq!(round_trip("O:BA"), "O:BA");
+		assert_eq!(round_trip("O:BAS:"), "O:BAS:");
+
+		let sd = parse("D:").unwrap();
+		assert_eq!(sd.dacl, Some(Vec::new()));
+		assert_eq!(format(&sd), "D:");
+
+		assert_eq!(parse("D:NO_ACCESS_CONTROL(A;;GA;;;SY)").unwrap_err(), co::ERROR::INVALID_DATA);
+	}
+
+	#[test]
+	fn repeated_components() {
+		assert_eq!(parse("D:(A;;GA;;;SY)D:(A;;GR;;;BU)").unwrap_err(), co::ERROR::INVALID_DATA);
+		assert_eq!(parse("O:BAO:SY").unwrap_err(), co::ERROR::INVALID_DATA);
+		assert_eq!(parse("O:BAD:S:G:SYS:").unwrap_err(), co::ERROR::INVALID_DATA);
+	}
+
+	#[test]
+	fn whole_masks() {
+		let sd = parse("D:(A;;FA;;;BA)(A;;KA;;;SY)(A;;KR;;;BU)").unwrap();
+		let masks = sd.dacl.as_ref().unwrap().iter().map(|ace| ace.access_mask).collect::<Vec<_>>();
+		assert_eq!(masks, [0x001f_01ff, 0x000f_003f, 0x0002_0019]);
+		assert_eq!(format(&sd), "D:(A;;FA;;;BA)(A;;KA;;;SY)(A;;KR;;;BU)");
+		assert_eq!(round_trip("D:(A;;KX;;;BU)"), "D:(A;;KR;;;BU)"); // same mask
+	}
+
+	#[test]
+	fn hex_masks() {
+		assert_eq!(round_trip("D:(A;;0x1f01ff;;;BA)"), "D:(A;;FA;;;BA)");
+		assert_eq!(round_trip("D:(A;;0x10000000;;;BA)"), "D:(A;;GA;;;BA)");
+		assert_eq!(round_trip("D:(A;;0x30000;;;BA)"), "D:(A;;SDRC;;;BA)");
+		assert_eq!(round_trip("D:(A;;0x1200a9;;;BA)"), "D:(A;;0x1200a9;;;BA)"); // SYNCHRONIZE has no alias
+		assert_eq!(round_trip("D:(A;;0;;;BA)"), "D:(A;;0x0;;;BA)");
+		assert_eq!(parse("D:(A;;0xZZ;;;BA)").unwrap_err(), co::ERROR::INVALID_DATA);
+	}
+
+	#[test]
+	fn mandatory_label() {
+		let sd = parse("S:(ML;;NWNR;;;HI)").unwrap();
+		let ace = &sd.sacl.as_ref().unwrap()[0];
+		assert_eq!(ace.ace_type, co::ACE_TYPE::SYSTEM_MANDATORY_LABEL);
+		assert_eq!(ace.access_mask, 0x0000_0003);
+		assert_eq!(ace.sid.to_string(), "S-1-16-12288");
+		assert_eq!(format(&sd), "S:(ML;;NRNW;;;HI)");
+	}
+
+	#[test]
+	fn unsupported_aces() {
+		for sddl in [
+			"D:(OA;;CR;ab721a53-1e2f-11d0-9819-00aa0040529b;;WD)",
+			"D:(A;;GA;ab721a53-1e2f-11d0-9819-00aa0040529b;;WD)",
+			"D:(XA;;FX;;;WD;(Member_of {SID(BA)}))",
+			"S:(RA;;;;;WD;(\"Project\",TS,0,\"Windows\"))",
+		] {
+			assert_eq!(parse(sddl).unwrap_err(), co::ERROR::CALL_NOT_IMPLEMENTED, "{}", sddl);
+		}
+		assert_eq!(parse("D:(Q;;GA;;;WD)").unwrap_err(), co::ERROR::INVALID_DATA);
+		assert_eq!(parse("D:(A;;GA;;WD)").unwrap_err(), co::ERROR::INVALID_DATA);
+	}
+}
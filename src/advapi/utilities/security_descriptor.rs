@@ -0,0 +1,516 @@
+use crate::advapi::utilities::sddl;
+use crate::co;
+use crate::decl::*;
+use crate::prelude::*;
+
+/// `ACL_REVISION`, used for ACLs without object ACEs.
+const ACL_REVISION: u8 = 2;
+
+/// `SECURITY_DESCRIPTOR_REVISION`.
+const SECURITY_DESCRIPTOR_REVISION: u8 = 1;
+
+/// Size of `SECURITY_DESCRIPTOR_RELATIVE`.
+const SD_HEADER_LEN: usize = 20;
+
+/// An owned access control entry, which is part of the DACL or the SACL of a
+/// [`SecurityDescriptor`](crate::SecurityDescriptor).
+///
+/// Only ACEs with a plain access mask and a [`Sid`](crate::Sid) are
+/// represented: allowed, denied, audit, alarm and mandatory label. Object and
+/// callback ACEs are not supported.
+///
+/// # Examples
+///
+/// Allowing the users to read, in the current folder and its subfolders:
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*, co};
+///
+/// let ace = w::Ace::allow(
+///     "S-1-5-32-545".parse()?,
+///     co::GENERIC::READ.raw(),
+/// ).flags(co::ACE_FLAG::CONTAINER_INHERIT | co::ACE_FLAG::OBJECT_INHERIT);
+/// # w::SysResult::Ok(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ace {
+	/// The type of the ACE.
+	pub ace_type: co::ACE_TYPE,
+	/// Inheritance and audit flags.
+	pub flags: co::ACE_FLAG,
+	/// The access rights, which are specific to each object type, like
+	/// [`co::SERVICE`](crate::co::SERVICE) or
+	/// [`co::KEY`](crate::co::KEY), or generic ones, like
+	/// [`co::GENERIC`](crate::co::GENERIC).
+	pub access_mask: u32,
+	/// The trustee.
+	pub sid: Sid,
+}
+
+impl Ace {
+	/// Creates a new ACE, without flags.
+	#[must_use]
+	pub fn new(ace_type: co::ACE_TYPE, access_mask: u32, sid: Sid) -> Self {
+		Self {
+			ace_type,
+			flags: co::ACE_FLAG::default(),
+			access_mask,
+			sid,
+		}
+	}
+
+	/// Creates a new [`co::ACE_TYPE::ACCESS_ALLOWED`](crate::co::ACE_TYPE::ACCESS_ALLOWED)
+	/// ACE.
+	#[must_use]
+	pub fn allow(sid: Sid, access_mask: u32) -> Self {
+		Self::new(co::ACE_TYPE::ACCESS_ALLOWED, access_mask, sid)
+	}
+
+	/// Creates a new [`co::ACE_TYPE::ACCESS_DENIED`](crate::co::ACE_TYPE::ACCESS_DENIED)
+	/// ACE.
+	#[must_use]
+	pub fn deny(sid: Sid, access_mask: u32) -> Self {
+		Self::new(co::ACE_TYPE::ACCESS_DENIED, access_mask, sid)
+	}
+
+	/// Creates a new [`co::ACE_TYPE::SYSTEM_AUDIT`](crate::co::ACE_TYPE::SYSTEM_AUDIT)
+	/// ACE, which audits successful and/or failed access attempts.
+	#[must_use]
+	pub fn audit(sid: Sid, access_mask: u32, success: bool, failure: bool) -> Self {
+		let mut flags = co::ACE_FLAG::default();
+		if success {
+			flags |= co::ACE_FLAG::SUCCESSFUL_ACCESS;
+		}
+		if failure {
+			flags |= co::ACE_FLAG::FAILED_ACCESS;
+		}
+		Self::new(co::ACE_TYPE::SYSTEM_AUDIT, access_mask, sid).flags(flags)
+	}
+
+	/// Adds flags to the ACE.
+	#[must_use]
+	pub fn flags(mut self, flags: co::ACE_FLAG) -> Self {
+		self.flags |= flags;
+		self
+	}
+
+	/// Returns `true` if the ACE is only inherited by child objects, not
+	/// applied to the object itself.
+	#[must_use]
+	pub fn is_inherit_only(&self) -> bool {
+		self.flags.has(co::ACE_FLAG::INHERIT_ONLY)
+	}
+
+	/// Returns `true` if the ACE was inherited from a parent object.
+	#[must_use]
+	pub fn is_inherited(&self) -> bool {
+		self.flags.has(co::ACE_FLAG::INHERITED)
+	}
+}
+
+/// An owned
+/// [security descriptor](https://learn.microsoft.com/en-us/windows/win32/secauthz/security-descriptors),
+/// with owner, group, DACL and SACL.
+///
+/// It can be converted to and from the self-relative binary form, which is
+/// accepted by the system functions taking a
+/// [`SECURITY_DESCRIPTOR`](crate::SECURITY_DESCRIPTOR), and to and from the
+/// [SDDL](https://learn.microsoft.com/en-us/windows/win32/secauthz/security-descriptor-string-format)
+/// string form. All conversions are made in pure Rust.
+///
+/// # Examples
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*, co};
+///
+/// let mut sd = w::SecurityDescriptor::from_sddl("O:BAG:SYD:(A;;GA;;;SY)")?;
+/// sd.dacl.as_mut().unwrap().push(
+///     w::Ace::allow("S-1-5-32-545".parse()?, co::GENERIC::READ.raw()),
+/// );
+///
+/// assert_eq!(sd.to_sddl(), "O:BAG:SYD:(A;;GA;;;SY)(A;;GR;;;BU)");
+/// let bytes = sd.to_bytes();
+/// assert_eq!(w::SecurityDescriptor::from_bytes(&bytes)?, sd);
+/// # w::SysResult::Ok(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SecurityDescriptor {
+	/// Control flags, like
+	/// [`co::SE::DACL_PROTECTED`](crate::co::SE::DACL_PROTECTED). The
+	/// `SELF_RELATIVE` flag is computed when serializing, and is never stored
+	/// here. `DACL_PRESENT` and `SACL_PRESENT` are stored only when the
+	/// respective ACL is a null ACL, that is, present but `None`.
+	pub control: co::SE,
+	/// The owner.
+	pub owner: Option<Sid>,
+	/// The primary group.
+	pub group: Option<Sid>,
+	/// The discretionary ACL, which controls the access to the object.
+	///
+	/// Note the difference: `None` means no DACL, which grants full access to
+	/// everyone, while an empty vector denies all access. If `control` has
+	/// [`co::SE::DACL_PRESENT`](crate::co::SE::DACL_PRESENT), `None` is a null
+	/// DACL; otherwise the DACL is not present in the descriptor at all, like
+	/// when only the owner was queried.
+	pub dacl: Option<Vec<Ace>>,
+	/// The system ACL, which controls the auditing and the mandatory label.
+	pub sacl: Option<Vec<Ace>>,
+}
+
+impl std::fmt::Display for SecurityDescriptor {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.to_sddl())
+	}
+}
+
+impl SecurityDescriptor {
+	/// Creates a new, empty security descriptor, without owner, group or ACLs.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Parses a security descriptor in self-relative binary form.
+	///
+	/// If the data is malformed, returns:
+	///
+	/// * [`co::ERROR::INVALID_SECURITY_DESCR`](crate::co::ERROR::INVALID_SECURITY_DESCR)
+	///   for a bad header or an offset out of bounds;
+	/// * [`co::ERROR::INVALID_ACL`](crate::co::ERROR::INVALID_ACL) for a bad
+	///   ACL or ACE;
+	/// * [`co::ERROR::INVALID_SID`](crate::co::ERROR::INVALID_SID) for a bad
+	///   owner, group or ACE SID.
+	///
+	/// Returns
+	/// [`co::ERROR::CALL_NOT_IMPLEMENTED`](crate::co::ERROR::CALL_NOT_IMPLEMENTED)
+	/// if an ACL contains an unsupported ACE type.
+	pub fn from_bytes(buf: &[u8]) -> SysResult<Self> {
+		if buf.len() < SD_HEADER_LEN || buf[0] != SECURITY_DESCRIPTOR_REVISION {
+			return Err(co::ERROR::INVALID_SECURITY_DESCR);
+		}
+		let raw_control = unsafe { co::SE::from_raw(u16::from_le_bytes([buf[2], buf[3]])) };
+		if !raw_control.has(co::SE::SELF_RELATIVE) {
+			return Err(co::ERROR::INVALID_SECURITY_DESCR);
+		}
+		let offset = |idx: usize| {
+			let off = idx * 4 + 4;
+			u32::from_le_bytes([buf[off], buf[off + 1], buf[off + 2], buf[off + 3]]) as usize
+		};
+		let at = |off: usize| buf.get(off..).ok_or(co::ERROR::INVALID_SECURITY_DESCR);
+
+		let sid_at = |off: usize| match off {
+			0 => Ok(None),
+			off => Sid::parse(at(off)?).map(|(sid, _)| Some(sid)),
+		};
+		let acl_at = |present: co::SE, off: usize| {
+			if !raw_control.has(present) || off == 0 {
+				Ok(None) // not present, or null ACL
+			} else {
+				parse_acl(at(off)?).map(Some)
+			}
+		};
+
+		let sacl = acl_at(co::SE::SACL_PRESENT, offset(2))?;
+		let dacl = acl_at(co::SE::DACL_PRESENT, offset(3))?;
+		let mut control = raw_control & !co::SE::SELF_RELATIVE;
+		if sacl.is_some() {
+			control &= !co::SE::SACL_PRESENT; // implied by the ACL itself
+		}
+		if dacl.is_some() {
+			control &= !co::SE::DACL_PRESENT;
+		}
+
+		Ok(Self {
+			control,
+			owner: sid_at(offset(0))?,
+			group: sid_at(offset(1))?,
+			sacl,
+			dacl,
+		})
+	}
+
+	/// Serializes the security descriptor into self-relative binary form.
+	#[must_use]
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut control = self.control | co::SE::SELF_RELATIVE; // null ACLs keep their flags
+		let mut body = Vec::<u8>::new();
+		let mut offsets = [0u32; 4]; // owner, group, SACL, DACL
+
+		if let Some(sacl) = &self.sacl {
+			control |= co::SE::SACL_PRESENT;
+			offsets[2] = (SD_HEADER_LEN + body.len()) as _;
+			write_acl(sacl, &mut body);
+		}
+		if let Some(dacl) = &self.dacl {
+			control |= co::SE::DACL_PRESENT;
+			offsets[3] = (SD_HEADER_LEN + body.len()) as _;
+			write_acl(dacl, &mut body);
+		}
+		if let Some(owner) = &self.owner {
+			offsets[0] = (SD_HEADER_LEN + body.len()) as _;
+			owner.write(&mut body);
+		}
+		if let Some(group) = &self.group {
+			offsets[1] = (SD_HEADER_LEN + body.len()) as _;
+			group.write(&mut body);
+		}
+
+		let mut buf = Vec::with_capacity(SD_HEADER_LEN + body.len());
+		buf.push(SECURITY_DESCRIPTOR_REVISION);
+		buf.push(0); // Sbz1
+		buf.extend_from_slice(&control.raw().to_le_bytes());
+		for off in offsets.iter() {
+			buf.extend_from_slice(&off.to_le_bytes());
+		}
+		buf.extend_from_slice(&body);
+		buf
+	}
+
+	/// Parses a security descriptor in
+	/// [SDDL](https://learn.microsoft.com/en-us/windows/win32/secauthz/security-descriptor-string-format)
+	/// form, like `O:BAG:SYD:(A;;GA;;;SY)`.
+	///
+	/// The SID aliases which depend on the domain, like `DA`, are not
+	/// supported. Returns [`co::ERROR::INVALID_DATA`](crate::co::ERROR::INVALID_DATA)
+	/// if the string is malformed, or
+	/// [`co::ERROR::CALL_NOT_IMPLEMENTED`](crate::co::ERROR::CALL_NOT_IMPLEMENTED)
+	/// if it has object or conditional ACEs.
+	pub fn from_sddl(sddl: &str) -> SysResult<Self> {
+		sddl::parse(sddl)
+	}
+
+	/// Formats the security descriptor in
+	/// [SDDL](https://learn.microsoft.com/en-us/windows/win32/secauthz/security-descriptor-string-format)
+	/// form. Well-known SIDs and access rights are written with their aliases.
+	#[must_use]
+	pub fn to_sddl(&self) -> String {
+		sddl::format(self)
+	}
+
+	/// Grants access rights to a trustee, merging them with the rights already
+	/// granted by an explicit ACE with the same inheritance flags, like the
+	/// `GRANT_ACCESS` mode of
+	/// [`SetEntriesInAcl`](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-setentriesinaclw).
+	///
+	/// `inheritance` can have only the inheritance flags, like
+	/// [`co::ACE_FLAG::CONTAINER_INHERIT`](crate::co::ACE_FLAG::CONTAINER_INHERIT);
+	/// the others are ignored. The ACEs are kept in canonical order: explicit
+	/// denied, explicit allowed, then inherited. A null DACL becomes an empty
+	/// one before the new ACE is added.
+	///
+	/// # Examples
+	///
+	/// Granting read access to a user, inherited by files and subfolders:
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let mut sd = w::SecurityDescriptor::from_sddl("D:P(A;OICI;FA;;;BA)")?;
+	/// let (_, user, _) = w::LookupAccountName(None, "johnny")?;
+	///
+	/// sd.grant_access(
+	///     &w::Sid::from(&*user),
+	///     co::GENERIC::READ.raw(),
+	///     co::ACE_FLAG::CONTAINER_INHERIT | co::ACE_FLAG::OBJECT_INHERIT,
+	/// );
+	/// # w::SysResult::Ok(())
+	/// ```
+	pub fn grant_access(&mut self, sid: &Sid, access_mask: u32, inheritance: co::ACE_FLAG) {
+		self.add_explicit(co::ACE_TYPE::ACCESS_ALLOWED, sid, access_mask, inheritance);
+	}
+
+	/// Denies access rights to a trustee, merging them with the rights already
+	/// denied by an explicit ACE with the same inheritance flags, like the
+	/// `DENY_ACCESS` mode of
+	/// [`SetEntriesInAcl`](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-setentriesinaclw).
+	///
+	/// See [`grant_access`](crate::SecurityDescriptor::grant_access) for the
+	/// details.
+	pub fn deny_access(&mut self, sid: &Sid, access_mask: u32, inheritance: co::ACE_FLAG) {
+		self.add_explicit(co::ACE_TYPE::ACCESS_DENIED, sid, access_mask, inheritance);
+	}
+
+	/// Removes all the explicit allowed and denied ACEs of a trustee, like the
+	/// `REVOKE_ACCESS` mode of
+	/// [`SetEntriesInAcl`](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-setentriesinaclw).
+	/// Inherited ACEs are kept.
+	pub fn revoke_access(&mut self, sid: &Sid) {
+		if let Some(dacl) = &mut self.dacl {
+			dacl.retain(|ace| !(is_explicit_access(ace) && ace.sid == *sid));
+		}
+	}
+
+	/// Replaces all the explicit allowed and denied ACEs of a trustee with a
+	/// single allowed ACE, like the `SET_ACCESS` mode of
+	/// [`SetEntriesInAcl`](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-setentriesinaclw).
+	///
+	/// See [`grant_access`](crate::SecurityDescriptor::grant_access) for the
+	/// details.
+	pub fn set_access(&mut self, sid: &Sid, access_mask: u32, inheritance: co::ACE_FLAG) {
+		self.revoke_access(sid);
+		self.grant_access(sid, access_mask, inheritance);
+	}
+
+	fn add_explicit(&mut self,
+		ace_type: co::ACE_TYPE,
+		sid: &Sid,
+		access_mask: u32,
+		inheritance: co::ACE_FLAG,
+	) {
+		let flags = inheritance & (co::ACE_FLAG::OBJECT_INHERIT
+			| co::ACE_FLAG::CONTAINER_INHERIT
+			| co::ACE_FLAG::NO_PROPAGATE_INHERIT
+			| co::ACE_FLAG::INHERIT_ONLY);
+		self.control &= !co::SE::DACL_PRESENT; // no longer a null DACL
+		let dacl = self.dacl.get_or_insert_with(Vec::new);
+
+		if let Some(ace) = dacl.iter_mut()
+			.find(|ace| ace.ace_type == ace_type && ace.flags == flags && ace.sid == *sid)
+		{
+			ace.access_mask |= access_mask;
+			return;
+		}
+
+		let pos = match ace_type {
+			co::ACE_TYPE::ACCESS_DENIED => dacl.iter() // after the explicit denied ACEs
+				.position(|ace| !is_explicit_access(ace) || ace.ace_type != co::ACE_TYPE::ACCESS_DENIED),
+			_ => dacl.iter() // after all the explicit ACEs
+				.position(|ace| ace.is_inherited()),
+		}.unwrap_or(dacl.len());
+
+		dacl.insert(pos, Ace::new(ace_type, access_mask, sid.clone()).flags(flags));
+	}
+}
+
+/// Returns `true` if the ACE is an explicit, not inherited, allowed or denied
+/// ACE.
+fn is_explicit_access(ace: &Ace) -> bool {
+	!ace.is_inherited()
+		&& (ace.ace_type == co::ACE_TYPE::ACCESS_ALLOWED
+			|| ace.ace_type == co::ACE_TYPE::ACCESS_DENIED)
+}
+
+fn parse_acl(buf: &[u8]) -> SysResult<Vec<Ace>> {
+	if buf.len() < 8 {
+		return Err(co::ERROR::INVALID_ACL);
+	}
+	let acl_size = u16::from_le_bytes([buf[2], buf[3]]) as usize;
+	let ace_count = u16::from_le_bytes([buf[4], buf[5]]) as usize;
+	let acl = buf.get(..acl_size).ok_or(co::ERROR::INVALID_ACL)?;
+
+	let mut aces = Vec::with_capacity(ace_count);
+	let mut off = 8;
+	for _ in 0..ace_count {
+		let hdr = acl.get(off..off + 8).ok_or(co::ERROR::INVALID_ACL)?;
+		let ace_type = unsafe { co::ACE_TYPE::from_raw(hdr[0]) };
+		let ace_size = u16::from_le_bytes([hdr[2], hdr[3]]) as usize;
+		if ace_size < 8 + 4 || ace_size % 4 != 0 { // header plus the smallest SID
+			return Err(co::ERROR::INVALID_ACL);
+		}
+		let ace = acl.get(off..off + ace_size).ok_or(co::ERROR::INVALID_ACL)?;
+
+		match ace_type {
+			co::ACE_TYPE::ACCESS_ALLOWED
+				| co::ACE_TYPE::ACCESS_DENIED
+				| co::ACE_TYPE::SYSTEM_AUDIT
+				| co::ACE_TYPE::SYSTEM_ALARM
+				| co::ACE_TYPE::SYSTEM_MANDATORY_LABEL => {},
+			_ => return Err(co::ERROR::CALL_NOT_IMPLEMENTED),
+		}
+
+		aces.push(Ace {
+			ace_type,
+			flags: unsafe { co::ACE_FLAG::from_raw(hdr[1]) },
+			access_mask: u32::from_le_bytes([hdr[4], hdr[5], hdr[6], hdr[7]]),
+			sid: Sid::parse(&ace[8..])?.0,
+		});
+		off += ace_size;
+	}
+	Ok(aces)
+}
+
+fn write_acl(aces: &[Ace], buf: &mut Vec<u8>) {
+	let acl_size = 8 + aces.iter()
+		.map(|ace| 8 + ace.sid.len_bytes())
+		.sum::<usize>();
+
+	buf.push(ACL_REVISION);
+	buf.push(0); // Sbz1
+	buf.extend_from_slice(&(acl_size as u16).to_le_bytes());
+	buf.extend_from_slice(&(aces.len() as u16).to_le_bytes());
+	buf.extend_from_slice(&0u16.to_le_bytes()); // Sbz2
+
+	for ace in aces.iter() {
+		buf.push(ace.ace_type.raw());
+		buf.push(ace.flags.raw());
+		buf.extend_from_slice(&((8 + ace.sid.len_bytes()) as u16).to_le_bytes());
+		buf.extend_from_slice(&ace.access_mask.to_le_bytes());
+		ace.sid.write(buf);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn sd_with_ace_size(ace_size: u16) -> Vec<u8> {
+		let mut buf = SecurityDescriptor::from_sddl("D:(A;;GA;;;WD)(A;;GR;;;BU)")
+			.unwrap()
+			.to_bytes();
+		let dacl_off = SD_HEADER_LEN;
+		buf[dacl_off + 8 + 2..dacl_off + 8 + 4].copy_from_slice(&ace_size.to_le_bytes());
+		buf
+	}
+
+	#[test]
+	fn binary_round_trip() {
+		let sd = SecurityDescriptor::from_sddl("O:BAG:SYD:P(D;;SD;;;BU)(A;OICI;GA;;;BA)").unwrap();
+		assert_eq!(SecurityDescriptor::from_bytes(&sd.to_bytes()).unwrap(), sd);
+	}
+
+	#[test]
+	fn null_and_absent_dacl() {
+		let dacl_present = |sd: &SecurityDescriptor| {
+			let bytes = sd.to_bytes();
+			unsafe { co::SE::from_raw(u16::from_le_bytes([bytes[2], bytes[3]])) }.has(co::SE::DACL_PRESENT)
+		};
+
+		let sd_absent = SecurityDescriptor::from_sddl("O:BA").unwrap();
+		assert!(!dacl_present(&sd_absent));
+		assert_eq!(SecurityDescriptor::from_bytes(&sd_absent.to_bytes()).unwrap(), sd_absent);
+
+		let sd_null = SecurityDescriptor::from_sddl("O:BAD:NO_ACCESS_CONTROL").unwrap();
+		assert!(dacl_present(&sd_null));
+		assert_eq!(SecurityDescriptor::from_bytes(&sd_null.to_bytes()).unwrap(), sd_null);
+		assert_ne!(sd_null, sd_absent);
+
+		let mut sd_granted = sd_null.clone();
+		sd_granted.grant_access(&"S-1-5-18".parse().unwrap(), 0x1000_0000, co::ACE_FLAG::default());
+		assert_eq!(sd_granted.control, co::SE::default());
+		assert_eq!(sd_granted.to_sddl(), "O:BAD:(A;;GA;;;SY)");
+	}
+
+	#[test]
+	fn malformed_ace_size() {
+		for ace_size in [0, 4, 8, 13] {
+			assert_eq!(
+				SecurityDescriptor::from_bytes(&sd_with_ace_size(ace_size)),
+				Err(co::ERROR::INVALID_ACL),
+			);
+		}
+		assert!(SecurityDescriptor::from_bytes(&sd_with_ace_size(20)).is_ok());
+	}
+
+	#[test]
+	fn malformed_header_and_sid() {
+		let mut buf = SecurityDescriptor::from_sddl("O:BA").unwrap().to_bytes();
+		assert!(SecurityDescriptor::from_bytes(&buf).is_ok());
+
+		buf[SD_HEADER_LEN] = 9; // SID revision
+		assert_eq!(SecurityDescriptor::from_bytes(&buf), Err(co::ERROR::INVALID_SID));
+
+		buf[4..8].copy_from_slice(&0x1000u32.to_le_bytes()); // owner offset beyond the end
+		assert_eq!(SecurityDescriptor::from_bytes(&buf), Err(co::ERROR::INVALID_SECURITY_DESCR));
+		assert_eq!(SecurityDescriptor::from_bytes(&buf[..4]), Err(co::ERROR::INVALID_SECURITY_DESCR));
+	}
+}
@@ -0,0 +1,896 @@
+use std::sync::{Arc, mpsc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::advapi::{ffi, privs::*};
+use crate::co;
+use crate::decl::*;
+use crate::kernel::{ffi_types::*, privs::*};
+use crate::prelude::*;
+
+/// Wait hint reported along with the first checkpoint of each pending state,
+/// in milliseconds.
+const DEFAULT_WAIT_HINT: u32 = 3_000;
+
+/// Implemented by a service run by [`ServiceHost`](crate::ServiceHost), to
+/// respond to the requests of the Service Control Manager.
+///
+/// Only [`on_start`](crate::prelude::ServiceHandler::on_start) and
+/// [`on_stop`](crate::prelude::ServiceHandler::on_stop) are required; the
+/// remaining methods are only called if the corresponding control is listed in
+/// [`controls_accepted`](crate::prelude::ServiceHandler::controls_accepted).
+///
+/// If a method returns an error which is a [`co::ERROR`](crate::co::ERROR), it
+/// is reported as the Win32 exit code of the service; any other error is
+/// reported as the service-specific exit code returned by
+/// [`exit_code`](crate::prelude::ServiceHandler::exit_code).
+pub trait ServiceHandler {
+	/// Returns the controls accepted by the service while it's running. By
+	/// default,
+	/// [`co::SERVICE_ACCEPT::STOP`](crate::co::SERVICE_ACCEPT::STOP) and
+	/// [`co::SERVICE_ACCEPT::SHUTDOWN`](crate::co::SERVICE_ACCEPT::SHUTDOWN).
+	#[must_use]
+	fn controls_accepted(&self) -> co::SERVICE_ACCEPT {
+		co::SERVICE_ACCEPT::STOP | co::SERVICE_ACCEPT::SHUTDOWN
+	}
+
+	/// Called when the service starts, with the arguments passed to
+	/// [`HSERVICE::StartService`](crate::prelude::advapi_Hservice::StartService).
+	/// The service remains in
+	/// [`co::SERVICE_STATE::START_PENDING`](crate::co::SERVICE_STATE::START_PENDING)
+	/// until this method returns.
+	///
+	/// If an error is returned, the service is stopped.
+	fn on_start(&mut self,
+		args: &[String], progress: &mut ServiceProgress) -> AnyResult<()>;
+
+	/// Called when the service is stopped, or when the system shuts down. The
+	/// service remains in
+	/// [`co::SERVICE_STATE::STOP_PENDING`](crate::co::SERVICE_STATE::STOP_PENDING)
+	/// until this method returns.
+	fn on_stop(&mut self, progress: &mut ServiceProgress) -> AnyResult<()>;
+
+	/// Called when the service is paused. If an error is returned, the service
+	/// keeps running.
+	fn on_pause(&mut self, progress: &mut ServiceProgress) -> AnyResult<()> {
+		let _ = progress;
+		Ok(())
+	}
+
+	/// Called when the service is continued after being paused. If an error is
+	/// returned, the service stays paused.
+	fn on_continue(&mut self, progress: &mut ServiceProgress) -> AnyResult<()> {
+		let _ = progress;
+		Ok(())
+	}
+
+	/// Called when a session changes, if
+	/// [`co::SERVICE_ACCEPT::SESSIONCHANGE`](crate::co::SERVICE_ACCEPT::SESSIONCHANGE)
+	/// is accepted.
+	fn on_session_change(&mut self,
+		event: co::WTS, notification: &WTSSESSION_NOTIFICATION)
+	{
+		let _ = (event, notification);
+	}
+
+	/// Called on a device event, for devices registered with
+	/// [`HWND::RegisterDeviceNotification`](https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerdevicenotificationw).
+	///
+	/// Returning an error other than
+	/// [`co::ERROR::SUCCESS`](crate::co::ERROR::SUCCESS) denies a
+	/// [`co::DBT::DEVICEQUERYREMOVE`](crate::co::DBT::DEVICEQUERYREMOVE)
+	/// request.
+	#[must_use]
+	fn on_device_event(&mut self,
+		event: co::DBT, data: &SvcCtlDeviceEvent) -> co::ERROR
+	{
+		let _ = (event, data);
+		co::ERROR::SUCCESS
+	}
+
+	/// Called on any other control, like power events or user-defined
+	/// controls. By default, returns
+	/// [`co::ERROR::CALL_NOT_IMPLEMENTED`](crate::co::ERROR::CALL_NOT_IMPLEMENTED).
+	#[must_use]
+	fn on_other_control(&mut self, control: &SvcCtl) -> co::ERROR {
+		let _ = control;
+		co::ERROR::CALL_NOT_IMPLEMENTED
+	}
+
+	/// Converts an error returned by one of the methods into a service-specific
+	/// exit code, which can be read with
+	/// [`HSERVICE::QueryServiceStatusEx`](crate::prelude::advapi_Hservice::QueryServiceStatusEx).
+	/// By default, returns `1`.
+	#[must_use]
+	fn exit_code(&self, err: &(dyn std::error::Error + Send + Sync)) -> u32 {
+		let _ = err;
+		1
+	}
+}
+
+/// Receives the status reports of a
+/// [`ServiceStatusMachine`](crate::ServiceStatusMachine).
+///
+/// It's implemented by [`HSERVICESTATUS`](crate::HSERVICESTATUS), which
+/// forwards the status to the Service Control Manager. Another implementation
+/// can record the reports, so the state machine can be exercised without a
+/// running service.
+pub trait ServiceStatusSink {
+	/// Reports the current status of the service.
+	fn set_status(&mut self, status: &SERVICE_STATUS) -> SysResult<()>;
+}
+
+impl ServiceStatusSink for HSERVICESTATUS {
+	fn set_status(&mut self, status: &SERVICE_STATUS) -> SysResult<()> {
+		let mut status = *status;
+		self.SetServiceStatus(&mut status)
+	}
+}
+
+/// Passed to the [`ServiceHandler`](crate::prelude::ServiceHandler) methods
+/// which run in a pending state, so long operations can tell the Service
+/// Control Manager the service is still making progress.
+pub struct ServiceProgress<'a> {
+	state: co::SERVICE_STATE,
+	checkpoint: &'a mut dyn FnMut(u32) -> SysResult<()>,
+}
+
+impl<'a> ServiceProgress<'a> {
+	/// Returns the pending state being reported.
+	#[must_use]
+	pub const fn state(&self) -> co::SERVICE_STATE {
+		self.state
+	}
+
+	/// Increments the checkpoint and reports it, with the time the next step
+	/// is expected to take, in milliseconds.
+	pub fn checkpoint(&mut self, wait_hint_ms: u32) -> SysResult<()> {
+		(self.checkpoint)(wait_hint_ms)
+	}
+}
+
+/// Tracks the state of a service, calling the
+/// [`ServiceHandler`](crate::prelude::ServiceHandler) methods and reporting
+/// each transition to a [`ServiceStatusSink`](crate::prelude::ServiceStatusSink).
+///
+/// This is the state machine used by [`ServiceHost`](crate::ServiceHost), and
+/// all its methods run synchronously.
+///
+/// # Examples
+///
+/// Recording the status reports, without a running service:
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*, co};
+///
+/// struct Recorder(Vec<co::SERVICE_STATE>);
+///
+/// impl ServiceStatusSink for Recorder {
+///     fn set_status(&mut self, status: &w::SERVICE_STATUS) -> w::SysResult<()> {
+///         self.0.push(status.dwCurrentState);
+///         Ok(())
+///     }
+/// }
+///
+/// struct MyService;
+///
+/// impl ServiceHandler for MyService {
+///     fn on_start(&mut self,
+///         _: &[String], _: &mut w::ServiceProgress) -> w::AnyResult<()> { Ok(()) }
+///     fn on_stop(&mut self, _: &mut w::ServiceProgress) -> w::AnyResult<()> { Ok(()) }
+/// }
+///
+/// let mut machine = w::ServiceStatusMachine::new(
+///     MyService, Recorder(Vec::new()), co::SERVICE_TYPE::WIN32_OWN_PROCESS);
+///
+/// machine.start(&[])?;
+/// machine.control(&w::SvcCtl::Stop)?;
+///
+/// assert_eq!(machine.sink().0, [
+///     co::SERVICE_STATE::START_PENDING,
+///     co::SERVICE_STATE::RUNNING,
+///     co::SERVICE_STATE::STOP_PENDING,
+///     co::SERVICE_STATE::STOPPED,
+/// ]);
+/// # w::SysResult::Ok(())
+/// ```
+pub struct ServiceStatusMachine<H, S>
+	where H: ServiceHandler,
+		S: ServiceStatusSink,
+{
+	handler: H,
+	reporter: StatusReporter<S>,
+}
+
+impl<H, S> ServiceStatusMachine<H, S>
+	where H: ServiceHandler,
+		S: ServiceStatusSink,
+{
+	/// Creates a new state machine, in
+	/// [`co::SERVICE_STATE::STOPPED`](crate::co::SERVICE_STATE::STOPPED) state.
+	/// Nothing is reported until [`start`](crate::ServiceStatusMachine::start)
+	/// is called.
+	#[must_use]
+	pub fn new(handler: H, sink: S, service_type: co::SERVICE_TYPE) -> Self {
+		Self {
+			handler,
+			reporter: StatusReporter::new(sink, service_type),
+		}
+	}
+
+	/// Returns the handler.
+	#[must_use]
+	pub const fn handler(&self) -> &H {
+		&self.handler
+	}
+
+	/// Returns the handler.
+	#[must_use]
+	pub fn handler_mut(&mut self) -> &mut H {
+		&mut self.handler
+	}
+
+	/// Returns the status sink.
+	#[must_use]
+	pub const fn sink(&self) -> &S {
+		&self.reporter.sink
+	}
+
+	/// Returns the last reported status.
+	#[must_use]
+	pub const fn status(&self) -> &SERVICE_STATUS {
+		&self.reporter.status
+	}
+
+	/// Returns the current state.
+	#[must_use]
+	pub const fn state(&self) -> co::SERVICE_STATE {
+		self.reporter.status.dwCurrentState
+	}
+
+	/// Reports
+	/// [`co::SERVICE_STATE::START_PENDING`](crate::co::SERVICE_STATE::START_PENDING),
+	/// calls [`on_start`](crate::prelude::ServiceHandler::on_start), then
+	/// reports [`co::SERVICE_STATE::RUNNING`](crate::co::SERVICE_STATE::RUNNING)
+	/// or, if it failed,
+	/// [`co::SERVICE_STATE::STOPPED`](crate::co::SERVICE_STATE::STOPPED) with
+	/// the exit code.
+	///
+	/// Returns an error only if the status could not be reported.
+	pub fn start(&mut self, args: &[String]) -> SysResult<()> {
+		start_service(&mut self.handler, &mut self.reporter, args)
+	}
+
+	/// Processes a control sent by the Service Control Manager, returning the
+	/// code to be returned by the control handler.
+	///
+	/// Returns an error only if the status could not be reported.
+	pub fn control(&mut self, control: &SvcCtl) -> SysResult<co::ERROR> {
+		control_service(&mut self.handler, &mut self.reporter, control)
+	}
+}
+
+/// The current status of a service, and the sink it's reported to.
+struct StatusReporter<S: ServiceStatusSink> {
+	sink: S,
+	status: SERVICE_STATUS,
+}
+
+impl<S: ServiceStatusSink> StatusReporter<S> {
+	fn new(sink: S, service_type: co::SERVICE_TYPE) -> Self {
+		Self {
+			sink,
+			status: SERVICE_STATUS {
+				dwServiceType: service_type,
+				dwCurrentState: co::SERVICE_STATE::STOPPED,
+				..Default::default()
+			},
+		}
+	}
+
+	const fn state(&self) -> co::SERVICE_STATE {
+		self.status.dwCurrentState
+	}
+
+	fn report_pending(&mut self, state: co::SERVICE_STATE) -> SysResult<()> {
+		self.status.dwCurrentState = state;
+		self.status.dwControlsAccepted = co::SERVICE_ACCEPT::default(); // no controls while pending
+		self.status.dwCheckPoint = 1;
+		self.status.dwWaitPoint = DEFAULT_WAIT_HINT;
+		self.sink.set_status(&self.status)
+	}
+
+	fn checkpoint(&mut self, wait_hint_ms: u32) -> SysResult<()> {
+		self.status.dwCheckPoint += 1;
+		self.status.dwWaitPoint = wait_hint_ms;
+		self.sink.set_status(&self.status)
+	}
+
+	fn report(&mut self,
+		state: co::SERVICE_STATE, controls_accepted: co::SERVICE_ACCEPT) -> SysResult<()>
+	{
+		self.status.dwCurrentState = state;
+		self.status.dwControlsAccepted = if state == co::SERVICE_STATE::STOPPED {
+			co::SERVICE_ACCEPT::default()
+		} else {
+			controls_accepted
+		};
+		self.status.dwCheckPoint = 0;
+		self.status.dwWaitPoint = 0;
+		self.sink.set_status(&self.status)
+	}
+
+	fn report_stopped(&mut self, win32: co::ERROR, specific: u32) -> SysResult<()> {
+		self.status.dwWin32ExitCode = win32.raw();
+		self.status.dwServiceSpecificExitCode = specific;
+		self.report(co::SERVICE_STATE::STOPPED, co::SERVICE_ACCEPT::default())
+	}
+}
+
+/// Gives access to the `StatusReporter`, which is owned by the state machine,
+/// or shared by the service thread and the control handler of the host. The
+/// access is released while the handler methods run, so a shared reporter is
+/// never locked for long.
+trait StatusAccess {
+	type Sink: ServiceStatusSink;
+
+	fn with<T>(&mut self, f: impl FnOnce(&mut StatusReporter<Self::Sink>) -> T) -> T;
+}
+
+impl<S: ServiceStatusSink> StatusAccess for StatusReporter<S> {
+	type Sink = S;
+
+	fn with<T>(&mut self, f: impl FnOnce(&mut StatusReporter<S>) -> T) -> T {
+		f(self)
+	}
+}
+
+impl<S: ServiceStatusSink> StatusAccess for &Mutex<StatusReporter<S>> {
+	type Sink = S;
+
+	fn with<T>(&mut self, f: impl FnOnce(&mut StatusReporter<S>) -> T) -> T {
+		f(&mut self.lock().unwrap())
+	}
+}
+
+/// Converts an error returned by a handler method into the Win32 and the
+/// service-specific exit codes.
+fn exit_codes<H: ServiceHandler>(handler: &H,
+	err: Option<Box<dyn std::error::Error + Send + Sync>>) -> (co::ERROR, u32)
+{
+	match err {
+		None => (co::ERROR::SUCCESS, 0),
+		Some(err) => match err.downcast_ref::<co::ERROR>() {
+			Some(e) => (*e, 0),
+			None => (co::ERROR::SERVICE_SPECIFIC_ERROR, handler.exit_code(&*err)),
+		},
+	}
+}
+
+/// Calls a handler method which runs in a pending state, with a
+/// [`ServiceProgress`](crate::ServiceProgress) which reports the checkpoints.
+fn run_pending<A, F>(access: &mut A, state: co::SERVICE_STATE, f: F) -> AnyResult<()>
+	where A: StatusAccess,
+		F: FnOnce(&mut ServiceProgress) -> AnyResult<()>,
+{
+	let mut checkpoint = |wait_hint_ms| access.with(|r| r.checkpoint(wait_hint_ms));
+	f(&mut ServiceProgress { state, checkpoint: &mut checkpoint })
+}
+
+fn start_service<H, A>(handler: &mut H, access: &mut A, args: &[String]) -> SysResult<()>
+	where H: ServiceHandler,
+		A: StatusAccess,
+{
+	access.with(|r| {
+		if r.state() != co::SERVICE_STATE::STOPPED {
+			return Err(co::ERROR::SERVICE_ALREADY_RUNNING);
+		}
+		r.status.dwWin32ExitCode = co::ERROR::SUCCESS.raw();
+		r.status.dwServiceSpecificExitCode = 0;
+		r.report_pending(co::SERVICE_STATE::START_PENDING)
+	})?;
+
+	let res = run_pending(access, co::SERVICE_STATE::START_PENDING,
+		|progress| handler.on_start(args, progress));
+
+	match res {
+		Ok(_) => {
+			let controls_accepted = handler.controls_accepted();
+			access.with(|r| r.report(co::SERVICE_STATE::RUNNING, controls_accepted))
+		},
+		Err(err) => {
+			let (win32, specific) = exit_codes(handler, Some(err));
+			access.with(|r| r.report_stopped(win32, specific))
+		},
+	}
+}
+
+fn control_service<H, A>(handler: &mut H, access: &mut A, control: &SvcCtl) -> SysResult<co::ERROR>
+	where H: ServiceHandler,
+		A: StatusAccess,
+{
+	let state = access.with(|r| r.state());
+	let running = state == co::SERVICE_STATE::RUNNING;
+	let paused = state == co::SERVICE_STATE::PAUSED;
+
+	match control {
+		SvcCtl::Interrogate => {
+			access.with(|r| r.sink.set_status(&r.status))?;
+			Ok(co::ERROR::SUCCESS)
+		},
+		SvcCtl::Stop | SvcCtl::Shutdown | SvcCtl::PreShutdown => {
+			if !running && !paused {
+				return Ok(co::ERROR::SERVICE_CANNOT_ACCEPT_CTRL);
+			}
+			access.with(|r| r.report_pending(co::SERVICE_STATE::STOP_PENDING))?;
+			let res = run_pending(access, co::SERVICE_STATE::STOP_PENDING,
+				|progress| handler.on_stop(progress));
+			let (win32, specific) = exit_codes(handler, res.err());
+			access.with(|r| r.report_stopped(win32, specific))?;
+			Ok(co::ERROR::SUCCESS)
+		},
+		SvcCtl::Pause => {
+			if paused {
+				return Ok(co::ERROR::SUCCESS);
+			} else if !running {
+				return Ok(co::ERROR::SERVICE_CANNOT_ACCEPT_CTRL);
+			}
+			access.with(|r| r.report_pending(co::SERVICE_STATE::PAUSE_PENDING))?;
+			let res = run_pending(access, co::SERVICE_STATE::PAUSE_PENDING,
+				|progress| handler.on_pause(progress));
+			report_transition(handler, access, res,
+				co::SERVICE_STATE::PAUSED, co::SERVICE_STATE::RUNNING)
+		},
+		SvcCtl::Continue => {
+			if running {
+				return Ok(co::ERROR::SUCCESS);
+			} else if !paused {
+				return Ok(co::ERROR::SERVICE_CANNOT_ACCEPT_CTRL);
+			}
+			access.with(|r| r.report_pending(co::SERVICE_STATE::CONTINUE_PENDING))?;
+			let res = run_pending(access, co::SERVICE_STATE::CONTINUE_PENDING,
+				|progress| handler.on_continue(progress));
+			report_transition(handler, access, res,
+				co::SERVICE_STATE::RUNNING, co::SERVICE_STATE::PAUSED)
+		},
+		SvcCtl::SessionChange(event, notification) => {
+			handler.on_session_change(*event, notification);
+			Ok(co::ERROR::SUCCESS)
+		},
+		SvcCtl::DeviceEvent(event, data) => {
+			Ok(handler.on_device_event(*event, data))
+		},
+		other => Ok(handler.on_other_control(other)),
+	}
+}
+
+fn report_transition<H, A>(
+	handler: &H,
+	access: &mut A,
+	res: AnyResult<()>,
+	ok_state: co::SERVICE_STATE,
+	err_state: co::SERVICE_STATE,
+) -> SysResult<co::ERROR>
+	where H: ServiceHandler,
+		A: StatusAccess,
+{
+	let controls_accepted = handler.controls_accepted();
+	match res {
+		Ok(_) => {
+			access.with(|r| r.report(ok_state, controls_accepted))?;
+			Ok(co::ERROR::SUCCESS)
+		},
+		Err(err) => {
+			access.with(|r| r.report(err_state, controls_accepted))?;
+			Ok(match err.downcast_ref::<co::ERROR>() {
+				Some(e) => *e,
+				None => co::ERROR::SERVICE_SPECIFIC_ERROR,
+			})
+		},
+	}
+}
+
+/// Runs a service in its own process, calling
+/// [`StartServiceCtrlDispatcher`](https://learn.microsoft.com/en-us/windows/win32/api/winsvc/nf-winsvc-startservicectrldispatcherw),
+/// registering the control handler and tracking the service state like a
+/// [`ServiceStatusMachine`](crate::ServiceStatusMachine).
+///
+/// Start, stop, pause and continue are processed in the service thread, so
+/// the handler methods may take long, as long as they report their progress.
+/// Other controls are processed in the main thread, and must return quickly;
+/// if they arrive while a handler method is running in the service thread,
+/// they're refused with
+/// [`co::ERROR::SERVICE_CANNOT_ACCEPT_CTRL`](crate::co::ERROR::SERVICE_CANNOT_ACCEPT_CTRL),
+/// except for interrogation, which is always answered.
+///
+/// # Examples
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*, co};
+///
+/// struct MyService;
+///
+/// impl ServiceHandler for MyService {
+///     fn on_start(&mut self,
+///         _: &[String], _: &mut w::ServiceProgress) -> w::AnyResult<()>
+///     {
+///         // start the worker threads...
+///         Ok(())
+///     }
+///
+///     fn on_stop(&mut self,
+///         progress: &mut w::ServiceProgress) -> w::AnyResult<()>
+///     {
+///         // signal the worker threads and wait...
+///         progress.checkpoint(5_000)?;
+///         Ok(())
+///     }
+/// }
+///
+/// w::ServiceHost::run("MyService", MyService)?;
+/// # w::SysResult::Ok(())
+/// ```
+pub struct ServiceHost {}
+
+type ServiceMainFn = Box<dyn FnOnce(Vec<String>) + Send>;
+
+/// Entry point of the service, set by `ServiceHost::run` and taken by
+/// `service_main_proc`.
+static SERVICE_MAIN: Mutex<Option<ServiceMainFn>> = Mutex::new(None);
+
+/// Set by the first `ServiceHost::run` call, and never reset once the
+/// dispatcher connects, so the service can't run twice.
+static RUN_CALLED: AtomicBool = AtomicBool::new(false);
+
+impl ServiceHost {
+	/// Connects the calling thread to the Service Control Manager, blocking
+	/// until the service stops.
+	///
+	/// Only one service can be run by a process, so this function can be
+	/// called only once; further calls fail with
+	/// [`co::ERROR::SERVICE_ALREADY_RUNNING`](crate::co::ERROR::SERVICE_ALREADY_RUNNING),
+	/// even after the service stopped.
+	///
+	/// If the process was not started by the Service Control Manager, fails
+	/// with
+	/// [`co::ERROR::FAILED_SERVICE_CONTROLLER_CONNECT`](crate::co::ERROR::FAILED_SERVICE_CONTROLLER_CONNECT);
+	/// in this case, the call doesn't count.
+	pub fn run<H>(service_name: &str, handler: H) -> SysResult<()>
+		where H: ServiceHandler + Send + 'static,
+	{
+		if RUN_CALLED.swap(true, Ordering::SeqCst) {
+			return Err(co::ERROR::SERVICE_ALREADY_RUNNING);
+		}
+		let owned_name = service_name.to_owned();
+		*SERVICE_MAIN.lock().unwrap() =
+			Some(Box::new(move |args| run_service(&owned_name, handler, args)));
+
+		let name_w = WString::from_str(service_name);
+		let table = [
+			SERVICE_TABLE_ENTRY {
+				lpServiceName: name_w.as_ptr(),
+				lpServiceProc: service_main_proc as _,
+			},
+			SERVICE_TABLE_ENTRY { // terminating null entry
+				lpServiceName: std::ptr::null(),
+				lpServiceProc: std::ptr::null(),
+			},
+		];
+
+		bool_to_sysresult(
+			unsafe { ffi::StartServiceCtrlDispatcherW(table.as_ptr() as _) },
+		).inspect_err(|_| {
+			SERVICE_MAIN.lock().unwrap().take(); // the service never started
+			RUN_CALLED.store(false, Ordering::SeqCst);
+		})
+	}
+}
+
+/// State shared between the service thread and the control handler. The
+/// handler and the status have separate locks, so the status can be reported
+/// while a handler method runs.
+struct HostShared<H: ServiceHandler> {
+	handler: Mutex<H>,
+	reporter: Mutex<StatusReporter<HSERVICESTATUS>>,
+	pending: Mutex<mpsc::Sender<co::SERVICE_CONTROL>>,
+}
+
+extern "system" fn service_main_proc(argc: u32, argv: *const PCSTR) {
+	let args = (0..argc as usize)
+		.skip(1) // first argument is the service name
+		.map(|idx| unsafe { pcstr_to_opt_string(*argv.add(idx)) }.unwrap_or_default())
+		.collect::<Vec<_>>();
+
+	if let Some(service_main) = SERVICE_MAIN.lock().unwrap().take() {
+		service_main(args);
+	}
+}
+
+fn run_service<H>(service_name: &str, handler: H, args: Vec<String>)
+	where H: ServiceHandler,
+{
+	let (tx, rx) = mpsc::channel();
+	let shared = Arc::new(HostShared {
+		handler: Mutex::new(handler),
+		reporter: Mutex::new(
+			StatusReporter::new(HSERVICESTATUS::NULL, co::SERVICE_TYPE::WIN32_OWN_PROCESS),
+		),
+		pending: Mutex::new(tx),
+	});
+
+	// The control handler may still be called after the service thread
+	// returns, so its reference is never released.
+	let context = Arc::into_raw(shared.clone());
+	let hss = match unsafe {
+		ptr_to_sysresult_handle::<HSERVICESTATUS>(
+			ffi::RegisterServiceCtrlHandlerExW(
+				WString::from_str(service_name).as_ptr(),
+				service_ctrl_handler_proc::<H> as _,
+				context as _,
+			),
+		)
+	} {
+		Ok(hss) => hss,
+		Err(_) => return, // no way to report the failure
+	};
+
+	let mut access = &shared.reporter;
+	access.with(|r| r.sink = hss);
+	let is_stopped = |access: &mut &Mutex<StatusReporter<_>>| {
+		access.with(|r| r.state() == co::SERVICE_STATE::STOPPED)
+	};
+
+	let mut handler = shared.handler.lock().unwrap();
+	if start_service(&mut *handler, &mut access, &args).is_err() || is_stopped(&mut access) {
+		return;
+	}
+	drop(handler);
+
+	while let Ok(control) = rx.recv() {
+		let ctl = match control {
+			co::SERVICE_CONTROL::PAUSE => SvcCtl::Pause,
+			co::SERVICE_CONTROL::CONTINUE => SvcCtl::Continue,
+			co::SERVICE_CONTROL::SHUTDOWN => SvcCtl::Shutdown,
+			co::SERVICE_CONTROL::PRESHUTDOWN => SvcCtl::PreShutdown,
+			_ => SvcCtl::Stop,
+		};
+		let mut handler = shared.handler.lock().unwrap();
+		if control_service(&mut *handler, &mut access, &ctl).is_err() || is_stopped(&mut access) {
+			break;
+		}
+	}
+}
+
+extern "system" fn service_ctrl_handler_proc<H>(
+	control: u32,
+	event_type: u32,
+	event_data: PVOID,
+	context: PVOID,
+) -> u32
+	where H: ServiceHandler,
+{
+	let shared = unsafe { &*(context as *const HostShared<H>) };
+	let control = unsafe { co::SERVICE_CONTROL::from_raw(control) };
+
+	match control {
+		co::SERVICE_CONTROL::STOP
+			| co::SERVICE_CONTROL::SHUTDOWN
+			| co::SERVICE_CONTROL::PRESHUTDOWN
+			| co::SERVICE_CONTROL::PAUSE
+			| co::SERVICE_CONTROL::CONTINUE => {
+			// Processed by the service thread, which reports the pending state.
+			match shared.pending.lock().unwrap().send(control) {
+				Ok(_) => co::ERROR::SUCCESS.raw(),
+				Err(_) => co::ERROR::SERVICE_CANNOT_ACCEPT_CTRL.raw(),
+			}
+		},
+		co::SERVICE_CONTROL::INTERROGATE => {
+			// Doesn't need the handler, which may be busy in the service thread.
+			match (&shared.reporter).with(|r| r.sink.set_status(&r.status)) {
+				Ok(_) => co::ERROR::SUCCESS.raw(),
+				Err(err) => err.raw(),
+			}
+		},
+		_ => {
+			let Ok(mut handler) = shared.handler.try_lock() else {
+				return co::ERROR::SERVICE_CANNOT_ACCEPT_CTRL.raw(); // busy in the service thread
+			};
+			let ctl = unsafe { SvcCtl::from_raw(control.raw(), event_type, event_data) };
+			match control_service(&mut *handler, &mut &shared.reporter, &ctl) {
+				Ok(code) => code.raw(),
+				Err(err) => err.raw(),
+			}
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Status fields recorded by the fake sink: state, controls accepted,
+	/// checkpoint, Win32 and service-specific exit codes.
+	type Report = (co::SERVICE_STATE, co::SERVICE_ACCEPT, u32, u32, u32);
+
+	#[derive(Default)]
+	struct FakeSink(Vec<Report>);
+
+	impl ServiceStatusSink for FakeSink {
+		fn set_status(&mut self, status: &SERVICE_STATUS) -> SysResult<()> {
+			self.0.push((
+				status.dwCurrentState,
+				status.dwControlsAccepted,
+				status.dwCheckPoint,
+				status.dwWin32ExitCode,
+				status.dwServiceSpecificExitCode,
+			));
+			Ok(())
+		}
+	}
+
+	#[derive(Default)]
+	struct FakeService {
+		start_checkpoints: u32,
+		start_error: Option<fn() -> Box<dyn std::error::Error + Send + Sync>>,
+		pause_error: Option<co::ERROR>,
+		shared_reporter: Option<Arc<Mutex<StatusReporter<FakeSink>>>>,
+	}
+
+	impl ServiceHandler for FakeService {
+		fn controls_accepted(&self) -> co::SERVICE_ACCEPT {
+			co::SERVICE_ACCEPT::STOP | co::SERVICE_ACCEPT::PAUSE_CONTINUE
+		}
+
+		fn on_start(&mut self,
+			_: &[String], progress: &mut ServiceProgress) -> AnyResult<()>
+		{
+			assert_eq!(progress.state(), co::SERVICE_STATE::START_PENDING);
+			if let Some(reporter) = &self.shared_reporter {
+				assert!(reporter.try_lock().is_ok(), "status locked during on_start");
+			}
+			for _ in 0..self.start_checkpoints {
+				progress.checkpoint(1_000)?;
+			}
+			match self.start_error {
+				Some(make_err) => Err(make_err()),
+				None => Ok(()),
+			}
+		}
+
+		fn on_stop(&mut self, progress: &mut ServiceProgress) -> AnyResult<()> {
+			assert_eq!(progress.state(), co::SERVICE_STATE::STOP_PENDING);
+			if let Some(reporter) = &self.shared_reporter {
+				assert!(reporter.try_lock().is_ok(), "status locked during on_stop");
+			}
+			Ok(())
+		}
+
+		fn on_pause(&mut self, _: &mut ServiceProgress) -> AnyResult<()> {
+			match self.pause_error {
+				Some(err) => Err(err.into()),
+				None => Ok(()),
+			}
+		}
+
+		fn exit_code(&self, _: &(dyn std::error::Error + Send + Sync)) -> u32 {
+			42
+		}
+	}
+
+	fn machine(service: FakeService) -> ServiceStatusMachine<FakeService, FakeSink> {
+		ServiceStatusMachine::new(service, FakeSink::default(), co::SERVICE_TYPE::WIN32_OWN_PROCESS)
+	}
+
+	fn states(reports: &[Report]) -> Vec<co::SERVICE_STATE> {
+		reports.iter().map(|r| r.0).collect()
+	}
+
+	#[test]
+	fn start_and_stop() {
+		let mut m = machine(FakeService::default());
+		m.start(&[]).unwrap();
+		assert_eq!(m.start(&[]), Err(co::ERROR::SERVICE_ALREADY_RUNNING));
+		assert_eq!(m.control(&SvcCtl::Stop), Ok(co::ERROR::SUCCESS));
+		assert_eq!(m.control(&SvcCtl::Stop), Ok(co::ERROR::SERVICE_CANNOT_ACCEPT_CTRL));
+
+		let accepted = co::SERVICE_ACCEPT::STOP | co::SERVICE_ACCEPT::PAUSE_CONTINUE;
+		let none = co::SERVICE_ACCEPT::default();
+		assert_eq!(m.sink().0, [
+			(co::SERVICE_STATE::START_PENDING, none, 1, 0, 0),
+			(co::SERVICE_STATE::RUNNING, accepted, 0, 0, 0),
+			(co::SERVICE_STATE::STOP_PENDING, none, 1, 0, 0),
+			(co::SERVICE_STATE::STOPPED, none, 0, 0, 0),
+		]);
+	}
+
+	#[test]
+	fn start_checkpoints() {
+		let mut m = machine(FakeService { start_checkpoints: 2, ..Default::default() });
+		m.start(&[]).unwrap();
+		let checkpoints = m.sink().0.iter().map(|r| r.2).collect::<Vec<_>>();
+		assert_eq!(checkpoints, [1, 2, 3, 0]);
+		assert_eq!(m.status().dwWaitPoint, 0);
+		assert_eq!(m.state(), co::SERVICE_STATE::RUNNING);
+	}
+
+	#[test]
+	fn start_errors() {
+		let mut m = machine(FakeService {
+			start_error: Some(|| co::ERROR::FILE_NOT_FOUND.into()),
+			..Default::default()
+		});
+		m.start(&[]).unwrap();
+		assert_eq!(m.sink().0.last().unwrap(), &(co::SERVICE_STATE::STOPPED,
+			co::SERVICE_ACCEPT::default(), 0, co::ERROR::FILE_NOT_FOUND.raw(), 0));
+
+		let mut m = machine(FakeService {
+			start_error: Some(|| "not a Win32 error".into()),
+			..Default::default()
+		});
+		m.start(&[]).unwrap();
+		assert_eq!(m.sink().0.last().unwrap(), &(co::SERVICE_STATE::STOPPED,
+			co::SERVICE_ACCEPT::default(), 0, co::ERROR::SERVICE_SPECIFIC_ERROR.raw(), 42));
+
+		// A new start clears the previous exit codes.
+		m.handler_mut().start_error = None;
+		m.start(&[]).unwrap();
+		assert_eq!(m.status().dwWin32ExitCode, 0);
+		assert_eq!(m.status().dwServiceSpecificExitCode, 0);
+	}
+
+	#[test]
+	fn pause_and_continue() {
+		let mut m = machine(FakeService::default());
+		assert_eq!(m.control(&SvcCtl::Continue), Ok(co::ERROR::SERVICE_CANNOT_ACCEPT_CTRL));
+		m.start(&[]).unwrap();
+		assert_eq!(m.control(&SvcCtl::Continue), Ok(co::ERROR::SUCCESS)); // already running
+		assert_eq!(m.control(&SvcCtl::Pause), Ok(co::ERROR::SUCCESS));
+		assert_eq!(m.control(&SvcCtl::Pause), Ok(co::ERROR::SUCCESS)); // already paused
+		assert_eq!(m.control(&SvcCtl::Continue), Ok(co::ERROR::SUCCESS));
+
+		m.handler_mut().pause_error = Some(co::ERROR::BUSY);
+		assert_eq!(m.control(&SvcCtl::Pause), Ok(co::ERROR::BUSY));
+		assert_eq!(m.state(), co::SERVICE_STATE::RUNNING);
+
+		assert_eq!(states(&m.sink().0[2..]), [
+			co::SERVICE_STATE::PAUSE_PENDING,
+			co::SERVICE_STATE::PAUSED,
+			co::SERVICE_STATE::CONTINUE_PENDING,
+			co::SERVICE_STATE::RUNNING,
+			co::SERVICE_STATE::PAUSE_PENDING,
+			co::SERVICE_STATE::RUNNING,
+		]);
+	}
+
+	#[test]
+	fn interrogate_and_other_controls() {
+		let mut m = machine(FakeService::default());
+		m.start(&[]).unwrap();
+		assert_eq!(m.control(&SvcCtl::Interrogate), Ok(co::ERROR::SUCCESS));
+		assert_eq!(states(&m.sink().0[1..]),
+			[co::SERVICE_STATE::RUNNING, co::SERVICE_STATE::RUNNING]);
+		assert_eq!(m.control(&SvcCtl::ParamChange), Ok(co::ERROR::CALL_NOT_IMPLEMENTED));
+	}
+
+	#[test]
+	fn shared_status_unlocked_during_handler() {
+		let reporter = Arc::new(Mutex::new(
+			StatusReporter::new(FakeSink::default(), co::SERVICE_TYPE::WIN32_OWN_PROCESS),
+		));
+		let mut service = FakeService {
+			start_checkpoints: 1,
+			shared_reporter: Some(reporter.clone()),
+			..Default::default()
+		};
+
+		let mut access = &*reporter;
+		start_service(&mut service, &mut access, &[]).unwrap();
+		control_service(&mut service, &mut access, &SvcCtl::Stop).unwrap();
+		assert_eq!(states(&reporter.lock().unwrap().sink.0), [
+			co::SERVICE_STATE::START_PENDING,
+			co::SERVICE_STATE::START_PENDING,
+			co::SERVICE_STATE::RUNNING,
+			co::SERVICE_STATE::STOP_PENDING,
+			co::SERVICE_STATE::STOPPED,
+		]);
+	}
+}
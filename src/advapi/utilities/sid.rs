@@ -0,0 +1,560 @@
+use crate::co;
+use crate::decl::*;
+use crate::guard::*;
+use crate::prelude::*;
+
+/// An owned [security identifier](https://learn.microsoft.com/en-us/windows/win32/secauthz/security-identifiers),
+/// which, unlike [`SID`](crate::SID), is a plain Rust value, and can be
+/// created, parsed, formatted and compared without calling the system.
+///
+/// To pass it to a function which takes a [`SID`](crate::SID), call
+/// [`to_sid_guard`](crate::Sid::to_sid_guard). To convert a
+/// [`SID`](crate::SID) into a `Sid`, use [`From`](std::convert::From).
+///
+/// # Examples
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*, co};
+///
+/// let sid: w::Sid = "S-1-5-32-544".parse()?;
+/// assert_eq!(sid.to_string(), "S-1-5-32-544");
+/// assert_eq!(sid.rid(), Some(544));
+///
+/// let admins = w::Sid::well_known(co::WELL_KNOWN_SID_TYPE::BuiltinAdministrators, None)?;
+/// assert_eq!(sid, admins);
+///
+/// let sid_guard = sid.to_sid_guard()?;
+/// let (name, domain, _) = w::LookupAccountSid(None, &sid_guard)?;
+/// # w::SysResult::Ok(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Sid {
+	authority: [u8; 6],
+	sub_authorities: Vec<u32>,
+}
+
+impl From<&SID> for Sid {
+	fn from(sid: &SID) -> Self {
+		Self {
+			authority: sid.IdentifierAuthority.Value,
+			sub_authorities: sid.SubAuthority().iter().map(|rid| rid.raw()).collect(),
+		}
+	}
+}
+
+impl std::fmt::Display for Sid {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let authority = self.authority.iter()
+			.fold(0u64, |acc, b| (acc << 8) | *b as u64);
+		if authority >> 32 == 0 {
+			write!(f, "S-1-{}", authority)?;
+		} else {
+			write!(f, "S-1-0x{:012X}", authority)?; // too large, formatted as hex
+		}
+		for sub in self.sub_authorities.iter() {
+			write!(f, "-{}", sub)?;
+		}
+		Ok(())
+	}
+}
+
+impl std::str::FromStr for Sid {
+	type Err = co::ERROR;
+
+	/// Parses a SID in the `S-1-5-32-544` form.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut parts = s.split('-');
+		match (parts.next(), parts.next()) {
+			(Some("S" | "s"), Some("1")) => {},
+			_ => return Err(co::ERROR::INVALID_SID),
+		}
+
+		let authority = match parts.next() {
+			Some(a) => match a.strip_prefix("0x").or_else(|| a.strip_prefix("0X")) {
+				Some(hex) => parse_digits(hex, 16)?,
+				None => parse_digits(a, 10)?,
+			},
+			None => return Err(co::ERROR::INVALID_SID),
+		};
+		if authority >> 48 != 0 {
+			return Err(co::ERROR::INVALID_SID);
+		}
+
+		let sub_authorities = parts
+			.map(|p| u32::try_from(parse_digits(p, 10)?).map_err(|_| co::ERROR::INVALID_SID))
+			.collect::<SysResult<Vec<_>>>()?;
+		if sub_authorities.len() > Self::MAX_SUB_AUTHORITIES {
+			return Err(co::ERROR::INVALID_SID);
+		}
+
+		let mut authority_bytes = [0u8; 6];
+		authority_bytes.copy_from_slice(&authority.to_be_bytes()[2..]);
+		Ok(Self { authority: authority_bytes, sub_authorities })
+	}
+}
+
+/// Parses a number made only of digits, since the standard library also
+/// accepts a leading `+`.
+fn parse_digits(s: &str, radix: u32) -> SysResult<u64> {
+	if s.is_empty() || !s.chars().all(|ch| ch.is_digit(radix)) {
+		return Err(co::ERROR::INVALID_SID);
+	}
+	u64::from_str_radix(s, radix).map_err(|_| co::ERROR::INVALID_SID)
+}
+
+impl Sid {
+	/// Maximum number of sub-authorities a SID can have.
+	pub const MAX_SUB_AUTHORITIES: usize = 15;
+
+	/// Creates a new SID from its identifier authority and sub-authorities.
+	///
+	/// # Panics
+	///
+	/// Panics if there are more than
+	/// [`MAX_SUB_AUTHORITIES`](crate::Sid::MAX_SUB_AUTHORITIES)
+	/// sub-authorities.
+	#[must_use]
+	pub fn new(authority: [u8; 6], sub_authorities: &[u32]) -> Self {
+		if sub_authorities.len() > Self::MAX_SUB_AUTHORITIES {
+			panic!("Too many SID sub-authorities: {}.", sub_authorities.len());
+		}
+		Self { authority, sub_authorities: sub_authorities.to_vec() }
+	}
+
+	/// Creates a well-known SID, like
+	/// [`CreateWellKnownSid`](crate::CreateWellKnownSid), but without calling
+	/// the system.
+	///
+	/// The SIDs which are relative to a domain, like
+	/// [`co::WELL_KNOWN_SID_TYPE::AccountDomainAdmins`](crate::co::WELL_KNOWN_SID_TYPE::AccountDomainAdmins),
+	/// require `domain_sid`, otherwise
+	/// [`co::ERROR::INVALID_PARAMETER`](crate::co::ERROR::INVALID_PARAMETER) is
+	/// returned. Types which depend on the logon session are not supported.
+	pub fn well_known(
+		well_known_sid: co::WELL_KNOWN_SID_TYPE,
+		domain_sid: Option<&Sid>,
+	) -> SysResult<Self>
+	{
+		if let Some((_, str_sid)) = WELL_KNOWN.iter().find(|(t, _)| *t == well_known_sid) {
+			return str_sid.parse();
+		}
+
+		match DOMAIN_RELATIVE.iter().find(|(t, _)| *t == well_known_sid) {
+			Some((_, rid)) => match domain_sid.and_then(|d| d.domain()) {
+				Some(domain) => Ok(domain.with_rid(*rid)),
+				None => Err(co::ERROR::INVALID_PARAMETER),
+			},
+			None => Err(co::ERROR::INVALID_PARAMETER),
+		}
+	}
+
+	/// Returns the type of this SID, if it's a well-known SID which doesn't
+	/// depend on the domain, like [`IsWellKnownSid`](crate::IsWellKnownSid).
+	#[must_use]
+	pub fn well_known_type(&self) -> Option<co::WELL_KNOWN_SID_TYPE> {
+		let str_sid = self.to_string();
+		WELL_KNOWN.iter()
+			.find(|(_, s)| *s == str_sid)
+			.map(|(t, _)| *t)
+	}
+
+	/// Returns the identifier authority, as the big-endian bytes of the
+	/// [`SID_IDENTIFIER_AUTHORITY`](crate::SID_IDENTIFIER_AUTHORITY).
+	#[must_use]
+	pub const fn authority(&self) -> [u8; 6] {
+		self.authority
+	}
+
+	/// Returns the sub-authorities.
+	#[must_use]
+	pub fn sub_authorities(&self) -> &[u32] {
+		&self.sub_authorities
+	}
+
+	/// Returns the relative identifier, which is the last sub-authority, if
+	/// any.
+	#[must_use]
+	pub fn rid(&self) -> Option<u32> {
+		self.sub_authorities.last().copied()
+	}
+
+	/// Returns a new SID with the given relative identifier appended to the
+	/// sub-authorities, like a user SID created from its domain SID.
+	///
+	/// # Panics
+	///
+	/// Panics if the SID already has
+	/// [`MAX_SUB_AUTHORITIES`](crate::Sid::MAX_SUB_AUTHORITIES)
+	/// sub-authorities.
+	#[must_use]
+	pub fn with_rid(&self, rid: u32) -> Self {
+		let mut subs = self.sub_authorities.clone();
+		subs.push(rid);
+		Self::new(self.authority, &subs)
+	}
+
+	/// Returns the SID of the domain this SID belongs to: `S-1-5-21-x-y-z` for
+	/// account and domain SIDs, and `S-1-5-32` for builtin ones.
+	#[must_use]
+	pub fn domain(&self) -> Option<Self> {
+		if self.authority != SID_IDENTIFIER_AUTHORITY::NT.Value {
+			return None;
+		}
+		match self.sub_authorities.first() {
+			Some(21) if self.sub_authorities.len() >= 4
+				=> Some(Self::new(self.authority, &self.sub_authorities[..4])),
+			Some(32) => Some(Self::new(self.authority, &self.sub_authorities[..1])),
+			_ => None,
+		}
+	}
+
+	/// Returns `true` if both SIDs belong to the same domain, like
+	/// [`EqualDomainSid`](crate::EqualDomainSid).
+	#[must_use]
+	pub fn eq_domain(&self, other: &Sid) -> bool {
+		match (self.domain(), other.domain()) {
+			(Some(d1), Some(d2)) => d1 == d2,
+			_ => false,
+		}
+	}
+
+	/// Returns `true` if both SIDs have the same authority and the same number
+	/// of sub-authorities, and all sub-authorities but the last are equal, like
+	/// [`EqualPrefixSid`](crate::EqualPrefixSid).
+	#[must_use]
+	pub fn eq_prefix(&self, other: &Sid) -> bool {
+		let len = self.sub_authorities.len();
+		self.authority == other.authority
+			&& len == other.sub_authorities.len()
+			&& self.sub_authorities[..len.saturating_sub(1)]
+				== other.sub_authorities[..len.saturating_sub(1)]
+	}
+
+	/// Returns `true` if this SID starts with all the sub-authorities of the
+	/// given one, which must have the same authority.
+	#[must_use]
+	pub fn starts_with(&self, prefix: &Sid) -> bool {
+		self.authority == prefix.authority
+			&& self.sub_authorities.starts_with(&prefix.sub_authorities)
+	}
+
+	/// Parses a SID in binary form, which must have the exact length of the
+	/// SID.
+	pub fn from_bytes(buf: &[u8]) -> SysResult<Self> {
+		match Self::parse(buf)? {
+			(sid, len) if len == buf.len() => Ok(sid),
+			_ => Err(co::ERROR::INVALID_SID),
+		}
+	}
+
+	/// Serializes the SID into its binary form, which is the memory layout of
+	/// a [`SID`](crate::SID).
+	#[must_use]
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut buf = Vec::with_capacity(self.len_bytes());
+		self.write(&mut buf);
+		buf
+	}
+
+	/// Returns the length of the binary form, in bytes.
+	#[must_use]
+	pub fn len_bytes(&self) -> usize {
+		8 + self.sub_authorities.len() * 4
+	}
+
+	/// Allocates a [`SID`](crate::SID) with the contents of this SID, so it
+	/// can be passed to the functions which take a `&SID`.
+	#[must_use]
+	pub fn to_sid_guard(&self) -> SysResult<SidGuard> {
+		let bytes = self.to_bytes();
+		let sid_buf = HGLOBAL::GlobalAlloc(
+			Some(co::GMEM::FIXED | co::GMEM::ZEROINIT),
+			bytes.len(),
+		)?;
+
+		unsafe {
+			std::ptr::copy_nonoverlapping(bytes.as_ptr(), sid_buf.ptr() as *mut u8, bytes.len());
+			Ok(SidGuard::new(sid_buf))
+		}
+	}
+
+	/// Parses a SID at the beginning of the buffer, returning it along with its
+	/// length in bytes.
+	pub(in crate::advapi) fn parse(buf: &[u8]) -> SysResult<(Self, usize)> {
+		if buf.len() < 8 || buf[0] != 1 || buf[1] as usize > Self::MAX_SUB_AUTHORITIES {
+			return Err(co::ERROR::INVALID_SID);
+		}
+		let len = 8 + buf[1] as usize * 4;
+		if buf.len() < len {
+			return Err(co::ERROR::INVALID_SID);
+		}
+
+		let mut authority = [0u8; 6];
+		authority.copy_from_slice(&buf[2..8]);
+		let sub_authorities = buf[8..len].chunks_exact(4)
+			.map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+			.collect();
+		Ok((Self { authority, sub_authorities }, len))
+	}
+
+	/// Appends the binary form to the buffer.
+	pub(in crate::advapi) fn write(&self, buf: &mut Vec<u8>) {
+		buf.push(1); // revision
+		buf.push(self.sub_authorities.len() as _);
+		buf.extend_from_slice(&self.authority);
+		for sub in self.sub_authorities.iter() {
+			buf.extend_from_slice(&sub.to_le_bytes());
+		}
+	}
+}
+
+/// Well-known SIDs which don't depend on the domain.
+const WELL_KNOWN: &[(co::WELL_KNOWN_SID_TYPE, &str)] = &[
+	(co::WELL_KNOWN_SID_TYPE::Null, "S-1-0-0"),
+	(co::WELL_KNOWN_SID_TYPE::World, "S-1-1-0"),
+	(co::WELL_KNOWN_SID_TYPE::Local, "S-1-2-0"),
+	(co::WELL_KNOWN_SID_TYPE::ConsoleLogon, "S-1-2-1"),
+	(co::WELL_KNOWN_SID_TYPE::CreatorOwner, "S-1-3-0"),
+	(co::WELL_KNOWN_SID_TYPE::CreatorGroup, "S-1-3-1"),
+	(co::WELL_KNOWN_SID_TYPE::CreatorOwnerServer, "S-1-3-2"),
+	(co::WELL_KNOWN_SID_TYPE::CreatorGroupServer, "S-1-3-3"),
+	(co::WELL_KNOWN_SID_TYPE::CreatorOwnerRights, "S-1-3-4"),
+	(co::WELL_KNOWN_SID_TYPE::NtAuthority, "S-1-5"),
+	(co::WELL_KNOWN_SID_TYPE::Dialup, "S-1-5-1"),
+	(co::WELL_KNOWN_SID_TYPE::Network, "S-1-5-2"),
+	(co::WELL_KNOWN_SID_TYPE::Batch, "S-1-5-3"),
+	(co::WELL_KNOWN_SID_TYPE::Interactive, "S-1-5-4"),
+	(co::WELL_KNOWN_SID_TYPE::Service, "S-1-5-6"),
+	(co::WELL_KNOWN_SID_TYPE::Anonymous, "S-1-5-7"),
+	(co::WELL_KNOWN_SID_TYPE::Proxy, "S-1-5-8"),
+	(co::WELL_KNOWN_SID_TYPE::EnterpriseControllers, "S-1-5-9"),
+	(co::WELL_KNOWN_SID_TYPE::SelfSid, "S-1-5-10"),
+	(co::WELL_KNOWN_SID_TYPE::AuthenticatedUser, "S-1-5-11"),
+	(co::WELL_KNOWN_SID_TYPE::RestrictedCode, "S-1-5-12"),
+	(co::WELL_KNOWN_SID_TYPE::TerminalServer, "S-1-5-13"),
+	(co::WELL_KNOWN_SID_TYPE::RemoteLogonId, "S-1-5-14"),
+	(co::WELL_KNOWN_SID_TYPE::ThisOrganization, "S-1-5-15"),
+	(co::WELL_KNOWN_SID_TYPE::IUser, "S-1-5-17"),
+	(co::WELL_KNOWN_SID_TYPE::LocalSystem, "S-1-5-18"),
+	(co::WELL_KNOWN_SID_TYPE::LocalService, "S-1-5-19"),
+	(co::WELL_KNOWN_SID_TYPE::NetworkService, "S-1-5-20"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinDomain, "S-1-5-32"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinAdministrators, "S-1-5-32-544"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinUsers, "S-1-5-32-545"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinGuests, "S-1-5-32-546"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinPowerUsers, "S-1-5-32-547"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinAccountOperators, "S-1-5-32-548"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinSystemOperators, "S-1-5-32-549"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinPrintOperators, "S-1-5-32-550"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinBackupOperators, "S-1-5-32-551"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinReplicator, "S-1-5-32-552"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinPreWindows2000CompatibleAccess, "S-1-5-32-554"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinRemoteDesktopUsers, "S-1-5-32-555"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinNetworkConfigurationOperators, "S-1-5-32-556"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinIncomingForestTrustBuilders, "S-1-5-32-557"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinPerfMonitoringUsers, "S-1-5-32-558"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinPerfLoggingUsers, "S-1-5-32-559"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinAuthorizationAccess, "S-1-5-32-560"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinTerminalServerLicenseServers, "S-1-5-32-561"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinDCOMUsers, "S-1-5-32-562"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinIUsers, "S-1-5-32-568"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinCryptoOperators, "S-1-5-32-569"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinEventLogReadersGroup, "S-1-5-32-573"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinCertSvcDComAccessGroup, "S-1-5-32-574"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinRDSRemoteAccessServers, "S-1-5-32-575"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinRDSEndpointServers, "S-1-5-32-576"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinRDSManagementServers, "S-1-5-32-577"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinHyperVAdmins, "S-1-5-32-578"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinAccessControlAssistanceOperators, "S-1-5-32-579"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinRemoteManagementUsers, "S-1-5-32-580"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinDefaultSystemManagedGroup, "S-1-5-32-581"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinStorageReplicaAdmins, "S-1-5-32-582"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinDeviceOwners, "S-1-5-32-583"),
+	(co::WELL_KNOWN_SID_TYPE::WriteRestrictedCode, "S-1-5-33"),
+	(co::WELL_KNOWN_SID_TYPE::NTLMAuthentication, "S-1-5-64-10"),
+	(co::WELL_KNOWN_SID_TYPE::SChannelAuthentication, "S-1-5-64-14"),
+	(co::WELL_KNOWN_SID_TYPE::DigestAuthentication, "S-1-5-64-21"),
+	(co::WELL_KNOWN_SID_TYPE::ThisOrganizationCertificate, "S-1-5-65-1"),
+	(co::WELL_KNOWN_SID_TYPE::UserModeDrivers, "S-1-5-84-0-0-0-0-0"),
+	(co::WELL_KNOWN_SID_TYPE::LocalAccount, "S-1-5-113"),
+	(co::WELL_KNOWN_SID_TYPE::LocalAccountAndAdministrator, "S-1-5-114"),
+	(co::WELL_KNOWN_SID_TYPE::OtherOrganization, "S-1-5-1000"),
+	(co::WELL_KNOWN_SID_TYPE::ApplicationPackageAuthority, "S-1-15"),
+	(co::WELL_KNOWN_SID_TYPE::BuiltinAnyPackage, "S-1-15-2-1"),
+	(co::WELL_KNOWN_SID_TYPE::CapabilityInternetClient, "S-1-15-3-1"),
+	(co::WELL_KNOWN_SID_TYPE::CapabilityInternetClientServer, "S-1-15-3-2"),
+	(co::WELL_KNOWN_SID_TYPE::CapabilityPrivateNetworkClientServer, "S-1-15-3-3"),
+	(co::WELL_KNOWN_SID_TYPE::CapabilityPicturesLibrary, "S-1-15-3-4"),
+	(co::WELL_KNOWN_SID_TYPE::CapabilityVideosLibrary, "S-1-15-3-5"),
+	(co::WELL_KNOWN_SID_TYPE::CapabilityMusicLibrary, "S-1-15-3-6"),
+	(co::WELL_KNOWN_SID_TYPE::CapabilityDocumentsLibrary, "S-1-15-3-7"),
+	(co::WELL_KNOWN_SID_TYPE::CapabilityEnterpriseAuthentication, "S-1-15-3-8"),
+	(co::WELL_KNOWN_SID_TYPE::CapabilitySharedUserCertificates, "S-1-15-3-9"),
+	(co::WELL_KNOWN_SID_TYPE::CapabilityRemovableStorage, "S-1-15-3-10"),
+	(co::WELL_KNOWN_SID_TYPE::CapabilityAppointments, "S-1-15-3-11"),
+	(co::WELL_KNOWN_SID_TYPE::CapabilityContacts, "S-1-15-3-12"),
+	(co::WELL_KNOWN_SID_TYPE::UntrustedLabel, "S-1-16-0"),
+	(co::WELL_KNOWN_SID_TYPE::LowLabel, "S-1-16-4096"),
+	(co::WELL_KNOWN_SID_TYPE::MediumLabel, "S-1-16-8192"),
+	(co::WELL_KNOWN_SID_TYPE::MediumPlusLabel, "S-1-16-8448"),
+	(co::WELL_KNOWN_SID_TYPE::HighLabel, "S-1-16-12288"),
+	(co::WELL_KNOWN_SID_TYPE::SystemLabel, "S-1-16-16384"),
+	(co::WELL_KNOWN_SID_TYPE::AuthenticationAuthorityAsserted, "S-1-18-1"),
+	(co::WELL_KNOWN_SID_TYPE::AuthenticationServiceAsserted, "S-1-18-2"),
+	(co::WELL_KNOWN_SID_TYPE::AuthenticationFreshKeyAuth, "S-1-18-3"),
+	(co::WELL_KNOWN_SID_TYPE::AuthenticationKeyTrust, "S-1-18-4"),
+	(co::WELL_KNOWN_SID_TYPE::AuthenticationKeyPropertyMFA, "S-1-18-5"),
+	(co::WELL_KNOWN_SID_TYPE::AuthenticationKeyPropertyAttestation, "S-1-18-6"),
+];
+
+/// Well-known SIDs relative to a domain, and their relative identifiers.
+const DOMAIN_RELATIVE: &[(co::WELL_KNOWN_SID_TYPE, u32)] = &[
+	(co::WELL_KNOWN_SID_TYPE::EnterpriseReadonlyControllers, 498),
+	(co::WELL_KNOWN_SID_TYPE::AccountAdministrator, 500),
+	(co::WELL_KNOWN_SID_TYPE::AccountGuest, 501),
+	(co::WELL_KNOWN_SID_TYPE::AccountKrbtgt, 502),
+	(co::WELL_KNOWN_SID_TYPE::AccountDefaultSystemManaged, 503),
+	(co::WELL_KNOWN_SID_TYPE::AccountDomainAdmins, 512),
+	(co::WELL_KNOWN_SID_TYPE::AccountDomainUsers, 513),
+	(co::WELL_KNOWN_SID_TYPE::AccountDomainGuests, 514),
+	(co::WELL_KNOWN_SID_TYPE::AccountComputers, 515),
+	(co::WELL_KNOWN_SID_TYPE::AccountControllers, 516),
+	(co::WELL_KNOWN_SID_TYPE::AccountCertAdmins, 517),
+	(co::WELL_KNOWN_SID_TYPE::AccountSchemaAdmins, 518),
+	(co::WELL_KNOWN_SID_TYPE::AccountEnterpriseAdmins, 519),
+	(co::WELL_KNOWN_SID_TYPE::AccountPolicyAdmins, 520),
+	(co::WELL_KNOWN_SID_TYPE::AccountReadonlyControllers, 521),
+	(co::WELL_KNOWN_SID_TYPE::AccountCloneableControllers, 522),
+	(co::WELL_KNOWN_SID_TYPE::AccountProtectedUsers, 525),
+	(co::WELL_KNOWN_SID_TYPE::AccountKeyAdmins, 526),
+	(co::WELL_KNOWN_SID_TYPE::AccountEnterpriseKeyAdmins, 527),
+	(co::WELL_KNOWN_SID_TYPE::AccountRasAndIasServers, 553),
+	(co::WELL_KNOWN_SID_TYPE::CacheablePrincipalsGroup, 571),
+	(co::WELL_KNOWN_SID_TYPE::NonCacheablePrincipalsGroup, 572),
+];
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn sid(s: &str) -> Sid {
+		s.parse().unwrap()
+	}
+
+	#[test]
+	fn string_round_trip() {
+		for s in [
+			"S-1-5",
+			"S-1-5-18",
+			"S-1-5-21-3623811015-3361044348-30300820-1013",
+			"S-1-5-21-1-2-3-4-5-6-7-8-9-10-11-12-13-14", // 15 sub-authorities
+			"S-1-0x123456789ABC-1",
+			"S-1-4294967295-0",
+		] {
+			assert_eq!(sid(s).to_string(), s);
+		}
+		assert_eq!(sid("s-1-5-18").to_string(), "S-1-5-18");
+		assert_eq!(sid("S-1-0x5-18"), sid("S-1-5-18"));
+		assert_eq!(sid("S-1-4294967296-1").to_string(), "S-1-0x000100000000-1"); // too large for decimal
+		assert_eq!(sid("S-1-0xffffffffffff").authority(), [0xff; 6]);
+		assert_eq!(sid("S-1-5-32-544").sub_authorities(), [32, 544]);
+	}
+
+	#[test]
+	fn string_malformed() {
+		for s in [
+			"", "S", "S-1", "S-2-5-18", "X-1-5-18", "S-1-", "S-1-5-", "S-1-5--18",
+			"S-1-+5-18", "S-1-5-+18", "S-1--5-18", "S-1-5- 18", "S-1-0x", "S-1-0x+5",
+			"S-1-0x1000000000000", "S-1-281474976710656", "S-1-5-4294967296", "S-1-5-18a",
+			"S-1-5-21-1-2-3-4-5-6-7-8-9-10-11-12-13-14-15", // 16 sub-authorities
+		] {
+			assert_eq!(s.parse::<Sid>(), Err(co::ERROR::INVALID_SID), "{}", s);
+		}
+	}
+
+	#[test]
+	fn bytes_round_trip() {
+		let admins = sid("S-1-5-32-544");
+		let bytes = admins.to_bytes();
+		assert_eq!(bytes, [1, 2, 0, 0, 0, 0, 0, 5, 32, 0, 0, 0, 0x20, 2, 0, 0]);
+		assert_eq!(admins.len_bytes(), bytes.len());
+		assert_eq!(Sid::from_bytes(&bytes).unwrap(), admins);
+
+		let max = sid("S-1-0x123456789ABC-1-2-3-4-5-6-7-8-9-10-11-12-13-14-15");
+		assert_eq!(Sid::from_bytes(&max.to_bytes()).unwrap(), max);
+		assert_eq!(Sid::from_bytes(&sid("S-1-1").to_bytes()).unwrap(), sid("S-1-1"));
+
+		let mut trailing = bytes.clone();
+		trailing.push(0);
+		assert_eq!(Sid::parse(&trailing).unwrap(), (admins, 16));
+		assert_eq!(Sid::from_bytes(&trailing), Err(co::ERROR::INVALID_SID));
+		assert_eq!(Sid::from_bytes(&bytes[..15]), Err(co::ERROR::INVALID_SID));
+		assert_eq!(Sid::from_bytes(&[2, 0, 0, 0, 0, 0, 0, 5]), Err(co::ERROR::INVALID_SID)); // revision
+		assert_eq!(Sid::from_bytes(&[1, 16, 0, 0, 0, 0, 0, 5]), Err(co::ERROR::INVALID_SID)); // too many
+	}
+
+	#[test]
+	fn domain() {
+		let user = sid("S-1-5-21-1-2-3-1001");
+		assert_eq!(user.domain(), Some(sid("S-1-5-21-1-2-3")));
+		assert_eq!(user.rid(), Some(1001));
+		assert_eq!(sid("S-1-5-21-1-2-3").with_rid(1001), user);
+		assert_eq!(sid("S-1-5-32-544").domain(), Some(sid("S-1-5-32")));
+		assert_eq!(sid("S-1-5-18").domain(), None);
+		assert_eq!(sid("S-1-5-21-1-2").domain(), None);
+		assert_eq!(sid("S-1-16-21-1-2-3").domain(), None);
+
+		assert!(user.eq_domain(&sid("S-1-5-21-1-2-3-500")));
+		assert!(!user.eq_domain(&sid("S-1-5-21-1-2-4-500")));
+		assert!(sid("S-1-5-32-544").eq_domain(&sid("S-1-5-32-545")));
+		assert!(!sid("S-1-5-18").eq_domain(&sid("S-1-5-18")));
+
+		assert!(user.eq_prefix(&sid("S-1-5-21-1-2-3-1002")));
+		assert!(!user.eq_prefix(&sid("S-1-5-21-1-2-4-1001")));
+		assert!(!user.eq_prefix(&sid("S-1-5-21-1-2-3")));
+		assert!(sid("S-1-5").eq_prefix(&sid("S-1-5")));
+		assert!(!sid("S-1-5-18").eq_prefix(&sid("S-1-16-18")));
+
+		assert!(user.starts_with(&sid("S-1-5-21")));
+		assert!(!user.starts_with(&sid("S-1-16-21")));
+	}
+
+	#[test]
+	fn well_known() {
+		for (well_known_type, str_sid) in WELL_KNOWN.iter() {
+			let s = Sid::well_known(*well_known_type, None).unwrap();
+			assert_eq!(s.to_string(), *str_sid);
+			assert_eq!(s.well_known_type(), Some(*well_known_type));
+		}
+		assert_eq!(
+			Sid::well_known(co::WELL_KNOWN_SID_TYPE::BuiltinAdministrators, None).unwrap(),
+			sid("S-1-5-32-544"),
+		);
+		assert_eq!(sid("S-1-5-21-1-2-3-1001").well_known_type(), None);
+	}
+
+	#[test]
+	fn well_known_domain_relative() {
+		let user = sid("S-1-5-21-1-2-3-1001");
+		assert_eq!(
+			Sid::well_known(co::WELL_KNOWN_SID_TYPE::AccountDomainAdmins, Some(&user)).unwrap(),
+			sid("S-1-5-21-1-2-3-512"),
+		);
+		assert_eq!(
+			Sid::well_known(co::WELL_KNOWN_SID_TYPE::AccountAdministrator, Some(&sid("S-1-5-21-1-2-3"))).unwrap(),
+			sid("S-1-5-21-1-2-3-500"),
+		);
+		assert_eq!(
+			Sid::well_known(co::WELL_KNOWN_SID_TYPE::AccountDomainAdmins, None),
+			Err(co::ERROR::INVALID_PARAMETER),
+		);
+		assert_eq!(
+			Sid::well_known(co::WELL_KNOWN_SID_TYPE::AccountDomainAdmins, Some(&sid("S-1-5-18"))),
+			Err(co::ERROR::INVALID_PARAMETER),
+		);
+		assert_eq!(
+			Sid::well_known(co::WELL_KNOWN_SID_TYPE::LogonIds, Some(&user)),
+			Err(co::ERROR::INVALID_PARAMETER),
+		);
+	}
+}
@@ -0,0 +1,245 @@
+use crate::co;
+use crate::decl::*;
+use crate::guard::*;
+use crate::prelude::*;
+
+/// A group of a [`TokenSnapshot`](crate::TokenSnapshot).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenGroup {
+	/// The SID of the group.
+	pub sid: Sid,
+	/// The group attributes.
+	pub attributes: co::SE_GROUP,
+}
+
+impl TokenGroup {
+	/// Returns `true` if the group is enabled, and it's not deny-only.
+	#[must_use]
+	pub fn is_enabled(&self) -> bool {
+		self.attributes.has(co::SE_GROUP::ENABLED) && !self.is_deny_only()
+	}
+
+	/// Returns `true` if the group is used only to deny access.
+	#[must_use]
+	pub fn is_deny_only(&self) -> bool {
+		self.attributes.has(co::SE_GROUP::USE_FOR_DENY_ONLY)
+	}
+}
+
+/// A privilege of a [`TokenSnapshot`](crate::TokenSnapshot).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenPrivilege {
+	/// The name of the privilege.
+	pub name: co::SE_PRIV,
+	/// The locally unique identifier of the privilege.
+	pub luid: LUID,
+	/// The privilege attributes.
+	pub attributes: co::SE_PRIV_ATTR,
+}
+
+impl TokenPrivilege {
+	/// Returns `true` if the privilege is enabled.
+	#[must_use]
+	pub fn is_enabled(&self) -> bool {
+		self.attributes.has(co::SE_PRIV_ATTR::ENABLED)
+	}
+}
+
+/// An owned snapshot of the information of an access token, returned by
+/// [`HACCESSTOKEN::snapshot`](crate::prelude::advapi_Haccesstoken::snapshot).
+///
+/// Unlike [`TokenInfo`](crate::TokenInfo), which holds the raw structs of a
+/// single information class, the snapshot gathers the most used information
+/// at once, with the SIDs as [`Sid`](crate::Sid) values and the privileges
+/// already resolved to their names.
+///
+/// # Examples
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*, co};
+///
+/// let nfo = w::HPROCESS::GetCurrentProcess()
+///     .OpenProcessToken(co::TOKEN::QUERY)?
+///     .snapshot()?;
+///
+/// println!("User: {}", nfo.user);
+/// println!("Integrity: {}", nfo.integrity_level);
+/// for priv_ in nfo.privileges.iter().filter(|p| p.is_enabled()) {
+///     println!("Enabled: {}", priv_.name);
+/// }
+/// # w::SysResult::Ok(())
+/// ```
+pub struct TokenSnapshot {
+	/// The user of the token.
+	pub user: Sid,
+	/// The groups of the token, with their attributes.
+	pub groups: Vec<TokenGroup>,
+	/// The privileges of the token, with their attributes.
+	pub privileges: Vec<TokenPrivilege>,
+	/// The mandatory integrity level, like `S-1-16-8192` for medium; see
+	/// [`co::WELL_KNOWN_SID_TYPE::MediumLabel`](crate::co::WELL_KNOWN_SID_TYPE::MediumLabel).
+	pub integrity_level: Sid,
+	/// The elevation type.
+	pub elevation_type: co::TOKEN_ELEVATION_TYPE,
+	/// The linked token, which is the elevated token for a limited one, and
+	/// vice-versa. Present only if the elevation type is not
+	/// [`co::TOKEN_ELEVATION_TYPE::Default`](crate::co::TOKEN_ELEVATION_TYPE::Default).
+	pub linked_token: Option<CloseHandleGuard<HACCESSTOKEN>>,
+}
+
+impl TokenSnapshot {
+	/// Reads the information of the access token, which must have been opened
+	/// with [`co::TOKEN::QUERY`](crate::co::TOKEN::QUERY).
+	pub fn read(htoken: &HACCESSTOKEN) -> SysResult<Self> {
+		let TokenInfo::User(user) = htoken.GetTokenInformation(co::TOKEN_INFORMATION_CLASS::User)?
+			else { return Err(co::ERROR::INVALID_PARAMETER) };
+		let TokenInfo::Groups(groups) = htoken.GetTokenInformation(co::TOKEN_INFORMATION_CLASS::Groups)?
+			else { return Err(co::ERROR::INVALID_PARAMETER) };
+		let TokenInfo::Privileges(privs) = htoken.GetTokenInformation(co::TOKEN_INFORMATION_CLASS::Privileges)?
+			else { return Err(co::ERROR::INVALID_PARAMETER) };
+		let TokenInfo::IntegrityLevel(label) = htoken.GetTokenInformation(co::TOKEN_INFORMATION_CLASS::IntegrityLevel)?
+			else { return Err(co::ERROR::INVALID_PARAMETER) };
+		let TokenInfo::ElevationType(elevation_type) = htoken.GetTokenInformation(co::TOKEN_INFORMATION_CLASS::ElevationType)?
+			else { return Err(co::ERROR::INVALID_PARAMETER) };
+
+		let linked_token = match *elevation_type {
+			co::TOKEN_ELEVATION_TYPE::Default => None,
+			_ => match htoken.GetTokenInformation(co::TOKEN_INFORMATION_CLASS::LinkedToken)? {
+				TokenInfo::LinkedToken(linked) => Some(unsafe {
+					CloseHandleGuard::new(linked.LinkedToken.raw_copy())
+				}),
+				_ => return Err(co::ERROR::INVALID_PARAMETER),
+			},
+		};
+
+		let sid_of = |sa: &SID_AND_ATTRIBUTES| sa.Sid()
+			.map(|sid| Sid::from(&*sid))
+			.ok_or(co::ERROR::INVALID_SID);
+
+		Ok(Self {
+			user: sid_of(&user.User)?,
+			groups: groups.Groups().iter()
+				.map(|g| Ok(TokenGroup {
+					sid: sid_of(g)?,
+					attributes: unsafe { co::SE_GROUP::from_raw(g.Attributes) },
+				}))
+				.collect::<SysResult<Vec<_>>>()?,
+			privileges: privs.Privileges().iter()
+				.map(|p| Ok(TokenPrivilege {
+					name: LookupPrivilegeName(None, p.Luid)?,
+					luid: p.Luid,
+					attributes: p.Attributes,
+				}))
+				.collect::<SysResult<Vec<_>>>()?,
+			integrity_level: sid_of(&label.Label)?,
+			elevation_type: *elevation_type,
+			linked_token,
+		})
+	}
+
+	/// Returns `true` if the elevation type is
+	/// [`co::TOKEN_ELEVATION_TYPE::Full`](crate::co::TOKEN_ELEVATION_TYPE::Full).
+	#[must_use]
+	pub fn is_elevated(&self) -> bool {
+		self.elevation_type == co::TOKEN_ELEVATION_TYPE::Full
+	}
+
+	/// Returns the privilege with the given name, if the token has it.
+	#[must_use]
+	pub fn privilege(&self, name: co::SE_PRIV) -> Option<&TokenPrivilege> {
+		self.privileges.iter().find(|p| p.name == name)
+	}
+
+	/// Returns `true` if the SID is the user or an enabled group of the token.
+	#[must_use]
+	pub fn is_member(&self, sid: &Sid) -> bool {
+		self.user == *sid
+			|| self.groups.iter().any(|g| g.is_enabled() && g.sid == *sid)
+	}
+}
+
+impl From<&TokenSnapshot> for AccessEvaluator {
+	/// Creates an evaluator with the user, the groups and the enabled
+	/// privileges of the token.
+	fn from(nfo: &TokenSnapshot) -> Self {
+		Self {
+			sids: std::iter::once(nfo.user.clone())
+				.chain(nfo.groups.iter().filter(|g| g.is_enabled()).map(|g| g.sid.clone()))
+				.collect(),
+			deny_only_sids: nfo.groups.iter()
+				.filter(|g| g.is_deny_only())
+				.map(|g| g.sid.clone())
+				.collect(),
+			privileges: nfo.privileges.iter()
+				.filter(|p| p.is_enabled())
+				.map(|p| p.name)
+				.collect(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn sid(s: &str) -> Sid {
+		s.parse().unwrap()
+	}
+
+	fn snapshot() -> TokenSnapshot {
+		TokenSnapshot {
+			user: sid("S-1-5-21-1-2-3-1001"),
+			groups: vec![
+				TokenGroup { // enabled
+					sid: sid("S-1-5-32-545"),
+					attributes: co::SE_GROUP::MANDATORY | co::SE_GROUP::ENABLED,
+				},
+				TokenGroup { // disabled
+					sid: sid("S-1-5-32-551"),
+					attributes: co::SE_GROUP::ENABLED_BY_DEFAULT,
+				},
+				TokenGroup { // deny-only, even if marked as enabled
+					sid: sid("S-1-5-32-544"),
+					attributes: co::SE_GROUP::ENABLED | co::SE_GROUP::USE_FOR_DENY_ONLY,
+				},
+			],
+			privileges: vec![
+				TokenPrivilege {
+					name: co::SE_PRIV::CHANGE_NOTIFY_NAME,
+					luid: LUID::new(23, 0),
+					attributes: co::SE_PRIV_ATTR::ENABLED | co::SE_PRIV_ATTR::ENABLED_BY_DEFAULT,
+				},
+				TokenPrivilege {
+					name: co::SE_PRIV::SHUTDOWN_NAME,
+					luid: LUID::new(19, 0),
+					attributes: co::SE_PRIV_ATTR::NoValue,
+				},
+			],
+			integrity_level: sid("S-1-16-8192"),
+			elevation_type: co::TOKEN_ELEVATION_TYPE::Limited,
+			linked_token: None,
+		}
+	}
+
+	#[test]
+	fn queries() {
+		let nfo = snapshot();
+		assert!(!nfo.is_elevated());
+		assert!(nfo.is_member(&sid("S-1-5-21-1-2-3-1001")));
+		assert!(nfo.is_member(&sid("S-1-5-32-545")));
+		assert!(!nfo.is_member(&sid("S-1-5-32-551")));
+		assert!(!nfo.is_member(&sid("S-1-5-32-544")));
+
+		assert!(nfo.privilege(co::SE_PRIV::CHANGE_NOTIFY_NAME).unwrap().is_enabled());
+		assert!(!nfo.privilege(co::SE_PRIV::SHUTDOWN_NAME).unwrap().is_enabled());
+		assert!(nfo.privilege(co::SE_PRIV::DEBUG_NAME).is_none());
+	}
+
+	#[test]
+	fn to_access_evaluator() {
+		let evaluator = AccessEvaluator::from(&snapshot());
+		assert_eq!(evaluator.sids, [sid("S-1-5-21-1-2-3-1001"), sid("S-1-5-32-545")]);
+		assert_eq!(evaluator.deny_only_sids, [sid("S-1-5-32-544")]);
+		assert_eq!(evaluator.privileges, [co::SE_PRIV::CHANGE_NOTIFY_NAME]);
+	}
+}
@@ -125,7 +125,7 @@ const_ordinary! { CAL: u32;
 	UMALQURA 23
 }
 
-const_values_pub! { CB;
+const_values_pub_named! { CB, CB_NAMES_COMCTL;
 	SETMINVISIBLE CB_FIRST + 1
 	GETMINVISIBLE CB_FIRST + 2
 	SETCUEBANNER CB_FIRST + 3
@@ -286,7 +286,7 @@ const_ws! { DTS: u32;
 	RIGHTALIGN 0x0020
 }
 
-const_values_pub! { EM;
+const_values_pub_named! { EM, EM_NAMES_COMCTL;
 	SETCUEBANNER EM_FIRST + 1
 	GETCUEBANNER EM_FIRST + 2
 	SHOWBALLOONTIP EM_FIRST + 3
@@ -2547,3 +2547,16 @@ const_ordinary! { VK_DIR: u16;
 	RIGHT 0x27
 	DOWN 0x28
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn names_declared_in_comctl() {
+		assert_eq!(format!("{:?}", CB::SETCUEBANNER), "CB::SETCUEBANNER");
+		assert_eq!(format!("{:?}", CB::ADDSTRING), "CB::ADDSTRING");
+		assert_eq!("EM::SHOWBALLOONTIP".parse::<EM>().unwrap(), EM::SHOWBALLOONTIP);
+		assert_eq!("co::EM::GETSEL".parse::<EM>().unwrap(), EM::GETSEL);
+	}
+}
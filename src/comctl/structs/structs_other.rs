@@ -803,7 +803,7 @@ impl NMHDR {
 /// # w::SysResult::Ok(())
 /// ```
 #[repr(transparent)]
-#[derive(Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct NmhdrCode(i32);
 
 impl From<i32> for NmhdrCode {
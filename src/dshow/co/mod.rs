@@ -4,3 +4,5 @@ mod hresult;
 
 pub use consts::*;
 pub use guids::*;
+
+pub(crate) use hresult::HRESULT_NAMES_DSHOW;
@@ -1,6 +1,6 @@
 use crate::co::*;
 
-const_values_pub! { HRESULT;
+const_values_pub_named! { HRESULT, HRESULT_NAMES_DXGI;
 	DXGI_STATUS_OCCLUDED 0x087a_0001
 	DXGI_STATUS_CLIPPED 0x087a_0002
 	DXGI_STATUS_NO_REDIRECTION 0x087a_0004
@@ -2,3 +2,5 @@ mod consts;
 mod hresult;
 
 pub use consts::*;
+
+pub(crate) use hresult::HRESULT_NAMES_DXGI;
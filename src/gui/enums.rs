@@ -129,6 +129,53 @@ pub enum Horz {
 	/// When parent window resizes, the control width will stretch/shrink
 	/// accordingly. Position will remain fixed.
 	Resize,
+	/// When parent window resizes, each edge of the control moves by a
+	/// percentage of the width change, and the resulting width is clamped by
+	/// the optional minimum and maximum, in pixels. If clamped, the left edge
+	/// is kept.
+	///
+	/// [`Horz::None`](crate::gui::Horz::None) is equivalent to 0% and 0%,
+	/// [`Horz::Repos`](crate::gui::Horz::Repos) to 100% and 100%, and
+	/// [`Horz::Resize`](crate::gui::Horz::Resize) to 0% and 100%.
+	///
+	/// Both percentages must be within 0 and 100, and `left` must not be
+	/// greater than `right`, otherwise the resizing panics.
+	///
+	/// # Examples
+	///
+	/// Two side-by-side panes which split the extra width 60/40:
+	///
+	/// ```no_run
+	/// use winsafe::gui;
+	///
+	/// let left_pane = gui::Horz::Anchor { left: 0, right: 60, min: Some(100), max: None };
+	/// let right_pane = gui::Horz::Anchor { left: 60, right: 100, min: None, max: None };
+	/// ```
+	Anchor {
+		/// Percentage of the width change applied to the left edge, from 0 to
+		/// `right`.
+		left: u8,
+		/// Percentage of the width change applied to the right edge, from
+		/// `left` to 100.
+		right: u8,
+		/// Minimum width, in pixels.
+		min: Option<u32>,
+		/// Maximum width, in pixels.
+		max: Option<u32>,
+	},
+}
+
+impl Horz {
+	/// Returns `true` if the control width may change when the parent window
+	/// is resized.
+	#[must_use]
+	pub(in crate::gui) const fn resizes(&self) -> bool {
+		match self {
+			Self::None | Self::Repos => false,
+			Self::Resize => true,
+			Self::Anchor { left, right, .. } => *left != *right,
+		}
+	}
 }
 
 /// The class icon to be loaded for
@@ -199,6 +246,42 @@ pub enum Vert {
 	/// When parent window resizes, the control height will stretch/shrink
 	/// accordingly. Position will remain fixed.
 	Resize,
+	/// When parent window resizes, each edge of the control moves by a
+	/// percentage of the height change, and the resulting height is clamped by
+	/// the optional minimum and maximum, in pixels. If clamped, the top edge is
+	/// kept.
+	///
+	/// [`Vert::None`](crate::gui::Vert::None) is equivalent to 0% and 0%,
+	/// [`Vert::Repos`](crate::gui::Vert::Repos) to 100% and 100%, and
+	/// [`Vert::Resize`](crate::gui::Vert::Resize) to 0% and 100%.
+	///
+	/// Both percentages must be within 0 and 100, and `top` must not be
+	/// greater than `bottom`, otherwise the resizing panics.
+	Anchor {
+		/// Percentage of the height change applied to the top edge, from 0 to
+		/// `bottom`.
+		top: u8,
+		/// Percentage of the height change applied to the bottom edge, from
+		/// `top` to 100.
+		bottom: u8,
+		/// Minimum height, in pixels.
+		min: Option<u32>,
+		/// Maximum height, in pixels.
+		max: Option<u32>,
+	},
+}
+
+impl Vert {
+	/// Returns `true` if the control height may change when the parent window
+	/// is resized.
+	#[must_use]
+	pub(in crate::gui) const fn resizes(&self) -> bool {
+		match self {
+			Self::None | Self::Repos => false,
+			Self::Resize => true,
+			Self::Anchor { top, bottom, .. } => *top != *bottom,
+		}
+	}
 }
 
 /// The result of processing a raw [`WM`](crate::gui::events::WindowEvents::wm),
@@ -2,7 +2,7 @@ use std::ptr::NonNull;
 
 use crate::co;
 use crate::decl::*;
-use crate::gui::{*, privs::*};
+use crate::gui::{*, events::*, privs::*};
 use crate::msg::*;
 
 /// Base to all native control events. This is actually a proxy to the events of
@@ -23,21 +23,21 @@ impl BaseCtrlEventsProxy {
 	}
 
 	/// Adds a `WM_COMMAND` event to the parent window.
-	pub(in crate::gui) fn wm_command<F>(&self, code: impl Into<co::CMD>, func: F)
+	pub(in crate::gui) fn wm_command<F>(&self, code: impl Into<co::CMD>, func: F) -> Subscription
 		where F: Fn() -> AnyResult<WmRet> + 'static,
 	{
 		let parent_base_ref = unsafe { self.parent_ptr.as_ref() };
-		parent_base_ref.on().wm_command(self.ctrl_id, code, func);
+		parent_base_ref.on().wm_command(self.ctrl_id, code, func)
 	}
 
 	/// Adds a `WM_NOTIFY` event to the parent window.
 	pub(in crate::gui) fn wm_notify<F>(&self,
 		code: impl Into<NmhdrCode>,
 		func: F,
-	)
+	) -> Subscription
 		where F: Fn(wm::Notify) -> AnyResult<WmRet> + 'static,
 	{
 		let parent_base_ref = unsafe { self.parent_ptr.as_ref() };
-		parent_base_ref.on().wm_notify(self.ctrl_id, code, func);
+		parent_base_ref.on().wm_notify(self.ctrl_id, code, func)
 	}
 }
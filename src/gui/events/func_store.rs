@@ -1,30 +1,105 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Serial numbers of all stored closures, unique across all stores.
+static NEXT_SERIAL: AtomicU64 = AtomicU64::new(1);
+
 /// Stores closures, associating them with an identifier.
-pub(in crate::gui) struct FuncStore<K: Copy + Eq, F> {
-	elems: Vec<(K, F)>,
+///
+/// The closures are indexed by their identifiers, so retrieving them has
+/// constant cost regardless of how many identifiers are stored. Closures sharing
+/// the same identifier are kept in insertion order.
+///
+/// Each closure also receives a unique serial number, which can be used to
+/// remove it.
+///
+/// Closures added while the store is being iterated are kept apart as pending,
+/// until [`commit_pending`](FuncStore::commit_pending) is called.
+pub(in crate::gui) struct FuncStore<K: Copy + Eq + Hash, F> {
+	elems: HashMap<K, Vec<(u64, F)>>,
+	keys: HashMap<u64, K>, // identifier of each serial number
+	pending: RefCell<Vec<(K, u64, F)>>, // added while iterating
 }
 
-impl<K: Copy + Eq, F> FuncStore<K, F> {
+impl<K: Copy + Eq + Hash, F> FuncStore<K, F> {
 	/// Creates a new, empty store.
 	#[must_use]
-	pub(in crate::gui) const fn new() -> Self {
-		Self { elems: Vec::new() }
+	pub(in crate::gui) fn new() -> Self {
+		Self {
+			elems: HashMap::new(),
+			keys: HashMap::new(),
+			pending: RefCell::new(Vec::new()),
+		}
 	}
 
 	/// Adds a new function into the store, associated to the given identifier.
-	pub(in crate::gui) fn push(&mut self, id: K, func: F) {
+	/// Returns the serial number of the function.
+	pub(in crate::gui) fn push(&mut self, id: K, func: F) -> u64 {
 		if self.elems.is_empty() {
 			self.elems.reserve(16); // arbitrary, prealloc for speed
 		}
-		self.elems.push((id, func));
+		let serial = NEXT_SERIAL.fetch_add(1, Ordering::Relaxed);
+		self.elems.entry(id).or_default().push((serial, func));
+		self.keys.insert(serial, id);
+		serial
+	}
+
+	/// Adds a new function as pending, associated to the given identifier. It
+	/// doesn't touch the stored functions, so it can be called while they are
+	/// being iterated. Returns the serial number of the function.
+	pub(in crate::gui) fn push_pending(&self, id: K, func: F) -> u64 {
+		let serial = NEXT_SERIAL.fetch_add(1, Ordering::Relaxed);
+		self.pending.borrow_mut().push((id, serial, func));
+		serial
+	}
+
+	/// Moves all pending functions into the store, after the existing ones.
+	pub(in crate::gui) fn commit_pending(&mut self) {
+		for (id, serial, func) in self.pending.get_mut().drain(..) {
+			self.elems.entry(id).or_default().push((serial, func));
+			self.keys.insert(serial, id);
+		}
+	}
+
+	/// Removes the function with the given serial number, if present. Returns
+	/// `true` if it was found.
+	pub(in crate::gui) fn remove(&mut self, serial: u64) -> bool {
+		let pending = self.pending.get_mut();
+		if let Some(idx) = pending.iter().position(|(_, s, _)| *s == serial) {
+			pending.remove(idx);
+			return true;
+		}
+
+		let id = match self.keys.remove(&serial) {
+			Some(id) => id,
+			None => return false,
+		};
+		if let Some(funcs) = self.elems.get_mut(&id) {
+			funcs.retain(|(elem_serial, _)| *elem_serial != serial);
+			if funcs.is_empty() {
+				self.elems.remove(&id);
+			}
+		}
+		true
+	}
+
+	/// Tells whether the function with the given serial number is present.
+	#[must_use]
+	pub(in crate::gui) fn contains(&self, serial: u64) -> bool {
+		self.keys.contains_key(&serial)
+			|| self.pending.borrow().iter().any(|(_, s, _)| *s == serial)
 	}
 
 	/// Returns an iterator over all the functions associated to the given
 	/// identifier, if any.
 	#[must_use]
 	pub(in crate::gui) fn filter(&self, id: K) -> impl Iterator<Item = &F> {
-		// https://depth-first.com/articles/2020/06/22/returning-rust-iterators
-		self.elems.iter()
-			.filter(move |(elem_id, _)| *elem_id == id)
+		self.elems.get(&id)
+			.map(|funcs| funcs.iter())
+			.into_iter()
+			.flatten()
 			.map(|(_, func)| func)
 	}
 
@@ -32,20 +107,110 @@ impl<K: Copy + Eq, F> FuncStore<K, F> {
 	/// to the given identifier, if any.
 	#[must_use]
 	pub(in crate::gui) fn filter_rev(&self, id: K) -> impl Iterator<Item = &F> {
-		self.elems.iter()
-			.rev()
-			.filter(move |(elem_id, _)| *elem_id == id)
+		self.elems.get(&id)
+			.map(|funcs| funcs.iter().rev())
+			.into_iter()
+			.flatten()
 			.map(|(_, func)| func)
 	}
 
 	/// Tells whether no functions have been added.
 	#[must_use]
 	pub(in crate::gui) fn is_empty(&self) -> bool {
-		self.elems.is_empty()
+		self.elems.is_empty() && self.pending.borrow().is_empty()
 	}
 
 	/// Removes all identifiers and closures.
 	pub(in crate::gui) fn clear(&mut self) {
 		self.elems.clear();
+		self.keys.clear();
+		self.pending.get_mut().clear();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::cell::Cell;
+	use super::*;
+
+	thread_local! {
+		static COMPARISONS: Cell<usize> = const { Cell::new(0) };
+	}
+
+	/// Identifier which counts how many times it's compared.
+	#[derive(Clone, Copy)]
+	struct Id(u32);
+
+	impl Hash for Id {
+		fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+			self.0.hash(state);
+		}
+	}
+
+	impl PartialEq for Id {
+		fn eq(&self, other: &Self) -> bool {
+			COMPARISONS.with(|c| c.set(c.get() + 1));
+			self.0 == other.0
+		}
+	}
+
+	impl Eq for Id {}
+
+	fn store(num_ids: u32) -> FuncStore<Id, u32> {
+		let mut store = FuncStore::new();
+		for id in 0..num_ids {
+			store.push(Id(id), id * 10);
+			store.push(Id(id), id * 10 + 1);
+		}
+		store
+	}
+
+	#[test]
+	fn filter() {
+		let store = store(3);
+		assert_eq!(store.filter(Id(1)).copied().collect::<Vec<_>>(), [10, 11]);
+		assert_eq!(store.filter_rev(Id(1)).copied().collect::<Vec<_>>(), [11, 10]);
+		assert_eq!(store.filter(Id(3)).count(), 0);
+	}
+
+	#[test]
+	fn filter_cost() {
+		// A linear search would compare the identifier with all 10,000 stored
+		// ones; the hash map compares only on hash matches.
+		let store = store(10_000);
+		COMPARISONS.with(|c| c.set(0));
+		for id in [0, 5_000, 9_999, 10_000] {
+			store.filter(Id(id)).count();
+			store.filter_rev(Id(id)).count();
+		}
+		assert!(COMPARISONS.with(|c| c.get()) <= 16);
+	}
+
+	#[test]
+	fn remove() {
+		let mut store = FuncStore::new();
+		let first = store.push(Id(1), 10);
+		let second = store.push(Id(1), 11);
+		assert!(store.remove(first));
+		assert!(!store.remove(first));
+		assert_eq!(store.filter(Id(1)).copied().collect::<Vec<_>>(), [11]);
+		assert!(store.remove(second));
+		assert!(store.is_empty());
+	}
+
+	#[test]
+	fn pending() {
+		let mut store = FuncStore::new();
+		store.push(Id(1), 10);
+		let added = store.push_pending(Id(1), 11);
+		let removed = store.push_pending(Id(1), 12);
+		assert!(store.contains(added));
+		assert_eq!(store.filter(Id(1)).copied().collect::<Vec<_>>(), [10]);
+
+		assert!(store.remove(removed));
+		assert!(!store.contains(removed));
+		store.commit_pending();
+		assert_eq!(store.filter(Id(1)).copied().collect::<Vec<_>>(), [10, 11]);
+		assert!(store.remove(added));
 	}
 }
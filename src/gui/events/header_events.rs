@@ -1,6 +1,6 @@
 use crate::co;
 use crate::decl::*;
-use crate::gui::{* ,privs::*};
+use crate::gui::{*, events::*, privs::*};
 
 /// Exposes header control
 /// [notifications](https://learn.microsoft.com/en-us/windows/win32/controls/bumper-header-control-reference-notifications).
@@ -71,13 +71,13 @@ impl HeaderEvents {
 
 	/// [`HDN_GETDISPINFO`](https://learn.microsoft.com/en-us/windows/win32/controls/hdn-getdispinfo)
 	/// notification.
-	pub fn hdn_get_disp_info<F>(&self, func: F)
+	pub fn hdn_get_disp_info<F>(&self, func: F) -> Subscription
 		where F: Fn(&mut NMHDDISPINFO) -> AnyResult<isize> + 'static,
 	{
 		self.0.wm_notify(co::HDN::GETDISPINFO, move |p| {
 			let ret_val = func(unsafe { p.cast_nmhdr_mut::<NMHDDISPINFO>() })?;
 			Ok(WmRet::HandledWithRet(ret_val))
-		});
+		})
 	}
 
 	pub_fn_nfy_withparm_noret! { hdn_item_changed, co::HDN::ITEMCHANGED, NMHEADER;
@@ -122,13 +122,13 @@ impl HeaderEvents {
 
 	/// [`NM_CUSTOMDRAW`](https://learn.microsoft.com/en-us/windows/win32/controls/nm-customdraw-header)
 	/// notification.
-	pub fn nm_custom_draw<F>(&self, func: F)
+	pub fn nm_custom_draw<F>(&self, func: F) -> Subscription
 		where F: Fn(&mut NMCUSTOMDRAW) -> AnyResult<co::CDRF> + 'static,
 	{
 		self.0.wm_notify(co::NM::CUSTOMDRAW, move |p| {
 			let ret_val = func(unsafe { p.cast_nmhdr_mut::<NMCUSTOMDRAW>() })?.raw() as isize;
 			Ok(WmRet::HandledWithRet(ret_val))
-		});
+		})
 	}
 
 	pub_fn_nfy_noparm_i32ret! { nm_r_click, co::NM::RCLICK;
@@ -1,6 +1,6 @@
 use crate::co;
 use crate::decl::*;
-use crate::gui::{*, privs::*};
+use crate::gui::{*, events::*, privs::*};
 
 /// Exposes list view control
 /// [notifications](https://learn.microsoft.com/en-us/windows/win32/controls/bumper-list-view-control-reference-notifications).
@@ -117,13 +117,13 @@ impl ListViewEvents {
 
 	/// [`LVN_GETEMPTYMARKUP`](https://learn.microsoft.com/en-us/windows/win32/controls/lvn-getemptymarkup)
 	/// notification.
-	pub fn lvn_get_empty_markup<F>(&self, func: F)
+	pub fn lvn_get_empty_markup<F>(&self, func: F) -> Subscription
 		where F: Fn(&mut NMLVEMPTYMARKUP) -> AnyResult<bool> + 'static,
 	{
 		self.0.wm_notify(co::LVN::GETEMPTYMARKUP, move |p| {
 			let ret_val = func(unsafe { p.cast_nmhdr_mut::<NMLVEMPTYMARKUP>() })? as isize;
 			Ok(WmRet::HandledWithRet(ret_val))
-		});
+		})
 	}
 
 	pub_fn_nfy_withparm_noret! { lvn_get_info_tip, co::LVN::GETINFOTIP, NMLVGETINFOTIP;
@@ -221,7 +221,7 @@ impl ListViewEvents {
 
 	/// [`LVN_ODFINDITEM`](https://learn.microsoft.com/en-us/windows/win32/controls/lvn-odfinditem)
 	/// notification.
-	pub fn lvn_od_find_item<F>(&self, func: F)
+	pub fn lvn_od_find_item<F>(&self, func: F) -> Subscription
 		where F: Fn(&mut NMLVFINDITEM) -> AnyResult<Option<u32>> + 'static,
 	{
 		self.0.wm_notify(co::LVN::ODFINDITEM, move |p| {
@@ -230,7 +230,7 @@ impl ListViewEvents {
 				None => -1,
 			};
 			Ok(WmRet::HandledWithRet(ret_val))
-		});
+		})
 	}
 
 	pub_fn_nfy_withparm_noret! { lvn_od_state_changed, co::LVN::ODSTATECHANGED, NMLVODSTATECHANGE;
@@ -250,13 +250,13 @@ impl ListViewEvents {
 
 	/// [`NM_CUSTOMDRAW`](https://learn.microsoft.com/en-us/windows/win32/controls/nm-customdraw-list-view)
 	/// notification.
-	pub fn nm_custom_draw<F>(&self, func: F)
+	pub fn nm_custom_draw<F>(&self, func: F) -> Subscription
 		where F: Fn(&mut NMLVCUSTOMDRAW) -> AnyResult<co::CDRF> + 'static,
 	{
 		self.0.wm_notify(co::NM::CUSTOMDRAW, move |p| {
 			let ret_val = func(unsafe { p.cast_nmhdr_mut::<NMLVCUSTOMDRAW>() })?.raw() as isize;
 			Ok(WmRet::HandledWithRet(ret_val))
-		});
+		})
 	}
 
 	pub_fn_nfy_withparm_noret! { nm_dbl_clk, co::NM::DBLCLK, NMITEMACTIVATE;
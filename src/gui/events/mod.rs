@@ -14,6 +14,7 @@ mod list_view_events;
 mod month_calendar_events;
 mod radio_group_events;
 mod status_bar_events;
+mod subscription;
 mod tab_events;
 mod trackbar_events;
 mod tree_view_events;
@@ -23,6 +24,7 @@ mod window_events;
 pub(in crate::gui) mod privs {
 	pub(in crate::gui) use super::base_ctrl_events_proxy::BaseCtrlEventsProxy;
 	pub(in crate::gui) use super::func_store::FuncStore;
+	pub(in crate::gui) use super::window_events::EventStores;
 }
 
 pub use button_events::ButtonEvents;
@@ -36,6 +38,7 @@ pub use list_view_events::ListViewEvents;
 pub use month_calendar_events::MonthCalendarEvents;
 pub use radio_group_events::RadioGroupEvents;
 pub use status_bar_events::StatusBarEvents;
+pub use subscription::{Subscription, SubscriptionGuard};
 pub use tab_events::TabEvents;
 pub use trackbar_events::TrackbarEvents;
 pub use tree_view_events::TreeViewEvents;
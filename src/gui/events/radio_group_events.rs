@@ -64,20 +64,22 @@ impl RadioGroupEvents {
 	///     }
 	/// });
 	/// ```
-	pub fn bn_clicked<F>(&self, func: F)
+	pub fn bn_clicked<F>(&self, func: F) -> Subscription
 		where F: Fn() -> AnyResult<()> + 'static,
 	{
 		let shared_func = Rc::new(func);
 
-		for ctrl_id in self.ctrl_ids.iter() {
-			self.parent_user_events().wm_command(*ctrl_id, co::BN::CLICKED, {
-				let shared_func = shared_func.clone();
-				move || {
-					shared_func()?;
-					Ok(WmRet::HandledOk)
-				}
-			});
-		}
+		Subscription::join(
+			self.ctrl_ids.iter().map(|ctrl_id| {
+				self.parent_user_events().wm_command(*ctrl_id, co::BN::CLICKED, {
+					let shared_func = shared_func.clone();
+					move || {
+						shared_func()?;
+						Ok(WmRet::HandledOk)
+					}
+				})
+			}),
+		)
 	}
 
 	/// [`BN_DBLCLK`](https://learn.microsoft.com/en-us/windows/win32/controls/bn-dblclk)
@@ -88,20 +90,22 @@ impl RadioGroupEvents {
 	/// [`BS::RADIOBUTTON`](crate::co::BS::RADIOBUTTON), and
 	/// [`BS::OWNERDRAW`](crate::co::BS::OWNERDRAW) buttons. Other button types
 	/// send only if they have the [`BS::NOTIFY`](crate::co::BS::NOTIFY) style.
-	pub fn bn_dbl_clk<F>(&self, func: F)
+	pub fn bn_dbl_clk<F>(&self, func: F) -> Subscription
 		where F: Fn() -> AnyResult<()> + 'static,
 	{
 		let shared_func = Rc::new(func);
 
-		for ctrl_id in self.ctrl_ids.iter() {
-			self.parent_user_events().wm_command(*ctrl_id, co::BN::DBLCLK, {
-				let shared_func = shared_func.clone();
-				move || {
-					shared_func()?;
-					Ok(WmRet::HandledOk)
-				}
-			});
-		}
+		Subscription::join(
+			self.ctrl_ids.iter().map(|ctrl_id| {
+				self.parent_user_events().wm_command(*ctrl_id, co::BN::DBLCLK, {
+					let shared_func = shared_func.clone();
+					move || {
+						shared_func()?;
+						Ok(WmRet::HandledOk)
+					}
+				})
+			}),
+		)
 	}
 
 	/// [`BN_KILLFOCUS`](https://learn.microsoft.com/en-us/windows/win32/controls/bn-killfocus)
@@ -110,20 +114,22 @@ impl RadioGroupEvents {
 	/// Sent when a button loses the keyboard focus. The button must have the
 	/// [`BS::NOTIFY`](crate::co::BS::NOTIFY) style to send this notification
 	/// code.
-	pub fn bn_kill_focus<F>(&self, func: F)
+	pub fn bn_kill_focus<F>(&self, func: F) -> Subscription
 		where F: Fn() -> AnyResult<()> + 'static,
 	{
 		let shared_func = Rc::new(func);
 
-		for ctrl_id in self.ctrl_ids.iter() {
-			self.parent_user_events().wm_command(*ctrl_id, co::BN::KILLFOCUS, {
-				let shared_func = shared_func.clone();
-				move || {
-					shared_func()?;
-					Ok(WmRet::HandledOk)
-				}
-			});
-		}
+		Subscription::join(
+			self.ctrl_ids.iter().map(|ctrl_id| {
+				self.parent_user_events().wm_command(*ctrl_id, co::BN::KILLFOCUS, {
+					let shared_func = shared_func.clone();
+					move || {
+						shared_func()?;
+						Ok(WmRet::HandledOk)
+					}
+				})
+			}),
+		)
 	}
 
 	/// [`BN_SETFOCUS`](https://learn.microsoft.com/en-us/windows/win32/controls/bn-setfocus)
@@ -132,19 +138,21 @@ impl RadioGroupEvents {
 	/// Sent when a button receives the keyboard focus. The button must have the
 	/// [`BS::NOTIFY`](crate::co::BS::NOTIFY) style to send this notification
 	/// code.
-	pub fn bn_set_focus<F>(&self, func: F)
+	pub fn bn_set_focus<F>(&self, func: F) -> Subscription
 		where F: Fn() -> AnyResult<()> + 'static,
 	{
 		let shared_func = Rc::new(func);
 
-		for ctrl_id in self.ctrl_ids.iter() {
-			self.parent_user_events().wm_command(*ctrl_id, co::BN::SETFOCUS, {
-				let shared_func = shared_func.clone();
-				move || {
-					shared_func()?;
-					Ok(WmRet::HandledOk)
-				}
-			});
-		}
+		Subscription::join(
+			self.ctrl_ids.iter().map(|ctrl_id| {
+				self.parent_user_events().wm_command(*ctrl_id, co::BN::SETFOCUS, {
+					let shared_func = shared_func.clone();
+					move || {
+						shared_func()?;
+						Ok(WmRet::HandledOk)
+					}
+				})
+			}),
+		)
 	}
 }
@@ -0,0 +1,110 @@
+use std::rc::Weak;
+
+use crate::gui::events::privs::*;
+
+/// Identifies the closures added to a window or control event, allowing them to
+/// be removed later with
+/// [`unsubscribe`](crate::gui::events::Subscription::unsubscribe).
+///
+/// It's returned by all event methods of the
+/// [`gui::events`](crate::gui::events) module. If you don't need to remove the
+/// closures, just ignore it.
+///
+/// # Examples
+///
+/// Handling a button click only once:
+///
+/// ```no_run
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+/// use winsafe::{self as w, prelude::*, gui};
+///
+/// let btn: gui::Button; // initialized somewhere
+/// # let wnd = gui::WindowMain::new(gui::WindowMainOpts::default());
+/// # let btn = gui::Button::new(&wnd, gui::ButtonOpts::default());
+///
+/// let sub = Rc::new(RefCell::new(None::<gui::events::Subscription>));
+///
+/// *sub.borrow_mut() = Some(btn.on().bn_clicked({
+///     let sub = sub.clone();
+///     move || -> w::AnyResult<()> {
+///         println!("Clicked for the first and last time.");
+///         sub.borrow().as_ref().unwrap().unsubscribe();
+///         Ok(())
+///     }
+/// }));
+/// ```
+#[derive(Clone)]
+pub struct Subscription {
+	entries: Vec<(Weak<EventStores>, u64)>, // serial numbers of the closures
+}
+
+impl Subscription {
+	#[must_use]
+	pub(in crate::gui) fn new(stores: Weak<EventStores>, serial: u64) -> Self {
+		Self { entries: vec![(stores, serial)] }
+	}
+
+	/// Merges many subscriptions into one, used when a single event method adds
+	/// many closures.
+	#[must_use]
+	pub(in crate::gui) fn join(subs: impl IntoIterator<Item = Self>) -> Self {
+		Self {
+			entries: subs.into_iter()
+				.flat_map(|sub| sub.entries)
+				.collect(),
+		}
+	}
+
+	/// Removes the closures from the event, so they won't be called anymore.
+	///
+	/// It's safe to call this method from within the closure itself: in this
+	/// case, the removal happens right after the current message is processed.
+	///
+	/// Returns `true` if at least one closure was removed, or `false` if they
+	/// were already removed, or if the window no longer exists.
+	pub fn unsubscribe(&self) -> bool {
+		let mut removed = false;
+		for (stores, serial) in self.entries.iter() {
+			if let Some(stores) = stores.upgrade() {
+				removed |= stores.remove(*serial);
+			}
+		}
+		removed
+	}
+
+	/// Converts the subscription into a
+	/// [`SubscriptionGuard`](crate::gui::events::SubscriptionGuard), which
+	/// unsubscribes automatically when it goes out of scope.
+	#[must_use]
+	pub fn into_guard(self) -> SubscriptionGuard {
+		SubscriptionGuard { sub: Some(self) }
+	}
+}
+
+/// RAII implementation for a [`Subscription`](crate::gui::events::Subscription)
+/// which automatically calls
+/// [`unsubscribe`](crate::gui::events::Subscription::unsubscribe) when the
+/// object goes out of scope.
+///
+/// Useful when a window is reused for several screens, like in a wizard, where
+/// each screen keeps the guards of its own event closures.
+pub struct SubscriptionGuard {
+	sub: Option<Subscription>,
+}
+
+impl Drop for SubscriptionGuard {
+	fn drop(&mut self) {
+		if let Some(sub) = self.sub.take() {
+			sub.unsubscribe();
+		}
+	}
+}
+
+impl SubscriptionGuard {
+	/// Ejects the underlying subscription, leaving the closures in place.
+	#[must_use]
+	pub fn leak(mut self) -> Subscription {
+		self.sub.take().unwrap()
+	}
+}
@@ -1,9 +1,10 @@
-use std::cell::UnsafeCell;
+use std::cell::{Cell, UnsafeCell};
+use std::hash::Hash;
 use std::rc::Rc;
 
 use crate::co;
 use crate::decl::*;
-use crate::gui::{*, privs::*};
+use crate::gui::{*, events::*, privs::*};
 use crate::msg::*;
 use crate::prelude::*;
 
@@ -14,6 +15,13 @@ use crate::prelude::*;
 /// window.
 pub struct WindowEvents {
 	is_dialog: bool,
+	stores: Rc<EventStores>,
+}
+
+/// The closures of a [`WindowEvents`](crate::gui::events::WindowEvents), which
+/// are shared with the [`Subscription`](crate::gui::events::Subscription)
+/// objects.
+pub(in crate::gui) struct EventStores {
 	msgs: UnsafeCell<
 		FuncStore< // ordinary WM messages
 			co::WM,
@@ -44,32 +52,76 @@ pub struct WindowEvents {
 			Box<dyn Fn() -> AnyResult<()>>, // return value is never meaningful
 		>,
 	>,
+	depth: Cell<u32>, // how many messages are being processed, may be nested
+	pending_removals: UnsafeCell<Vec<u64>>, // deferred while processing
+	pending_clear: Cell<bool>, // deferred while processing
 }
 
-impl WindowEvents {
-	#[must_use]
-	pub(in crate::gui) const fn new(is_dialog: bool) -> Self {
-		Self {
-			is_dialog,
-			msgs: UnsafeCell::new(FuncStore::new()),
-			inis: UnsafeCell::new(FuncStore::new()),
-			cmds: UnsafeCell::new(FuncStore::new()),
-			nfys: UnsafeCell::new(FuncStore::new()),
-			tmrs: UnsafeCell::new(FuncStore::new()),
+impl EventStores {
+	/// Adds the closure to one of the stores. If a message is being processed,
+	/// the store may be being iterated, so the closure is kept as pending until
+	/// the processing ends.
+	fn push<K: Copy + Eq + Hash, F>(&self,
+		store: &UnsafeCell<FuncStore<K, F>>,
+		id: K,
+		func: F,
+	) -> u64
+	{
+		if self.depth.get() > 0 {
+			unsafe { &*store.get() }.push_pending(id, func)
+		} else {
+			unsafe { &mut *store.get() }.push(id, func)
 		}
 	}
 
-	pub(in crate::gui) fn is_empty(&self) -> bool {
+	/// Removes the closure with the given serial number. If a message is being
+	/// processed, the closure may be running, so the removal is deferred until
+	/// the processing ends.
+	pub(in crate::gui) fn remove(&self, serial: u64) -> bool {
+		if self.depth.get() > 0 {
+			let pending = unsafe { &mut *self.pending_removals.get() };
+			if pending.contains(&serial) || !self.contains(serial) {
+				false
+			} else {
+				pending.push(serial);
+				true
+			}
+		} else {
+			self.remove_now(serial)
+		}
+	}
+
+	fn contains(&self, serial: u64) -> bool {
 		unsafe {
-			{ &*self.msgs.get() }.is_empty()
-				&& { &*self.inis.get() }.is_empty()
-				&& { &*self.cmds.get() }.is_empty()
-				&& { &*self.nfys.get() }.is_empty()
-				&& { &*self.tmrs.get() }.is_empty()
+			{ &*self.msgs.get() }.contains(serial)
+				|| { &*self.inis.get() }.contains(serial)
+				|| { &*self.cmds.get() }.contains(serial)
+				|| { &*self.nfys.get() }.contains(serial)
+				|| { &*self.tmrs.get() }.contains(serial)
 		}
 	}
 
-	pub(in crate::gui) fn clear_events(&self) {
+	fn commit_pending(&self) {
+		unsafe {
+			{ &mut *self.msgs.get() }.commit_pending();
+			{ &mut *self.inis.get() }.commit_pending();
+			{ &mut *self.cmds.get() }.commit_pending();
+			{ &mut *self.nfys.get() }.commit_pending();
+			{ &mut *self.tmrs.get() }.commit_pending();
+		}
+	}
+
+	/// Removes all the closures. If a message is being processed, the closures
+	/// may be running, so the removal is deferred until the processing ends.
+	fn clear(&self) {
+		if self.depth.get() > 0 {
+			self.pending_clear.set(true);
+		} else {
+			self.clear_now();
+		}
+	}
+
+	fn clear_now(&self) {
 		unsafe {
 			{ &mut *self.tmrs.get() }.clear();
 			{ &mut *self.nfys.get() }.clear();
@@ -79,6 +131,88 @@ impl WindowEvents {
 		}
 	}
 
+	fn remove_now(&self, serial: u64) -> bool {
+		unsafe {
+			{ &mut *self.msgs.get() }.remove(serial)
+				|| { &mut *self.inis.get() }.remove(serial)
+				|| { &mut *self.cmds.get() }.remove(serial)
+				|| { &mut *self.nfys.get() }.remove(serial)
+				|| { &mut *self.tmrs.get() }.remove(serial)
+		}
+	}
+}
+
+/// Marks the stores as processing a message while alive, then performs the
+/// deferred additions, removals and clearing when the outermost message
+/// finishes.
+struct ProcessingGuard<'a>(&'a EventStores);
+
+impl<'a> ProcessingGuard<'a> {
+	#[must_use]
+	fn new(stores: &'a EventStores) -> Self {
+		stores.depth.set(stores.depth.get() + 1);
+		Self(stores)
+	}
+}
+
+impl<'a> Drop for ProcessingGuard<'a> {
+	fn drop(&mut self) {
+		self.0.depth.set(self.0.depth.get() - 1);
+		if self.0.depth.get() == 0 {
+			let pending = std::mem::take(unsafe { &mut *self.0.pending_removals.get() });
+			if self.0.pending_clear.replace(false) {
+				self.0.clear_now(); // also discards pending additions
+			} else {
+				self.0.commit_pending(); // before removals, which may refer to them
+				for serial in pending.iter() {
+					self.0.remove_now(*serial);
+				}
+			}
+		}
+	}
+}
+
+impl WindowEvents {
+	#[must_use]
+	pub(in crate::gui) fn new(is_dialog: bool) -> Self {
+		Self {
+			is_dialog,
+			stores: Rc::new(EventStores {
+				msgs: UnsafeCell::new(FuncStore::new()),
+				inis: UnsafeCell::new(FuncStore::new()),
+				cmds: UnsafeCell::new(FuncStore::new()),
+				nfys: UnsafeCell::new(FuncStore::new()),
+				tmrs: UnsafeCell::new(FuncStore::new()),
+				depth: Cell::new(0),
+				pending_removals: UnsafeCell::new(Vec::new()),
+				pending_clear: Cell::new(false),
+			}),
+		}
+	}
+
+	pub(in crate::gui) fn is_empty(&self) -> bool {
+		unsafe {
+			{ &*self.stores.msgs.get() }.is_empty()
+				&& { &*self.stores.inis.get() }.is_empty()
+				&& { &*self.stores.cmds.get() }.is_empty()
+				&& { &*self.stores.nfys.get() }.is_empty()
+				&& { &*self.stores.tmrs.get() }.is_empty()
+		}
+	}
+
+	/// Removes all the closures; if a message is being processed, like when a
+	/// closure destroys its own window, the removal is deferred until the
+	/// processing ends.
+	pub(in crate::gui) fn clear_events(&self) {
+		self.stores.clear();
+	}
+
+	/// Returns a subscription to the closure with the given serial number.
+	#[must_use]
+	fn subscription(&self, serial: u64) -> Subscription {
+		Subscription::new(Rc::downgrade(&self.stores), serial)
+	}
+
 	/// Searches for all functions for the given message, and runs all of them,
 	/// discarding the results.
 	///
@@ -88,10 +222,11 @@ impl WindowEvents {
 		wm_any: WndMsg,
 	) -> AnyResult<bool>
 	{
+		let _processing = ProcessingGuard::new(&self.stores);
 		let mut at_least_one = false;
 
 		if wm_any.msg_id == co::WM::CREATE || wm_any.msg_id == co::WM::INITDIALOG {
-			let inis = unsafe { &*self.inis.get() };
+			let inis = unsafe { &*self.stores.inis.get() };
 			for func in inis.filter(wm_any.msg_id) {
 				match func(hwnd, wm_any)? {
 					WmRet::HandledWithRet(_)
@@ -102,7 +237,7 @@ impl WindowEvents {
 		} else if wm_any.msg_id == co::WM::COMMAND {
 			let wm_cmd = unsafe { wm::Command::from_generic_wm(wm_any) };
 			let key_cmd = (wm_cmd.event.ctrl_id(), wm_cmd.event.code());
-			let cmds = unsafe { &*self.cmds.get() };
+			let cmds = unsafe { &*self.stores.cmds.get() };
 			for func in cmds.filter(key_cmd) {
 				match func()? {
 					WmRet::HandledWithRet(_)
@@ -113,7 +248,7 @@ impl WindowEvents {
 		} else if wm_any.msg_id == co::WM::NOTIFY {
 			let wm_nfy = unsafe { wm::Notify::from_generic_wm(wm_any) };
 			let key_nfy = (wm_nfy.nmhdr.idFrom(), wm_nfy.nmhdr.code);
-			let nfys = unsafe { &*self.nfys.get() };
+			let nfys = unsafe { &*self.stores.nfys.get() };
 			for func in nfys.filter(key_nfy) {
 				match func(unsafe { wm::Notify::from_generic_wm(wm_any) })? { // wm::Notify cannot be Copy
 					WmRet::HandledWithRet(_)
@@ -123,14 +258,14 @@ impl WindowEvents {
 			}
 		} else if wm_any.msg_id == co::WM::TIMER {
 			let wm_tmr = unsafe { wm::Timer::from_generic_wm(wm_any) };
-			let tmrs = unsafe { &*self.tmrs.get() };
+			let tmrs = unsafe { &*self.stores.tmrs.get() };
 			for func in tmrs.filter(wm_tmr.timer_id) {
 				func()?;
 				at_least_one = true;
 			}
 		}
 
-		let msgs = unsafe { &*self.msgs.get() };
+		let msgs = unsafe { &*self.stores.msgs.get() };
 		for func in msgs.filter(wm_any.msg_id) {
 			match func(wm_any)? {
 				WmRet::HandledWithRet(_)
@@ -148,8 +283,10 @@ impl WindowEvents {
 		wm_any: WndMsg,
 	) -> AnyResult<WmRet>
 	{
+		let _processing = ProcessingGuard::new(&self.stores);
+
 		if wm_any.msg_id == co::WM::CREATE || wm_any.msg_id == co::WM::INITDIALOG {
-			let inis = unsafe { &*self.inis.get() };
+			let inis = unsafe { &*self.stores.inis.get() };
 			for func in inis.filter_rev(wm_any.msg_id) {
 				match func(hwnd, wm_any)? {
 					WmRet::NotHandled => {},
@@ -159,7 +296,7 @@ impl WindowEvents {
 		} else if wm_any.msg_id == co::WM::COMMAND {
 			let wm_cmd = unsafe { wm::Command::from_generic_wm(wm_any) };
 			let key_cmd = (wm_cmd.event.ctrl_id(), wm_cmd.event.code());
-			let cmds = unsafe { &*self.cmds.get() };
+			let cmds = unsafe { &*self.stores.cmds.get() };
 			for func in cmds.filter_rev(key_cmd) {
 				match func()? {
 					WmRet::NotHandled => {},
@@ -169,7 +306,7 @@ impl WindowEvents {
 		} else if wm_any.msg_id == co::WM::NOTIFY {
 			let wm_nfy = unsafe { wm::Notify::from_generic_wm(wm_any) };
 			let key_nfy = (wm_nfy.nmhdr.idFrom(), wm_nfy.nmhdr.code);
-			let nfys = unsafe { &*self.nfys.get() };
+			let nfys = unsafe { &*self.stores.nfys.get() };
 			for func in nfys.filter_rev(key_nfy) {
 				match unsafe { func(wm::Notify::from_generic_wm(wm_any))? } { // wm::Notify cannot be Copy
 					WmRet::NotHandled => {},
@@ -178,14 +315,14 @@ impl WindowEvents {
 			}
 		} else if wm_any.msg_id == co::WM::TIMER {
 			let wm_tmr = unsafe { wm::Timer::from_generic_wm(wm_any) };
-			let tmrs = unsafe { &*self.tmrs.get() };
+			let tmrs = unsafe { &*self.stores.tmrs.get() };
 			if let Some(func) = tmrs.filter_rev(wm_tmr.timer_id).next() { // just execute the last, if any
 				func()?;
 				return Ok(WmRet::HandledOk); // handled: stop here
 			}
 		}
 
-		let msgs = unsafe { &*self.msgs.get() };
+		let msgs = unsafe { &*self.stores.msgs.get() };
 		for func in msgs.filter_rev(wm_any.msg_id) {
 			match func(wm_any)? {
 				WmRet::NotHandled => {},
@@ -222,21 +359,24 @@ impl WindowEvents {
 	///     },
 	/// );
 	/// ```
-	pub fn wm<F>(&self, ident: co::WM, func: F)
+	pub fn wm<F>(&self, ident: co::WM, func: F) -> Subscription
 		where F: Fn(WndMsg) -> AnyResult<WmRet> + 'static,
 	{
-		unsafe { &mut *self.msgs.get() }.push(ident, Box::new(func));
+		let serial = self.stores.push(&self.stores.msgs, ident, Box::new(func));
+		self.subscription(serial)
 	}
 
 	/// If a dialog window, will handle `co::WM::INITDIALOG`, otherwise will
 	/// handle `co::WM::CREATE`.
-	pub(in crate::gui) fn wm_create_or_initdialog<F>(&self, func: F)
+	pub(in crate::gui) fn wm_create_or_initdialog<F>(&self, func: F) -> Subscription
 		where F: Fn(&HWND, WndMsg) -> AnyResult<WmRet> + 'static,
 	{
-		unsafe { &mut *self.inis.get() }.push(
+		let serial = self.stores.push(
+			&self.stores.inis,
 			if self.is_dialog { co::WM::INITDIALOG } else { co::WM::CREATE },
 			Box::new(func),
 		);
+		self.subscription(serial)
 	}
 
 	/// [`WM_COMMAND`](https://learn.microsoft.com/en-us/windows/win32/menurc/wm-command)
@@ -272,14 +412,16 @@ impl WindowEvents {
 		ctrl_id: impl Into<u16>,
 		code: impl Into<co::CMD>,
 		func: F,
-	)
+	) -> Subscription
 		where F: Fn() -> AnyResult<WmRet> + 'static,
 	{
 		let code: co::CMD = code.into();
-		unsafe { &mut *self.cmds.get() }.push(
+		let serial = self.stores.push(
+			&self.stores.cmds,
 			(ctrl_id.into(), code),
 			Box::new(func),
 		);
+		self.subscription(serial)
 	}
 
 	/// [`WM_NOTIFY`](crate::msg::wm::Notify) message, for specific ID and
@@ -311,21 +453,24 @@ impl WindowEvents {
 		id_from: impl Into<u16>,
 		code: impl Into<NmhdrCode>,
 		func: F,
-	)
+	) -> Subscription
 		where F: Fn(wm::Notify) -> AnyResult<WmRet> + 'static,
 	{
-		unsafe { &mut *self.nfys.get() }.push(
+		let serial = self.stores.push(
+			&self.stores.nfys,
 			(id_from.into(), code.into()),
 			Box::new(func),
 		);
+		self.subscription(serial)
 	}
 
 	/// [`WM_TIMER`](https://learn.microsoft.com/en-us/windows/win32/winmsg/wm-timer)
 	/// message, narrowed to a specific timer ID.
-	pub fn wm_timer<F>(&self, timer_id: usize, func: F)
+	pub fn wm_timer<F>(&self, timer_id: usize, func: F) -> Subscription
 		where F: Fn() -> AnyResult<()> + 'static,
 	{
-		unsafe { &mut *self.tmrs.get() }.push(timer_id, Box::new(func));
+		let serial = self.stores.push(&self.stores.tmrs, timer_id, Box::new(func));
+		self.subscription(serial)
 	}
 
 	/// [`WM_CREATE`](https://learn.microsoft.com/en-us/windows/win32/winmsg/wm-create)
@@ -351,16 +496,18 @@ impl WindowEvents {
 	///     },
 	/// );
 	/// ```
-	pub fn wm_create<F>(&self, func: F)
+	pub fn wm_create<F>(&self, func: F) -> Subscription
 		where F: Fn(wm::Create) -> AnyResult<i32> + 'static,
 	{
-		unsafe { &mut *self.inis.get() }.push(
+		let serial = self.stores.push(
+			&self.stores.inis,
 			co::WM::CREATE,
 			Box::new(move |_, p| {
 				let ret_val = func(unsafe { wm::Create::from_generic_wm(p) })? as isize;
 				Ok(WmRet::HandledWithRet(ret_val))
 			}),
 		);
+		self.subscription(serial)
 	}
 
 	/// [`WM_INITDIALOG`](https://learn.microsoft.com/en-us/windows/win32/dlgbox/wm-initdialog)
@@ -384,16 +531,18 @@ impl WindowEvents {
 	///     },
 	/// );
 	/// ```
-	pub fn wm_init_dialog<F>(&self, func: F)
+	pub fn wm_init_dialog<F>(&self, func: F) -> Subscription
 		where F: Fn(wm::InitDialog) -> AnyResult<bool> + 'static,
 	{
-		unsafe { &mut *self.inis.get() }.push(
+		let serial = self.stores.push(
+			&self.stores.inis,
 			co::WM::INITDIALOG,
 			Box::new(move |_, p| {
 				let ret_val = func(unsafe { wm::InitDialog::from_generic_wm(p) })? as isize;
 				Ok(WmRet::HandledWithRet(ret_val))
 			}),
 		);
+		self.subscription(serial)
 	}
 
 	/// [`WM_COMMAND`](https://learn.microsoft.com/en-us/windows/win32/menurc/wm-command)
@@ -421,12 +570,15 @@ impl WindowEvents {
 	///     },
 	/// );
 	/// ```
-	pub fn wm_command_accel_menu<F>(&self, ctrl_id: impl Into<u16> + Copy, func: F)
+	pub fn wm_command_accel_menu<F>(&self,
+		ctrl_id: impl Into<u16> + Copy,
+		func: F,
+	) -> Subscription
 		where F: Fn() -> AnyResult<()> + 'static,
 	{
 		let shared_func = Rc::new(func);
 
-		self.wm_command(ctrl_id, co::CMD::Menu, {
+		let sub_menu = self.wm_command(ctrl_id, co::CMD::Menu, {
 			let shared_func = shared_func.clone();
 			move || {
 				shared_func()?;
@@ -434,13 +586,15 @@ impl WindowEvents {
 			}
 		});
 
-		self.wm_command(ctrl_id, co::CMD::Accelerator, {
+		let sub_accel = self.wm_command(ctrl_id, co::CMD::Accelerator, {
 			let shared_func = shared_func.clone();
 			move || {
 				shared_func()?;
 				Ok(WmRet::HandledOk)
 			}
 		});
+
+		Subscription::join([sub_menu, sub_accel])
 	}
 
 	pub_fn_wm_withparm_noret! { wm_activate, co::WM::ACTIVATE, wm::Activate;
@@ -455,13 +609,13 @@ impl WindowEvents {
 
 	/// [`WM_APPCOMMAND`](https://learn.microsoft.com/en-us/windows/win32/inputdev/wm-appcommand)
 	/// message.
-	pub fn wm_app_command<F>(&self, func: F)
+	pub fn wm_app_command<F>(&self, func: F) -> Subscription
 		where F: Fn(wm::AppCommand) -> AnyResult<()> + 'static,
 	{
 		self.wm(co::WM::APPCOMMAND, move |p| {
 			func(unsafe { wm::AppCommand::from_generic_wm(p) })?;
 			Ok(WmRet::HandledWithRet(1)) // TRUE
-		});
+		})
 	}
 
 	pub_fn_wm_noparm_noret! { wm_cancel_mode, co::WM::CANCELMODE;
@@ -627,13 +781,13 @@ impl WindowEvents {
 
 	/// [`WM_ERASEBKGND`](https://learn.microsoft.com/en-us/windows/win32/winmsg/wm-erasebkgnd)
 	/// message.
-	pub fn wm_erase_bkgnd<F>(&self, func: F)
+	pub fn wm_erase_bkgnd<F>(&self, func: F) -> Subscription
 		where F: Fn(wm::EraseBkgnd) -> AnyResult<i32> + 'static,
 	{
 		self.wm(co::WM::ERASEBKGND, move |p| {
 			let ret_val = func(unsafe { wm::EraseBkgnd::from_generic_wm(p) })? as isize;
 			Ok(WmRet::HandledWithRet(ret_val))
-		});
+		})
 	}
 
 	pub_fn_wm_withparm_noret! { wm_exit_menu_loop, co::WM::EXITMENULOOP, wm::ExitMenuLoop;
@@ -653,24 +807,24 @@ impl WindowEvents {
 
 	/// [`WM_GETFONT`](https://learn.microsoft.com/en-us/windows/win32/winmsg/wm-getfont)
 	/// message.
-	pub fn wm_get_font<F>(&self, func: F)
+	pub fn wm_get_font<F>(&self, func: F) -> Subscription
 		where F: Fn() -> AnyResult<Option<HFONT>> + 'static,
 	{
 		self.wm(co::WM::GETFONT, move |_| {
 			let ret_val = func()?.map_or(0, |h| h.ptr() as isize);
 			Ok(WmRet::HandledWithRet(ret_val))
-		});
+		})
 	}
 
 	/// [`WM_GETHMENU`](https://learn.microsoft.com/en-us/windows/win32/winmsg/mn-gethmenu)
 	/// message. Originally has `MN` prefix.
-	pub fn wm_get_hmenu<F>(&self, func: F)
+	pub fn wm_get_hmenu<F>(&self, func: F) -> Subscription
 		where F: Fn() -> AnyResult<Option<HMENU>> + 'static
 	{
 		self.wm(co::WM::MN_GETHMENU, move |_| {
 			let ret_val = func()?.map_or(0, |h| h.ptr() as isize);
 			Ok(WmRet::HandledWithRet(ret_val))
-		});
+		})
 	}
 
 	pub_fn_wm_withparm_noret! { wm_get_min_max_info, co::WM::GETMINMAXINFO, wm::GetMinMaxInfo;
@@ -680,24 +834,24 @@ impl WindowEvents {
 
 	/// [`WM_GETTEXT`](https://learn.microsoft.com/en-us/windows/win32/winmsg/wm-gettext)
 	/// message.
-	pub fn wm_get_text<F>(&self, func: F)
+	pub fn wm_get_text<F>(&self, func: F) -> Subscription
 		where F: Fn(wm::GetText) -> AnyResult<u32> + 'static,
 	{
 		self.wm(co::WM::GETTEXT, move |p| {
 			let ret_val = func(unsafe { wm::GetText::from_generic_wm(p) })? as isize;
 			Ok(WmRet::HandledWithRet(ret_val))
-		});
+		})
 	}
 
 	/// [`WM_GETTEXTLENGTH`](https://learn.microsoft.com/en-us/windows/win32/winmsg/wm-gettextlength)
 	/// message.
-	pub fn wm_get_text_length<F>(&self, func: F)
+	pub fn wm_get_text_length<F>(&self, func: F) -> Subscription
 		where F: Fn() -> AnyResult<u32> + 'static,
 	{
 		self.wm(co::WM::GETTEXTLENGTH, move |_| {
 			let ret_val = func()? as isize;
 			Ok(WmRet::HandledWithRet(ret_val))
-		});
+		})
 	}
 
 	pub_fn_wm_withparm_noret! { wm_get_title_bar_info_ex, co::WM::GETTITLEBARINFOEX, wm::GetTitleBarInfoEx;
@@ -983,14 +1137,14 @@ impl WindowEvents {
 
 	/// [`WM_SETICON`](https://learn.microsoft.com/en-us/windows/win32/winmsg/wm-seticon)
 	/// message.
-	pub fn wm_set_icon<F>(&self, func: F)
+	pub fn wm_set_icon<F>(&self, func: F) -> Subscription
 		where F: Fn(wm::SetIcon) -> AnyResult<Option<HICON>> + 'static,
 	{
 		self.wm(co::WM::SETICON, move |p| {
 			let ret_val = func(unsafe { wm::SetIcon::from_generic_wm(p) })?
 				.map_or(0, |h| h.ptr() as isize);
 			Ok(WmRet::HandledWithRet(ret_val))
-		});
+		})
 	}
 
 	pub_fn_wm_withparm_noret! { wm_set_redraw, co::WM::SETREDRAW, wm::SetRedraw;
@@ -1127,3 +1281,118 @@ impl WindowEvents {
 		/// message.
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use std::cell::RefCell;
+	use super::*;
+
+	fn msg(msg_id: co::WM) -> WndMsg {
+		WndMsg { msg_id, wparam: 0, lparam: 0 }
+	}
+
+	#[test]
+	fn unsubscribe_while_processing() {
+		let events = WindowEvents::new(false);
+		let (count_once, count_always) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+		let sub_once = Rc::new(RefCell::new(None::<Subscription>));
+
+		*sub_once.borrow_mut() = Some(events.wm(co::WM::CLOSE, {
+			let (count_once, sub_once) = (count_once.clone(), sub_once.clone());
+			move |_| {
+				count_once.set(count_once.get() + 1);
+				let sub_once = sub_once.borrow();
+				assert!(sub_once.as_ref().unwrap().unsubscribe()); // deferred
+				assert!(!sub_once.as_ref().unwrap().unsubscribe()); // already pending
+				Ok(WmRet::HandledOk)
+			}
+		}));
+		let sub_always = events.wm(co::WM::CLOSE, {
+			let count_always = count_always.clone();
+			move |_| {
+				count_always.set(count_always.get() + 1);
+				Ok(WmRet::HandledOk)
+			}
+		});
+
+		assert!(events.process_all_messages(&HWND::NULL, msg(co::WM::CLOSE)).unwrap());
+		assert!(events.process_all_messages(&HWND::NULL, msg(co::WM::CLOSE)).unwrap());
+		assert_eq!((count_once.get(), count_always.get()), (1, 2));
+		assert!(!sub_once.borrow().as_ref().unwrap().unsubscribe());
+
+		assert!(sub_always.unsubscribe()); // not processing, immediate
+		assert!(!sub_always.unsubscribe());
+		assert!(events.is_empty());
+		assert!(!events.process_all_messages(&HWND::NULL, msg(co::WM::CLOSE)).unwrap());
+	}
+
+	#[test]
+	fn subscribe_while_processing() {
+		let events = Rc::new(WindowEvents::new(false));
+		let (count_added, count_removed) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+
+		let sub = events.wm(co::WM::CLOSE, {
+			let events = Rc::downgrade(&events);
+			let (count_added, count_removed) = (count_added.clone(), count_removed.clone());
+			move |_| {
+				let events = events.upgrade().unwrap();
+				events.wm(co::WM::CLOSE, { // kept, called from the next message on
+					let count_added = count_added.clone();
+					move |_| {
+						count_added.set(count_added.get() + 1);
+						Ok(WmRet::HandledOk)
+					}
+				});
+				let sub_removed = events.wm(co::WM::CLOSE, { // removed before being called
+					let count_removed = count_removed.clone();
+					move |_| {
+						count_removed.set(count_removed.get() + 1);
+						Ok(WmRet::HandledOk)
+					}
+				});
+				assert!(sub_removed.unsubscribe());
+				Ok(WmRet::HandledOk)
+			}
+		});
+
+		events.process_last_message(&HWND::NULL, msg(co::WM::CLOSE)).unwrap();
+		assert_eq!((count_added.get(), count_removed.get()), (0, 0));
+
+		sub.unsubscribe();
+		events.process_all_messages(&HWND::NULL, msg(co::WM::CLOSE)).unwrap();
+		assert_eq!((count_added.get(), count_removed.get()), (1, 0));
+	}
+
+	#[test]
+	fn clear_while_processing() {
+		let events = Rc::new(WindowEvents::new(false));
+		let count = Rc::new(Cell::new(0));
+
+		events.wm(co::WM::CLOSE, {
+			let events = Rc::downgrade(&events);
+			let count = count.clone();
+			move |_| {
+				let events = events.upgrade().unwrap();
+				events.process_all_messages(&HWND::NULL, msg(co::WM::NCDESTROY)).unwrap(); // nested
+				assert!(!events.is_empty()); // deferred, this closure is still running
+				count.set(count.get() + 1);
+				Ok(WmRet::HandledOk)
+			}
+		});
+		events.wm(co::WM::NCDESTROY, {
+			let events = Rc::downgrade(&events);
+			move |_| {
+				events.upgrade().unwrap().clear_events(); // like the window does
+				Ok(WmRet::HandledOk)
+			}
+		});
+
+		assert!(events.process_all_messages(&HWND::NULL, msg(co::WM::CLOSE)).unwrap());
+		assert_eq!(count.get(), 1);
+		assert!(events.is_empty());
+
+		events.wm(co::WM::CLOSE, |_| Ok(WmRet::HandledOk));
+		events.clear_events(); // not processing, immediate
+		assert!(events.is_empty());
+	}
+}
@@ -53,11 +53,6 @@ impl GuiNativeControl for Button {}
 
 impl GuiNativeControlEvents<ButtonEvents> for Button {
 	fn on(&self) -> &ButtonEvents {
-		if *self.hwnd() != HWND::NULL {
-			panic!("Cannot add events after the control creation.");
-		} else if *self.0.base.parent().hwnd() != HWND::NULL {
-			panic!("Cannot add events after the parent window creation.");
-		}
 		&self.0.events
 	}
 }
@@ -275,3 +270,38 @@ impl AutoCtrlId for ButtonOpts {
 		&mut self.ctrl_id
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use std::cell::Cell;
+	use std::rc::Rc;
+	use super::*;
+
+	#[test]
+	fn subscribe_after_creation() {
+		let wnd = WindowMain::new(WindowMainOpts::default());
+		let btn = Button::new(&wnd, ButtonOpts::default());
+		btn.0.base.assign_hctrl(unsafe { HWND::from_ptr(0x1234 as _) }); // pretend it was created
+
+		let clicks = Rc::new(Cell::new(0));
+		let sub = btn.on().bn_clicked({
+			let clicks = clicks.clone();
+			move || {
+				clicks.set(clicks.get() + 1);
+				Ok(())
+			}
+		});
+
+		let click = WndMsg {
+			msg_id: co::WM::COMMAND,
+			wparam: btn.ctrl_id() as usize | ((co::BN::CLICKED.raw() as usize) << 16),
+			lparam: 0x1234,
+		};
+		wnd.as_ref().process_user_message(click).unwrap();
+		assert_eq!(clicks.get(), 1);
+
+		assert!(sub.unsubscribe());
+		wnd.as_ref().process_user_message(click).unwrap();
+		assert_eq!(clicks.get(), 1);
+	}
+}
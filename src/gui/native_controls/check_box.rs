@@ -54,11 +54,6 @@ impl GuiNativeControl for CheckBox {}
 
 impl GuiNativeControlEvents<ButtonEvents> for CheckBox {
 	fn on(&self) -> &ButtonEvents {
-		if *self.hwnd() != HWND::NULL {
-			panic!("Cannot add events after the control creation.");
-		} else if *self.0.base.parent().hwnd() != HWND::NULL {
-			panic!("Cannot add events after the parent window creation.");
-		}
 		&self.0.events
 	}
 }
@@ -53,11 +53,6 @@ impl GuiNativeControl for ComboBox {}
 
 impl GuiNativeControlEvents<ComboBoxEvents> for ComboBox {
 	fn on(&self) -> &ComboBoxEvents {
-		if *self.hwnd() != HWND::NULL {
-			panic!("Cannot add events after the control creation.");
-		} else if *self.0.base.parent().hwnd() != HWND::NULL {
-			panic!("Cannot add events after the parent window creation.");
-		}
 		&self.0.events
 	}
 }
@@ -155,8 +150,8 @@ impl ComboBox {
 	}
 
 	fn create(&self, opts_resz: OptsResz<&ComboBoxOpts>) -> SysResult<()> {
-		if opts_resz.resize_behavior().1 == Vert::Resize {
-			panic!("ComboBox cannot be resized with Vert::Resize.");
+		if opts_resz.resize_behavior().1.resizes() {
+			panic!("ComboBox cannot be resized vertically.");
 		}
 
 		match opts_resz {
@@ -245,7 +240,7 @@ pub struct ComboBoxOpts {
 	/// is resized.
 	///
 	/// **Note:** A `ComboBox` cannot be resized vertically, so it will panic if
-	/// you use `Vert::Resize`.
+	/// you use `Vert::Resize`, or a `Vert::Anchor` which changes the height.
 	///
 	/// Defaults to `(gui::Horz::None, gui::Vert::None)`.
 	pub resize_behavior: (Horz, Vert),
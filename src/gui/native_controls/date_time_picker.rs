@@ -51,11 +51,6 @@ impl GuiNativeControl for DateTimePicker {}
 
 impl GuiNativeControlEvents<DateTimePickerEvents> for DateTimePicker {
 	fn on(&self) -> &DateTimePickerEvents {
-		if *self.hwnd() != HWND::NULL {
-			panic!("Cannot add events after the control creation.");
-		} else if *self.0.base.parent().hwnd() != HWND::NULL {
-			panic!("Cannot add events after the parent window creation.");
-		}
 		&self.0.events
 	}
 }
@@ -128,8 +123,8 @@ impl DateTimePicker {
 	}
 
 	fn create(&self, opts_resz: OptsResz<&DateTimePickerOpts>) -> SysResult<()> {
-		if opts_resz.resize_behavior().1 == Vert::Resize {
-			panic!("DateTimePicker cannot be resized with Vert::Resize.");
+		if opts_resz.resize_behavior().1.resizes() {
+			panic!("DateTimePicker cannot be resized vertically.");
 		}
 
 		match opts_resz {
@@ -244,7 +239,8 @@ pub struct DateTimePickerOpts {
 	/// is resized.
 	///
 	/// **Note:** A `DateTimePicker` cannot be resized vertically, so it will
-	/// panic if you use `Vert::Resize`.
+	/// panic if you use `Vert::Resize`, or a `Vert::Anchor` which changes the
+	/// height.
 	///
 	/// Defaults to `(gui::Horz::None, gui::Vert::None)`.
 	pub resize_behavior: (Horz, Vert),
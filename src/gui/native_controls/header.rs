@@ -63,11 +63,6 @@ impl GuiNativeControl for Header {}
 
 impl GuiNativeControlEvents<HeaderEvents> for Header {
 	fn on(&self) -> &HeaderEvents {
-		if *self.hwnd() != HWND::NULL {
-			panic!("Cannot add events after the control creation.");
-		} else if *self.0.base.parent().hwnd() != HWND::NULL {
-			panic!("Cannot add events after the parent window creation.");
-		}
 		&self.0.events
 	}
 }
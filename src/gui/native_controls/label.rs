@@ -51,11 +51,6 @@ impl GuiNativeControl for Label {}
 
 impl GuiNativeControlEvents<LabelEvents> for Label {
 	fn on(&self) -> &LabelEvents {
-		if *self.hwnd() != HWND::NULL {
-			panic!("Cannot add events after the control creation.");
-		} else if *self.0.base.parent().hwnd() != HWND::NULL {
-			panic!("Cannot add events after the parent window creation.");
-		}
 		&self.0.events
 	}
 }
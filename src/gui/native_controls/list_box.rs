@@ -52,11 +52,6 @@ impl GuiNativeControl for ListBox {}
 
 impl GuiNativeControlEvents<ListBoxEvents> for ListBox {
 	fn on(&self) -> &ListBoxEvents {
-		if *self.hwnd() != HWND::NULL {
-			panic!("Cannot add events after the control creation.");
-		} else if *self.0.base.parent().hwnd() != HWND::NULL {
-			panic!("Cannot add events after the parent window creation.");
-		}
 		&self.0.events
 	}
 }
@@ -69,11 +69,6 @@ impl<T> GuiNativeControl for ListView<T> {}
 
 impl<T> GuiNativeControlEvents<ListViewEvents> for ListView<T> {
 	fn on(&self) -> &ListViewEvents {
-		if *self.hwnd() != HWND::NULL {
-			panic!("Cannot add events after the control creation.");
-		} else if *self.0.base.parent().hwnd() != HWND::NULL {
-			panic!("Cannot add events after the parent window creation.");
-		}
 		&self.0.events
 	}
 }
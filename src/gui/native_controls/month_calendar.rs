@@ -51,11 +51,6 @@ impl GuiNativeControl for MonthCalendar {}
 
 impl GuiNativeControlEvents<MonthCalendarEvents> for MonthCalendar {
 	fn on(&self) -> &MonthCalendarEvents {
-		if *self.hwnd() != HWND::NULL {
-			panic!("Cannot add events after the control creation.");
-		} else if *self.0.base.parent().hwnd() != HWND::NULL {
-			panic!("Cannot add events after the parent window creation.");
-		}
 		&self.0.events
 	}
 }
@@ -128,10 +123,10 @@ impl MonthCalendar {
 	}
 
 	fn create(&self, opts_resz: OptsResz<&MonthCalendarOpts>) -> SysResult<()> {
-		if opts_resz.resize_behavior().0 == Horz::Resize {
-			panic!("MonthCalendar cannot be resized with Horz::Resize.");
-		} else if opts_resz.resize_behavior().1 == Vert::Resize {
-			panic!("MonthCalendar cannot be resized with Vert::Resize.");
+		if opts_resz.resize_behavior().0.resizes() {
+			panic!("MonthCalendar cannot be resized horizontally.");
+		} else if opts_resz.resize_behavior().1.resizes() {
+			panic!("MonthCalendar cannot be resized vertically.");
 		}
 
 		match opts_resz {
@@ -222,7 +217,7 @@ pub struct MonthCalendarOpts {
 	///
 	/// **Note:** A `MonthCalendar` cannot be resized horizontally or
 	/// vertically, so it will panic if you use `Horz::Resize` or
-	/// `Vert::Resize`.
+	/// `Vert::Resize`, or an `Anchor` which changes the size.
 	///
 	/// Defaults to `(gui::Horz::None, gui::Vert::None)`.
 	pub resize_behavior: (Horz, Vert),
@@ -1,7 +1,6 @@
 use std::marker::PhantomPinned;
 use std::ops::Index;
 use std::pin::Pin;
-use std::ptr::NonNull;
 use std::sync::Arc;
 
 use crate::co;
@@ -10,7 +9,6 @@ use crate::gui::{*, events::*, privs::*};
 use crate::prelude::*;
 
 struct Obj { // actual fields of RadioGroup
-	parent_ptr: NonNull<Base>,
 	radios: Vec<RadioButton>,
 	events: RadioGroupEvents,
 	_pin: PhantomPinned,
@@ -32,11 +30,6 @@ impl Index<usize> for RadioGroup {
 
 impl GuiNativeControlEvents<RadioGroupEvents> for RadioGroup {
 	fn on(&self) -> &RadioGroupEvents {
-		if *self.index(0).hwnd() != HWND::NULL {
-			panic!("Cannot add events after the control creation.");
-		} else if *unsafe { self.0.parent_ptr.as_ref() }.hwnd() != HWND::NULL {
-			panic!("Cannot add events after the parent window creation.");
-		}
 		&self.0.events
 	}
 }
@@ -83,7 +76,6 @@ impl RadioGroup {
 		let new_self = Self(
 			Arc::pin(
 				Obj {
-					parent_ptr: NonNull::from(parent.as_ref()),
 					radios,
 					events: RadioGroupEvents::new(parent, ctrl_ids),
 					_pin: PhantomPinned,
@@ -135,7 +127,6 @@ impl RadioGroup {
 		let new_self = Self(
 			Arc::pin(
 				Obj {
-					parent_ptr: NonNull::from(parent.as_ref()),
 					radios,
 					events: RadioGroupEvents::new(parent, ctrl_ids),
 					_pin: PhantomPinned,
@@ -53,11 +53,6 @@ impl GuiNativeControl for Tab {}
 
 impl GuiNativeControlEvents<TabEvents> for Tab {
 	fn on(&self) -> &TabEvents {
-		if *self.hwnd() != HWND::NULL {
-			panic!("Cannot add events after the control creation.");
-		} else if *self.0.base.parent().hwnd() != HWND::NULL {
-			panic!("Cannot add events after the parent window creation.");
-		}
 		&self.0.events
 	}
 }
@@ -65,11 +65,6 @@ impl<T> GuiNativeControl for TreeView<T> {}
 
 impl<T> GuiNativeControlEvents<TreeViewEvents> for TreeView<T> {
 	fn on(&self) -> &TreeViewEvents {
-		if *self.hwnd() != HWND::NULL {
-			panic!("Cannot add events after the control creation.");
-		} else if *self.0.base.parent().hwnd() != HWND::NULL {
-			panic!("Cannot add events after the parent window creation.");
-		}
 		&self.0.events
 	}
 }
@@ -55,11 +55,6 @@ impl GuiNativeControl for UpDown {}
 
 impl GuiNativeControlEvents<UpDownEvents> for UpDown {
 	fn on(&self) -> &UpDownEvents {
-		if *self.hwnd() != HWND::NULL {
-			panic!("Cannot add events after the control creation.");
-		} else if *self.0.base.parent().hwnd() != HWND::NULL {
-			panic!("Cannot add events after the parent window creation.");
-		}
 		&self.0.events
 	}
 }
@@ -86,10 +86,11 @@ pub trait GuiParent: GuiWindow + AsRef<Base> {
 	/// Exposes methods to handle the basic window messages, plus timer and
 	/// native control notifications.
 	///
-	/// # Panics
-	///
-	/// Panics if the window is already created. Events must be set before
-	/// window creation.
+	/// Events can also be added after window creation, like from within another
+	/// event closure. In this case, the new closures will be called starting
+	/// from the next message, and messages already sent – like
+	/// [`wm_create`](crate::gui::events::WindowEvents::wm_create) – won't reach
+	/// them.
 	#[must_use]
 	fn on(&self) -> &WindowEvents {
 		self.as_ref().on()
@@ -306,10 +307,9 @@ pub trait GuiNativeControl: GuiChild + AsRef<BaseNativeControl> {
 pub trait GuiNativeControlEvents<E> {
 	/// Exposes the specific control events.
 	///
-	/// # Panics
-	///
-	/// Panics if the control is already created. Events must be set before
-	/// control creation.
+	/// The events are actually added to the parent window, so, like
+	/// [`GuiParent::on`](crate::prelude::GuiParent::on), they can also be added
+	/// after the control creation.
 	#[must_use]
 	fn on(&self) -> &E;
 }
@@ -89,12 +89,10 @@ impl Base {
 		&self.before_user_events
 	}
 
-	/// User events can be overriden; only the last one is executed.
+	/// User events can be overriden; only the last one is executed. They can be
+	/// added at any time, even after window creation.
 	#[must_use]
 	pub(in crate::gui) fn on(&self) -> &WindowEvents {
-		if self.hwnd != HWND::NULL {
-			panic!("Cannot add event after window creation.");
-		}
 		&self.user_events
 	}
 
@@ -4,12 +4,20 @@ use crate::gui::{*, privs::*};
 use crate::msg::*;
 use crate::prelude::*;
 
+/// Where a dialog window is loaded from.
+pub(in crate::gui) enum DlgSource {
+	/// Dialog resource ID.
+	Id(u16),
+	/// In-memory template, built when the dialog is created.
+	Template(DialogTemplateBuilder),
+}
+
 /// Base to all dialog windows.
 ///
 /// Owns the window procedure for all dialog windows.
 pub(in crate::gui) struct DlgBase {
 	base: Base,
-	dialog_id: u16,
+	source: DlgSource,
 }
 
 impl Drop for DlgBase {
@@ -24,12 +32,12 @@ impl DlgBase {
 	#[must_use]
 	pub(in crate::gui) fn new(
 		parent: Option<&impl AsRef<Base>>,
-		dialog_id: u16,
+		source: DlgSource,
 	) -> Self
 	{
 		Self {
 			base: Base::new(true, parent),
-			dialog_id,
+			source,
 		}
 	}
 
@@ -45,15 +53,28 @@ impl DlgBase {
 
 		// Our hwnd member is set during WM_INITDIALOG processing; already set
 		// when CreateDialogParam returns.
-		unsafe {
-			self.base.parent_hinstance()?.CreateDialogParam(
-				IdStr::Id(self.dialog_id),
-				self.base.parent().map(|parent| parent.hwnd()),
-				Self::dialog_proc,
-				// Pass pointer to Self.
-				// At this moment, the parent struct is already created and pinned.
-				Some(self as *const _ as _),
-			)?;
+		let hinst = self.base.parent_hinstance()?;
+		let hparent = self.base.parent().map(|parent| parent.hwnd());
+		match &self.source {
+			DlgSource::Id(dialog_id) => unsafe {
+				hinst.CreateDialogParam(
+					IdStr::Id(*dialog_id),
+					hparent,
+					Self::dialog_proc,
+					// Pass pointer to Self.
+					// At this moment, the parent struct is already created and pinned.
+					Some(self as *const _ as _),
+				)?;
+			},
+			DlgSource::Template(template) => unsafe {
+				let buf = Self::aligned_template(template)?;
+				hinst.CreateDialogIndirectParam(
+					&*(buf.as_ptr() as *const DLGTEMPLATE),
+					hparent,
+					Self::dialog_proc,
+					Some(self as *const _ as _),
+				)?;
+			},
 		}
 
 		Ok(())
@@ -66,20 +87,48 @@ impl DlgBase {
 
 		// Our hwnd member is set during WM_INITDIALOG processing; already set
 		// when DialogBoxParam returns.
-		let ret = unsafe {
-			self.base.parent_hinstance()?.DialogBoxParam(
-				IdStr::Id(self.dialog_id),
-				self.base.parent().map(|parent| parent.hwnd()),
-				Self::dialog_proc,
-				// Pass pointer to Self.
-				// At this moment, the parent struct is already created and pinned.
-				Some(self as *const _ as _),
-			)?
+		let hinst = self.base.parent_hinstance()?;
+		let hparent = self.base.parent().map(|parent| parent.hwnd());
+		let ret = match &self.source {
+			DlgSource::Id(dialog_id) => unsafe {
+				hinst.DialogBoxParam(
+					IdStr::Id(*dialog_id),
+					hparent,
+					Self::dialog_proc,
+					// Pass pointer to Self.
+					// At this moment, the parent struct is already created and pinned.
+					Some(self as *const _ as _),
+				)?
+			},
+			DlgSource::Template(template) => unsafe {
+				let buf = Self::aligned_template(template)?;
+				hinst.DialogBoxIndirectParam(
+					&*(buf.as_ptr() as *const DLGTEMPLATE),
+					hparent,
+					Self::dialog_proc,
+					Some(self as *const _ as _),
+				)?
+			},
 		};
 
 		Ok(ret as _)
 	}
 
+	/// Builds the template into a DWORD-aligned buffer, as required by the
+	/// indirect dialog functions.
+	fn aligned_template(template: &DialogTemplateBuilder) -> SysResult<Vec<u32>> {
+		let bytes = template.build()?;
+		let mut buf = vec![0u32; bytes.len().div_ceil(4)];
+		bytes.chunks(4)
+			.zip(buf.iter_mut())
+			.for_each(|(chunk, dw)| {
+				let mut quad = [0u8; 4];
+				quad[..chunk.len()].copy_from_slice(chunk);
+				*dw = u32::from_le_bytes(quad);
+			});
+		Ok(buf)
+	}
+
 	extern "system" fn dialog_proc(
 		hwnd: HWND,
 		msg: co::WM,
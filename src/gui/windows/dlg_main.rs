@@ -22,7 +22,7 @@ pub(in crate::gui) struct DlgMain(Pin<Arc<Obj>>);
 impl DlgMain {
 	#[must_use]
 	pub(in crate::gui) fn new(
-		dialog_id: u16,
+		source: DlgSource,
 		icon_id: Option<u16>,
 		accel_table_id: Option<u16>,
 	) -> Self
@@ -30,7 +30,7 @@ impl DlgMain {
 		let new_self = Self(
 			Arc::pin(
 				Obj {
-					dlg_base: DlgBase::new(None::<&WindowMain>, dialog_id),
+					dlg_base: DlgBase::new(None::<&WindowMain>, source),
 					icon_id,
 					accel_table_id,
 					_pin: PhantomPinned,
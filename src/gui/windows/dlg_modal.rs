@@ -20,13 +20,13 @@ impl DlgModal {
 	#[must_use]
 	pub(in crate::gui) fn new(
 		parent: &impl AsRef<Base>,
-		dialog_id: u16,
+		source: DlgSource,
 	) -> Self
 	{
 		let new_self = Self(
 			Arc::pin(
 				Obj {
-					dlg_base: DlgBase::new(Some(parent), dialog_id),
+					dlg_base: DlgBase::new(Some(parent), source),
 					_pin: PhantomPinned,
 				},
 			),
@@ -0,0 +1,579 @@
+use crate::co;
+use crate::decl::*;
+use crate::gui::*;
+use crate::prelude::*;
+
+/// Computes the new rectangle of a control when its parent window is resized,
+/// according to its horizontal and vertical behavior.
+///
+/// This is a pure function, which doesn't touch any window. It's used
+/// internally to rearrange the controls, and it can be used to test the
+/// resizing rules.
+///
+/// # Panics
+///
+/// Panics if an [`Horz::Anchor`](crate::gui::Horz::Anchor) or
+/// [`Vert::Anchor`](crate::gui::Vert::Anchor) has a percentage over 100, or
+/// its first percentage greater than the second.
+///
+/// # Examples
+///
+/// ```no_run
+/// use winsafe::{self as w, gui};
+///
+/// let rc = gui::arrange_rect(
+///     w::RECT { left: 10, top: 10, right: 110, bottom: 30 },
+///     w::SIZE::new(200, 100),
+///     w::SIZE::new(300, 100),
+///     (gui::Horz::Anchor { left: 0, right: 60, min: None, max: None }, gui::Vert::None),
+/// );
+///
+/// assert_eq!(rc.right, 170);
+/// ```
+#[must_use]
+pub fn arrange_rect(
+	rc_orig: RECT,
+	sz_parent_orig: SIZE,
+	sz_parent_new: SIZE,
+	resize_behavior: (Horz, Vert),
+) -> RECT
+{
+	let (horz, vert) = resize_behavior;
+
+	let (left, right) = arrange_axis(
+		rc_orig.left,
+		rc_orig.right,
+		sz_parent_new.cx - sz_parent_orig.cx,
+		match horz {
+			Horz::None => (0, 0, None, None),
+			Horz::Repos => (100, 100, None, None),
+			Horz::Resize => (0, 100, None, None),
+			Horz::Anchor { left, right, min, max } => (left, right, min, max),
+		},
+	);
+
+	let (top, bottom) = arrange_axis(
+		rc_orig.top,
+		rc_orig.bottom,
+		sz_parent_new.cy - sz_parent_orig.cy,
+		match vert {
+			Vert::None => (0, 0, None, None),
+			Vert::Repos => (100, 100, None, None),
+			Vert::Resize => (0, 100, None, None),
+			Vert::Anchor { top, bottom, min, max } => (top, bottom, min, max),
+		},
+	);
+
+	RECT { left, top, right, bottom }
+}
+
+/// Moves both edges of one axis by their percentages of the parent size change,
+/// then clamps the length, keeping the first edge.
+///
+/// Panics if the percentages are out of order or over 100.
+fn arrange_axis(
+	start: i32,
+	end: i32,
+	delta: i32,
+	(pct_start, pct_end, min, max): (u8, u8, Option<u32>, Option<u32>),
+) -> (i32, i32)
+{
+	assert!(pct_start <= pct_end && pct_end <= 100,
+		"Invalid anchor percentages: {} and {}.", pct_start, pct_end);
+
+	let new_start = start + delta * pct_start as i32 / 100;
+	let new_end = end + delta * pct_end as i32 / 100;
+
+	let mut len = new_end - new_start;
+	if let Some(max) = max {
+		len = len.min(max as _);
+	}
+	if let Some(min) = min {
+		len = len.max(min as _);
+	}
+	(new_start, new_start + len.max(0))
+}
+
+/// Size of an item in a [`LayoutStack`](crate::gui::LayoutStack), or of a row
+/// or column in a [`LayoutGrid`](crate::gui::LayoutGrid).
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LayoutTrack {
+	/// A fixed size, in pixels.
+	Fixed(u32),
+	/// Fills the space left by the fixed-size tracks, proportionally to the
+	/// weight, and clamped by the minimum and maximum sizes, in pixels.
+	///
+	/// How weights work:
+	///
+	/// 1. Suppose you have 2 tracks, respectively with weights of 3 and 2.
+	/// 2. If available space is 500px, respective sizes will be 300 and 200px.
+	/// 3. If one track is clamped by its minimum or maximum size, the remaining space is split among the others.
+	Fill {
+		/// Proportion of the available space. A weight of 0 takes no space
+		/// beyond the minimum size.
+		weight: u32,
+		/// Minimum size, in pixels.
+		min: u32,
+		/// Maximum size, in pixels.
+		max: Option<u32>,
+	},
+}
+
+impl LayoutTrack {
+	/// Returns a [`LayoutTrack::Fill`](crate::gui::LayoutTrack::Fill) with the
+	/// given weight, and no minimum or maximum sizes.
+	#[must_use]
+	pub const fn fill(weight: u32) -> Self {
+		Self::Fill { weight, min: 0, max: None }
+	}
+}
+
+/// Distributes the available length among the tracks, returning the length of
+/// each one.
+fn distribute(tracks: &[LayoutTrack], avail: i32) -> Vec<i32> {
+	let mut lens = vec![0; tracks.len()];
+	let mut frozen = vec![false; tracks.len()];
+
+	let mut remaining = avail;
+	for (idx, track) in tracks.iter().enumerate() {
+		if let LayoutTrack::Fixed(sz) = track {
+			lens[idx] = *sz as _;
+			frozen[idx] = true;
+			remaining -= *sz as i32;
+		}
+	}
+
+	loop {
+		let tot_weight = tracks.iter()
+			.zip(frozen.iter())
+			.filter(|(_, frozen)| !**frozen)
+			.fold(0u64, |tot, (track, _)| match track {
+				LayoutTrack::Fill { weight, .. } => tot + *weight as u64,
+				LayoutTrack::Fixed(_) => tot,
+			});
+		let space = remaining.max(0) as i64;
+
+		let mut violated = false;
+		let mut given = 0i64;
+		let mut unfrozen = Vec::with_capacity(tracks.len());
+		for (idx, track) in tracks.iter().enumerate() {
+			if frozen[idx] {
+				continue;
+			}
+			if let LayoutTrack::Fill { weight, min, max } = track {
+				let share = if tot_weight == 0 { 0 } else { space * *weight as i64 / tot_weight as i64 };
+				let clamped = share.max(*min as _).min(max.map_or(i64::MAX, |max| max.max(*min) as _));
+				if clamped != share { // clamped tracks are frozen, and we start over
+					lens[idx] = clamped as _;
+					frozen[idx] = true;
+					remaining -= clamped as i32;
+					violated = true;
+				} else {
+					lens[idx] = share as _;
+					given += share;
+					unfrozen.push(idx);
+				}
+			}
+		}
+
+		if !violated {
+			let mut leftover = space - given; // rounding leftover goes to the last tracks, up to their max
+			for idx in unfrozen.iter().rev() {
+				if let LayoutTrack::Fill { weight, min, max } = tracks[*idx] {
+					if weight == 0 {
+						continue; // takes no extra space
+					}
+					let room = max.map_or(i64::MAX, |max| max.max(min) as i64 - lens[*idx] as i64);
+					let extra = leftover.min(room);
+					lens[*idx] += extra as i32;
+					leftover -= extra;
+				}
+			}
+			return lens;
+		}
+	}
+}
+
+/// Computes the start position and length of each track, along one axis.
+fn tracks_positions(tracks: &[LayoutTrack], start: i32, len: i32, gap: u32) -> Vec<(i32, i32)> {
+	let gaps = gap as i32 * (tracks.len().max(1) as i32 - 1);
+	let mut pos = start;
+	distribute(tracks, len - gaps)
+		.iter()
+		.map(|len| {
+			let cur = (pos, *len);
+			pos += *len + gap as i32;
+			cur
+		})
+		.collect()
+}
+
+/// Applies the rectangles to the windows with
+/// [`HDWP::DeferWindowPos`](crate::prelude::user_Hdwp::DeferWindowPos).
+fn apply_rects(hwnds_rcs: &[(&HWND, RECT)]) -> SysResult<()> {
+	let mut hdwp = HDWP::BeginDeferWindowPos(hwnds_rcs.len() as _)?;
+	for (hwnd, rc) in hwnds_rcs.iter() {
+		hdwp.DeferWindowPos(
+			hwnd,
+			HwndPlace::None,
+			POINT::new(rc.left, rc.top),
+			SIZE::new(rc.right - rc.left, rc.bottom - rc.top),
+			co::SWP::NOZORDER,
+		)?;
+	}
+	Ok(())
+}
+
+/// A container which stacks its items side by side, either horizontally or
+/// vertically, each one with a [`LayoutTrack`](crate::gui::LayoutTrack) size.
+///
+/// The geometry is computed by
+/// [`arrange`](crate::gui::LayoutStack::arrange), which is a pure function, and
+/// can be applied to the windows with
+/// [`apply`](crate::gui::LayoutStack::apply), usually when handling
+/// [`wm_size`](crate::gui::events::WindowEvents::wm_size).
+///
+/// # Examples
+///
+/// Two side-by-side panes splitting the width 60/40, with a fixed-width toolbar
+/// at right:
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*, gui};
+///
+/// let wnd: gui::WindowMain; // initialized somewhere
+/// # let wnd = gui::WindowMain::new(gui::WindowMainOpts::default());
+/// let (pane1, pane2, toolbar): (w::HWND, w::HWND, w::HWND); // created somewhere
+/// # let (pane1, pane2, toolbar) = (w::HWND::NULL, w::HWND::NULL, w::HWND::NULL);
+///
+/// let stack = gui::LayoutStack {
+///     horizontal: true,
+///     gap: 4,
+///     items: vec![
+///         gui::LayoutTrack::fill(3),
+///         gui::LayoutTrack::fill(2),
+///         gui::LayoutTrack::Fixed(32),
+///     ],
+/// };
+///
+/// wnd.on().wm_size(move |p| {
+///     let rc = w::RECT { left: 0, top: 0, right: p.client_area.cx, bottom: p.client_area.cy };
+///     stack.apply(rc, &[&pane1, &pane2, &toolbar])?;
+///     Ok(())
+/// });
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct LayoutStack {
+	/// If `true`, items are stacked from left to right; otherwise, from top to
+	/// bottom.
+	pub horizontal: bool,
+	/// Space between the items, in pixels.
+	pub gap: u32,
+	/// Size of each item along the stacking direction. Across it, the items
+	/// take the whole space.
+	pub items: Vec<LayoutTrack>,
+}
+
+impl LayoutStack {
+	/// Computes the rectangle of each item, within the given rectangle.
+	#[must_use]
+	pub fn arrange(&self, rc: RECT) -> Vec<RECT> {
+		if self.horizontal {
+			tracks_positions(&self.items, rc.left, rc.right - rc.left, self.gap)
+				.iter()
+				.map(|(x, cx)| RECT { left: *x, top: rc.top, right: x + cx, bottom: rc.bottom })
+				.collect()
+		} else {
+			tracks_positions(&self.items, rc.top, rc.bottom - rc.top, self.gap)
+				.iter()
+				.map(|(y, cy)| RECT { left: rc.left, top: *y, right: rc.right, bottom: y + cy })
+				.collect()
+		}
+	}
+
+	/// Computes the rectangle of each item with
+	/// [`arrange`](crate::gui::LayoutStack::arrange), then moves the windows,
+	/// which are given in the same order of the items.
+	///
+	/// # Panics
+	///
+	/// Panics if the number of windows is different from the number of items.
+	pub fn apply(&self, rc: RECT, hwnds: &[&HWND]) -> SysResult<()> {
+		if hwnds.len() != self.items.len() {
+			panic!("LayoutStack has {} items, but {} windows were given.",
+				self.items.len(), hwnds.len());
+		}
+		apply_rects(
+			&hwnds.iter()
+				.zip(self.arrange(rc))
+				.map(|(hwnd, rc)| (*hwnd, rc))
+				.collect::<Vec<_>>(),
+		)
+	}
+}
+
+/// The position of an item in a [`LayoutGrid`](crate::gui::LayoutGrid).
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayoutCell {
+	/// Zero-based row index.
+	pub row: usize,
+	/// Zero-based column index.
+	pub col: usize,
+	/// Number of rows spanned by the item, at least 1.
+	pub row_span: usize,
+	/// Number of columns spanned by the item, at least 1.
+	pub col_span: usize,
+}
+
+impl LayoutCell {
+	/// Creates a new cell spanning one row and one column.
+	#[must_use]
+	pub const fn new(row: usize, col: usize) -> Self {
+		Self { row, col, row_span: 1, col_span: 1 }
+	}
+}
+
+/// A container which arranges its items in rows and columns, each one with a
+/// [`LayoutTrack`](crate::gui::LayoutTrack) size. An item may span multiple
+/// rows and columns.
+///
+/// The geometry is computed by
+/// [`arrange`](crate::gui::LayoutGrid::arrange), which is a pure function, and
+/// can be applied to the windows with
+/// [`apply`](crate::gui::LayoutGrid::apply), usually when handling
+/// [`wm_size`](crate::gui::events::WindowEvents::wm_size).
+///
+/// # Examples
+///
+/// A form with fixed-width labels and stretched fields:
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*, gui};
+///
+/// let grid = gui::LayoutGrid {
+///     cols: vec![gui::LayoutTrack::Fixed(80), gui::LayoutTrack::fill(1)],
+///     rows: vec![gui::LayoutTrack::Fixed(24), gui::LayoutTrack::Fixed(24)],
+///     gap: (8, 4),
+/// };
+///
+/// let rcs = grid.arrange(
+///     w::RECT { left: 0, top: 0, right: 300, bottom: 100 },
+///     &[
+///         gui::LayoutCell::new(0, 0), gui::LayoutCell::new(0, 1),
+///         gui::LayoutCell::new(1, 0), gui::LayoutCell::new(1, 1),
+///     ],
+/// );
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct LayoutGrid {
+	/// Width of each column.
+	pub cols: Vec<LayoutTrack>,
+	/// Height of each row.
+	pub rows: Vec<LayoutTrack>,
+	/// Horizontal and vertical space between the cells, in pixels.
+	pub gap: (u32, u32),
+}
+
+impl LayoutGrid {
+	/// Computes the rectangle of each item, within the given rectangle.
+	///
+	/// # Panics
+	///
+	/// Panics if a cell is out of the grid bounds.
+	#[must_use]
+	pub fn arrange(&self, rc: RECT, cells: &[LayoutCell]) -> Vec<RECT> {
+		let cols = tracks_positions(&self.cols, rc.left, rc.right - rc.left, self.gap.0);
+		let rows = tracks_positions(&self.rows, rc.top, rc.bottom - rc.top, self.gap.1);
+
+		cells.iter()
+			.map(|cell| {
+				let last_col = cell.col + cell.col_span.max(1) - 1;
+				let last_row = cell.row + cell.row_span.max(1) - 1;
+				if last_col >= cols.len() || last_row >= rows.len() {
+					panic!("Cell at row {}, col {} is out of the {}x{} grid.",
+						cell.row, cell.col, rows.len(), cols.len());
+				}
+				RECT {
+					left: cols[cell.col].0,
+					top: rows[cell.row].0,
+					right: cols[last_col].0 + cols[last_col].1,
+					bottom: rows[last_row].0 + rows[last_row].1,
+				}
+			})
+			.collect()
+	}
+
+	/// Computes the rectangle of each item with
+	/// [`arrange`](crate::gui::LayoutGrid::arrange), then moves the windows.
+	///
+	/// # Panics
+	///
+	/// Panics if a cell is out of the grid bounds.
+	pub fn apply(&self, rc: RECT, items: &[(&HWND, LayoutCell)]) -> SysResult<()> {
+		let cells = items.iter()
+			.map(|(_, cell)| *cell)
+			.collect::<Vec<_>>();
+		apply_rects(
+			&items.iter()
+				.zip(self.arrange(rc, &cells))
+				.map(|((hwnd, _), rc)| (*hwnd, rc))
+				.collect::<Vec<_>>(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn clamped(weight: u32, min: u32, max: Option<u32>) -> LayoutTrack {
+		LayoutTrack::Fill { weight, min, max }
+	}
+
+	fn rect(left: i32, top: i32, right: i32, bottom: i32) -> RECT {
+		RECT { left, top, right, bottom }
+	}
+
+	fn ltrb(rcs: &[RECT]) -> Vec<(i32, i32, i32, i32)> {
+		rcs.iter().map(|rc| (rc.left, rc.top, rc.right, rc.bottom)).collect()
+	}
+
+	#[test]
+	fn arrange_rect_anchors() {
+		let rc = rect(10, 10, 110, 30);
+		let (sz_orig, sz_wider) = (SIZE::new(200, 100), SIZE::new(300, 150));
+		let arrange = |sz_new, horz, vert| ltrb(&[arrange_rect(rc, sz_orig, sz_new, (horz, vert))]);
+		let anchor = |left, right, min, max| Horz::Anchor { left, right, min, max };
+
+		assert_eq!(arrange(sz_wider, Horz::None, Vert::None), [(10, 10, 110, 30)]);
+		assert_eq!(arrange(sz_wider, Horz::Repos, Vert::Repos), [(110, 60, 210, 80)]);
+		assert_eq!(arrange(sz_wider, Horz::Resize, Vert::Resize), [(10, 10, 210, 80)]);
+		assert_eq!(arrange(sz_wider, anchor(0, 60, None, None), Vert::None), [(10, 10, 170, 30)]);
+		assert_eq!(arrange(sz_wider, anchor(50, 50, None, None), Vert::None), [(60, 10, 160, 30)]);
+		assert_eq!(arrange(sz_wider, anchor(20, 80, None, None), Vert::None), [(30, 10, 190, 30)]);
+
+		assert_eq!(arrange(sz_wider, anchor(0, 100, None, Some(150)), Vert::None), [(10, 10, 160, 30)]);
+		assert_eq!(arrange(SIZE::new(100, 100), anchor(0, 100, Some(40), None), Vert::None), [(10, 10, 50, 30)]);
+		assert_eq!(arrange(SIZE::new(100, 100), Horz::Resize, Vert::None), [(10, 10, 10, 30)]); // never negative
+		assert_eq!(
+			arrange(sz_wider, Horz::None, Vert::Anchor { top: 0, bottom: 100, min: None, max: Some(40) }),
+			[(10, 10, 110, 50)],
+		);
+	}
+
+	#[test]
+	#[should_panic(expected = "Invalid anchor percentages: 250 and 250.")]
+	fn arrange_rect_anchor_over_100() {
+		let _ = arrange_rect(rect(10, 10, 110, 30), SIZE::new(200, 100), SIZE::new(300, 100),
+			(Horz::Anchor { left: 250, right: 250, min: None, max: None }, Vert::None));
+	}
+
+	#[test]
+	#[should_panic(expected = "Invalid anchor percentages: 80 and 20.")]
+	fn arrange_rect_anchor_reversed() {
+		let _ = arrange_rect(rect(10, 10, 110, 30), SIZE::new(200, 100), SIZE::new(200, 300),
+			(Horz::None, Vert::Anchor { top: 80, bottom: 20, min: None, max: None }));
+	}
+
+	#[test]
+	fn stack_two_panes() {
+		let stack = LayoutStack {
+			horizontal: true,
+			gap: 4,
+			items: vec![LayoutTrack::fill(3), LayoutTrack::fill(2)],
+		};
+		assert_eq!(ltrb(&stack.arrange(rect(0, 0, 504, 300))), [(0, 0, 300, 300), (304, 0, 504, 300)]); // 60/40
+		assert_eq!(ltrb(&stack.arrange(rect(10, 20, 114, 40))), [(10, 20, 70, 40), (74, 20, 114, 40)]);
+	}
+
+	#[test]
+	fn stack_gaps() {
+		let stack = LayoutStack {
+			horizontal: false,
+			gap: 5,
+			items: vec![LayoutTrack::Fixed(20), LayoutTrack::fill(1), LayoutTrack::Fixed(20)],
+		};
+		assert_eq!(
+			ltrb(&stack.arrange(rect(10, 10, 110, 210))),
+			[(10, 10, 110, 30), (10, 35, 110, 185), (10, 190, 110, 210)],
+		);
+
+		let toolbar = LayoutStack {
+			horizontal: true,
+			gap: 4,
+			items: vec![LayoutTrack::fill(3), LayoutTrack::fill(2), LayoutTrack::Fixed(32)],
+		};
+		assert_eq!(
+			ltrb(&toolbar.arrange(rect(0, 0, 500, 40))),
+			[(0, 0, 276, 40), (280, 0, 464, 40), (468, 0, 500, 40)],
+		);
+	}
+
+	#[test]
+	fn grid_spans() {
+		let grid = LayoutGrid {
+			cols: vec![LayoutTrack::Fixed(80), LayoutTrack::fill(1)],
+			rows: vec![LayoutTrack::Fixed(24), LayoutTrack::Fixed(24), LayoutTrack::fill(1)],
+			gap: (8, 4),
+		};
+		let rcs = grid.arrange(rect(0, 0, 300, 100), &[
+			LayoutCell::new(0, 0),
+			LayoutCell::new(1, 1),
+			LayoutCell { row: 2, col: 0, row_span: 1, col_span: 2 },
+			LayoutCell { row: 0, col: 1, row_span: 2, col_span: 1 },
+			LayoutCell { row: 1, col: 0, row_span: 0, col_span: 0 }, // treated as 1
+		]);
+		assert_eq!(ltrb(&rcs), [
+			(0, 0, 80, 24),
+			(88, 28, 300, 52),
+			(0, 56, 300, 100),
+			(88, 0, 300, 52),
+			(0, 28, 80, 52),
+		]);
+	}
+
+	#[test]
+	#[should_panic]
+	fn grid_out_of_bounds() {
+		let grid = LayoutGrid {
+			cols: vec![LayoutTrack::fill(1), LayoutTrack::fill(1)],
+			rows: vec![LayoutTrack::fill(1)],
+			gap: (0, 0),
+		};
+		let _ = grid.arrange(rect(0, 0, 100, 100), &[LayoutCell { row: 0, col: 1, row_span: 1, col_span: 2 }]);
+	}
+
+	#[test]
+	fn distribute_weights() {
+		assert_eq!(distribute(&[LayoutTrack::Fixed(100), LayoutTrack::fill(3), LayoutTrack::fill(2)], 600), [100, 300, 200]);
+		assert_eq!(distribute(&[LayoutTrack::fill(1), LayoutTrack::fill(1), LayoutTrack::fill(1)], 100), [33, 33, 34]);
+		assert_eq!(distribute(&[LayoutTrack::Fixed(50), LayoutTrack::fill(1)], 30), [50, 0]);
+		assert_eq!(distribute(&[], 100), []);
+	}
+
+	#[test]
+	fn distribute_min_max() {
+		assert_eq!(distribute(&[clamped(1, 80, None), LayoutTrack::fill(1)], 100), [80, 20]);
+		assert_eq!(distribute(&[clamped(1, 0, Some(10)), LayoutTrack::fill(1)], 100), [10, 90]);
+		assert_eq!(distribute(&[clamped(1, 0, Some(10)), clamped(1, 0, Some(10))], 100), [10, 10]);
+	}
+
+	#[test]
+	fn distribute_leftover_max() {
+		assert_eq!(distribute(&[LayoutTrack::fill(1), clamped(1, 0, Some(33))], 67), [34, 33]);
+		assert_eq!(distribute(&[clamped(1, 0, Some(33)), clamped(1, 0, Some(33))], 67), [33, 33]);
+	}
+
+	#[test]
+	fn distribute_zero_weights() {
+		assert_eq!(distribute(&[LayoutTrack::fill(0), LayoutTrack::fill(0)], 100), [0, 0]);
+		assert_eq!(distribute(&[clamped(0, 10, None), LayoutTrack::fill(0)], 100), [10, 0]);
+		assert_eq!(distribute(&[LayoutTrack::fill(1), LayoutTrack::fill(0)], 100), [100, 0]);
+		assert_eq!(distribute(&[clamped(0, 0, Some(5))], 100), [0]);
+	}
+
+	#[test]
+	fn positions() {
+		assert_eq!(tracks_positions(&[LayoutTrack::Fixed(10), LayoutTrack::fill(1)], 5, 100, 4), [(5, 10), (19, 86)]);
+	}
+}
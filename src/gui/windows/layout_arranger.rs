@@ -22,7 +22,9 @@ struct Obj { // actual fields of LayoutArranger
 	_pin: PhantomPinned,
 }
 
-/// Rearranges the stored controls according to their predefined rules.
+/// Rearranges the stored controls according to their predefined rules. The
+/// geometry is computed by [`arrange_rect`](crate::gui::arrange_rect), then
+/// applied with [`HDWP`](crate::HDWP).
 #[derive(Clone)]
 pub(in crate::gui) struct LayoutArranger(Pin<Arc<Obj>>);
 
@@ -113,29 +115,12 @@ impl LayoutArranger {
 				},
 			};
 
+			let rc = arrange_rect(rc_orig, sz_parent_orig, p.client_area, (ctrl.horz, ctrl.vert));
 			hdwp.DeferWindowPos(
 				&ctrl.hchild,
 				HwndPlace::None,
-				POINT::new(
-					match ctrl.horz {
-						Horz::Repos => p.client_area.cx - sz_parent_orig.cx + rc_orig.left,
-						_ => rc_orig.left // keep original x pos
-					},
-					match ctrl.vert {
-						Vert::Repos => p.client_area.cy - sz_parent_orig.cy + rc_orig.top,
-						_ => rc_orig.top // keep original y pos
-					},
-				),
-				SIZE::new(
-					match ctrl.horz {
-						Horz::Resize => p.client_area.cx - sz_parent_orig.cx + rc_orig.right - rc_orig.left,
-						_ => rc_orig.right - rc_orig.left // keep original width
-					},
-					match ctrl.vert {
-						Vert::Resize => p.client_area.cy - sz_parent_orig.cy + rc_orig.bottom - rc_orig.top,
-						_ => rc_orig.bottom - rc_orig.top // keep original height
-					},
-				),
+				POINT::new(rc.left, rc.top),
+				SIZE::new(rc.right - rc.left, rc.bottom - rc.top),
 				uflags,
 			)?;
 		}
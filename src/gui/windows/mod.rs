@@ -7,6 +7,7 @@ mod dlg_main;
 mod dlg_modal;
 mod dlg_modeless;
 mod layout_arranger;
+mod layout;
 mod msg_error;
 mod raw_base;
 mod raw_control;
@@ -21,7 +22,7 @@ mod window_modeless;
 
 pub(in crate::gui) mod privs {
 	pub(in crate::gui) use super::base::Base;
-	pub(in crate::gui) use super::dlg_base::DlgBase;
+	pub(in crate::gui) use super::dlg_base::{DlgBase, DlgSource};
 	pub(in crate::gui) use super::dlg_control::DlgControl;
 	pub(in crate::gui) use super::dlg_main::DlgMain;
 	pub(in crate::gui) use super::dlg_modal::DlgModal;
@@ -35,6 +36,7 @@ pub(in crate::gui) mod privs {
 }
 
 pub mod decl {
+	pub use super::layout::{arrange_rect, LayoutCell, LayoutGrid, LayoutStack, LayoutTrack};
 	pub use super::msg_error::MsgError;
 	pub use super::raw_control::WindowControlOpts;
 	pub use super::raw_main::WindowMainOpts;
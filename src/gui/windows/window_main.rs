@@ -65,7 +65,30 @@ impl WindowMain {
 	{
 		Self(
 			RawDlg::Dlg(
-				DlgMain::new(dialog_id, icon_id, accel_table_id),
+				DlgMain::new(DlgSource::Id(dialog_id), icon_id, accel_table_id),
+			),
+		)
+	}
+
+	/// Instantiates a new `WindowMain` object, to be created from an in-memory
+	/// template with
+	/// [`HINSTANCE::CreateDialogIndirectParam`](crate::prelude::user_Hinstance::CreateDialogIndirectParam).
+	///
+	/// The template is built when the window is created.
+	#[must_use]
+	pub fn new_dlg_template(
+		template: &DialogTemplateBuilder,
+		icon_id: Option<u16>,
+		accel_table_id: Option<u16>,
+	) -> Self
+	{
+		Self(
+			RawDlg::Dlg(
+				DlgMain::new(
+					DlgSource::Template(template.clone()),
+					icon_id,
+					accel_table_id,
+				),
 			),
 		)
 	}
@@ -57,7 +57,40 @@ impl WindowModal {
 	pub fn new_dlg(parent: &impl GuiParent, dialog_id: u16) -> Self {
 		Self(
 			RawDlg::Dlg(
-				DlgModal::new(parent, dialog_id),
+				DlgModal::new(parent, DlgSource::Id(dialog_id)),
+			),
+		)
+	}
+
+	/// Instantiates a new `WindowModal` object, to be created from an
+	/// in-memory template with
+	/// [`HINSTANCE::DialogBoxIndirectParam`](crate::prelude::user_Hinstance::DialogBoxIndirectParam).
+	///
+	/// The template is built when the window is created.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, gui};
+	///
+	/// fn confirm(parent: &impl GuiParent) -> w::AnyResult<i32> {
+	///     let template = w::DialogTemplateBuilder::new("Confirm", 160, 60)
+	///         .item(w::DialogTemplateItem::label("Proceed?", 0xffff, 7, 7, 140, 8))
+	///         .item(w::DialogTemplateItem::button("OK", 1, 103, 39, 50, 14));
+	///
+	///     let modal = gui::WindowModal::new_dlg_template(parent, &template);
+	///     modal.show_modal()
+	/// }
+	/// ```
+	#[must_use]
+	pub fn new_dlg_template(
+		parent: &impl GuiParent,
+		template: &DialogTemplateBuilder,
+	) -> Self
+	{
+		Self(
+			RawDlg::Dlg(
+				DlgModal::new(parent, DlgSource::Template(template.clone())),
 			),
 		)
 	}
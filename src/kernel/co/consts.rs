@@ -12,6 +12,9 @@ const_bitflag! { ACCESS_RIGHTS: u32;
 	WRITE_DAC 0x0004_0000
 	WRITE_OWNER 0x0008_0000
 	SYNCHRONIZE 0x0010_0000
+
+	ACCESS_SYSTEM_SECURITY 0x0100_0000
+	MAXIMUM_ALLOWED 0x0200_0000
 }
 
 const_bitflag! { CLAIM_SECURITY_ATTRIBUTE: u16;
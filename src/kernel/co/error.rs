@@ -33,6 +33,11 @@ const_basic_decl! { ERROR: u32;
 	///
 	/// The numbers inside the brackets are the system error code, in
 	/// hexadecimal and decimal formats for convenience.
+	///
+	/// Since [`FormatMessage`](crate::prelude::FormattedError::FormatMessage)
+	/// depends on the system and its UI language, the name of the constant can
+	/// be retrieved with [`name`](crate::co::ERROR::name), which uses a
+	/// built-in table.
 }
 
 impl std::error::Error for ERROR {
@@ -48,19 +53,59 @@ impl std::fmt::Display for ERROR {
 }
 impl std::fmt::Debug for ERROR {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.name() {
+			Some(name) => write!(f, "ERROR::{}", name)?,
+			None => write!(f, "ERROR")?,
+		}
 		if self.0 > 0xffff {
-			write!(f, "ERROR({:#010x} {}) {}",
+			write!(f, "({:#010x} {}) {}",
 				self.0, self.0, self.FormatMessage().trim_end())
 		} else {
-			write!(f, "ERROR({:#06x} {}) {}",
+			write!(f, "({:#06x} {}) {}",
 				self.0, self.0, self.FormatMessage().trim_end())
 		}
 	}
 }
 
+impl std::str::FromStr for ERROR {
+	type Err = ERROR;
+
+	/// Parses the name of a constant, like `ACCESS_DENIED` or
+	/// `co::ERROR::ACCESS_DENIED`, or a number.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let names = ERROR_NAMES.iter()
+			.map(|(name, v)| (*name, v.0 as u64))
+			.collect::<Vec<_>>();
+		crate::kernel::privs::const_parse("ERROR", &names, std::mem::size_of::<u32>(), s, false)
+			.map(|v| unsafe { Self::from_raw(v as _) })
+	}
+}
+
 impl FormattedError for ERROR {}
 
-const_values_pub! { ERROR;
+impl ERROR {
+	/// Returns the name of the constant, taken from a built-in table, without
+	/// calling [`FormatMessage`](crate::prelude::FormattedError::FormatMessage).
+	///
+	/// Returns `None` if the value is not declared in this library.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let err = co::ERROR::ACCESS_DENIED;
+	/// assert_eq!(err.name(), Some("ACCESS_DENIED"));
+	/// ```
+	#[must_use]
+	pub fn name(self) -> Option<&'static str> {
+		ERROR_NAMES.iter()
+			.find(|(_, v)| *v == self)
+			.map(|(name, _)| *name)
+	}
+}
+
+const_values_pub_named! { ERROR, ERROR_NAMES;
 	/// The operation completed successfully.
 	SUCCESS 0
 	INVALID_FUNCTION 1
@@ -2668,3 +2713,21 @@ const_values_pub! { ERROR;
 	STATE_CONTAINER_NAME_SIZE_LIMIT_EXCEEDED 15818
 	API_UNAVAILABLE 15841
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn from_str() {
+		for err in [ERROR::SUCCESS, ERROR::ACCESS_DENIED, ERROR::API_UNAVAILABLE] {
+			let name = err.name().unwrap();
+			assert_eq!(name.parse::<ERROR>().unwrap(), err);
+			assert_eq!(format!("ERROR::{}", name).parse::<ERROR>().unwrap(), err);
+		}
+		assert_eq!("co::ERROR::INVALID_DATA".parse::<ERROR>().unwrap(), ERROR::INVALID_DATA);
+		assert_eq!("5".parse::<ERROR>().unwrap(), ERROR::ACCESS_DENIED);
+		assert_eq!("ERROR::NOT_A_NAME".parse::<ERROR>(), Err(ERROR::INVALID_DATA));
+		assert_eq!("".parse::<ERROR>(), Err(ERROR::INVALID_DATA));
+	}
+}
@@ -1,5 +1,6 @@
 #![allow(non_camel_case_types, non_snake_case)]
 
+use crate::co;
 use crate::decl::*;
 use crate::guard::*;
 use crate::kernel::{ffi, privs::*};
@@ -62,4 +63,239 @@ pub trait kernel_Hupdatersrc: Handle {
 			},
 		)
 	}
+
+	/// Adds or replaces an icon group, along with its icon images, from the
+	/// contents of an `.ico` file.
+	///
+	/// Each image of the file is written as a
+	/// [`co::RT::ICON`](crate::co::RT::ICON) resource, with sequential IDs
+	/// starting at `first_icon_id`, which must not clash with the IDs of other
+	/// icon images in the file. Then the
+	/// [`co::RT::GROUP_ICON`](crate::co::RT::GROUP_ICON) directory which
+	/// references them is written with `group_id`.
+	///
+	/// Returns [`co::ERROR::INVALID_DATA`](crate::co::ERROR::INVALID_DATA) if
+	/// `ico_data` isn't a valid `.ico` file.
+	///
+	/// # Examples
+	///
+	/// Replacing the main icon of an executable:
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let ico = w::FileMapped::open("C:\\Temp\\app.ico", w::FileAccess::ExistingReadOnly)?;
+	///
+	/// let hupd = w::HUPDATERSRC::BeginUpdateResource("C:\\Temp\\foo.exe", false)?;
+	/// hupd.update_icon_group(
+	///     w::IdStr::Id(1),
+	///     1,
+	///     w::LANGID::new(co::LANG::NEUTRAL, co::SUBLANG::NEUTRAL),
+	///     ico.as_slice(),
+	/// )?;
+	///
+	/// // EndUpdateResource() called automatically
+	/// # w::SysResult::Ok(())
+	/// ```
+	fn update_icon_group(&self,
+		group_id: IdStr,
+		first_icon_id: u16,
+		language: LANGID,
+		ico_data: &[u8],
+	) -> SysResult<()>
+	{
+		let (group_dir, images) = encode_icon_group(ico_data, first_icon_id)?;
+		for (icon_id, image) in (first_icon_id..).zip(images) {
+			self.UpdateResource(RtStr::Rt(co::RT::ICON), IdStr::Id(icon_id), language, image)?;
+		}
+		self.UpdateResource(RtStr::Rt(co::RT::GROUP_ICON), group_id, language, &group_dir)
+	}
+
+	/// Adds or replaces a [manifest](https://learn.microsoft.com/en-us/windows/win32/sbscs/manifests),
+	/// encoded as UTF-8.
+	///
+	/// The `resource_id` is usually `1` for executables and `2` for DLLs.
+	fn update_manifest(&self,
+		resource_id: u16,
+		language: LANGID,
+		manifest: &str,
+	) -> SysResult<()>
+	{
+		self.UpdateResource(
+			RtStr::Rt(co::RT::MANIFEST),
+			IdStr::Id(resource_id),
+			language,
+			manifest.as_bytes(),
+		)
+	}
+
+	/// Adds or replaces strings of the string table.
+	///
+	/// Strings are stored in blocks of 16, and each block is written as a whole,
+	/// so any string of an affected block which is not in `strings` will be
+	/// removed. Strings longer than 65,535 UTF-16 code units are truncated.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hupd = w::HUPDATERSRC::BeginUpdateResource("C:\\Temp\\foo.exe", false)?;
+	/// hupd.update_string_table(
+	///     w::LANGID::new(co::LANG::ENGLISH, co::SUBLANG::ENGLISH_US),
+	///     &[(100, "First"), (101, "Second")],
+	/// )?;
+	/// # w::SysResult::Ok(())
+	/// ```
+	fn update_string_table(&self,
+		language: LANGID,
+		strings: &[(u16, &str)],
+	) -> SysResult<()>
+	{
+		for (block_id, block) in encode_string_table(strings) {
+			self.UpdateResource(RtStr::Rt(co::RT::STRING), IdStr::Id(block_id), language, &block)?;
+		}
+		Ok(())
+	}
+}
+
+/// Parses an `.ico` file, returning the `GRPICONDIR` resource data and the
+/// image data of each icon, whose resource IDs start at `first_icon_id`.
+pub(crate) fn encode_icon_group(
+	ico_data: &[u8],
+	first_icon_id: u16,
+) -> SysResult<(Vec<u8>, Vec<&[u8]>)>
+{
+	let word_at = |off: usize| u16::from_le_bytes([ico_data[off], ico_data[off + 1]]);
+	let dword_at = |off: usize| u32::from_le_bytes(
+		[ico_data[off], ico_data[off + 1], ico_data[off + 2], ico_data[off + 3]]);
+
+	const ICONDIR_SZ: usize = 6;
+	const ICONDIRENTRY_SZ: usize = 16;
+
+	if ico_data.len() < ICONDIR_SZ || word_at(0) != 0 || word_at(2) != 1 {
+		return Err(co::ERROR::INVALID_DATA); // not an icon file
+	}
+	let num_icons = word_at(4) as usize;
+	if num_icons == 0 || ico_data.len() < ICONDIR_SZ + num_icons * ICONDIRENTRY_SZ {
+		return Err(co::ERROR::INVALID_DATA);
+	}
+
+	let mut group_dir = ico_data[..ICONDIR_SZ].to_vec(); // same header
+	let mut images = Vec::with_capacity(num_icons);
+
+	for idx in 0..num_icons {
+		let entry_off = ICONDIR_SZ + idx * ICONDIRENTRY_SZ;
+		let img_sz = dword_at(entry_off + 8) as usize;
+		let img_off = dword_at(entry_off + 12) as usize;
+		let image = img_off.checked_add(img_sz)
+			.and_then(|img_end| ico_data.get(img_off..img_end))
+			.ok_or(co::ERROR::INVALID_DATA)?;
+
+		let icon_id = first_icon_id.checked_add(idx as _)
+			.ok_or(co::ERROR::INVALID_DATA)?;
+		group_dir.extend_from_slice(&ico_data[entry_off..entry_off + 12]); // width, height, colors, reserved, planes, bit count, size
+		group_dir.extend_from_slice(&icon_id.to_le_bytes()); // GRPICONDIRENTRY has the ID instead of the offset
+		images.push(image);
+	}
+
+	Ok((group_dir, images))
+}
+
+/// Groups the strings into blocks of 16, returning the resource ID and the
+/// data of each block.
+pub(crate) fn encode_string_table(strings: &[(u16, &str)]) -> Vec<(u16, Vec<u8>)> {
+	let mut block_ids = strings.iter()
+		.map(|(id, _)| id >> 4)
+		.collect::<Vec<_>>();
+	block_ids.sort_unstable();
+	block_ids.dedup();
+
+	block_ids.iter()
+		.map(|block_idx| {
+			let mut data = Vec::new();
+			for slot in 0..16 {
+				let id = (block_idx << 4) | slot;
+				let chars = strings.iter()
+					.rev() // last one wins
+					.find(|(s_id, _)| *s_id == id)
+					.map(|(_, s)| s.encode_utf16().take(0xffff).collect::<Vec<_>>())
+					.unwrap_or_default();
+				data.extend_from_slice(&(chars.len() as u16).to_le_bytes()); // strings are length-prefixed, not null-terminated
+				chars.iter().for_each(|ch| data.extend_from_slice(&ch.to_le_bytes()));
+			}
+			(block_idx + 1, data)
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Builds an `.ico` file with the given image sizes, each image filled
+	/// with its index.
+	fn ico(sizes: &[u8]) -> Vec<u8> {
+		let mut data = vec![0, 0, 1, 0];
+		data.extend_from_slice(&(sizes.len() as u16).to_le_bytes());
+		let mut img_off = 6 + sizes.len() as u32 * 16;
+		for (idx, sz) in sizes.iter().enumerate() {
+			data.extend_from_slice(&[16 * (idx as u8 + 1), 16 * (idx as u8 + 1), 0, 0]); // width, height, colors, reserved
+			data.extend_from_slice(&1u16.to_le_bytes()); // planes
+			data.extend_from_slice(&32u16.to_le_bytes()); // bit count
+			data.extend_from_slice(&(*sz as u32).to_le_bytes());
+			data.extend_from_slice(&img_off.to_le_bytes());
+			img_off += *sz as u32;
+		}
+		for (idx, sz) in sizes.iter().enumerate() {
+			data.extend_from_slice(&vec![idx as u8; *sz as _]);
+		}
+		data
+	}
+
+	#[test]
+	fn icon_group() {
+		let data = ico(&[3, 5]);
+		let (group_dir, images) = encode_icon_group(&data, 7).unwrap();
+
+		assert_eq!(images, [&[0u8, 0, 0][..], &[1u8, 1, 1, 1, 1][..]]);
+		assert_eq!(group_dir.len(), 6 + 2 * 14);
+		assert_eq!(&group_dir[..6], &data[..6]);
+		assert_eq!(&group_dir[6..18], &data[6..18]);
+		assert_eq!(&group_dir[18..20], &7u16.to_le_bytes()); // ID instead of offset
+		assert_eq!(&group_dir[20..32], &data[22..34]);
+		assert_eq!(&group_dir[32..34], &8u16.to_le_bytes());
+	}
+
+	#[test]
+	fn icon_group_malformed() {
+		let mut truncated = ico(&[3]);
+		truncated.pop();
+		let mut cursor = ico(&[3]);
+		cursor[2] = 2; // cursor file, not icon
+
+		for data in [vec![], vec![0, 0, 1, 0, 0, 0], ico(&[3])[..10].to_vec(), truncated, cursor] {
+			assert_eq!(encode_icon_group(&data, 1).unwrap_err(), co::ERROR::INVALID_DATA);
+		}
+		assert_eq!(encode_icon_group(&ico(&[1, 1]), 0xffff).unwrap_err(), co::ERROR::INVALID_DATA); // ID overflow
+	}
+
+	#[test]
+	fn string_table() {
+		let blocks = encode_string_table(&[(17, "ab"), (0, "x"), (17, "c"), (40, "")]);
+		assert_eq!(blocks.iter().map(|(id, _)| *id).collect::<Vec<_>>(), [1, 2, 3]);
+
+		let (_, block0) = &blocks[0];
+		assert_eq!(block0.len(), 16 * 2 + 2);
+		assert_eq!(&block0[..4], &[1, 0, b'x', 0]);
+		assert!(block0[4..].iter().all(|b| *b == 0));
+
+		let (_, block1) = &blocks[1];
+		assert_eq!(&block1[..2], &[0, 0]); // string 16 is empty
+		assert_eq!(&block1[2..6], &[1, 0, b'c', 0]); // last one wins
+		assert_eq!(block1.len(), 16 * 2 + 2);
+
+		let (_, block2) = &blocks[2];
+		assert_eq!(block2, &vec![0u8; 16 * 2]);
+	}
 }
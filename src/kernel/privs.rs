@@ -173,3 +173,172 @@ pub(crate) fn create_wstr_ptr_vecs(
 		None => (Vec::default(), Vec::default()),
 	}
 }
+
+/// Reorders the names of the values of a constant, moving to the end the ones
+/// which are aliases – that is, whose value expression, as given by
+/// `stringify!`, is just another value of the same type, like
+/// `Self::OVERLAPPED.0`. Otherwise the declaration order is kept.
+#[must_use]
+pub(crate) const fn const_aliases_last<const N: usize>(
+	names: [(&'static str, u64, &'static str); N],
+) -> [(&'static str, u64); N]
+{
+	let mut sorted = [("", 0); N];
+	let mut count = 0;
+	let mut pass = 0;
+	while pass < 2 {
+		let mut i = 0;
+		while i < N {
+			let (name, val, expr) = names[i];
+			if is_const_alias(expr) == (pass == 1) {
+				sorted[count] = (name, val);
+				count += 1;
+			}
+			i += 1;
+		}
+		pass += 1;
+	}
+	sorted
+}
+
+/// Tells whether the value expression is just `Self::NAME.0`.
+#[must_use]
+const fn is_const_alias(expr: &str) -> bool {
+	let bytes = expr.as_bytes();
+	let prefix = b"Self::";
+	if bytes.len() <= prefix.len() + 2 {
+		return false;
+	}
+	let mut i = 0;
+	while i < prefix.len() {
+		if bytes[i] != prefix[i] {
+			return false;
+		}
+		i += 1;
+	}
+	while i < bytes.len() - 2 {
+		if !(bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+			return false;
+		}
+		i += 1;
+	}
+	bytes[i] == b'.' && bytes[i + 1] == b'0'
+}
+
+/// Breaks down the value of a constant into the names of its values, returning
+/// also the remaining bits which couldn't be named, masked to the size of the
+/// underlying type.
+///
+/// An ordinary constant yields at most one name, while a bitflag constant
+/// yields the fewest names which compose the value, the larger ones first.
+/// Among equal values, the one which comes first in `names` is used.
+#[must_use]
+pub(crate) fn const_names_of<'a>(
+	names: &[(&'a str, u64)],
+	type_size: usize,
+	val: u64,
+	is_bitflag: bool,
+) -> (Vec<&'a str>, u64)
+{
+	let mask = u64::MAX >> (64 - type_size * 8);
+	let val = val & mask;
+	if let Some((name, _)) = names.iter().find(|(_, v)| *v & mask == val) {
+		return (vec![name], 0); // exact match, including zero
+	}
+	if !is_bitflag || val == 0 {
+		return (Vec::new(), val);
+	}
+
+	let mut parts = names.iter()
+		.map(|(name, v)| (*name, *v & mask))
+		.filter(|(_, v)| *v != 0 && *v & !val == 0)
+		.collect::<Vec<_>>();
+	parts.sort_by_key(|(_, v)| std::cmp::Reverse(v.count_ones())); // stable, keeps declaration order
+
+	let mut found = Vec::new();
+	let mut remaining = val;
+	for (name, v) in parts.iter() {
+		if *v & remaining == *v {
+			found.push(*name);
+			remaining &= !*v;
+		}
+	}
+	(found, remaining)
+}
+
+/// Parses a constant from a string like `"WS::CHILD | WS::VISIBLE"`, returning
+/// the value masked to the size of the underlying type. Names may be prefixed
+/// with `co::` and with the type name; numbers in decimal or hexadecimal
+/// (`0x`) are also accepted. Ordinary constants accept a single term.
+pub(crate) fn const_parse(
+	type_name: &str,
+	names: &[(&str, u64)],
+	type_size: usize,
+	s: &str,
+	is_bitflag: bool,
+) -> SysResult<u64>
+{
+	let mask = u64::MAX >> (64 - type_size * 8);
+	let terms = s.split('|').map(|t| t.trim()).collect::<Vec<_>>();
+	if terms.iter().any(|t| t.is_empty()) || (!is_bitflag && terms.len() > 1) {
+		return Err(co::ERROR::INVALID_DATA);
+	}
+
+	let mut val = 0u64;
+	for term in terms.iter() {
+		let term = term.strip_prefix("co::").unwrap_or(term);
+		let term = term.strip_prefix(type_name)
+			.and_then(|t| t.strip_prefix("::"))
+			.unwrap_or(term);
+
+		val |= if term.starts_with(|ch: char| ch.is_ascii_digit() || ch == '-') {
+			let num = match term.strip_prefix("0x").or_else(|| term.strip_prefix("0X")) {
+				Some(hex) => u64::from_str_radix(hex, 16).ok(),
+				None => term.parse::<i64>().ok().map(|n| n as u64),
+			}.ok_or(co::ERROR::INVALID_DATA)?;
+			let sign_bits = num | mask; // negative values are sign-extended
+			if num & !mask != 0 && sign_bits != u64::MAX {
+				return Err(co::ERROR::INVALID_DATA); // doesn't fit the type
+			}
+			num & mask
+		} else {
+			names.iter()
+				.find(|(name, _)| *name == term)
+				.map(|(_, v)| *v & mask)
+				.ok_or(co::ERROR::INVALID_DATA)?
+		};
+	}
+	Ok(val)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn aliases_last() {
+		assert!(is_const_alias("Self::OVERLAPPEDWINDOW.0"));
+		assert!(!is_const_alias("Self::OVERLAPPED.0 | Self::CAPTION.0"));
+		assert!(!is_const_alias("0x0001_0000"));
+		assert!(!is_const_alias("Self::"));
+
+		let names = const_aliases_last([
+			("TILED", 0, "Self::OVERLAPPED.0"),
+			("OVERLAPPED", 0, "0x0000_0000"),
+			("SIZEBOX", 4, "Self::THICKFRAME.0"),
+			("THICKFRAME", 4, "0x0000_0004"),
+			("BORDER", 8, "0x0000_0008"),
+		]);
+		assert_eq!(names, [("OVERLAPPED", 0), ("THICKFRAME", 4), ("BORDER", 8), ("TILED", 0), ("SIZEBOX", 4)]);
+	}
+
+	#[test]
+	fn names_of() {
+		let names = [("A", 0b0001), ("B", 0b0010), ("AB", 0b0011), ("C", 0b0100), ("NONE", 0)];
+		assert_eq!(const_names_of(&names, 1, 0b0011, true), (vec!["AB"], 0));
+		assert_eq!(const_names_of(&names, 1, 0b0111, true), (vec!["AB", "C"], 0));
+		assert_eq!(const_names_of(&names, 1, 0b1101, true), (vec!["A", "C"], 0b1000));
+		assert_eq!(const_names_of(&names, 1, 0, true), (vec!["NONE"], 0));
+		assert_eq!(const_names_of(&names, 1, 0b0101, false), (vec![], 0b0101));
+	}
+}
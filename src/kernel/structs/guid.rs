@@ -1,9 +1,35 @@
+use crate::co;
+
 /// [`GUID`](https://learn.microsoft.com/en-us/windows/win32/api/guiddef/ns-guiddef-guid)
 /// struct.
 ///
 /// The [`Default`](std::default::Default) implementation returns `GUID::NULL`
 /// (all zeros). To create a new random `GUID`, use
-/// [`CoCreateGuid`](crate::CoCreateGuid).
+/// [`CoCreateGuid`](crate::CoCreateGuid); a name-based `GUID` can be created
+/// with [`new_v5`](crate::GUID::new_v5).
+///
+/// A `GUID` can be parsed from a string with [`FromStr`](std::str::FromStr)
+/// or [`TryFrom`](std::convert::TryFrom), which accept braces, optional
+/// hyphens and any letter case. It can be formatted as:
+///
+/// * `{}` or `{:x}` – `43826d1e-e718-42ee-bc55-a1e261c37bfe`;
+/// * `{:X}` – `43826D1E-E718-42EE-BC55-A1E261C37BFE`;
+/// * `{:#}` or `{:#x}` – `{43826d1e-e718-42ee-bc55-a1e261c37bfe}`;
+/// * `{:#X}` – `{43826D1E-E718-42EE-BC55-A1E261C37BFE}`, as found in the
+///   registry.
+///
+/// # Examples
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*};
+///
+/// let g1: w::GUID = "43826d1e-e718-42ee-bc55-a1e261c37bfe".parse()?;
+/// let g2 = w::GUID::try_from("{43826D1E-E718-42EE-BC55-A1E261C37BFE}")?;
+/// assert_eq!(g1, g2);
+///
+/// println!("{:#X}", g1);
+/// # w::SysResult::Ok(())
+/// ```
 #[repr(C)]
 #[derive(Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct GUID {
@@ -15,11 +41,7 @@ pub struct GUID {
 
 impl std::fmt::Display for GUID {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
-			self.data1, self.data2, self.data3,
-			self.data4.swap_bytes() >> 48,
-			self.data4.swap_bytes() & 0x0000_ffff_ffff_ffff,
-		)
+		self.fmt_hex(f, false)
 	}
 }
 impl std::fmt::Debug for GUID {
@@ -27,6 +49,32 @@ impl std::fmt::Debug for GUID {
 		std::fmt::Display::fmt(self, f)
 	}
 }
+impl std::fmt::LowerHex for GUID {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		self.fmt_hex(f, false)
+	}
+}
+impl std::fmt::UpperHex for GUID {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		self.fmt_hex(f, true)
+	}
+}
+
+impl std::str::FromStr for GUID {
+	type Err = co::ERROR;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::parse(s).ok_or(co::ERROR::INVALID_DATA)
+	}
+}
+
+impl TryFrom<&str> for GUID {
+	type Error = co::ERROR;
+
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		value.parse()
+	}
+}
 
 impl GUID {
 	/// Creates a new `GUID` from a representative hex string, which can be
@@ -34,7 +82,9 @@ impl GUID {
 	///
 	/// # Panics
 	///
-	/// Panics if the string has an invalid format.
+	/// Panics if the string has an invalid format. To parse strings which may
+	/// be malformed, or which are enclosed in braces, use
+	/// [`FromStr`](std::str::FromStr) instead.
 	///
 	/// # Examples
 	///
@@ -66,6 +116,161 @@ impl GUID {
 		}
 	}
 
+	/// Parses a `GUID` from a string, which can be enclosed in braces, and
+	/// whose hyphens are optional. Both lowercase and uppercase hex digits are
+	/// accepted.
+	#[must_use]
+	fn parse(s: &str) -> Option<Self> {
+		let s = s.trim();
+		let s = match s.strip_prefix('{') {
+			Some(inner) => inner.strip_suffix('}')?,
+			None => s,
+		};
+
+		let digits = match s.len() {
+			36 => {
+				let bytes = s.as_bytes();
+				if [8, 13, 18, 23].iter().any(|idx| bytes[*idx] != b'-') {
+					return None;
+				}
+				s.split('-').collect::<String>()
+			},
+			32 => s.to_owned(),
+			_ => return None,
+		};
+		if digits.len() != 32 || !digits.bytes().all(|ch| ch.is_ascii_hexdigit()) {
+			return None;
+		}
+
+		let num = u128::from_str_radix(&digits, 16).ok()?;
+		Some(Self::from_rfc_bytes(num.to_be_bytes()))
+	}
+
+	/// Creates a `GUID` from its 16 bytes, in the mixed-endian layout used by
+	/// Windows: the first three fields are little-endian, and the last 8 bytes
+	/// are stored as they are.
+	///
+	/// This is the layout found in binary registry values and in files written
+	/// by Windows applications.
+	#[must_use]
+	pub const fn from_bytes(bytes: [u8; 16]) -> Self {
+		let b = bytes;
+		Self {
+			data1: u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+			data2: u16::from_le_bytes([b[4], b[5]]),
+			data3: u16::from_le_bytes([b[6], b[7]]),
+			data4: u64::from_le_bytes([b[8], b[9], b[10], b[11],
+				b[12], b[13], b[14], b[15]]),
+		}
+	}
+
+	/// Returns the 16 bytes of the `GUID`, in the mixed-endian layout used by
+	/// Windows; the opposite of [`from_bytes`](crate::GUID::from_bytes).
+	#[must_use]
+	pub const fn to_bytes(&self) -> [u8; 16] {
+		let d1 = self.data1.to_le_bytes();
+		let d2 = self.data2.to_le_bytes();
+		let d3 = self.data3.to_le_bytes();
+		let d4 = self.data4.to_le_bytes();
+		[d1[0], d1[1], d1[2], d1[3], d2[0], d2[1], d3[0], d3[1],
+			d4[0], d4[1], d4[2], d4[3], d4[4], d4[5], d4[6], d4[7]]
+	}
+
+	/// Creates a name-based `GUID`, version 5, as specified by
+	/// [RFC 4122](https://www.rfc-editor.org/rfc/rfc4122#section-4.3), which
+	/// hashes a namespace `GUID` and a name with SHA-1. The same namespace and
+	/// name always yield the same `GUID`.
+	///
+	/// Standard namespaces are available as
+	/// [`NAMESPACE_DNS`](crate::GUID::NAMESPACE_DNS),
+	/// [`NAMESPACE_URL`](crate::GUID::NAMESPACE_URL),
+	/// [`NAMESPACE_OID`](crate::GUID::NAMESPACE_OID) and
+	/// [`NAMESPACE_X500`](crate::GUID::NAMESPACE_X500).
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*};
+	///
+	/// let g = w::GUID::new_v5(&w::GUID::NAMESPACE_DNS, "python.org".as_bytes());
+	/// assert_eq!(g.to_string(), "886313e1-3b8a-5372-9b90-0c9aee199e5d");
+	/// ```
+	#[must_use]
+	pub fn new_v5(namespace: &GUID, name: &[u8]) -> Self {
+		let mut data = namespace.to_rfc_bytes().to_vec();
+		data.extend_from_slice(name);
+		let hash = sha1(&data);
+
+		let mut bytes = [0u8; 16];
+		bytes.copy_from_slice(&hash[..16]);
+		bytes[6] = (bytes[6] & 0x0f) | 0x50; // version 5
+		bytes[8] = (bytes[8] & 0x3f) | 0x80; // RFC 4122 variant
+		Self::from_rfc_bytes(bytes)
+	}
+
+	/// Namespace for fully-qualified domain names, to be used with
+	/// [`new_v5`](crate::GUID::new_v5).
+	pub const NAMESPACE_DNS: Self = Self::new("6ba7b810-9dad-11d1-80b4-00c04fd430c8");
+	/// Namespace for URLs, to be used with
+	/// [`new_v5`](crate::GUID::new_v5).
+	pub const NAMESPACE_URL: Self = Self::new("6ba7b811-9dad-11d1-80b4-00c04fd430c8");
+	/// Namespace for ISO OIDs, to be used with
+	/// [`new_v5`](crate::GUID::new_v5).
+	pub const NAMESPACE_OID: Self = Self::new("6ba7b812-9dad-11d1-80b4-00c04fd430c8");
+	/// Namespace for X.500 DNs, to be used with
+	/// [`new_v5`](crate::GUID::new_v5).
+	pub const NAMESPACE_X500: Self = Self::new("6ba7b814-9dad-11d1-80b4-00c04fd430c8");
+
+	/// Creates a `GUID` from its 16 bytes in the order they're written, which
+	/// is the big-endian layout of RFC 4122.
+	#[must_use]
+	const fn from_rfc_bytes(b: [u8; 16]) -> Self {
+		Self {
+			data1: u32::from_be_bytes([b[0], b[1], b[2], b[3]]),
+			data2: u16::from_be_bytes([b[4], b[5]]),
+			data3: u16::from_be_bytes([b[6], b[7]]),
+			data4: u64::from_le_bytes([b[8], b[9], b[10], b[11],
+				b[12], b[13], b[14], b[15]]),
+		}
+	}
+
+	/// Returns the 16 bytes in the order they're written, which is the
+	/// big-endian layout of RFC 4122.
+	#[must_use]
+	const fn to_rfc_bytes(self) -> [u8; 16] {
+		let d1 = self.data1.to_be_bytes();
+		let d2 = self.data2.to_be_bytes();
+		let d3 = self.data3.to_be_bytes();
+		let d4 = self.data4.to_le_bytes();
+		[d1[0], d1[1], d1[2], d1[3], d2[0], d2[1], d3[0], d3[1],
+			d4[0], d4[1], d4[2], d4[3], d4[4], d4[5], d4[6], d4[7]]
+	}
+
+	fn fmt_hex(&self,
+		f: &mut std::fmt::Formatter<'_>,
+		upper: bool,
+	) -> std::fmt::Result
+	{
+		let b = self.to_rfc_bytes();
+		if f.alternate() {
+			write!(f, "{{")?;
+		}
+		for (idx, byte) in b.iter().enumerate() {
+			if idx == 4 || idx == 6 || idx == 8 || idx == 10 {
+				write!(f, "-")?;
+			}
+			if upper {
+				write!(f, "{:02X}", byte)?;
+			} else {
+				write!(f, "{:02x}", byte)?;
+			}
+		}
+		if f.alternate() {
+			write!(f, "}}")?;
+		}
+		Ok(())
+	}
+
 	#[must_use]
 	const fn parse_block<const N: usize>(chars: [u8; N]) -> u64 {
 		let mut res: u64 = 0;
@@ -101,3 +306,158 @@ impl GUID {
 		}
 	}
 }
+
+/// Computes the SHA-1 hash of the data, used by
+/// [`GUID::new_v5`](crate::GUID::new_v5).
+#[must_use]
+fn sha1(data: &[u8]) -> [u8; 20] {
+	let mut h: [u32; 5] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476, 0xc3d2_e1f0];
+
+	let mut msg = data.to_vec();
+	msg.push(0x80);
+	while msg.len() % 64 != 56 {
+		msg.push(0x00);
+	}
+	msg.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+	for chunk in msg.chunks_exact(64) {
+		let mut w = [0u32; 80];
+		for (i, word) in chunk.chunks_exact(4).enumerate() {
+			w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+		}
+		for i in 16..80 {
+			w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+		}
+
+		let [mut a, mut b, mut c, mut d, mut e] = h;
+		for (i, wi) in w.iter().enumerate() {
+			let (f, k) = match i {
+				0..=19 => ((b & c) | (!b & d), 0x5a82_7999),
+				20..=39 => (b ^ c ^ d, 0x6ed9_eba1),
+				40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1b_bcdc),
+				_ => (b ^ c ^ d, 0xca62_c1d6),
+			};
+			let temp = a.rotate_left(5)
+				.wrapping_add(f)
+				.wrapping_add(e)
+				.wrapping_add(k)
+				.wrapping_add(*wi);
+			e = d;
+			d = c;
+			c = b.rotate_left(30);
+			b = a;
+			a = temp;
+		}
+
+		for (hi, v) in h.iter_mut().zip([a, b, c, d, e]) {
+			*hi = hi.wrapping_add(v);
+		}
+	}
+
+	let mut digest = [0u8; 20];
+	for (i, hi) in h.iter().enumerate() {
+		digest[i * 4..i * 4 + 4].copy_from_slice(&hi.to_be_bytes());
+	}
+	digest
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const STR: &str = "43826d1e-e718-42ee-bc55-a1e261c37bfe";
+
+	#[test]
+	fn parse() {
+		let g = GUID::new(STR);
+		for s in [
+			STR,
+			"43826D1E-E718-42EE-BC55-A1E261C37BFE",
+			"{43826d1e-e718-42ee-bc55-a1e261c37bfe}",
+			"43826d1ee71842eebc55a1e261c37bfe",
+			"{43826D1EE71842EEBC55A1E261C37BFE}",
+			"  43826d1e-e718-42ee-bc55-a1e261c37bfe\r\n",
+		] {
+			assert_eq!(s.parse::<GUID>(), Ok(g), "{}", s);
+			assert_eq!(GUID::try_from(s), Ok(g), "{}", s);
+		}
+	}
+
+	#[test]
+	fn parse_malformed() {
+		for s in [
+			"",
+			"{}",
+			"43826d1e-e718-42ee-bc55-a1e261c37bf", // too short
+			"43826d1e-e718-42ee-bc55-a1e261c37bfe0", // too long
+			"{43826d1e-e718-42ee-bc55-a1e261c37bfe", // unbalanced braces
+			"43826d1e-e718-42ee-bc55-a1e261c37bfe}",
+			"43826d1ee-718-42ee-bc55-a1e261c37bfe", // misplaced hyphen
+			"43826d1e_e718_42ee_bc55_a1e261c37bfe",
+			"43826d1e-e718-42ee-bc55-a1e261c37bfg", // not hex
+			"+3826d1ee71842eebc55a1e261c37bfe", // sign accepted by from_str_radix
+			"43826d1e-e718-42ee-bc55-a1e261c37bé",
+		] {
+			assert_eq!(s.parse::<GUID>(), Err(co::ERROR::INVALID_DATA), "{}", s);
+		}
+	}
+
+	#[test]
+	fn format() {
+		let g = GUID::new(STR);
+		assert_eq!(g.to_string(), STR);
+		assert_eq!(format!("{:?}", g), STR);
+		assert_eq!(format!("{:x}", g), STR);
+		assert_eq!(format!("{:X}", g), "43826D1E-E718-42EE-BC55-A1E261C37BFE");
+		assert_eq!(format!("{:#}", g), "{43826d1e-e718-42ee-bc55-a1e261c37bfe}");
+		assert_eq!(format!("{:#x}", g), "{43826d1e-e718-42ee-bc55-a1e261c37bfe}");
+		assert_eq!(format!("{:#X}", g), "{43826D1E-E718-42EE-BC55-A1E261C37BFE}");
+		assert_eq!(GUID::default().to_string(), "00000000-0000-0000-0000-000000000000");
+	}
+
+	#[test]
+	fn bytes() {
+		let g = GUID::new(STR);
+		let bytes = [
+			0x1e, 0x6d, 0x82, 0x43, // data1, little-endian
+			0x18, 0xe7, // data2, little-endian
+			0xee, 0x42, // data3, little-endian
+			0xbc, 0x55, 0xa1, 0xe2, 0x61, 0xc3, 0x7b, 0xfe, // data4, as written
+		];
+		assert_eq!(g.to_bytes(), bytes);
+		assert_eq!(GUID::from_bytes(bytes), g);
+		assert_eq!(g.to_rfc_bytes(), [
+			0x43, 0x82, 0x6d, 0x1e, 0xe7, 0x18, 0x42, 0xee,
+			0xbc, 0x55, 0xa1, 0xe2, 0x61, 0xc3, 0x7b, 0xfe,
+		]);
+		assert_eq!(GUID::from_rfc_bytes(g.to_rfc_bytes()), g);
+
+		// Same layout as the struct in memory.
+		let raw = unsafe { std::mem::transmute::<GUID, [u8; 16]>(g) };
+		assert_eq!(raw, bytes);
+	}
+
+	#[test]
+	fn v5() {
+		assert_eq!(
+			GUID::new_v5(&GUID::NAMESPACE_DNS, "python.org".as_bytes()).to_string(),
+			"886313e1-3b8a-5372-9b90-0c9aee199e5d",
+		);
+		assert_eq!(
+			GUID::new_v5(&GUID::NAMESPACE_URL, "https://example.com/".as_bytes()).to_string(),
+			"dd2c1780-811a-5296-81c5-178a0ef488bc",
+		);
+		assert_ne!(
+			GUID::new_v5(&GUID::NAMESPACE_DNS, "python.org".as_bytes()),
+			GUID::new_v5(&GUID::NAMESPACE_URL, "python.org".as_bytes()),
+		);
+	}
+
+	#[test]
+	fn sha1_vectors() {
+		let hex = |d: [u8; 20]| d.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+		assert_eq!(hex(sha1(b"")), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+		assert_eq!(hex(sha1(b"abc")), "a9993e364706816aba3e25717850c26c9cd0d89d");
+		assert_eq!(hex(sha1(&[b'a'; 1000])), "291e9a6c66994949b57ba5e650361e98fc36b1ba"); // many blocks
+	}
+}
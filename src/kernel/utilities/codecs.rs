@@ -0,0 +1,640 @@
+//! Pure-Rust converters between raw bytes and UTF-16, one pair of functions
+//! for each [`Encoding`](crate::Encoding) variant.
+
+use crate::co;
+use crate::decl::*;
+
+/// Decodes the raw bytes into UTF-16 chars, according to the given encoding.
+/// The BOM, if any, must be already skipped.
+pub(crate) fn decode(encoding: Encoding, data: &[u8]) -> SysResult<Vec<u16>> {
+	match encoding {
+		Encoding::Unknown => Err(co::ERROR::INVALID_PARAMETER),
+		Encoding::Ansi => decode_ansi(data),
+		Encoding::Win1252 => decode_win1252(data),
+		Encoding::Utf8 => decode_utf8(data),
+		Encoding::Utf16be => decode_utf16(data, true),
+		Encoding::Utf16le => decode_utf16(data, false),
+		Encoding::Utf32be => decode_utf32(data, true),
+		Encoding::Utf32le => decode_utf32(data, false),
+		Encoding::Scsu => decode_scsu(data),
+		Encoding::Bocu1 => decode_bocu1(data),
+	}
+}
+
+/// Encodes the UTF-16 chars into raw bytes, according to the given encoding.
+/// No BOM is written.
+pub(crate) fn encode(encoding: Encoding, wchars: &[u16]) -> SysResult<Vec<u8>> {
+	match encoding {
+		Encoding::Unknown => Err(co::ERROR::INVALID_PARAMETER),
+		Encoding::Ansi => encode_ansi(wchars),
+		Encoding::Win1252 => encode_win1252(wchars),
+		Encoding::Utf8 => encode_utf8(wchars),
+		Encoding::Utf16be => Ok(encode_utf16(wchars, true)),
+		Encoding::Utf16le => Ok(encode_utf16(wchars, false)),
+		Encoding::Utf32be => encode_utf32(wchars, true),
+		Encoding::Utf32le => encode_utf32(wchars, false),
+		Encoding::Scsu => encode_scsu(wchars),
+		Encoding::Bocu1 => encode_bocu1(wchars),
+	}
+}
+
+/// Converts UTF-16 chars into Unicode code points, failing on unpaired
+/// surrogates.
+fn code_points(wchars: &[u16]) -> SysResult<Vec<u32>> {
+	char::decode_utf16(wchars.iter().copied())
+		.map(|ch| ch.map(|ch| ch as u32).map_err(|_| co::ERROR::NO_UNICODE_TRANSLATION))
+		.collect()
+}
+
+/// Appends a Unicode code point as UTF-16, failing if it's not valid.
+fn push_code_point(dest: &mut Vec<u16>, cp: u32) -> SysResult<()> {
+	let ch = char::from_u32(cp).ok_or(co::ERROR::NO_UNICODE_TRANSLATION)?;
+	let mut buf = [0u16; 2];
+	dest.extend_from_slice(ch.encode_utf16(&mut buf));
+	Ok(())
+}
+
+//------------------------------------------------------------------------------
+
+fn decode_ansi(data: &[u8]) -> SysResult<Vec<u16>> {
+	Ok(data.iter()
+		.map(|ch| *ch as u16) // raw u8 to u16 conversion
+		.collect())
+}
+
+fn encode_ansi(wchars: &[u16]) -> SysResult<Vec<u8>> {
+	wchars.iter()
+		.map(|ch| if *ch <= 0x7f {
+			Ok(*ch as u8)
+		} else {
+			Err(co::ERROR::NO_UNICODE_TRANSLATION)
+		})
+		.collect()
+}
+
+//------------------------------------------------------------------------------
+
+/// Windows-1252 chars in the 0x80-0x9f range, which differ from ISO-8859-1.
+/// Undefined positions map to the C1 control char with the same value, the
+/// same way `MultiByteToWideChar` does.
+const WIN1252_80_9F: [u16; 32] = [
+	0x20ac, 0x0081, 0x201a, 0x0192, 0x201e, 0x2026, 0x2020, 0x2021,
+	0x02c6, 0x2030, 0x0160, 0x2039, 0x0152, 0x008d, 0x017d, 0x008f,
+	0x0090, 0x2018, 0x2019, 0x201c, 0x201d, 0x2022, 0x2013, 0x2014,
+	0x02dc, 0x2122, 0x0161, 0x203a, 0x0153, 0x009d, 0x017e, 0x0178,
+];
+
+fn decode_win1252(data: &[u8]) -> SysResult<Vec<u16>> {
+	Ok(data.iter()
+		.map(|ch| match *ch {
+			0x80..=0x9f => WIN1252_80_9F[(*ch - 0x80) as usize],
+			_ => *ch as u16,
+		})
+		.collect())
+}
+
+fn encode_win1252(wchars: &[u16]) -> SysResult<Vec<u8>> {
+	wchars.iter()
+		.map(|ch| match *ch {
+			0x0000..=0x007f | 0x00a0..=0x00ff => Ok(*ch as u8),
+			_ => WIN1252_80_9F.iter()
+				.position(|w| *w == *ch)
+				.map(|idx| 0x80 + idx as u8)
+				.ok_or(co::ERROR::NO_UNICODE_TRANSLATION),
+		})
+		.collect()
+}
+
+//------------------------------------------------------------------------------
+
+fn decode_utf8(data: &[u8]) -> SysResult<Vec<u16>> {
+	std::str::from_utf8(data)
+		.map(|s| s.encode_utf16().collect())
+		.map_err(|_| co::ERROR::NO_UNICODE_TRANSLATION)
+}
+
+fn encode_utf8(wchars: &[u16]) -> SysResult<Vec<u8>> {
+	String::from_utf16(wchars)
+		.map(|s| s.into_bytes())
+		.map_err(|_| co::ERROR::NO_UNICODE_TRANSLATION)
+}
+
+//------------------------------------------------------------------------------
+
+fn decode_utf16(data: &[u8], is_big_endian: bool) -> SysResult<Vec<u16>> {
+	Ok(data.chunks_exact(2) // if odd number of bytes, discard last one
+		.map(|ch2| if is_big_endian {
+			u16::from_be_bytes([ch2[0], ch2[1]])
+		} else {
+			u16::from_le_bytes([ch2[0], ch2[1]])
+		})
+		.collect())
+}
+
+fn encode_utf16(wchars: &[u16], is_big_endian: bool) -> Vec<u8> {
+	wchars.iter()
+		.flat_map(|ch| if is_big_endian {
+			ch.to_be_bytes()
+		} else {
+			ch.to_le_bytes()
+		})
+		.collect()
+}
+
+//------------------------------------------------------------------------------
+
+fn decode_utf32(data: &[u8], is_big_endian: bool) -> SysResult<Vec<u16>> {
+	if data.len() % 4 != 0 {
+		return Err(co::ERROR::NO_UNICODE_TRANSLATION); // truncated char
+	}
+	let mut wchars = Vec::with_capacity(data.len() / 4);
+	for ch4 in data.chunks_exact(4) {
+		let ch4 = [ch4[0], ch4[1], ch4[2], ch4[3]];
+		push_code_point(&mut wchars, if is_big_endian {
+			u32::from_be_bytes(ch4)
+		} else {
+			u32::from_le_bytes(ch4)
+		})?;
+	}
+	Ok(wchars)
+}
+
+fn encode_utf32(wchars: &[u16], is_big_endian: bool) -> SysResult<Vec<u8>> {
+	Ok(code_points(wchars)?
+		.iter()
+		.flat_map(|cp| if is_big_endian {
+			cp.to_be_bytes()
+		} else {
+			cp.to_le_bytes()
+		})
+		.collect())
+}
+
+//------------------------------------------------------------------------------
+
+// SCSU, as specified in Unicode Technical Standard #6.
+// https://www.unicode.org/reports/tr6/
+
+const SCSU_SQ0: u8 = 0x01; // quote from window 0-7, single-byte mode
+const SCSU_SDX: u8 = 0x0b; // define extended window
+const SCSU_SQU: u8 = 0x0e; // quote UTF-16 char
+const SCSU_SCU: u8 = 0x0f; // change to Unicode mode
+const SCSU_SC0: u8 = 0x10; // change to window 0-7
+const SCSU_SD0: u8 = 0x18; // define window 0-7
+const SCSU_UC0: u8 = 0xe0; // change to window 0-7, Unicode mode
+const SCSU_UD0: u8 = 0xe8; // define window 0-7, Unicode mode
+const SCSU_UQU: u8 = 0xf0; // quote UTF-16 char, Unicode mode
+const SCSU_UDX: u8 = 0xf1; // define extended window, Unicode mode
+
+const SCSU_STATIC_WINDOWS: [u32; 8] = [
+	0x0000, 0x0080, 0x0100, 0x0300, 0x2000, 0x2080, 0x2100, 0x3000,
+];
+const SCSU_DYNAMIC_WINDOWS: [u32; 8] = [
+	0x0080, 0x00c0, 0x0400, 0x0600, 0x0900, 0x3040, 0x30a0, 0xff00,
+];
+
+/// Returns the window offset represented by a byte following SDn or UDn.
+fn scsu_window_offset(b: u8) -> SysResult<u32> {
+	match b {
+		0x01..=0x67 => Ok(b as u32 * 0x80),
+		0x68..=0xa7 => Ok(b as u32 * 0x80 + 0xac00),
+		0xf9 => Ok(0x00c0),
+		0xfa => Ok(0x0250),
+		0xfb => Ok(0x0370),
+		0xfc => Ok(0x0530),
+		0xfd => Ok(0x3040),
+		0xfe => Ok(0x30a0),
+		0xff => Ok(0xff60),
+		_ => Err(co::ERROR::NO_UNICODE_TRANSLATION), // reserved
+	}
+}
+
+/// Returns the byte which defines a window containing the given code point,
+/// if any, along with the window offset.
+fn scsu_window_byte(cp: u32) -> Option<(u8, u32)> {
+	const SPECIAL: [(u8, u32); 7] = [
+		(0xf9, 0x00c0), (0xfa, 0x0250), (0xfb, 0x0370), (0xfc, 0x0530),
+		(0xfd, 0x3040), (0xfe, 0x30a0), (0xff, 0xff60),
+	];
+	if let Some(sp) = SPECIAL.iter().find(|(_, off)| cp >= *off && cp < *off + 0x80) {
+		Some(*sp)
+	} else if (0x0080..0x3400).contains(&cp) {
+		Some(((cp / 0x80) as u8, cp & !0x7f))
+	} else if (0xe000..0x10000).contains(&cp) {
+		Some((((cp - 0xac00) / 0x80) as u8, cp & !0x7f))
+	} else {
+		None
+	}
+}
+
+fn decode_scsu(data: &[u8]) -> SysResult<Vec<u16>> {
+	let mut dynamic = SCSU_DYNAMIC_WINDOWS;
+	let mut active = 0usize;
+	let mut unicode_mode = false;
+	let mut wchars = Vec::with_capacity(data.len());
+
+	let mut bytes = data.iter().copied();
+	let mut next = || bytes.next().ok_or(co::ERROR::NO_UNICODE_TRANSLATION);
+
+	while let Ok(b) = next() {
+		if unicode_mode {
+			match b {
+				0xe0..=0xe7 => {
+					active = (b - SCSU_UC0) as usize;
+					unicode_mode = false;
+				},
+				0xe8..=0xef => {
+					active = (b - SCSU_UD0) as usize;
+					dynamic[active] = scsu_window_offset(next()?)?;
+					unicode_mode = false;
+				},
+				SCSU_UQU => {
+					wchars.push(u16::from_be_bytes([next()?, next()?]));
+				},
+				SCSU_UDX => {
+					let (hi, lo) = (next()?, next()?);
+					active = (hi >> 5) as usize;
+					dynamic[active] = 0x10000 + (((((hi & 0x1f) as u32) << 8) | lo as u32) << 7);
+					unicode_mode = false;
+				},
+				0xf2 => return Err(co::ERROR::NO_UNICODE_TRANSLATION), // reserved
+				_ => wchars.push(u16::from_be_bytes([b, next()?])),
+			}
+		} else {
+			match b {
+				0x00 | 0x09 | 0x0a | 0x0d | 0x20..=0x7f => wchars.push(b as u16),
+				0x80..=0xff => push_code_point(&mut wchars, dynamic[active] + (b - 0x80) as u32)?,
+				0x01..=0x08 => {
+					let win = (b - SCSU_SQ0) as usize;
+					let q = next()?;
+					push_code_point(&mut wchars, if q < 0x80 {
+						SCSU_STATIC_WINDOWS[win] + q as u32
+					} else {
+						dynamic[win] + (q - 0x80) as u32
+					})?;
+				},
+				SCSU_SDX => {
+					let (hi, lo) = (next()?, next()?);
+					active = (hi >> 5) as usize;
+					dynamic[active] = 0x10000 + (((((hi & 0x1f) as u32) << 8) | lo as u32) << 7);
+				},
+				SCSU_SQU => {
+					wchars.push(u16::from_be_bytes([next()?, next()?]));
+				},
+				SCSU_SCU => unicode_mode = true,
+				0x10..=0x17 => active = (b - SCSU_SC0) as usize,
+				0x18..=0x1f => {
+					active = (b - SCSU_SD0) as usize;
+					dynamic[active] = scsu_window_offset(next()?)?;
+				},
+				_ => return Err(co::ERROR::NO_UNICODE_TRANSLATION), // 0x0c is reserved
+			}
+		}
+	}
+	Ok(wchars)
+}
+
+fn encode_scsu(wchars: &[u16]) -> SysResult<Vec<u8>> {
+	let cps = code_points(wchars)?;
+	let mut dynamic = SCSU_DYNAMIC_WINDOWS;
+	let mut active = 0usize;
+	let mut next_redefined = 0usize; // round-robin choice of window to redefine
+	let mut unicode_mode = false;
+	let mut out = Vec::with_capacity(cps.len());
+
+	let in_window = |off: u32, cp: u32| cp >= off && cp < off + 0x80;
+	let is_passthrough = |cp: u32| matches!(cp, 0x00 | 0x09 | 0x0a | 0x0d | 0x20..=0x7f);
+
+	for (idx, cp) in cps.iter().copied().enumerate() {
+		let next_cp = cps.get(idx + 1).copied();
+
+		if unicode_mode {
+			if cp < 0x80 || dynamic.iter().any(|off| in_window(*off, cp)) {
+				let win = dynamic.iter().position(|off| in_window(*off, cp)).unwrap_or(active);
+				out.push(SCSU_UC0 + win as u8);
+				active = win;
+				unicode_mode = false; // and fall through to single-byte mode
+			} else {
+				let mut buf = [0u16; 2];
+				for w in char::from_u32(cp).unwrap().encode_utf16(&mut buf) {
+					let [hi, lo] = w.to_be_bytes();
+					if (SCSU_UC0..=0xf2).contains(&hi) {
+						out.push(SCSU_UQU); // high byte would be a tag
+					}
+					out.extend_from_slice(&[hi, lo]);
+				}
+				continue;
+			}
+		}
+
+		if is_passthrough(cp) {
+			out.push(cp as u8);
+		} else if cp < 0x20 {
+			out.extend_from_slice(&[SCSU_SQ0, cp as u8]); // static window 0
+		} else if in_window(dynamic[active], cp) {
+			out.push(0x80 + (cp - dynamic[active]) as u8);
+		} else if let Some(win) = dynamic.iter().position(|off| in_window(*off, cp)) {
+			if next_cp.is_some_and(|n| in_window(dynamic[win], n)) {
+				out.push(SCSU_SC0 + win as u8);
+				active = win;
+				out.push(0x80 + (cp - dynamic[win]) as u8);
+			} else {
+				out.extend_from_slice(&[SCSU_SQ0 + win as u8, 0x80 + (cp - dynamic[win]) as u8]);
+			}
+		} else if let Some(win) = SCSU_STATIC_WINDOWS.iter().position(|off| in_window(*off, cp)) {
+			out.extend_from_slice(&[SCSU_SQ0 + win as u8, (cp - SCSU_STATIC_WINDOWS[win]) as u8]);
+		} else if let Some((def_byte, off)) = scsu_window_byte(cp) {
+			let win = next_redefined;
+			next_redefined = (next_redefined + 1) % 8;
+			out.extend_from_slice(&[SCSU_SD0 + win as u8, def_byte]);
+			dynamic[win] = off;
+			active = win;
+			out.push(0x80 + (cp - off) as u8);
+		} else if cp >= 0x10000 {
+			let win = next_redefined;
+			next_redefined = (next_redefined + 1) % 8;
+			let off_idx = (cp - 0x10000) >> 7; // 13 bits
+			out.extend_from_slice(&[
+				SCSU_SDX,
+				((win as u32) << 5 | (off_idx >> 8)) as u8,
+				(off_idx & 0xff) as u8,
+			]);
+			dynamic[win] = 0x10000 + (off_idx << 7);
+			active = win;
+			out.push(0x80 + (cp - dynamic[win]) as u8);
+		} else {
+			let [hi, lo] = (cp as u16).to_be_bytes();
+			if next_cp.is_some_and(|n| !is_passthrough(n) && scsu_window_byte(n).is_none()) {
+				out.push(SCSU_SCU); // a run of chars without windows, like CJK
+				unicode_mode = true;
+				if (SCSU_UC0..=0xf2).contains(&hi) {
+					out.push(SCSU_UQU);
+				}
+				out.extend_from_slice(&[hi, lo]);
+			} else {
+				out.extend_from_slice(&[SCSU_SQU, hi, lo]);
+			}
+		}
+	}
+	Ok(out)
+}
+
+//------------------------------------------------------------------------------
+
+// BOCU-1, as specified in Unicode Technical Note #6.
+// https://www.unicode.org/notes/tn6/
+
+const BOCU1_ASCII_PREV: i32 = 0x40;
+const BOCU1_MIN: i32 = 0x21;
+const BOCU1_MIDDLE: i32 = 0x90;
+const BOCU1_RESET: u8 = 0xff;
+const BOCU1_TRAIL_CONTROLS_COUNT: i32 = 20;
+const BOCU1_TRAIL_BYTE_OFFSET: i32 = BOCU1_MIN - BOCU1_TRAIL_CONTROLS_COUNT;
+const BOCU1_TRAIL_COUNT: i32 = (0xff - BOCU1_MIN + 1) + BOCU1_TRAIL_CONTROLS_COUNT;
+const BOCU1_SINGLE: i32 = 64;
+const BOCU1_LEAD_2: i32 = 43;
+const BOCU1_LEAD_3: i32 = 3;
+const BOCU1_REACH_POS_1: i32 = BOCU1_SINGLE - 1;
+const BOCU1_REACH_NEG_1: i32 = -BOCU1_SINGLE;
+const BOCU1_REACH_POS_2: i32 = BOCU1_REACH_POS_1 + BOCU1_LEAD_2 * BOCU1_TRAIL_COUNT;
+const BOCU1_REACH_NEG_2: i32 = BOCU1_REACH_NEG_1 - BOCU1_LEAD_2 * BOCU1_TRAIL_COUNT;
+const BOCU1_REACH_POS_3: i32 = BOCU1_REACH_POS_2 + BOCU1_LEAD_3 * BOCU1_TRAIL_COUNT * BOCU1_TRAIL_COUNT;
+const BOCU1_REACH_NEG_3: i32 = BOCU1_REACH_NEG_2 - BOCU1_LEAD_3 * BOCU1_TRAIL_COUNT * BOCU1_TRAIL_COUNT;
+const BOCU1_START_POS_2: i32 = BOCU1_MIDDLE + BOCU1_REACH_POS_1 + 1;
+const BOCU1_START_POS_3: i32 = BOCU1_START_POS_2 + BOCU1_LEAD_2;
+const BOCU1_START_POS_4: i32 = BOCU1_START_POS_3 + BOCU1_LEAD_3;
+const BOCU1_START_NEG_2: i32 = BOCU1_MIDDLE + BOCU1_REACH_NEG_1;
+const BOCU1_START_NEG_3: i32 = BOCU1_START_NEG_2 - BOCU1_LEAD_2;
+const BOCU1_START_NEG_4: i32 = BOCU1_START_NEG_3 - BOCU1_LEAD_3;
+
+/// Control bytes which can be used as trail bytes, in trail value order.
+const BOCU1_TRAIL_CONTROLS: [u8; BOCU1_TRAIL_CONTROLS_COUNT as usize] = [
+	0x01, 0x02, 0x03, 0x04, 0x05, 0x06,
+	0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19,
+	0x1c, 0x1d, 0x1e, 0x1f,
+];
+
+/// Computes the next "previous" code point, which is the base for the
+/// difference of the next one.
+fn bocu1_prev(cp: i32) -> i32 {
+	if (0x3040..=0x309f).contains(&cp) {
+		0x3070 // Hiragana is not 128-aligned
+	} else if (0x4e00..=0x9fa5).contains(&cp) {
+		0x4e00 - BOCU1_REACH_NEG_2 // CJK Unihan
+	} else if (0xac00..=0xd7a3).contains(&cp) {
+		(0xd7a3 + 0xac00) / 2 // Korean Hangul
+	} else {
+		(cp & !0x7f) + BOCU1_ASCII_PREV // mostly small scripts
+	}
+}
+
+fn bocu1_trail_to_byte(t: i32) -> u8 {
+	if t >= BOCU1_TRAIL_CONTROLS_COUNT {
+		(t + BOCU1_TRAIL_BYTE_OFFSET) as u8
+	} else {
+		BOCU1_TRAIL_CONTROLS[t as usize]
+	}
+}
+
+fn bocu1_byte_to_trail(b: u8) -> Option<i32> {
+	if b as i32 >= BOCU1_MIN {
+		Some(b as i32 - BOCU1_TRAIL_BYTE_OFFSET)
+	} else {
+		BOCU1_TRAIL_CONTROLS.iter()
+			.position(|c| *c == b)
+			.map(|t| t as i32)
+	}
+}
+
+fn bocu1_pack_diff(out: &mut Vec<u8>, diff: i32) {
+	let (mut diff, lead, count) = if diff >= BOCU1_REACH_NEG_1 {
+		if diff <= BOCU1_REACH_POS_1 {
+			out.push((BOCU1_MIDDLE + diff) as u8); // single byte
+			return;
+		} else if diff <= BOCU1_REACH_POS_2 {
+			(diff - (BOCU1_REACH_POS_1 + 1), BOCU1_START_POS_2, 1)
+		} else if diff <= BOCU1_REACH_POS_3 {
+			(diff - (BOCU1_REACH_POS_2 + 1), BOCU1_START_POS_3, 2)
+		} else {
+			(diff - (BOCU1_REACH_POS_3 + 1), BOCU1_START_POS_4, 3)
+		}
+	} else if diff >= BOCU1_REACH_NEG_2 {
+		(diff - BOCU1_REACH_NEG_1, BOCU1_START_NEG_2, 1)
+	} else if diff >= BOCU1_REACH_NEG_3 {
+		(diff - BOCU1_REACH_NEG_2, BOCU1_START_NEG_3, 2)
+	} else {
+		(diff - BOCU1_REACH_NEG_3, BOCU1_START_NEG_4, 3)
+	};
+
+	let mut trails = [0u8; 3]; // computed like digits, least significant first
+	for trail in trails[..count].iter_mut() {
+		let m = diff.rem_euclid(BOCU1_TRAIL_COUNT);
+		diff = diff.div_euclid(BOCU1_TRAIL_COUNT);
+		*trail = bocu1_trail_to_byte(m);
+	}
+	out.push((lead + diff) as u8);
+	out.extend(trails[..count].iter().rev());
+}
+
+fn decode_bocu1(data: &[u8]) -> SysResult<Vec<u16>> {
+	let mut prev = BOCU1_ASCII_PREV;
+	let mut wchars = Vec::with_capacity(data.len());
+	let mut i = 0;
+
+	while i < data.len() {
+		let b = data[i];
+		i += 1;
+
+		if b <= 0x20 { // control chars and space are encoded directly
+			if b != 0x20 {
+				prev = BOCU1_ASCII_PREV;
+			}
+			wchars.push(b as u16);
+			continue;
+		} else if b == BOCU1_RESET {
+			prev = BOCU1_ASCII_PREV;
+			continue;
+		}
+
+		let lead = b as i32;
+		let (mut diff, count) = if (BOCU1_START_NEG_2..BOCU1_START_POS_2).contains(&lead) {
+			(lead - BOCU1_MIDDLE, 0) // single byte
+		} else if lead >= BOCU1_START_POS_2 {
+			if lead < BOCU1_START_POS_3 {
+				((lead - BOCU1_START_POS_2) * BOCU1_TRAIL_COUNT + BOCU1_REACH_POS_1 + 1, 1)
+			} else if lead < BOCU1_START_POS_4 {
+				((lead - BOCU1_START_POS_3) * BOCU1_TRAIL_COUNT * BOCU1_TRAIL_COUNT + BOCU1_REACH_POS_2 + 1, 2)
+			} else {
+				(BOCU1_REACH_POS_3 + 1, 3)
+			}
+		} else if lead >= BOCU1_START_NEG_3 {
+			((lead - BOCU1_START_NEG_2) * BOCU1_TRAIL_COUNT + BOCU1_REACH_NEG_1, 1)
+		} else if lead > BOCU1_MIN {
+			((lead - BOCU1_START_NEG_3) * BOCU1_TRAIL_COUNT * BOCU1_TRAIL_COUNT + BOCU1_REACH_NEG_2, 2)
+		} else {
+			(-BOCU1_TRAIL_COUNT * BOCU1_TRAIL_COUNT * BOCU1_TRAIL_COUNT + BOCU1_REACH_NEG_3, 3)
+		};
+
+		let mut multiplier = BOCU1_TRAIL_COUNT.pow(count as u32);
+		for _ in 0..count {
+			multiplier /= BOCU1_TRAIL_COUNT;
+			let t = data.get(i)
+				.and_then(|b| bocu1_byte_to_trail(*b))
+				.ok_or(co::ERROR::NO_UNICODE_TRANSLATION)?;
+			diff += t * multiplier;
+			i += 1;
+		}
+
+		let cp = prev + diff;
+		if !(0..=0x10ffff).contains(&cp) {
+			return Err(co::ERROR::NO_UNICODE_TRANSLATION);
+		}
+		push_code_point(&mut wchars, cp as u32)?;
+		prev = bocu1_prev(cp);
+	}
+	Ok(wchars)
+}
+
+fn encode_bocu1(wchars: &[u16]) -> SysResult<Vec<u8>> {
+	let mut prev = BOCU1_ASCII_PREV;
+	let mut out = Vec::with_capacity(wchars.len());
+
+	for cp in code_points(wchars)? {
+		let cp = cp as i32;
+		if cp <= 0x20 { // control chars and space are encoded directly
+			if cp != 0x20 {
+				prev = BOCU1_ASCII_PREV;
+			}
+			out.push(cp as u8);
+		} else {
+			bocu1_pack_diff(&mut out, cp - prev);
+			prev = bocu1_prev(cp);
+		}
+	}
+	Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn wchars(s: &str) -> Vec<u16> {
+		s.encode_utf16().collect()
+	}
+
+	#[test]
+	fn ansi() {
+		assert_eq!(decode(Encoding::Ansi, b"a\x00\x7f").unwrap(), [0x61, 0x00, 0x7f]);
+		assert_eq!(encode(Encoding::Ansi, &[0x61, 0x00, 0x7f]).unwrap(), b"a\x00\x7f");
+		assert_eq!(decode(Encoding::Ansi, &[0x61, 0x80]).unwrap(), [0x61, 0x80]); // lossy
+		assert_eq!(encode(Encoding::Ansi, &[0x61, 0x80]), Err(co::ERROR::NO_UNICODE_TRANSLATION));
+		assert_eq!(decode(Encoding::Ansi, &[0xe9]).unwrap(), [0xe9]);
+		assert_eq!(encode(Encoding::Ansi, &[0xe9]), Err(co::ERROR::NO_UNICODE_TRANSLATION));
+	}
+
+	#[test]
+	fn utf16_odd_length() {
+		assert_eq!(decode(Encoding::Utf16le, b"a\x00b").unwrap(), [0x61]);
+		assert_eq!(decode(Encoding::Utf16be, b"\x00a\x00").unwrap(), [0x61]);
+		assert!(decode(Encoding::Utf16le, b"a").unwrap().is_empty());
+		assert_eq!(decode(Encoding::Utf32le, b"a\x00\x00\x00\x00"), Err(co::ERROR::NO_UNICODE_TRANSLATION));
+	}
+
+	#[test]
+	fn win1252() {
+		assert_eq!(decode(Encoding::Win1252, &[0x80, 0x81, 0xe9]).unwrap(), [0x20ac, 0x0081, 0x00e9]);
+		assert_eq!(encode(Encoding::Win1252, &[0x20ac, 0x0081, 0x00e9]).unwrap(), [0x80, 0x81, 0xe9]);
+		assert_eq!(encode(Encoding::Win1252, &wchars("\u{4e16}")), Err(co::ERROR::NO_UNICODE_TRANSLATION));
+	}
+
+	#[test]
+	fn round_trip() {
+		let all_bytes = (0..=0xffu8).collect::<Vec<_>>();
+		assert_eq!(encode(Encoding::Win1252, &decode(Encoding::Win1252, &all_bytes).unwrap()).unwrap(), all_bytes);
+		assert_eq!(encode(Encoding::Ansi, &decode(Encoding::Ansi, &all_bytes[..0x80]).unwrap()).unwrap(), &all_bytes[..0x80]);
+
+		let ascii = wchars("Hello, world!\r\n\t~");
+		let latin = wchars("Ol\u{e1}, \u{20ac}10 \u{2014} \u{201c}quoted\u{201d}");
+		let unicode = wchars("Ol\u{e1}, \u{4e16}\u{754c}! \u{43f}\u{440}\u{438}\u{432}\u{435}\u{442} \u{1f980}\0end");
+
+		let cases = [
+			(Encoding::Ansi, vec![&ascii]),
+			(Encoding::Win1252, vec![&ascii, &latin]),
+			(Encoding::Utf8, vec![&ascii, &latin, &unicode]),
+			(Encoding::Utf16be, vec![&ascii, &latin, &unicode]),
+			(Encoding::Utf16le, vec![&ascii, &latin, &unicode]),
+			(Encoding::Utf32be, vec![&ascii, &latin, &unicode]),
+			(Encoding::Utf32le, vec![&ascii, &latin, &unicode]),
+			(Encoding::Scsu, vec![&ascii, &latin, &unicode]),
+			(Encoding::Bocu1, vec![&ascii, &latin, &unicode]),
+		];
+		for (encoding, samples) in cases {
+			for sample in samples {
+				let data = encode(encoding, sample).unwrap();
+				assert_eq!(decode(encoding, &data).unwrap(), *sample, "{}", encoding);
+			}
+		}
+	}
+
+	#[test]
+	fn bocu1_icu() {
+		// Reference bytes written by ICU, without BOM.
+		for (s, icu) in [
+			("abc", &b"\xb1\xb2\xb3"[..]),
+			("line 1\r\nline 2", b"\xbc\xb9\xbe\xb5\x20\x81\x0d\x0a\xbc\xb9\xbe\xb5\x20\x82"),
+			("a\u{f1}\u{20ac}", b"\xb1\xd0\x7e\xf1\x66"),
+			("Ol\u{e1}, \u{4e16}\u{754c}! \u{43f}\u{440}\u{438}\u{432}\u{435}\u{442} \u{1f980}",
+				b"\x9f\xbc\xd0\x6e\x4f\xac\x20\xfb\x33\xc0\x4e\x6e\x24\xad\xf7\x20\xd3\xf3\x90\x88\x82\x85\x92\x20\xfc\xfe\xd0"),
+			("\u{d55c}\u{ad6d}\u{c5b4}", b"\xfb\xc2\x49\x3a\xcb\xd3\xd7"),
+		] {
+			assert_eq!(encode(Encoding::Bocu1, &wchars(s)).unwrap(), icu, "{:?}", s);
+			assert_eq!(decode(Encoding::Bocu1, icu).unwrap(), wchars(s), "{:?}", s);
+		}
+	}
+
+	#[test]
+	fn unknown() {
+		assert_eq!(decode(Encoding::Unknown, b"a"), Err(co::ERROR::INVALID_PARAMETER));
+		assert_eq!(encode(Encoding::Unknown, &[0x61]), Err(co::ERROR::INVALID_PARAMETER));
+	}
+}
@@ -1,4 +1,5 @@
-use std::cmp::Ordering;
+use crate::decl::*;
+use crate::kernel::utilities::codecs;
 
 /// String encodings.
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -46,73 +47,116 @@ impl std::fmt::Display for Encoding {
 }
 
 impl Encoding {
-	/// Guesses the encoding of the given raw data, also returning the size of
-	/// its [BOM](https://en.wikipedia.org/wiki/Byte_order_mark), if any.
+	/// Decodes the raw data into UTF-16 chars, which can be used to build a
+	/// [`WString`](crate::WString). The BOM, if any, must be already skipped.
+	///
+	/// The conversion is implemented in pure Rust, so no system call is made.
+	/// It's lossy in two cases: [`Encoding::Ansi`](crate::Encoding::Ansi)
+	/// bytes above `0x7f` are converted straight into the chars with the same
+	/// value – although [`encode`](crate::Encoding::encode) rejects them –
+	/// and a trailing odd byte of UTF-16 data is ignored.
+	///
+	/// Returns
+	/// [`co::ERROR::NO_UNICODE_TRANSLATION`](crate::co::ERROR::NO_UNICODE_TRANSLATION)
+	/// if the data is malformed, or
+	/// [`co::ERROR::INVALID_PARAMETER`](crate::co::ERROR::INVALID_PARAMETER)
+	/// if the encoding is [`Encoding::Unknown`](crate::Encoding::Unknown).
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*};
+	///
+	/// let raw = [0x00, 0x00, 0x00, 0x41, 0x00, 0x01, 0xf6, 0x00];
+	/// let wchars = w::Encoding::Utf32be.decode(&raw)?;
+	/// let text = w::WString::from_wchars_slice(&wchars).to_string();
+	/// # w::SysResult::Ok(())
+	/// ```
 	#[must_use]
-	pub fn guess(data: &[u8]) -> (Self, usize) {
-		if let Some((enc, bom_sz)) = Self::guess_bom(data) {
-			return (enc, bom_sz); // BOM found, we already guessed the encoding
-		}
-
-		if Self::guess_utf8(data) {
-			return (Self::Utf8, 0);
-		}
-
-		let has_non_ansi_char = data.iter().find(|ch| **ch > 0x7f).is_some();
-		if has_non_ansi_char {
-			(Self::Win1252, 0) // by exclusion, not assertive
-		} else {
-			(Self::Ansi, 0)
-		}
+	pub fn decode(&self, data: &[u8]) -> SysResult<Vec<u16>> {
+		codecs::decode(*self, data)
 	}
 
+	/// Encodes the UTF-16 chars into raw data. No BOM is written.
+	///
+	/// The conversion is implemented in pure Rust, so no system call is made.
+	///
+	/// Returns
+	/// [`co::ERROR::NO_UNICODE_TRANSLATION`](crate::co::ERROR::NO_UNICODE_TRANSLATION)
+	/// if a char cannot be represented in the encoding, or if the chars have
+	/// unpaired surrogates, or
+	/// [`co::ERROR::INVALID_PARAMETER`](crate::co::ERROR::INVALID_PARAMETER)
+	/// if the encoding is [`Encoding::Unknown`](crate::Encoding::Unknown).
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*};
+	///
+	/// let wstr = w::WString::from_str("Olá");
+	/// let raw = w::Encoding::Win1252.encode(
+	///     &wstr.as_slice()[..wstr.str_len()])?;
+	/// # w::SysResult::Ok(())
+	/// ```
 	#[must_use]
-	fn guess_bom(data: &[u8]) -> Option<(Self, usize)> {
-		let has_bom = |bom_bytes: &[u8]| -> bool {
-			data.len() >= bom_bytes.len()
-				&& data[..bom_bytes.len()].cmp(bom_bytes) == Ordering::Equal
-		};
-
-		const UTF8: [u8; 3] = [0xef, 0xbb, 0xbf];
-		if has_bom(&UTF8) { // UTF-8 BOM
-			return Some((Self::Utf8, UTF8.len()));
-		}
-
-		const UTF16BE: [u8; 2] = [0xfe, 0xff];
-		if has_bom(&UTF16BE) {
-			return Some((Self::Utf16be, UTF16BE.len()));
-		}
-
-		const UTF16LE: [u8; 2] = [0xff, 0xfe];
-		if has_bom(&UTF16LE) {
-			return Some((Self::Utf16le, UTF16LE.len()));
-		}
-
-		const UTF32BE: [u8; 4] = [0x00, 0x00, 0xfe, 0xff];
-		if has_bom(&UTF32BE) {
-			return Some((Self::Utf32be, UTF32BE.len()));
-		}
-
-		const UTF32LE: [u8; 4] = [0xff, 0xfe, 0x00, 0x00];
-		if has_bom(&UTF32LE) {
-			return Some((Self::Utf32le, UTF32LE.len()));
-		}
+	pub fn encode(&self, wchars: &[u16]) -> SysResult<Vec<u8>> {
+		codecs::encode(*self, wchars)
+	}
 
-		const SCSU: [u8; 3] = [0x0e, 0xfe, 0xff];
-		if has_bom(&SCSU) {
-			return Some((Self::Scsu, SCSU.len()));
+	/// Returns the [BOM](https://en.wikipedia.org/wiki/Byte_order_mark) bytes
+	/// of the encoding. Encodings without a BOM return an empty slice.
+	///
+	/// The BOCU-1 BOM is U+FEFF encoded from the initial state, and the text
+	/// which follows it is encoded from the state left by U+FEFF. So, unlike
+	/// the other encodings, the BOM cannot be simply prepended or skipped;
+	/// [`WString::serialize`](crate::WString::serialize) and
+	/// [`WString::parse`](crate::WString::parse) handle it properly.
+	#[must_use]
+	pub const fn bom(&self) -> &'static [u8] {
+		match self {
+			Self::Unknown | Self::Ansi | Self::Win1252 => &[],
+			Self::Utf8 => &[0xef, 0xbb, 0xbf],
+			Self::Utf16be => &[0xfe, 0xff],
+			Self::Utf16le => &[0xff, 0xfe],
+			Self::Utf32be => &[0x00, 0x00, 0xfe, 0xff],
+			Self::Utf32le => &[0xff, 0xfe, 0x00, 0x00],
+			Self::Scsu => &[0x0e, 0xfe, 0xff],
+			Self::Bocu1 => &[0xfb, 0xee, 0x28],
 		}
+	}
 
-		const BOCU1: [u8; 3] = [0xfb, 0xee, 0x28];
-		if has_bom(&BOCU1) {
-			return Some((Self::Bocu1, BOCU1.len()));
-		}
+	/// Guesses the encoding of the given raw data, also returning the size of
+	/// its [BOM](https://en.wikipedia.org/wiki/Byte_order_mark), if any.
+	///
+	/// This method returns the best candidate found by the built-in detectors
+	/// of [`EncodingDetector`](crate::EncodingDetector). If no candidate is
+	/// found, [`Encoding::Win1252`](crate::Encoding::Win1252) is returned by
+	/// exclusion.
+	#[must_use]
+	pub fn guess(data: &[u8]) -> (Self, usize) {
+		EncodingDetector::new()
+			.detect(data)
+			.first()
+			.map_or((Self::Win1252, 0), |guess| (guess.encoding, guess.bom_size))
+	}
 
-		None // no BOM found
+	#[must_use]
+	pub(crate) fn guess_bom(data: &[u8]) -> Option<(Self, usize)> {
+		[
+			Self::Utf32le, // must come before UTF-16 LE, which shares the first 2 bytes
+			Self::Utf32be,
+			Self::Utf8,
+			Self::Utf16be,
+			Self::Utf16le,
+			Self::Scsu,
+			Self::Bocu1,
+		].iter()
+			.find(|enc| data.starts_with(enc.bom()))
+			.map(|enc| (*enc, enc.bom().len()))
 	}
 
 	#[must_use]
-	fn guess_utf8(data: &[u8]) -> bool {
+	pub(crate) fn guess_utf8(data: &[u8]) -> bool {
 		let mut i = 0; // https://stackoverflow.com/a/1031773/6923555
 		while i < data.len() {
 			let ch0 = unsafe { *data.get_unchecked(i) };
@@ -198,3 +242,25 @@ impl Encoding {
 		true // all the conditions accepted through the whole string
 	}
 }
+
+/// Line endings, used in
+/// [`WString::normalize_line_endings`](crate::WString::normalize_line_endings).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+	/// Carriage return followed by a line feed, `\r\n`, used by Windows.
+	Crlf,
+	/// A single line feed, `\n`, used by Unix-like systems.
+	Lf,
+}
+
+impl std::fmt::Display for LineEnding {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		std::fmt::Display::fmt(
+			match self {
+				Self::Crlf => "CRLF",
+				Self::Lf => "LF",
+			},
+			f,
+		)
+	}
+}
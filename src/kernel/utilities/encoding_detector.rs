@@ -0,0 +1,364 @@
+use crate::decl::*;
+
+/// A candidate encoding returned by
+/// [`EncodingDetector::detect`](crate::EncodingDetector::detect).
+#[derive(Clone, Copy, PartialEq)]
+pub struct EncodingGuess {
+	/// The guessed encoding.
+	pub encoding: Encoding,
+	/// Size of the [BOM](https://en.wikipedia.org/wiki/Byte_order_mark), in
+	/// bytes, or zero if the data has no BOM.
+	pub bom_size: usize,
+	/// How likely the guess is right, from `0.0` to `1.0`.
+	pub confidence: f32,
+}
+
+impl std::fmt::Display for EncodingGuess {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} ({:.0}%)", self.encoding, self.confidence * 100.0)
+	}
+}
+
+/// Detects the encoding of raw data by running a series of detectors, each one
+/// returning zero or more scored candidates, which are then ranked.
+///
+/// The built-in detectors look for:
+///
+/// * a [BOM](https://en.wikipedia.org/wiki/Byte_order_mark);
+/// * null byte patterns of BOM-less UTF-16 and UTF-32;
+/// * UTF-8 and ASCII validity;
+/// * byte frequencies typical of Windows-1252 text.
+///
+/// [`Encoding::guess`](crate::Encoding::guess) uses the built-in detectors,
+/// returning the best candidate.
+///
+/// # Examples
+///
+/// Listing the candidates:
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*};
+///
+/// let f = w::FileMapped::open(
+///     "C:\\Temp\\foo.txt",
+///     w::FileAccess::ExistingReadOnly,
+/// )?;
+///
+/// for guess in w::EncodingDetector::new().detect(f.as_slice()) {
+///     println!("{} {} {}", guess.encoding, guess.bom_size, guess.confidence);
+/// }
+/// # w::SysResult::Ok(())
+/// ```
+///
+/// Adding a custom detector, which knows that all our files starting with
+/// `"#!"` are UTF-8:
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*};
+///
+/// let mut detector = w::EncodingDetector::new();
+/// detector.add(|data| {
+///     if data.starts_with(b"#!") {
+///         vec![w::EncodingGuess {
+///             encoding: w::Encoding::Utf8,
+///             bom_size: 0,
+///             confidence: 0.99,
+///         }]
+///     } else {
+///         Vec::new()
+///     }
+/// });
+///
+/// let best = detector.detect(b"#!/bin/sh")[0];
+/// ```
+pub struct EncodingDetector {
+	detectors: Vec<Box<DetectorFn>>,
+}
+
+/// A detector, which returns the candidates found in the raw data.
+type DetectorFn = dyn Fn(&[u8]) -> Vec<EncodingGuess>;
+
+impl Default for EncodingDetector {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl EncodingDetector {
+	/// Creates a new detector with the built-in detectors.
+	#[must_use]
+	pub fn new() -> Self {
+		let mut new_self = Self::new_empty();
+		new_self.add(detect_bom)
+			.add(detect_utf16_utf32)
+			.add(detect_utf8_ansi)
+			.add(detect_win1252);
+		new_self
+	}
+
+	/// Creates a new detector without any detectors, so only the ones added
+	/// with [`add`](crate::EncodingDetector::add) will run.
+	#[must_use]
+	pub fn new_empty() -> Self {
+		Self { detectors: Vec::new() }
+	}
+
+	/// Adds a detector, which receives the raw data and returns the candidates
+	/// it could find, if any.
+	pub fn add<F>(&mut self, func: F) -> &mut Self
+		where F: Fn(&[u8]) -> Vec<EncodingGuess> + 'static,
+	{
+		self.detectors.push(Box::new(func));
+		self
+	}
+
+	/// Runs all the detectors, returning the candidates sorted by descending
+	/// confidence. If more than one detector returns the same encoding, only
+	/// the highest confidence is kept.
+	#[must_use]
+	pub fn detect(&self, data: &[u8]) -> Vec<EncodingGuess> {
+		let mut guesses = Vec::<EncodingGuess>::new();
+		for detector in self.detectors.iter() {
+			for guess in detector(data) {
+				let guess = EncodingGuess {
+					confidence: guess.confidence.clamp(0.0, 1.0),
+					..guess
+				};
+				match guesses.iter_mut().find(|g| g.encoding == guess.encoding) {
+					Some(existing) => if guess.confidence > existing.confidence {
+						*existing = guess;
+					},
+					None => guesses.push(guess),
+				}
+			}
+		}
+		guesses.sort_by(|a, b| b.confidence.total_cmp(&a.confidence)); // stable, keeps detector order on ties
+		guesses
+	}
+}
+
+/// Returns the data without the trailing nulls, if any.
+fn trim_nulls(data: &[u8]) -> &[u8] {
+	let len = data.iter().rposition(|b| *b != 0x00).map_or(0, |pos| pos + 1);
+	&data[..len]
+}
+
+fn detect_bom(data: &[u8]) -> Vec<EncodingGuess> {
+	Encoding::guess_bom(data)
+		.map(|(encoding, bom_size)| EncodingGuess { encoding, bom_size, confidence: 1.0 })
+		.into_iter()
+		.collect()
+}
+
+fn detect_utf16_utf32(data: &[u8]) -> Vec<EncodingGuess> {
+	let mut guesses = Vec::new();
+	if data.len() < 4 {
+		return guesses; // too short to find any pattern
+	}
+
+	if data.len() % 4 == 0 {
+		let quads = data.chunks_exact(4);
+		let n = quads.len() as f32;
+		for is_be in [false, true] {
+			let cp = |q: &[u8]| if is_be {
+				u32::from_be_bytes([q[0], q[1], q[2], q[3]])
+			} else {
+				u32::from_le_bytes([q[0], q[1], q[2], q[3]])
+			};
+			if quads.clone().all(|q| char::from_u32(cp(q)).is_some()) {
+				let bmp_latin = quads.clone().filter(|q| cp(q) < 0x100).count() as f32;
+				guesses.push(EncodingGuess {
+					encoding: if is_be { Encoding::Utf32be } else { Encoding::Utf32le },
+					bom_size: 0,
+					confidence: 0.7 + 0.25 * (bmp_latin / n),
+				});
+			}
+		}
+	}
+
+	if data.len() % 2 == 0 {
+		let pairs = data.chunks_exact(2);
+		let n = pairs.len() as f32;
+		let zeros_even = pairs.clone().filter(|p| p[0] == 0x00).count() as f32;
+		let zeros_odd = pairs.clone().filter(|p| p[1] == 0x00).count() as f32;
+		for is_be in [false, true] {
+			let score = if is_be { zeros_even - zeros_odd } else { zeros_odd - zeros_even } / n;
+			if score <= 0.0 {
+				continue;
+			}
+			let is_valid = char::decode_utf16(pairs.clone().map(|p| if is_be {
+				u16::from_be_bytes([p[0], p[1]])
+			} else {
+				u16::from_le_bytes([p[0], p[1]])
+			})).all(|ch| ch.is_ok());
+			if is_valid {
+				guesses.push(EncodingGuess {
+					encoding: if is_be { Encoding::Utf16be } else { Encoding::Utf16le },
+					bom_size: 0,
+					confidence: 0.5 + 0.45 * score,
+				});
+			}
+		}
+	}
+
+	guesses
+}
+
+fn detect_utf8_ansi(data: &[u8]) -> Vec<EncodingGuess> {
+	let mut guesses = Vec::new();
+	let content = trim_nulls(data);
+	let has_inner_nulls = content.contains(&0x00);
+
+	let is_ascii = content.iter().all(|b| *b <= 0x7f);
+
+	if Encoding::guess_utf8(content) { // stops at the first null
+		guesses.push(EncodingGuess {
+			encoding: Encoding::Utf8,
+			bom_size: 0,
+			confidence: if has_inner_nulls {
+				0.3
+			} else if is_ascii {
+				0.9
+			} else {
+				0.95 // multi-byte sequences are hardly valid by chance
+			},
+		});
+	}
+	if !has_inner_nulls && is_ascii {
+		guesses.push(EncodingGuess {
+			encoding: Encoding::Ansi,
+			bom_size: 0,
+			confidence: 0.85, // also valid UTF-8, which is preferred
+		});
+	}
+	guesses
+}
+
+fn detect_win1252(data: &[u8]) -> Vec<EncodingGuess> {
+	let content = trim_nulls(data);
+	let num_high = content.iter().filter(|b| **b > 0x7f).count();
+	if num_high == 0 {
+		return Vec::new(); // plain ASCII, nothing to tell
+	}
+
+	let num_bad = content.iter()
+		.filter(|b| matches!(**b,
+			0x81 | 0x8d | 0x8f | 0x90 | 0x9d // undefined in Windows-1252
+			| 0x00..=0x08 | 0x0e..=0x1a | 0x1c..=0x1f)) // binary data
+		.count();
+	let num_letters = content.iter()
+		.filter(|b| matches!(**b, 0xc0..=0xff) && **b != 0xd7 && **b != 0xf7)
+		.count();
+	let num_ascii_letters = content.iter()
+		.filter(|b| b.is_ascii_alphabetic())
+		.count();
+
+	let good_ratio = 1.0 - (num_bad as f32 / content.len() as f32).min(1.0);
+	let letter_ratio = num_letters as f32 / num_high as f32;
+	let accent_ratio = num_high as f32 / (num_high + num_ascii_letters) as f32; // Latin text has few accented letters
+
+	vec![EncodingGuess {
+		encoding: Encoding::Win1252,
+		bom_size: 0,
+		confidence: good_ratio * good_ratio
+			* (0.35 + 0.15 * letter_ratio + 0.2 * (1.0 - accent_ratio)),
+	}]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Returns the encodings of the candidates, as strings, since `Encoding`
+	/// has no `Debug`.
+	fn names(guesses: &[EncodingGuess]) -> Vec<String> {
+		guesses.iter().map(|g| g.encoding.to_string()).collect()
+	}
+
+	fn guess(data: &[u8]) -> (String, usize) {
+		let (encoding, bom_size) = Encoding::guess(data);
+		(encoding.to_string(), bom_size)
+	}
+
+	#[test]
+	fn bom() {
+		for (data, encoding, bom_size) in [
+			(&b"\xef\xbb\xbfabc"[..], Encoding::Utf8, 3),
+			(b"\xfe\xff\x00a", Encoding::Utf16be, 2),
+			(b"\xff\xfea\x00", Encoding::Utf16le, 2),
+			(b"\x00\x00\xfe\xff\x00\x00\x00a", Encoding::Utf32be, 4),
+			(b"\xff\xfe\x00\x00a\x00\x00\x00", Encoding::Utf32le, 4), // not UTF-16 LE
+			(b"\x0e\xfe\xffabc", Encoding::Scsu, 3),
+			(b"\xfb\xee\x28abc", Encoding::Bocu1, 3),
+		] {
+			let best = EncodingDetector::new().detect(data)[0];
+			assert!(best.encoding == encoding, "{}", encoding);
+			assert_eq!(best.bom_size, bom_size, "{}", encoding);
+			assert_eq!(best.confidence, 1.0, "{}", encoding);
+		}
+	}
+
+	#[test]
+	fn bomless_utf16_utf32() {
+		let utf16le = "hello".encode_utf16().flat_map(|ch| ch.to_le_bytes()).collect::<Vec<_>>();
+		let utf16be = "hello".encode_utf16().flat_map(|ch| ch.to_be_bytes()).collect::<Vec<_>>();
+		let utf32le = "hey".chars().flat_map(|ch| (ch as u32).to_le_bytes()).collect::<Vec<_>>();
+		assert_eq!(guess(&utf16le), ("UTF-16 LE".to_owned(), 0));
+		assert_eq!(guess(&utf16be), ("UTF-16 BE".to_owned(), 0));
+		assert_eq!(guess(&utf32le), ("UTF-32 LE".to_owned(), 0));
+	}
+
+	#[test]
+	fn utf8_ansi_scoring() {
+		// Plain ASCII is valid as both, but UTF-8 is preferred.
+		let guesses = EncodingDetector::new().detect(b"hello world");
+		assert_eq!(names(&guesses), ["UTF-8", "ANSI"]);
+		assert!(guesses[0].confidence > guesses[1].confidence);
+
+		// Multi-byte sequences score higher than plain ASCII.
+		let guesses = EncodingDetector::new().detect("héllo wörld".as_bytes());
+		assert_eq!(names(&guesses)[0], "UTF-8");
+		assert_eq!(guesses[0].confidence, 0.95);
+		assert!(!names(&guesses).contains(&"ANSI".to_owned()));
+
+		// Accented Latin letters not valid as UTF-8.
+		let guesses = EncodingDetector::new().detect(b"h\xe9llo w\xf6rld");
+		assert_eq!(names(&guesses), ["Windows 1252"]);
+
+		// Inner nulls make UTF-8 unlikely, and ANSI impossible.
+		let guesses = EncodingDetector::new().detect(b"abc\x00def");
+		assert_eq!(names(&guesses), ["UTF-8"]);
+		assert_eq!(guesses[0].confidence, 0.3);
+
+		assert_eq!(guess(b""), ("UTF-8".to_owned(), 0));
+		assert_eq!(guess(b"\x81\x8d\x8f"), ("Windows 1252".to_owned(), 0));
+	}
+
+	#[test]
+	fn custom_detector() {
+		let mut detector = EncodingDetector::new();
+		detector.add(|data| {
+			if data.starts_with(b"#!") {
+				vec![EncodingGuess { encoding: Encoding::Win1252, bom_size: 0, confidence: 5.0 }]
+			} else {
+				vec![EncodingGuess { encoding: Encoding::Utf8, bom_size: 0, confidence: 0.1 }]
+			}
+		});
+
+		// Overrides the built-in detectors, with the confidence clamped.
+		let guesses = detector.detect(b"#!/bin/sh");
+		assert_eq!(names(&guesses), ["Windows 1252", "UTF-8", "ANSI"]);
+		assert_eq!(guesses[0].confidence, 1.0);
+
+		// A lower confidence doesn't replace the built-in one.
+		let guesses = detector.detect(b"hello");
+		assert_eq!(names(&guesses), ["UTF-8", "ANSI"]);
+		assert_eq!(guesses[0].confidence, 0.9);
+
+		// Without the built-ins, only the custom one runs.
+		let mut detector = EncodingDetector::new_empty();
+		assert!(detector.detect(b"hello").is_empty());
+		detector.add(|_| vec![EncodingGuess { encoding: Encoding::Ansi, bom_size: 0, confidence: 0.5 }]);
+		assert_eq!(names(&detector.detect(b"\xef\xbb\xbfhello")), ["ANSI"]);
+	}
+}
@@ -1,11 +1,16 @@
+mod codecs;
 mod encoding;
+mod encoding_detector;
 mod file_mapped;
 mod file;
+mod pe_resources;
 mod w_string;
 
 pub mod path;
 
-pub use encoding::Encoding;
+pub use encoding::{Encoding, LineEnding};
+pub use encoding_detector::{EncodingDetector, EncodingGuess};
 pub use file_mapped::FileMapped;
 pub use file::{File, FileAccess};
+pub use pe_resources::{PeResource, PeResources};
 pub use w_string::WString;
@@ -0,0 +1,582 @@
+use std::collections::HashSet;
+
+use crate::co;
+use crate::decl::*;
+
+/// A resource found by [`PeResources`](crate::PeResources).
+#[derive(Clone)]
+pub struct PeResource<'a> {
+	/// The resource type.
+	pub resource_type: RtStr,
+	/// The resource identifier.
+	pub resource_id: IdStr,
+	/// The resource language.
+	pub language: LANGID,
+	/// The code page of the resource data, usually zero.
+	pub code_page: u32,
+	/// The raw resource data, which points into the parsed file.
+	pub data: &'a [u8],
+}
+
+/// Reads the resources of a
+/// [PE](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format)
+/// file – an executable or a DLL – by parsing its `.rsrc` section directly
+/// from the file contents, without loading it.
+///
+/// This is the pure-Rust counterpart of
+/// [`HINSTANCE::EnumResourceTypes`](crate::prelude::kernel_Hinstance::EnumResourceTypes),
+/// [`EnumResourceNames`](crate::prelude::kernel_Hinstance::EnumResourceNames),
+/// [`EnumResourceLanguages`](crate::prelude::kernel_Hinstance::EnumResourceLanguages),
+/// [`FindResource`](crate::prelude::kernel_Hinstance::FindResource) and
+/// [`LoadResource`](crate::prelude::kernel_Hinstance::LoadResource), so it
+/// works with 32 and 64-bit files, for any architecture.
+///
+/// # Examples
+///
+/// Listing all resources of an executable, and extracting its manifest:
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*, co};
+///
+/// let f = w::FileMapped::open(
+///     "C:\\Temp\\foo.exe",
+///     w::FileAccess::ExistingReadOnly,
+/// )?;
+/// let res = w::PeResources::parse(f.as_slice())?;
+///
+/// for r in res.iter() {
+///     println!("{} {} {} - {} bytes",
+///         r.resource_type, r.resource_id, r.language, r.data.len());
+/// }
+///
+/// if let Some(manifest) = res.find(
+///     &w::IdStr::Id(1),
+///     &w::RtStr::Rt(co::RT::MANIFEST),
+///     None,
+/// ) {
+///     println!("{}", String::from_utf8_lossy(manifest));
+/// }
+/// # w::SysResult::Ok(())
+/// ```
+#[derive(Clone)]
+pub struct PeResources<'a> {
+	entries: Vec<PeResource<'a>>,
+}
+
+impl<'a> PeResources<'a> {
+	/// Parses the contents of a PE file, reading all its resources.
+	///
+	/// A file without resources is valid, and yields no resources.
+	///
+	/// Returns
+	/// [`co::ERROR::BAD_EXE_FORMAT`](crate::co::ERROR::BAD_EXE_FORMAT) if the
+	/// data isn't a PE file, or if it's malformed.
+	#[must_use]
+	pub fn parse(data: &'a [u8]) -> SysResult<Self> {
+		let pe = PeFile::parse(data)?;
+		let mut entries = Vec::new();
+
+		if let Some(rsrc) = pe.rsrc_section()? {
+			let mut visited = HashSet::new(); // shared subdirectories would multiply the entries
+			let mut read_dir_once = |dir_off: usize| if visited.insert(dir_off) {
+				rsrc.read_dir(dir_off)
+			} else {
+				Err(co::ERROR::BAD_EXE_FORMAT)
+			};
+
+			for (type_name, type_off) in read_dir_once(0)? {
+				let type_off = type_off.ok_or(co::ERROR::BAD_EXE_FORMAT)?; // must be a subdirectory
+				for (res_name, res_off) in read_dir_once(type_off)? {
+					let res_off = res_off.ok_or(co::ERROR::BAD_EXE_FORMAT)?;
+					for (lang_name, data_off) in read_dir_once(res_off)? {
+						let data_off = match data_off {
+							None => lang_name.data_entry_off, // leaf
+							Some(_) => return Err(co::ERROR::BAD_EXE_FORMAT),
+						};
+						let data_rva = rsrc.dword_at(data_off)?;
+						let data_sz = rsrc.dword_at(data_off + 4)?;
+
+						entries.push(PeResource {
+							resource_type: match &type_name.name {
+								Name::Id(id) => RtStr::Rt(unsafe { co::RT::from_raw(*id) }),
+								Name::Str(s) => RtStr::Str(WString::from_str(s)),
+							},
+							resource_id: match &res_name.name {
+								Name::Id(id) => IdStr::Id(*id),
+								Name::Str(s) => IdStr::Str(WString::from_str(s)),
+							},
+							language: match lang_name.name {
+								Name::Id(id) => unsafe { LANGID::from_raw(id) },
+								Name::Str(_) => return Err(co::ERROR::BAD_EXE_FORMAT),
+							},
+							code_page: rsrc.dword_at(data_off + 8)?,
+							data: pe.slice_at_rva(data_rva, data_sz)?,
+						});
+					}
+				}
+			}
+		}
+
+		Ok(Self { entries })
+	}
+
+	/// Returns an iterator over all the resources, in the order they're stored:
+	/// by type, then by identifier, then by language.
+	#[must_use]
+	pub fn iter(&self) -> impl Iterator<Item = &PeResource<'a>> {
+		self.entries.iter()
+	}
+
+	/// Returns the distinct resource types.
+	///
+	/// This is the equivalent of
+	/// [`HINSTANCE::EnumResourceTypes`](crate::prelude::kernel_Hinstance::EnumResourceTypes).
+	#[must_use]
+	pub fn resource_types(&self) -> Vec<RtStr> {
+		let mut types = Vec::<RtStr>::new();
+		for entry in self.entries.iter() {
+			if !types.iter().any(|t| rt_eq(t, &entry.resource_type)) {
+				types.push(entry.resource_type.clone());
+			}
+		}
+		types
+	}
+
+	/// Returns the distinct resource identifiers of the given type.
+	///
+	/// This is the equivalent of
+	/// [`HINSTANCE::EnumResourceNames`](crate::prelude::kernel_Hinstance::EnumResourceNames).
+	#[must_use]
+	pub fn resource_names(&self, resource_type: &RtStr) -> Vec<IdStr> {
+		let mut names = Vec::<IdStr>::new();
+		for entry in self.entries.iter()
+			.filter(|e| rt_eq(&e.resource_type, resource_type))
+		{
+			if !names.iter().any(|n| id_eq(n, &entry.resource_id)) {
+				names.push(entry.resource_id.clone());
+			}
+		}
+		names
+	}
+
+	/// Returns the languages of the given resource.
+	///
+	/// This is the equivalent of
+	/// [`HINSTANCE::EnumResourceLanguages`](crate::prelude::kernel_Hinstance::EnumResourceLanguages).
+	#[must_use]
+	pub fn resource_languages(&self,
+		resource_type: &RtStr,
+		resource_id: &IdStr,
+	) -> Vec<LANGID>
+	{
+		self.entries.iter()
+			.filter(|e| rt_eq(&e.resource_type, resource_type)
+				&& id_eq(&e.resource_id, resource_id))
+			.map(|e| e.language)
+			.collect()
+	}
+
+	/// Returns the data of the given resource, if any.
+	///
+	/// If `language` is `None`, the first language found is returned. String
+	/// identifiers are compared case-insensitively.
+	///
+	/// This is the equivalent of
+	/// [`HINSTANCE::FindResourceEx`](crate::prelude::kernel_Hinstance::FindResourceEx)
+	/// followed by
+	/// [`HINSTANCE::LockResource`](crate::prelude::kernel_Hinstance::LockResource).
+	#[must_use]
+	pub fn find(&self,
+		resource_id: &IdStr,
+		resource_type: &RtStr,
+		language: Option<LANGID>,
+	) -> Option<&'a [u8]>
+	{
+		self.entries.iter()
+			.find(|e| rt_eq(&e.resource_type, resource_type)
+				&& id_eq(&e.resource_id, resource_id)
+				&& language.is_none_or(|lang| e.language == lang))
+			.map(|e| e.data)
+	}
+}
+
+fn rt_eq(a: &RtStr, b: &RtStr) -> bool {
+	match (a, b) {
+		(RtStr::Rt(a), RtStr::Rt(b)) => a == b,
+		(RtStr::Str(a), RtStr::Str(b)) => a.to_string().to_uppercase() == b.to_string().to_uppercase(),
+		_ => false,
+	}
+}
+
+fn id_eq(a: &IdStr, b: &IdStr) -> bool {
+	match (a, b) {
+		(IdStr::Id(a), IdStr::Id(b)) => a == b,
+		(IdStr::Str(a), IdStr::Str(b)) => a.to_string().to_uppercase() == b.to_string().to_uppercase(),
+		_ => false,
+	}
+}
+
+/// Returns the `len` bytes at the given offset, failing if they're out of
+/// bounds, or if the end offset overflows, which may happen on 32-bit targets.
+fn bytes_at(data: &[u8], off: usize, len: usize) -> SysResult<&[u8]> {
+	off.checked_add(len)
+		.and_then(|end| data.get(off..end))
+		.ok_or(co::ERROR::BAD_EXE_FORMAT)
+}
+
+/// The headers of a PE file which are needed to locate the resources.
+struct PeFile<'a> {
+	data: &'a [u8],
+	rsrc_rva: u32,
+	rsrc_sz: u32,
+	sections: Vec<(u32, u32, u32, u32)>, // virtual address, virtual size, raw pointer, raw size
+}
+
+impl<'a> PeFile<'a> {
+	fn parse(data: &'a [u8]) -> SysResult<Self> {
+		let word_at = |off: usize| bytes_at(data, off, 2)
+			.map(|b| u16::from_le_bytes([b[0], b[1]]));
+		let dword_at = |off: usize| bytes_at(data, off, 4)
+			.map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+
+		if data.get(0..2) != Some(b"MZ") {
+			return Err(co::ERROR::BAD_EXE_FORMAT);
+		}
+		let pe_off = dword_at(0x3c)? as usize; // e_lfanew
+		if bytes_at(data, pe_off, 4)? != b"PE\0\0" {
+			return Err(co::ERROR::BAD_EXE_FORMAT);
+		}
+
+		let coff_off = pe_off.checked_add(4).ok_or(co::ERROR::BAD_EXE_FORMAT)?;
+		let num_sections = word_at(coff_off + 2)? as usize;
+		let opt_sz = word_at(coff_off + 16)? as usize;
+		let opt_off = coff_off + 20;
+
+		let (num_dirs_off, dirs_off) = match word_at(opt_off)? {
+			0x10b => (opt_off + 92, opt_off + 96), // PE32
+			0x20b => (opt_off + 108, opt_off + 112), // PE32+
+			_ => return Err(co::ERROR::BAD_EXE_FORMAT),
+		};
+		const IMAGE_DIRECTORY_ENTRY_RESOURCE: usize = 2;
+		let (rsrc_rva, rsrc_sz) = if dword_at(num_dirs_off)? as usize > IMAGE_DIRECTORY_ENTRY_RESOURCE {
+			let dir_off = dirs_off + IMAGE_DIRECTORY_ENTRY_RESOURCE * 8;
+			(dword_at(dir_off)?, dword_at(dir_off + 4)?)
+		} else {
+			(0, 0)
+		};
+
+		let sections_off = opt_off + opt_sz;
+		let sections = (0..num_sections)
+			.map(|idx| {
+				let sec_off = sections_off + idx * 40; // IMAGE_SECTION_HEADER
+				Ok((
+					dword_at(sec_off + 12)?,
+					dword_at(sec_off + 8)?,
+					dword_at(sec_off + 20)?,
+					dword_at(sec_off + 16)?,
+				))
+			})
+			.collect::<SysResult<Vec<_>>>()?;
+
+		Ok(Self { data, rsrc_rva, rsrc_sz, sections })
+	}
+
+	/// Returns the file data at the given RVA, which must be entirely within
+	/// the raw data of a section.
+	fn slice_at_rva(&self, rva: u32, sz: u32) -> SysResult<&'a [u8]> {
+		self.sections.iter()
+			.find(|(va, vsz, _, rsz)| rva >= *va && rva < va.saturating_add(*vsz.max(rsz)))
+			.and_then(|(va, _, raw_ptr, raw_sz)| {
+				let start = (rva - va) as usize;
+				if start.checked_add(sz as usize)? > *raw_sz as usize {
+					return None; // beyond what's stored in the file
+				}
+				(*raw_ptr as usize).checked_add(start) // may overflow on 32-bit targets
+			})
+			.ok_or(co::ERROR::BAD_EXE_FORMAT)
+			.and_then(|off| bytes_at(self.data, off, sz as _))
+	}
+
+	/// Returns the resource section, if any.
+	fn rsrc_section(&self) -> SysResult<Option<RsrcSection<'a>>> {
+		if self.rsrc_rva == 0 || self.rsrc_sz == 0 {
+			Ok(None)
+		} else {
+			self.slice_at_rva(self.rsrc_rva, self.rsrc_sz)
+				.map(|data| Some(RsrcSection { data }))
+		}
+	}
+}
+
+/// The name of a resource directory entry.
+enum Name {
+	Id(u16),
+	Str(String),
+}
+
+/// A resource directory entry.
+struct DirEntry {
+	name: Name,
+	data_entry_off: usize, // offset of the IMAGE_RESOURCE_DATA_ENTRY, if a leaf
+}
+
+/// The resource section, whose offsets are relative to its beginning.
+struct RsrcSection<'a> {
+	data: &'a [u8],
+}
+
+impl<'a> RsrcSection<'a> {
+	fn word_at(&self, off: usize) -> SysResult<u16> {
+		bytes_at(self.data, off, 2)
+			.map(|b| u16::from_le_bytes([b[0], b[1]]))
+	}
+
+	fn dword_at(&self, off: usize) -> SysResult<u32> {
+		bytes_at(self.data, off, 4)
+			.map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+	}
+
+	/// Reads the entries of an `IMAGE_RESOURCE_DIRECTORY`, returning each one
+	/// along with the offset of its subdirectory, if it's not a leaf.
+	fn read_dir(&self, dir_off: usize) -> SysResult<Vec<(DirEntry, Option<usize>)>> {
+		const HIGH_BIT: u32 = 0x8000_0000;
+		let num_entries = self.word_at(dir_off + 12)? as usize // named entries
+			+ self.word_at(dir_off + 14)? as usize; // ID entries
+
+		(0..num_entries)
+			.map(|idx| {
+				let entry_off = dir_off + 16 + idx * 8; // IMAGE_RESOURCE_DIRECTORY_ENTRY
+				let name = self.dword_at(entry_off)?;
+				let target = self.dword_at(entry_off + 4)?;
+
+				let name = if name & HIGH_BIT != 0 {
+					let str_off = (name & !HIGH_BIT) as usize; // IMAGE_RESOURCE_DIR_STRING_U
+					let len = self.word_at(str_off)? as usize;
+					let chars = (0..len)
+						.map(|i| self.word_at(str_off + 2 + i * 2))
+						.collect::<SysResult<Vec<_>>>()?;
+					Name::Str(String::from_utf16_lossy(&chars))
+				} else {
+					Name::Id(name as _)
+				};
+
+				let sub_dir = if target & HIGH_BIT != 0 {
+					let sub_off = (target & !HIGH_BIT) as usize;
+					if sub_off <= dir_off {
+						return Err(co::ERROR::BAD_EXE_FORMAT); // would loop
+					}
+					Some(sub_off)
+				} else {
+					None
+				};
+
+				Ok((DirEntry { name, data_entry_off: target as _ }, sub_dir))
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const RSRC_RVA: u32 = 0x1000;
+	const RSRC_PTR: usize = 0x200;
+	const HIGH_BIT: u32 = 0x8000_0000;
+
+	fn put_u16(buf: &mut [u8], off: usize, val: u16) {
+		buf[off..off + 2].copy_from_slice(&val.to_le_bytes());
+	}
+
+	fn put_u32(buf: &mut [u8], off: usize, val: u32) {
+		buf[off..off + 4].copy_from_slice(&val.to_le_bytes());
+	}
+
+	/// Builds a PE image with a single section holding `rsrc`, if any.
+	fn pe(is_64: bool, rsrc: Option<&[u8]>) -> Vec<u8> {
+		let mut buf = vec![0u8; RSRC_PTR];
+		buf[0..2].copy_from_slice(b"MZ");
+		put_u32(&mut buf, 0x3c, 0x40); // e_lfanew
+		buf[0x40..0x44].copy_from_slice(b"PE\0\0");
+
+		let coff_off = 0x44;
+		let opt_off = coff_off + 20;
+		let (opt_sz, num_dirs_off) = if is_64 { (240, opt_off + 108) } else { (224, opt_off + 92) };
+		put_u16(&mut buf, coff_off + 2, 1); // NumberOfSections
+		put_u16(&mut buf, coff_off + 16, opt_sz as _); // SizeOfOptionalHeader
+		put_u16(&mut buf, opt_off, if is_64 { 0x20b } else { 0x10b });
+		put_u32(&mut buf, num_dirs_off, 16); // NumberOfRvaAndSizes
+
+		let rsrc = rsrc.unwrap_or_default();
+		if !rsrc.is_empty() {
+			put_u32(&mut buf, num_dirs_off + 4 + 2 * 8, RSRC_RVA); // resource directory
+			put_u32(&mut buf, num_dirs_off + 4 + 2 * 8 + 4, rsrc.len() as _);
+		}
+
+		let sec_off = opt_off + opt_sz;
+		buf[sec_off..sec_off + 5].copy_from_slice(b".rsrc");
+		put_u32(&mut buf, sec_off + 8, rsrc.len() as _); // VirtualSize
+		put_u32(&mut buf, sec_off + 12, RSRC_RVA); // VirtualAddress
+		put_u32(&mut buf, sec_off + 16, rsrc.len() as _); // SizeOfRawData
+		put_u32(&mut buf, sec_off + 20, RSRC_PTR as _); // PointerToRawData
+
+		buf.extend_from_slice(rsrc);
+		buf
+	}
+
+	enum N {
+		Id(u16),
+		Str(&'static str),
+	}
+
+	type Langs = &'static [(u16, &'static [u8])];
+
+	/// Builds a resource section: directories first, then the name strings,
+	/// the data entries and the data itself.
+	fn rsrc(types: &[(N, &[(N, Langs)])]) -> Vec<u8> {
+		let mut buf = Vec::new();
+		let mut strs = Vec::new(); // entry offset, name
+		let mut leaves = Vec::new(); // entry offset, data
+
+		fn write_dir(buf: &mut Vec<u8>, strs: &mut Vec<(usize, &'static str)>, names: &[&N]) -> Vec<usize> {
+			let dir_off = buf.len();
+			buf.resize(dir_off + 16 + names.len() * 8, 0);
+			let num_named = names.iter().filter(|n| matches!(n, N::Str(_))).count();
+			put_u16(buf, dir_off + 12, num_named as _);
+			put_u16(buf, dir_off + 14, (names.len() - num_named) as _);
+			names.iter()
+				.enumerate()
+				.map(|(idx, name)| {
+					let entry_off = dir_off + 16 + idx * 8;
+					match name {
+						N::Id(id) => put_u32(buf, entry_off, *id as _),
+						N::Str(s) => strs.push((entry_off, *s)),
+					}
+					entry_off
+				})
+				.collect()
+		}
+
+		let type_entries = write_dir(&mut buf, &mut strs, &types.iter().map(|t| &t.0).collect::<Vec<_>>());
+		for ((_, ress), type_entry) in types.iter().zip(type_entries) {
+			let sub_off = buf.len() as u32;
+			put_u32(&mut buf, type_entry + 4, sub_off | HIGH_BIT);
+			let res_entries = write_dir(&mut buf, &mut strs, &ress.iter().map(|r| &r.0).collect::<Vec<_>>());
+			for ((_, langs), res_entry) in ress.iter().zip(res_entries) {
+				let sub_off = buf.len() as u32;
+				put_u32(&mut buf, res_entry + 4, sub_off | HIGH_BIT);
+				let lang_ids = langs.iter().map(|(lang, _)| N::Id(*lang)).collect::<Vec<_>>();
+				let lang_entries = write_dir(&mut buf, &mut strs, &lang_ids.iter().collect::<Vec<_>>());
+				leaves.extend(lang_entries.into_iter().zip(langs.iter().map(|(_, data)| *data)));
+			}
+		}
+
+		for (entry_off, s) in strs {
+			let str_off = buf.len() as u32;
+			put_u32(&mut buf, entry_off, str_off | HIGH_BIT);
+			buf.extend_from_slice(&(s.len() as u16).to_le_bytes());
+			buf.extend(s.encode_utf16().flat_map(|ch| ch.to_le_bytes()));
+		}
+		let data_entries = leaves.iter()
+			.map(|(entry_off, _)| {
+				let data_entry_off = buf.len();
+				put_u32(&mut buf, *entry_off + 4, data_entry_off as _);
+				buf.resize(data_entry_off + 16, 0); // IMAGE_RESOURCE_DATA_ENTRY
+				data_entry_off
+			})
+			.collect::<Vec<_>>();
+		for ((_, data), data_entry_off) in leaves.iter().zip(data_entries) {
+			let data_rva = RSRC_RVA + buf.len() as u32;
+			put_u32(&mut buf, data_entry_off, data_rva);
+			put_u32(&mut buf, data_entry_off + 4, data.len() as _);
+			buf.extend_from_slice(data);
+		}
+		buf
+	}
+
+	fn sample_rsrc() -> Vec<u8> {
+		rsrc(&[
+			(N::Str("CUSTOM"), &[
+				(N::Str("Data"), &[(0x0409, b"en"), (0x0416, b"pt-BR")]),
+			]),
+			(N::Id(co::RT::MANIFEST.raw()), &[
+				(N::Id(1), &[(0x0409, b"<assembly/>")]),
+			]),
+		])
+	}
+
+	fn lang(id: u16) -> LANGID {
+		unsafe { LANGID::from_raw(id) }
+	}
+
+	#[test]
+	fn parse_pe32_and_pe32_plus() {
+		for is_64 in [false, true] {
+			let file = pe(is_64, Some(&sample_rsrc()));
+			let res = PeResources::parse(&file).unwrap();
+			assert_eq!(res.iter().count(), 3);
+
+			let types = res.resource_types();
+			assert_eq!(types.len(), 2);
+			assert!(rt_eq(&types[0], &RtStr::Str(WString::from_str("custom"))));
+			assert!(rt_eq(&types[1], &RtStr::Rt(co::RT::MANIFEST)));
+
+			let custom = RtStr::Str(WString::from_str("Custom"));
+			let data = IdStr::Str(WString::from_str("DATA"));
+			let names = res.resource_names(&custom);
+			assert_eq!(names.len(), 1);
+			assert!(id_eq(&names[0], &data));
+			assert!(res.resource_languages(&custom, &data) == [lang(0x0409), lang(0x0416)]);
+
+			assert_eq!(res.find(&data, &custom, None), Some(&b"en"[..]));
+			assert_eq!(res.find(&data, &custom, Some(lang(0x0416))), Some(&b"pt-BR"[..]));
+			assert_eq!(res.find(&data, &custom, Some(lang(0x0407))), None);
+			assert_eq!(res.find(&IdStr::Id(1), &RtStr::Rt(co::RT::MANIFEST), None), Some(&b"<assembly/>"[..]));
+			assert_eq!(res.find(&IdStr::Id(2), &RtStr::Rt(co::RT::MANIFEST), None), None);
+			assert!(res.iter().all(|r| r.code_page == 0));
+		}
+	}
+
+	#[test]
+	fn parse_without_rsrc() {
+		let file = pe(false, None);
+		let res = PeResources::parse(&file).unwrap();
+		assert_eq!(res.iter().count(), 0);
+		assert!(res.resource_types().is_empty());
+	}
+
+	#[test]
+	fn parse_malformed() {
+		let bad = |data: &[u8]| matches!(PeResources::parse(data), Err(co::ERROR::BAD_EXE_FORMAT));
+
+		assert!(bad(b""));
+		assert!(bad(b"MZ"));
+		let mut file = pe(false, None);
+		file[0x40] = b'X'; // no PE signature
+		assert!(bad(&file));
+		let mut file = pe(false, None);
+		put_u32(&mut file, 0x3c, u32::MAX - 1); // e_lfanew out of bounds
+		assert!(bad(&file));
+
+		let mut truncated = sample_rsrc();
+		truncated.truncate(20); // root directory with 2 entries, but only 4 bytes of them
+		assert!(bad(&pe(true, Some(&truncated))));
+
+		let mut looping = vec![0u8; 24];
+		put_u16(&mut looping, 14, 1); // 1 ID entry
+		put_u32(&mut looping, 16, co::RT::ICON.raw() as _);
+		put_u32(&mut looping, 20, HIGH_BIT); // subdirectory is the root itself
+		assert!(bad(&pe(false, Some(&looping))));
+
+		let mut shared = sample_rsrc();
+		let first_type_dir = u32::from_le_bytes([shared[20], shared[21], shared[22], shared[23]]);
+		put_u32(&mut shared, 28, first_type_dir); // both types point to the same name directory
+		assert!(bad(&pe(false, Some(&shared))));
+
+		let mut outside = sample_rsrc();
+		let len = outside.len();
+		let data_entry_off = len - b"en".len() - b"pt-BR".len() - b"<assembly/>".len() - 3 * 16;
+		put_u32(&mut outside, data_entry_off + 4, 0x1_0000); // data size beyond the section
+		assert!(bad(&pe(false, Some(&outside))));
+	}
+}
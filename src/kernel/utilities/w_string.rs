@@ -263,14 +263,55 @@ impl WString {
 		unsafe { ffi::CharUpperW(self.as_mut_ptr()); }
 	}
 
+	/// Returns a new string with all line breaks – `\r\n`, and also lone `\r`
+	/// and `\n` – replaced by the given line ending.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*};
+	///
+	/// let wstr = w::WString::from_str("one\ntwo\r\nthree");
+	/// let crlf = wstr.normalize_line_endings(w::LineEnding::Crlf);
+	///
+	/// assert_eq!(crlf.to_string(), "one\r\ntwo\r\nthree");
+	/// ```
+	#[must_use]
+	pub fn normalize_line_endings(&self, line_ending: LineEnding) -> Self {
+		let src = self.as_slice();
+		let mut dest = Vec::with_capacity(src.len() + src.len() / 8);
+		let mut i = 0;
+		while i < src.len() && src[i] != 0x0000 {
+			match src[i] {
+				0x000d | 0x000a => {
+					if src[i] == 0x000d && src.get(i + 1) == Some(&0x000a) {
+						i += 1; // CRLF is a single line break
+					}
+					match line_ending {
+						LineEnding::Crlf => dest.extend_from_slice(&[0x000d, 0x000a]),
+						LineEnding::Lf => dest.push(0x000a),
+					}
+				},
+				ch => dest.push(ch),
+			}
+			i += 1;
+		}
+		Self::from_wchars_slice(&dest)
+	}
+
 	/// Guesses the encoding with [`Encoding::guess`](crate::Encoding::guess)
-	/// and parses the data as a string.
+	/// and parses the data as a string, with
+	/// [`Encoding::decode`](crate::Encoding::decode).
+	///
+	/// Unlike [`Encoding::decode`](crate::Encoding::decode), invalid UTF-8
+	/// sequences don't fail: they're replaced with U+FFFD, the same way
+	/// `MultiByteToWideChar` does.
 	///
 	/// If you're sure the data has UTF-8 encoding, you can also use the
 	/// built-in [`String::from_utf8`](std::string::String::from_utf8).
 	///
-	/// To serialize the string back into UTF-8 bytes, use the built-in
-	/// [`String::into_bytes`](std::string::String::into_bytes).
+	/// To serialize the string back into raw bytes, use
+	/// [`WString::serialize`](crate::WString::serialize).
 	///
 	/// # Examples
 	///
@@ -290,54 +331,72 @@ impl WString {
 	/// ```
 	#[must_use]
 	pub fn parse(data: &[u8]) -> SysResult<Self> {
-		let mut data = data;
 		if data.is_empty() { // nothing to parse
 			return Ok(Self::new());
 		}
 
 		let (encoding, sz_bom) = Encoding::guess(data);
-		data = &data[sz_bom..]; // skip BOM, if any
-
-		Ok(Self::from_wchars_slice(
-			&match encoding {
-				Encoding::Ansi => Self::parse_ansi(data),
-				Encoding::Win1252 => MultiByteToWideChar(co::CP::WINDOWS_1252, co::MBC::NoValue, data)?,
-				Encoding::Utf8 => MultiByteToWideChar(co::CP::UTF8, co::MBC::NoValue, data)?,
-				Encoding::Utf16be => Self::parse_utf16(data, true),
-				Encoding::Utf16le => Self::parse_utf16(data, false),
-				Encoding::Utf32be
-				| Encoding::Utf32le
-				| Encoding::Scsu
-				| Encoding::Bocu1
-				| Encoding::Unknown => panic!("Encoding {} not implemented.", encoding),
-			}
-		))
+		let wchars = match encoding {
+			Encoding::Utf8 => String::from_utf8_lossy(&data[sz_bom..]).encode_utf16().collect(),
+			Encoding::Bocu1 if sz_bom > 0 => { // BOM is an encoded U+FEFF, the text depends on its state
+				let mut wchars = encoding.decode(data)?;
+				wchars.remove(0);
+				wchars
+			},
+			_ => encoding.decode(&data[sz_bom..])?, // skip BOM, if any
+		};
+		Ok(Self::from_wchars_slice(&wchars)) // stops at terminating null, if any
 	}
 
-	fn parse_ansi(data: &[u8]) -> Vec<u16> {
-		data.iter()
-			.take_while(|ch| **ch != 0x0000) // ignore terminating null, if any
-			.map(|ch| *ch as u16) // raw u8 to u16 conversion
-			.collect()
-	}
+	/// Serializes the string into raw bytes with the given encoding, optionally
+	/// prepending its [BOM](https://en.wikipedia.org/wiki/Byte_order_mark). The
+	/// terminating null is not included. This is the reverse of
+	/// [`WString::parse`](crate::WString::parse).
+	///
+	/// The conversion is made with
+	/// [`Encoding::encode`](crate::Encoding::encode), so it fails if a char
+	/// cannot be represented in the chosen encoding.
+	///
+	/// # Examples
+	///
+	/// Saving a file with the same encoding it was loaded with, and with
+	/// Windows line endings:
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*};
+	///
+	/// let f = w::File::open(
+	///     "C:\\Temp\\foo.txt",
+	///     w::FileAccess::ExistingRW,
+	/// )?;
+	/// let raw_bytes = f.read_all()?;
+	/// let (encoding, sz_bom) = w::Encoding::guess(&raw_bytes);
+	/// let wstr = w::WString::parse(&raw_bytes)?;
+	///
+	/// // ... edit the text ...
+	///
+	/// let wstr = wstr.normalize_line_endings(w::LineEnding::Crlf);
+	/// f.erase_and_write(&wstr.serialize(encoding, sz_bom > 0)?)?;
+	/// # w::SysResult::Ok(())
+	/// ```
+	#[must_use]
+	pub fn serialize(&self, encoding: Encoding, with_bom: bool) -> SysResult<Vec<u8>> {
+		let wchars: Vec<u16> = self.as_slice()
+			.iter()
+			.copied()
+			.take_while(|ch| *ch != 0x0000) // ignore terminating null
+			.collect();
+		if encoding == Encoding::Bocu1 && with_bom { // BOM is an encoded U+FEFF, the text depends on its state
+			return encoding.encode(&[&[0xfeff], &wchars[..]].concat());
+		}
 
-	fn parse_utf16(data: &[u8], is_big_endian: bool) -> Vec<u16> {
-		let data = if data.len() % 2 == 1 {
-			&data[..data.len() - 1] // if odd number of bytes, discard last one
+		let mut data = if with_bom {
+			encoding.bom().to_vec()
 		} else {
-			data
+			Vec::new()
 		};
-
-		data.chunks(2)
-			.take_while(|ch2| **ch2 != [0x00, 0x00]) // ignore terminating null, if any
-			.map(|ch2| {
-				if is_big_endian {
-					u16::from_be_bytes(ch2.try_into().unwrap())
-				} else {
-					u16::from_le_bytes(ch2.try_into().unwrap())
-				}
-			})
-			.collect()
+		data.append(&mut encoding.encode(&wchars)?);
+		Ok(data)
 	}
 }
 
@@ -558,3 +617,91 @@ impl Buffer {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Strings are kept under WString::SSO_LEN chars, so no heap allocation is
+	// made, and compared as String, since WString comparison calls lstrcmpW.
+
+	#[test]
+	fn normalize_line_endings() {
+		for (src, crlf, lf) in [
+			("a\r\nb", "a\r\nb", "a\nb"), // CRLF
+			("a\rb\r", "a\r\nb\r\n", "a\nb\n"), // lone CR
+			("\na\nb", "\r\na\r\nb", "\na\nb"), // lone LF
+			("\r\r\n\n", "\r\n\r\n\r\n", "\n\n\n"), // CR, CRLF, LF
+			("\n\r", "\r\n\r\n", "\n\n"), // LF followed by CR isn't a single break
+			("no breaks", "no breaks", "no breaks"),
+			("", "", ""),
+		] {
+			let wstr = WString::from_str(src);
+			assert_eq!(wstr.normalize_line_endings(LineEnding::Crlf).to_string(), crlf, "{:?}", src);
+			assert_eq!(wstr.normalize_line_endings(LineEnding::Lf).to_string(), lf, "{:?}", src);
+		}
+	}
+
+	#[test]
+	fn serialize() {
+		let wstr = WString::from_str("añ€");
+		for (encoding, data) in [
+			(Encoding::Utf8, &b"a\xc3\xb1\xe2\x82\xac"[..]),
+			(Encoding::Utf16le, b"a\x00\xf1\x00\xac\x20"),
+			(Encoding::Utf16be, b"\x00a\x00\xf1\x20\xac"),
+			(Encoding::Utf32le, b"a\x00\x00\x00\xf1\x00\x00\x00\xac\x20\x00\x00"),
+			(Encoding::Win1252, b"a\xf1\x80"),
+		] {
+			assert_eq!(wstr.serialize(encoding, false).unwrap(), data, "{}", encoding);
+
+			let mut with_bom = encoding.bom().to_vec();
+			with_bom.extend_from_slice(data);
+			assert_eq!(wstr.serialize(encoding, true).unwrap(), with_bom, "{}", encoding);
+		}
+
+		// No BOM to write, and no terminating null.
+		assert_eq!(WString::from_str("abc").serialize(Encoding::Ansi, true).unwrap(), b"abc");
+		assert_eq!(WString::new().serialize(Encoding::Utf8, true).unwrap(), b"\xef\xbb\xbf");
+		assert!(WString::new().serialize(Encoding::Utf8, false).unwrap().is_empty());
+
+		assert_eq!(wstr.serialize(Encoding::Ansi, false), Err(co::ERROR::NO_UNICODE_TRANSLATION));
+		assert_eq!(wstr.serialize(Encoding::Unknown, false), Err(co::ERROR::INVALID_PARAMETER));
+	}
+
+	#[test]
+	fn serialize_and_parse() {
+		let wstr = WString::from_str("línea 1\r\nlínea 2");
+		for encoding in [Encoding::Utf8, Encoding::Utf16le, Encoding::Utf16be, Encoding::Utf32le] {
+			let data = wstr.serialize(encoding, true).unwrap();
+			assert_eq!(WString::parse(&data).unwrap().to_string(), wstr.to_string(), "{}", encoding);
+		}
+		let data = wstr.serialize(Encoding::Win1252, false).unwrap();
+		assert_eq!(WString::parse(&data).unwrap().to_string(), wstr.to_string());
+	}
+
+	#[test]
+	fn bocu1_bom() {
+		// Reference bytes written by ICU, whose text continues from the state
+		// of the U+FEFF encoded in the BOM.
+		let (abc, abc_icu) = ("abc", &b"\xfb\xee\x28\x24\x1e\x52\xb2\xb3"[..]);
+		let (lines, lines_icu) = ("line 1\r\nline 2",
+			&b"\xfb\xee\x28\x24\x1e\x5d\xb9\xbe\xb5\x20\x81\x0d\x0a\xbc\xb9\xbe\xb5\x20\x82"[..]);
+		let (hangul, hangul_icu) = ("\u{d55c}\u{ad6d}\u{c5b4}", &b"\xfb\xee\x28\x24\xff\xad\x3a\xcb\xd3\xd7"[..]);
+
+		for (s, icu) in [(abc, abc_icu), (lines, lines_icu), (hangul, hangul_icu)] {
+			assert_eq!(WString::from_str(s).serialize(Encoding::Bocu1, true).unwrap(), icu, "{:?}", s);
+			assert_eq!(WString::parse(icu).unwrap().to_string(), s, "{:?}", s);
+		}
+
+		// Signature recommended by UTN #6, with a reset byte after the BOM.
+		assert_eq!(WString::parse(b"\xfb\xee\x28\xff\xb1\xb2\xb3").unwrap().to_string(), "abc");
+	}
+
+	#[test]
+	fn parse_invalid_utf8() {
+		// A stray Latin-1 byte in a BOM-marked file is replaced, not an error.
+		let data = b"\xef\xbb\xbfcaf\xe9 ok";
+		assert_eq!(WString::parse(data).unwrap().to_string(), "caf\u{fffd} ok");
+		assert_eq!(Encoding::Utf8.decode(&data[3..]), Err(co::ERROR::NO_UNICODE_TRANSLATION));
+	}
+}
@@ -69,6 +69,18 @@ pub mod co {
 	//! who can be combined as bitflags also implement
 	//! [`NativeBitflag`](crate::prelude::NativeBitflag).
 	//!
+	//! The [`Debug`](std::fmt::Debug) implementation prints the symbolic names
+	//! of the values, breaking down bitflags into their parts, and the
+	//! [`FromStr`](std::str::FromStr) implementation parses them back:
+	//!
+	//! ```no_run
+	//! use winsafe::{self as w, co};
+	//!
+	//! let ws: co::WS = "WS::CHILD | WS::VISIBLE".parse()?;
+	//! println!("{:?}", ws); // WS::CHILD | WS::VISIBLE
+	//! # w::SysResult::Ok(())
+	//! ```
+	//!
 	//! Among these constant types, three are error types:
 	//! [`CDERR`], [`ERROR`] and [`HRESULT`].
 
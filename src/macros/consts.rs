@@ -87,9 +87,71 @@ macro_rules! const_impl_bitflag {
 	};
 }
 
-/// Implements Debug and Display for numeric newtype constants.
+/// Implements Debug and Display for numeric newtype constants, along with
+/// FromStr. Debug and FromStr work with the symbolic names of the values, like
+/// `WS::CHILD | WS::VISIBLE`.
+///
+/// Values declared in other modules can be named by passing their tables,
+/// created with `const_values_pub_named`, after a semicolon.
+///
+/// When many values are equal, the name of the one which isn't declared as an
+/// alias of another is used.
 macro_rules! const_impl_debug_display {
-	( $name:ident ) => {
+	(
+		$name:ident : $ntype:ty, $is_bitflag:expr;
+		$( $valname:ident = $val:expr, )*
+	) => {
+		impl $name {
+			/// Names of the values declared along with the type.
+			/// Aliases, like `Self::OTHER.0`, come after the other values.
+			pub(crate) const NAMES: &'static [(&'static str, u64)] = &crate::kernel::privs::const_aliases_last([
+				$( (stringify!($valname), Self::$valname.0 as u64, stringify!($val)), )*
+			]);
+
+			/// Names of all values, used by Debug and FromStr.
+			fn all_names() -> std::borrow::Cow<'static, [(&'static str, u64)]> {
+				std::borrow::Cow::Borrowed(Self::NAMES)
+			}
+		}
+
+		const_impl_debug_display!(@impls $name: $ntype, $is_bitflag);
+	};
+
+	(
+		$name:ident : $ntype:ty, $is_bitflag:expr;
+		$( $valname:ident = $val:expr, )*
+		;
+		$( $( #[$extra_meta:meta] )* $extra:path )*
+	) => {
+		impl $name {
+			/// Names of the values declared along with the type.
+			/// Aliases, like `Self::OTHER.0`, come after the other values.
+			pub(crate) const NAMES: &'static [(&'static str, u64)] = &crate::kernel::privs::const_aliases_last([
+				$( (stringify!($valname), Self::$valname.0 as u64, stringify!($val)), )*
+			]);
+
+			/// Names of all values, including the ones declared in other
+			/// modules, used by Debug and FromStr.
+			fn all_names() -> std::borrow::Cow<'static, [(&'static str, u64)]> {
+				let tables: &[&[(&str, $name)]] = &[
+					$( $( #[$extra_meta] )* $extra, )*
+				];
+				std::borrow::Cow::Owned(
+					Self::NAMES.iter()
+						.copied()
+						.chain(tables.iter()
+							.flat_map(|table| table.iter())
+							.map(|(name, v)| (*name, v.0 as u64)))
+						.collect(),
+				)
+			}
+		}
+
+		const_impl_debug_display!(@impls $name: $ntype, $is_bitflag);
+	};
+
+	(@impls $name:ident : $ntype:ty, $is_bitflag:expr) => {
+
 		impl std::fmt::Display for $name {
 			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 				if self.0 as usize > 0xffff {
@@ -101,13 +163,39 @@ macro_rules! const_impl_debug_display {
 		}
 		impl std::fmt::Debug for $name {
 			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-				if self.0 as usize > 0xffff {
-					write!(f, "{}({:#010x} {})",
-						stringify!($name), self.0, self.0)
-				} else {
-					write!(f, "{}({:#06x} {})",
-						stringify!($name), self.0, self.0)
+				let (parts, rest) = crate::kernel::privs::const_names_of(
+					&Self::all_names(), std::mem::size_of::<$ntype>(), self.0 as u64, $is_bitflag);
+
+				if parts.is_empty() {
+					return if self.0 as usize > 0xffff {
+						write!(f, "{}({:#010x} {})",
+							stringify!($name), self.0, self.0)
+					} else {
+						write!(f, "{}({:#06x} {})",
+							stringify!($name), self.0, self.0)
+					};
+				}
+
+				for (i, part) in parts.iter().enumerate() {
+					if i > 0 {
+						write!(f, " | ")?;
+					}
+					write!(f, "{}::{}", stringify!($name), part)?;
 				}
+				if rest != 0 {
+					write!(f, " | {:#x}", rest as $ntype)?; // bits without a name
+				}
+				Ok(())
+			}
+		}
+
+		impl std::str::FromStr for $name {
+			type Err = crate::co::ERROR;
+
+			fn from_str(s: &str) -> Result<Self, Self::Err> {
+				crate::kernel::privs::const_parse(
+					stringify!($name), &Self::all_names(), std::mem::size_of::<$ntype>(), s, $is_bitflag)
+					.map(|v| unsafe { Self::from_raw(v as $ntype) })
 			}
 		}
 	};
@@ -131,6 +219,32 @@ macro_rules! const_values_pub {
 	};
 }
 
+/// Writes multiple pub values of a numeric newtype constant, along with a
+/// pub(crate) table with the names of the values; used by the error constants,
+/// whose values are spread across many modules.
+macro_rules! const_values_pub_named {
+	(
+		$name:ident, $table:ident;
+		$(
+			$( #[$valdoc:meta] )*
+			$valname:ident $val:expr
+		)*
+	) => {
+		const_values_pub! {
+			$name;
+			$(
+				$( #[$valdoc] )*
+				$valname $val
+			)*
+		}
+
+		/// Names of the values declared in this module.
+		pub(crate) const $table: &[(&str, $name)] = &[
+			$( (stringify!($valname), $name::$valname), )*
+		];
+	};
+}
+
 /// Writes multiple pub(crate) values of a numeric newtype constant.
 macro_rules! const_values_pubcrate {
 	(
@@ -174,7 +288,10 @@ macro_rules! const_ordinary {
 			$name: $ntype;
 			$( #[$doc] )*
 		}
-		const_impl_debug_display!($name);
+		const_impl_debug_display! {
+			$name: $ntype, false;
+			$( $valname = $val, )*
+		}
 		const_values_pub! {
 			$name;
 			$(
@@ -203,7 +320,10 @@ macro_rules! const_bitflag {
 			/// This is a bitflag constant.
 		}
 		const_impl_bitflag!($name);
-		const_impl_debug_display!($name);
+		const_impl_debug_display! {
+			$name: $ntype, true;
+			$( $valname = $val, )*
+		}
 		const_values_pub! {
 			$name;
 			$(
@@ -215,7 +335,7 @@ macro_rules! const_bitflag {
 }
 
 /// Complete declaration of a constant for a window message, convertible to the
-/// co::WM.
+/// co::WM. Tables of values declared in other modules may follow a semicolon.
 macro_rules! const_wm {
 	(
 		$name:ident;
@@ -225,6 +345,10 @@ macro_rules! const_wm {
 			$( #[$valdoc:meta] )*
 			$valname:ident $val:expr
 		)*
+		$(
+			;
+			$( $( #[$extra_meta:meta] )* $extra:path )*
+		)?
 	) => {
 		const_basic_decl! {
 			$name: u32;
@@ -233,7 +357,14 @@ macro_rules! const_wm {
 			/// This is a window message, convertible to [`WM`](crate::co::WM).
 		}
 		const_impl_bitflag!($name);
-		const_impl_debug_display!($name);
+		const_impl_debug_display! {
+			$name: u32, false;
+			$( $valname = $val, )*
+			$(
+				;
+				$( $( #[$extra_meta] )* $extra )*
+			)?
+		}
 		const_values_pub! {
 			$name;
 			$(
@@ -269,7 +400,10 @@ macro_rules! const_cmd {
 			/// code, convertible to [`CMD`](crate::co::CMD).
 		}
 		const_impl_bitflag!($name);
-		const_impl_debug_display!($name);
+		const_impl_debug_display! {
+			$name: u16, false;
+			$( $valname = $val, )*
+		}
 		const_values_pub! {
 			$name;
 			$(
@@ -305,7 +439,10 @@ macro_rules! const_nm {
 			/// code, convertible to/from [`NmhdrCode`](crate::NmhdrCode).
 		}
 		const_impl_bitflag!($name);
-		const_impl_debug_display!($name);
+		const_impl_debug_display! {
+			$name: i32, false;
+			$( $valname = $val, )*
+		}
 		const_values_pub! {
 			$name;
 			$(
@@ -352,7 +489,10 @@ macro_rules! const_ws {
 			/// This is a window style, convertible to [`WS`](crate::co::WS).
 		}
 		const_impl_bitflag!($name);
-		const_impl_debug_display!($name);
+		const_impl_debug_display! {
+			$name: $ntype, true;
+			$( $valname = $val, )*
+		}
 		const_values_pub! {
 			$name;
 			$(
@@ -393,7 +533,10 @@ macro_rules! const_wsex {
 			/// [`WS_EX`](crate::co::WS_EX).
 		}
 		const_impl_bitflag!($name);
-		const_impl_debug_display!($name);
+		const_impl_debug_display! {
+			$name: u32, true;
+			$( $valname = $val, )*
+		}
 		const_values_pub! {
 			$name;
 			$(
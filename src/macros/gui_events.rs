@@ -7,13 +7,13 @@ macro_rules! pub_fn_wm_noparm_noret {
 		$( #[$doc:meta] )*
 	) => {
 		$( #[$doc] )*
-		pub fn $name<F>(&self, func: F)
+		pub fn $name<F>(&self, func: F) -> crate::gui::events::Subscription
 			where F: Fn() -> AnyResult<()> + 'static,
 		{
 			self.wm($wmconst, move |_| {
 				func()?;
 				Ok(crate::gui::WmRet::HandledOk)
-			});
+			})
 		}
 	};
 }
@@ -25,13 +25,13 @@ macro_rules! pub_fn_wm_noparm_boolret {
 		$( #[$doc:meta] )*
 	) => {
 		$( #[$doc] )*
-		pub fn $name<F>(&self, func: F)
+		pub fn $name<F>(&self, func: F) -> crate::gui::events::Subscription
 			where F: Fn() -> AnyResult<bool> + 'static,
 		{
 			self.wm($wmconst, move |_| {
 				let ret_val = func()? as isize;
 				Ok(crate::gui::WmRet::HandledWithRet(ret_val))
-			});
+			})
 		}
 	};
 }
@@ -43,13 +43,13 @@ macro_rules! pub_fn_wm_withparm_noret {
 		$( #[$doc:meta] )*
 	) => {
 		$( #[$doc] )*
-		pub fn $name<F>(&self, func: F)
+		pub fn $name<F>(&self, func: F) -> crate::gui::events::Subscription
 			where F: Fn($parm) -> AnyResult<()> + 'static,
 		{
 			self.wm($wmconst, move |p| {
 				func(unsafe { <$parm>::from_generic_wm(p) })?;
 				Ok(crate::gui::WmRet::HandledOk)
-			});
+			})
 		}
 	};
 }
@@ -61,13 +61,13 @@ macro_rules! pub_fn_wm_withparm_boolret {
 		$( #[$doc:meta] )*
 	) => {
 		$( #[$doc] )*
-		pub fn $name<F>(&self, func: F)
+		pub fn $name<F>(&self, func: F) -> crate::gui::events::Subscription
 			where F: Fn($parm) -> AnyResult<bool> + 'static,
 		{
 			self.wm($wmconst, move |p| {
 				let ret_val = func(unsafe { <$parm>::from_generic_wm(p) })? as isize;
 				Ok(crate::gui::WmRet::HandledWithRet(ret_val))
-			});
+			})
 		}
 	};
 }
@@ -79,13 +79,13 @@ macro_rules! pub_fn_wm_withparm_coret {
 		$( #[$doc:meta] )*
 	) => {
 		$( #[$doc] )*
-		pub fn $name<F>(&self, func: F)
+		pub fn $name<F>(&self, func: F) -> crate::gui::events::Subscription
 			where F: Fn($parm) -> AnyResult<$coret> + 'static,
 		{
 			self.wm($wmconst, move |p| {
 				let ret_val = func(unsafe { <$parm>::from_generic_wm(p) })?.raw() as isize;
 				Ok(crate::gui::WmRet::HandledWithRet(ret_val))
-			});
+			})
 		}
 	};
 }
@@ -97,13 +97,13 @@ macro_rules! pub_fn_wm_ctlcolor {
 		$( #[$doc:meta] )*
 	) => {
 		$( #[$doc] )*
-		pub fn $name<F>(&self, func: F)
+		pub fn $name<F>(&self, func: F) -> crate::gui::events::Subscription
 			where F: Fn($parm) -> AnyResult<crate::user::decl::HBRUSH> + 'static,
 		{
 			self.wm($wmconst, move |p| {
 				let ret_val = func(unsafe { <$parm>::from_generic_wm(p) })?.ptr() as isize;
 				Ok(crate::gui::WmRet::HandledWithRet(ret_val))
-			});
+			})
 		}
 	};
 }
@@ -115,13 +115,13 @@ macro_rules! pub_fn_cmd_noparm_noret {
 		$( #[$doc:meta] )*
 	) => {
 		$( #[$doc] )*
-		pub fn $name<F>(&self, func: F)
+		pub fn $name<F>(&self, func: F) -> crate::gui::events::Subscription
 			where F: Fn() -> AnyResult<()> + 'static,
 		{
 			self.0.wm_command($cmd, move || {
 				func()?;
 				Ok(crate::gui::WmRet::HandledOk)
-			});
+			})
 		}
 	};
 }
@@ -133,13 +133,13 @@ macro_rules! pub_fn_nfy_noparm_noret {
 		$( #[$doc:meta] )*
 	) => {
 		$( #[$doc] )*
-		pub fn $name<F>(&self, func: F)
+		pub fn $name<F>(&self, func: F) -> crate::gui::events::Subscription
 			where F: Fn() -> AnyResult<()> + 'static,
 		{
 			self.0.wm_notify($nfy, move |_| {
 				func()?;
 				Ok(crate::gui::WmRet::HandledOk)
-			});
+			})
 		}
 	};
 }
@@ -151,13 +151,13 @@ macro_rules! pub_fn_nfy_withparm_noret {
 		$( #[$doc:meta] )*
 	) => {
 		$( #[$doc] )*
-		pub fn $name<F>(&self, func: F)
+		pub fn $name<F>(&self, func: F) -> crate::gui::events::Subscription
 			where F: Fn(&$param) -> AnyResult<()> + 'static,
 		{
 			self.0.wm_notify($nfy, move |p| {
 				func(unsafe { p.cast_nmhdr::<$param>() })?;
 				Ok(crate::gui::WmRet::HandledOk)
-			});
+			})
 		}
 	};
 }
@@ -169,13 +169,13 @@ macro_rules! pub_fn_nfy_withmutparm_noret {
 		$( #[$doc:meta] )*
 	) => {
 		$( #[$doc] )*
-		pub fn $name<F>(&self, func: F)
+		pub fn $name<F>(&self, func: F) -> crate::gui::events::Subscription
 			where F: Fn(&mut $param) -> AnyResult<()> + 'static,
 		{
 			self.0.wm_notify($nfy, move |p| {
 				func(unsafe { p.cast_nmhdr_mut::<$param>() })?;
 				Ok(crate::gui::WmRet::HandledOk)
-			});
+			})
 		}
 	};
 }
@@ -187,13 +187,13 @@ macro_rules! pub_fn_nfy_noparm_boolret {
 		$( #[$doc:meta] )*
 	) => {
 		$( #[$doc] )*
-		pub fn $name<F>(&self, func: F)
+		pub fn $name<F>(&self, func: F) -> crate::gui::events::Subscription
 			where F: Fn() -> AnyResult<bool> + 'static,
 		{
 			self.0.wm_notify($nfy, move |_| {
 				let ret_val = func()? as isize;
 				Ok(crate::gui::WmRet::HandledWithRet(ret_val))
-			});
+			})
 		}
 	};
 }
@@ -205,13 +205,13 @@ macro_rules! pub_fn_nfy_withparm_boolret {
 		$( #[$doc:meta] )*
 	) => {
 		$( #[$doc] )*
-		pub fn $name<F>(&self, func: F)
+		pub fn $name<F>(&self, func: F) -> crate::gui::events::Subscription
 			where F: Fn(&$param) -> AnyResult<bool> + 'static,
 		{
 			self.0.wm_notify($nfy, move |p| {
 				let ret_val = func(unsafe { p.cast_nmhdr::<$param>() })? as isize;
 				Ok(crate::gui::WmRet::HandledWithRet(ret_val))
-			});
+			})
 		}
 	};
 }
@@ -223,13 +223,13 @@ macro_rules! pub_fn_nfy_noparm_i32ret {
 		$( #[$doc:meta] )*
 	) => {
 		$( #[$doc] )*
-		pub fn $name<F>(&self, func: F)
+		pub fn $name<F>(&self, func: F) -> crate::gui::events::Subscription
 			where F: Fn() -> AnyResult<i32> + 'static,
 		{
 			self.0.wm_notify($nfy, move |_| {
 				let ret_val = func()? as isize;
 				Ok(crate::gui::WmRet::HandledWithRet(ret_val))
-			});
+			})
 		}
 	};
 }
@@ -241,13 +241,13 @@ macro_rules! pub_fn_nfy_withparm_i32ret {
 		$( #[$doc:meta] )*
 	) => {
 		$( #[$doc] )*
-		pub fn $name<F>(&self, func: F)
+		pub fn $name<F>(&self, func: F) -> crate::gui::events::Subscription
 			where F: Fn(&$param) -> AnyResult<i32> + 'static,
 		{
 			self.0.wm_notify($nfy, move |p| {
 				let ret_val = func(unsafe { p.cast_nmhdr::<$param>() })? as isize;
 				Ok(crate::gui::WmRet::HandledWithRet(ret_val))
-			});
+			})
 		}
 	};
 }
@@ -33,6 +33,11 @@ const_basic_decl! { HRESULT: u32;
 	/// The numbers inside the brackets are the system error code, in
 	/// hexadecimal and decimal formats for convenience.
 	///
+	/// Since [`FormatMessage`](crate::prelude::FormattedError::FormatMessage)
+	/// depends on the system and its UI language, a symbolic description can
+	/// be retrieved with [`describe`](crate::co::HRESULT::describe), which
+	/// uses built-in tables.
+	///
 	/// An [`ERROR`](crate::co::ERROR) can be seamlessly converted into an
 	/// `HRESULT`. This operation is equivalent to the
 	/// [`HRESULT_FROM_WIN32`](https://learn.microsoft.com/en-us/windows/win32/api/winerror/nf-winerror-hresult_from_win32)
@@ -64,18 +69,45 @@ impl std::fmt::Display for HRESULT {
 }
 impl std::fmt::Debug for HRESULT {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.name() {
+			Some(name) => write!(f, "HRESULT::{}", name)?,
+			None => write!(f, "HRESULT")?,
+		}
 		if self.0 > 0xffff {
-			write!(f, "HRESULT({:#010x} {}) {}",
+			write!(f, "({:#010x} {}) {}",
 				self.0, self.0, self.FormatMessage().trim_end())
 		} else {
-			write!(f, "HRESULT({:#06x} {}) {}",
+			write!(f, "({:#06x} {}) {}",
 				self.0, self.0, self.FormatMessage().trim_end())
 		}
 	}
 }
 
+impl std::str::FromStr for HRESULT {
+	type Err = co::ERROR;
+
+	/// Parses the name of a constant, like `E_ACCESSDENIED` or
+	/// `co::HRESULT::E_ACCESSDENIED`, or a number.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let names = HRESULT_TABLES.iter()
+			.flat_map(|table| table.iter())
+			.map(|(name, v)| (*name, v.0 as u64))
+			.collect::<Vec<_>>();
+		crate::kernel::privs::const_parse("HRESULT", &names, std::mem::size_of::<u32>(), s, false)
+			.map(|v| unsafe { Self::from_raw(v as _) })
+	}
+}
+
 impl FormattedError for HRESULT {}
 
+/// Names of the values declared in all modules.
+const HRESULT_TABLES: &[&[(&str, HRESULT)]] = &[
+	HRESULT_NAMES,
+	#[cfg(feature = "dshow")] co::HRESULT_NAMES_DSHOW,
+	#[cfg(feature = "dxgi")] co::HRESULT_NAMES_DXGI,
+	#[cfg(feature = "mf")] co::HRESULT_NAMES_MF,
+];
+
 impl co::ERROR {
 	/// [`HRESULT_FROM_WIN32`](https://learn.microsoft.com/en-us/windows/win32/api/winerror/nf-winerror-hresult_from_win32)
 	/// macro.
@@ -112,9 +144,65 @@ impl HRESULT {
 	pub const fn severity(self) -> co::SEVERITY {
 		unsafe { co::SEVERITY::from_raw(((self.0 >> 31) & 0x1) as _) }
 	}
+
+	/// Returns the name of the constant, taken from a built-in table, without
+	/// calling [`FormatMessage`](crate::prelude::FormattedError::FormatMessage).
+	///
+	/// Returns `None` if the value is not declared in this library.
+	#[must_use]
+	pub fn name(self) -> Option<&'static str> {
+		HRESULT_TABLES.iter()
+			.flat_map(|table| table.iter())
+			.find(|(_, v)| *v == self)
+			.map(|(name, _)| *name)
+	}
+
+	/// Returns a symbolic description of the error, with its name, facility
+	/// and code, taken from built-in tables. Unlike
+	/// [`FormatMessage`](crate::prelude::FormattedError::FormatMessage), the
+	/// result doesn't depend on the system or its UI language.
+	///
+	/// Unknown parts are written as numbers. The severity is not written; use
+	/// [`severity`](crate::co::HRESULT::severity) to retrieve it.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let hr = co::HRESULT::E_ACCESSDENIED;
+	///
+	/// assert_eq!(hr.describe(),
+	///     "E_ACCESSDENIED (FACILITY_WIN32, ERROR_ACCESS_DENIED)");
+	/// ```
+	#[must_use]
+	pub fn describe(self) -> String {
+		let facility = self.facility();
+		let facility_name = co::FACILITY::NAMES.iter()
+			.find(|(_, v)| *v == facility.raw() as u64)
+			.map(|(name, _)| *name);
+
+		let code_name = if facility == co::FACILITY::WIN32 {
+			unsafe { co::ERROR::from_raw(self.code() as _) }.name()
+		} else {
+			None
+		};
+
+		format!("{} ({}, {})",
+			self.name().map_or_else(|| format!("{:#010x}", self.0), |n| n.to_owned()),
+			facility_name.map_or_else(
+				|| format!("FACILITY {}", facility.raw()),
+				|n| format!("FACILITY_{}", n),
+			),
+			code_name.map_or_else(
+				|| format!("{:#06x}", self.code()),
+				|n| format!("ERROR_{}", n),
+			),
+		)
+	}
 }
 
-const_values_pub! { HRESULT;
+const_values_pub_named! { HRESULT, HRESULT_NAMES;
 	/// Operation successful.
 	S_OK 0
 	S_FALSE 1
@@ -3337,3 +3425,30 @@ const_values_pub! { HRESULT;
 	WINML_ERR_VALUE_NOTFOUND 0x8890_0003
 	WINML_ERR_SIZE_MISMATCH 0x8890_0004
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn describe() {
+		assert_eq!(co::HRESULT::E_ACCESSDENIED.describe(),
+			"E_ACCESSDENIED (FACILITY_WIN32, ERROR_ACCESS_DENIED)");
+		assert_eq!(co::HRESULT::S_FALSE.describe(),
+			"S_FALSE (FACILITY_NULL, 0x0001)");
+		assert_eq!(unsafe { co::HRESULT::from_raw(0x8fff_1234) }.describe(),
+			"0x8fff1234 (FACILITY 4095, 0x1234)");
+	}
+
+	#[test]
+	fn from_str() {
+		for hr in [co::HRESULT::S_OK, co::HRESULT::E_ACCESSDENIED, co::HRESULT::WINML_ERR_SIZE_MISMATCH] {
+			let name = hr.name().unwrap();
+			assert_eq!(name.parse::<co::HRESULT>().unwrap(), hr);
+			assert_eq!(format!("co::HRESULT::{}", name).parse::<co::HRESULT>().unwrap(), hr);
+		}
+		assert_eq!("0x80070005".parse::<co::HRESULT>().unwrap(), co::HRESULT::E_ACCESSDENIED);
+		assert_eq!("HRESULT::E_NOT_A_NAME".parse::<co::HRESULT>(), Err(co::ERROR::INVALID_DATA));
+		assert_eq!("E_FAIL | E_ABORT".parse::<co::HRESULT>(), Err(co::ERROR::INVALID_DATA));
+	}
+}
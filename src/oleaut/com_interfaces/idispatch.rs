@@ -1,9 +1,13 @@
 #![allow(non_camel_case_types, non_snake_case)]
 
+use std::mem::ManuallyDrop;
+
 use crate::co;
 use crate::decl::*;
+use crate::kernel::ffi_types::*;
 use crate::kernel::privs::*;
 use crate::ole::privs::*;
+use crate::oleaut::privs::*;
 use crate::oleaut::vts::*;
 use crate::prelude::*;
 
@@ -52,6 +56,182 @@ pub trait oleaut_IDispatch: ole_IUnknown {
 		).map(|_| ids)
 	}
 
+	/// [`IDispatch::Invoke`](https://learn.microsoft.com/en-us/windows/win32/api/oaidl/nf-oaidl-idispatch-invoke)
+	/// method.
+	///
+	/// The arguments are passed in their natural order, and they're not
+	/// modified. The `named_args` are the `DISPID`s of the named arguments,
+	/// which are the last ones: `named_args[0]` names the last element of
+	/// `args`, `named_args[1]` the one before it, and so on.
+	///
+	/// If the server raises an exception, the returned
+	/// [`DispatchError`](crate::DispatchError) will carry its information.
+	///
+	/// Prefer using the higher-level
+	/// [`invoke_method`](crate::prelude::oleaut_IDispatch::invoke_method),
+	/// [`get_property`](crate::prelude::oleaut_IDispatch::get_property),
+	/// [`put_property`](crate::prelude::oleaut_IDispatch::put_property) and
+	/// [`put_ref_property`](crate::prelude::oleaut_IDispatch::put_ref_property)
+	/// methods.
+	fn Invoke(&self,
+		disp_id: i32,
+		lcid: LCID,
+		flags: co::DISPATCH,
+		args: &[VARIANT],
+		named_args: &[i32],
+	) -> Result<VARIANT, DispatchError>
+	{
+		// Invoke expects the arguments in reverse order; the bitwise copies
+		// won't be dropped, since the original objects keep the ownership.
+		let mut rgvarg = args.iter()
+			.rev()
+			.map(|arg| ManuallyDrop::new(unsafe { std::ptr::read(arg) }))
+			.collect::<Vec<_>>();
+		let mut named_args = named_args.to_vec();
+
+		let mut params = DISPPARAMS {
+			rgvarg: if rgvarg.is_empty() {
+				std::ptr::null_mut()
+			} else {
+				rgvarg.as_mut_ptr() as _
+			},
+			rgdispidNamedArgs: if named_args.is_empty() {
+				std::ptr::null_mut()
+			} else {
+				named_args.as_mut_ptr()
+			},
+			cArgs: rgvarg.len() as _,
+			cNamedArgs: named_args.len() as _,
+		};
+
+		let is_put = flags.has(co::DISPATCH::PROPERTYPUT)
+			|| flags.has(co::DISPATCH::PROPERTYPUTREF);
+		let mut result = VARIANT::default();
+		let mut excep_info = EXCEPINFO::default();
+		let mut arg_err = u32::MAX;
+
+		let hr = unsafe {
+			co::HRESULT::from_raw(
+				(vt::<IDispatchVT>(self).Invoke)(
+					self.ptr(),
+					disp_id,
+					&co::IID::default() as *const _ as _,
+					lcid.into(),
+					flags.raw(),
+					&mut params as *mut _ as _,
+					if is_put { std::ptr::null_mut() } else { &mut result as *mut _ as _ },
+					&mut excep_info as *mut _ as _,
+					&mut arg_err,
+				) as _,
+			)
+		};
+
+		if hr == co::HRESULT::S_OK {
+			return Ok(result);
+		}
+
+		let mut err = DispatchError::new(format!("DISPID {}", disp_id), hr);
+		if hr == co::HRESULT::DISP_E_EXCEPTION {
+			if !excep_info.pfnDeferredFillIn.is_null() {
+				let fill_in: extern "system" fn(*mut EXCEPINFO) -> HRES =
+					unsafe { std::mem::transmute(excep_info.pfnDeferredFillIn) };
+				fill_in(&mut excep_info); // ignore errors
+			}
+			err.code = excep_info.wCode;
+			err.scode = excep_info.scode;
+			err.source = excep_info.bstrSource.to_string();
+			err.description = excep_info.bstrDescription.to_string();
+			err.help_file = excep_info.bstrHelpFile.to_string();
+			err.help_context = excep_info.dwHelpContext;
+		} else if (hr == co::HRESULT::DISP_E_TYPEMISMATCH
+			|| hr == co::HRESULT::DISP_E_PARAMNOTFOUND)
+			&& (arg_err as usize) < args.len()
+		{
+			err.arg_index = Some((args.len() - 1 - arg_err as usize) as _); // back to natural order
+		}
+		Err(err)
+	}
+
+	/// Calls a method by its name, with
+	/// [`IDispatch::Invoke`](crate::prelude::oleaut_IDispatch::Invoke) and
+	/// [`co::DISPATCH::METHOD`](crate::co::DISPATCH::METHOD). If the method
+	/// returns nothing, the returned `VARIANT` is
+	/// [`co::VT::EMPTY`](crate::co::VT::EMPTY).
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let _com_lib = w::CoInitializeEx(
+	///     co::COINIT::APARTMENTTHREADED)?;
+	///
+	/// let shell = w::CoCreateInstance::<w::IDispatch>(
+	///     &w::CLSIDFromProgID("WScript.Shell")?,
+	///     None,
+	///     co::CLSCTX::INPROC_SERVER,
+	/// )?;
+	///
+	/// let path = shell.invoke_method(
+	///     "ExpandEnvironmentStrings",
+	///     &[w::VARIANT::new_bstr("%WINDIR%")?],
+	/// )?;
+	/// println!("{}", path.bstr().unwrap());
+	/// # w::AnyResult::Ok(())
+	/// ```
+	fn invoke_method(&self,
+		name: &str,
+		args: &[VARIANT],
+	) -> Result<VARIANT, DispatchError>
+	{
+		invoke_by_name(self, name, co::DISPATCH::METHOD, args, &[])
+	}
+
+	/// Retrieves a property by its name, with
+	/// [`IDispatch::Invoke`](crate::prelude::oleaut_IDispatch::Invoke) and
+	/// [`co::DISPATCH::PROPERTYGET`](crate::co::DISPATCH::PROPERTYGET).
+	///
+	/// Indexed properties receive their indexes in `args`; ordinary
+	/// properties receive an empty slice.
+	fn get_property(&self,
+		name: &str,
+		args: &[VARIANT],
+	) -> Result<VARIANT, DispatchError>
+	{
+		invoke_by_name(self, name, co::DISPATCH::PROPERTYGET, args, &[])
+	}
+
+	/// Sets a property by its name, with
+	/// [`IDispatch::Invoke`](crate::prelude::oleaut_IDispatch::Invoke) and
+	/// [`co::DISPATCH::PROPERTYPUT`](crate::co::DISPATCH::PROPERTYPUT).
+	///
+	/// Indexed properties receive their indexes in `args`; ordinary
+	/// properties receive an empty slice.
+	fn put_property(&self,
+		name: &str,
+		args: &[VARIANT],
+		value: &VARIANT,
+	) -> Result<(), DispatchError>
+	{
+		put_by_name(self, name, co::DISPATCH::PROPERTYPUT, args, value)
+	}
+
+	/// Sets a property by its name, passing the value by reference, with
+	/// [`IDispatch::Invoke`](crate::prelude::oleaut_IDispatch::Invoke) and
+	/// [`co::DISPATCH::PROPERTYPUTREF`](crate::co::DISPATCH::PROPERTYPUTREF).
+	/// Used to assign objects, like the `Set` statement of Visual Basic.
+	///
+	/// Indexed properties receive their indexes in `args`; ordinary
+	/// properties receive an empty slice.
+	fn put_ref_property(&self,
+		name: &str,
+		args: &[VARIANT],
+		value: &VARIANT,
+	) -> Result<(), DispatchError>
+	{
+		put_by_name(self, name, co::DISPATCH::PROPERTYPUTREF, args, value)
+	}
+
 	/// [`IDispatch::GetTypeInfoCount`](https://learn.microsoft.com/en-us/windows/win32/api/oaidl/nf-oaidl-idispatch-gettypeinfocount)
 	/// method.
 	#[must_use]
@@ -81,3 +261,41 @@ pub trait oleaut_IDispatch: ole_IUnknown {
 		).map(|_| queried)
 	}
 }
+
+/// Retrieves the `DISPID` of the member, then calls
+/// [`IDispatch::Invoke`](crate::prelude::oleaut_IDispatch::Invoke).
+fn invoke_by_name(
+	obj: &impl oleaut_IDispatch,
+	name: &str,
+	flags: co::DISPATCH,
+	args: &[VARIANT],
+	named_args: &[i32],
+) -> Result<VARIANT, DispatchError>
+{
+	let disp_id = obj.GetIDsOfNames(&[name], LCID::USER_DEFAULT)
+		.map_err(|hr| DispatchError::new(name, hr))?[0];
+	obj.Invoke(disp_id, LCID::USER_DEFAULT, flags, args, named_args)
+		.map_err(|err| DispatchError { member: name.to_owned(), ..err })
+}
+
+/// Calls [`IDispatch::Invoke`](crate::prelude::oleaut_IDispatch::Invoke) with
+/// the value as the named `DISPID_PROPERTYPUT` argument, as required by
+/// property puts.
+fn put_by_name(
+	obj: &impl oleaut_IDispatch,
+	name: &str,
+	flags: co::DISPATCH,
+	args: &[VARIANT],
+	value: &VARIANT,
+) -> Result<(), DispatchError>
+{
+	let all_args = args.iter()
+		.chain(std::iter::once(value))
+		.map(|arg| ManuallyDrop::new(unsafe { std::ptr::read(arg) })) // bitwise copies, won't be dropped
+		.collect::<Vec<_>>();
+	let all_args = unsafe {
+		std::slice::from_raw_parts(all_args.as_ptr() as *const VARIANT, all_args.len())
+	};
+	invoke_by_name(obj, name, flags, all_args, &[DISPID_PROPERTYPUT])
+		.map(|_| ())
+}
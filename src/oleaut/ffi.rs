@@ -9,6 +9,17 @@ extern_sys! { "ole32";
 extern_sys! { "oleaut32";
 	OleLoadPicture(COMPTR, i32, BOOL, PCVOID, *mut COMPTR) -> HRES
 	OleLoadPicturePath(PCSTR, COMPTR, u32, u32, PCVOID, *mut COMPTR) -> HRES
+	SafeArrayCopy(PVOID, *mut PVOID) -> HRES
+	SafeArrayCreate(u16, u32, PCVOID) -> PVOID
+	SafeArrayCreateVector(u16, i32, u32) -> PVOID
+	SafeArrayDestroy(PVOID) -> HRES
+	SafeArrayGetDim(PVOID) -> u32
+	SafeArrayGetElement(PVOID, *const i32, PVOID) -> HRES
+	SafeArrayGetElemsize(PVOID) -> u32
+	SafeArrayGetLBound(PVOID, u32, *mut i32) -> HRES
+	SafeArrayGetUBound(PVOID, u32, *mut i32) -> HRES
+	SafeArrayGetVartype(PVOID, *mut u16) -> HRES
+	SafeArrayPutElement(PVOID, *const i32, PCVOID) -> HRES
 	SysAllocString(PCSTR) -> PSTR
 	SysFreeString(PSTR)
 	SysReAllocString(PSTR, PCSTR) -> PSTR
@@ -1,3 +1,4 @@
 const_values_num_privs! {
+	DISPID_PROPERTYPUT i32 = -3
 	PID_FIRST_USABLE u32 = 0x2
 }
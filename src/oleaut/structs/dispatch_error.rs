@@ -0,0 +1,113 @@
+use crate::co;
+
+/// Error returned by [`IDispatch`](crate::IDispatch) automation calls, like
+/// [`invoke_method`](crate::prelude::oleaut_IDispatch::invoke_method), carrying
+/// the
+/// [`EXCEPINFO`](https://learn.microsoft.com/en-us/windows/win32/api/oaidl/ns-oaidl-excepinfo)
+/// filled by the server, if any.
+///
+/// Implements the standard [`Error`](std::error::Error) trait, and can be
+/// converted into an [`HRESULT`](crate::co::HRESULT).
+///
+/// # Examples
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*};
+///
+/// let excel: w::IDispatch; // initialized somewhere
+/// # let excel = unsafe { w::IDispatch::null() };
+///
+/// match excel.get_property("Foo", &[]) {
+///     Ok(val) => println!("{:?}", val.vt()),
+///     Err(e) => println!("{} failed: {}, {}", e.member, e.source, e.description),
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct DispatchError {
+	/// Name of the member being invoked, or its `DISPID` if the name is
+	/// unknown.
+	pub member: String,
+	/// The error returned by the call. If the server raised an exception, this
+	/// is [`DISP_E_EXCEPTION`](crate::co::HRESULT::DISP_E_EXCEPTION), and the
+	/// remaining fields describe it.
+	pub hresult: co::HRESULT,
+	/// `EXCEPINFO::wCode`, the application error code.
+	pub code: u16,
+	/// `EXCEPINFO::scode`, the error code of the exception.
+	pub scode: co::HRESULT,
+	/// `EXCEPINFO::bstrSource`, usually the application name.
+	pub source: String,
+	/// `EXCEPINFO::bstrDescription`, the description of the error.
+	pub description: String,
+	/// `EXCEPINFO::bstrHelpFile`, the path of the help file.
+	pub help_file: String,
+	/// `EXCEPINFO::dwHelpContext`, the help context ID.
+	pub help_context: u32,
+	/// Zero-based index of the argument which caused
+	/// [`DISP_E_TYPEMISMATCH`](crate::co::HRESULT::DISP_E_TYPEMISMATCH) or
+	/// [`DISP_E_PARAMNOTFOUND`](crate::co::HRESULT::DISP_E_PARAMNOTFOUND).
+	pub arg_index: Option<u32>,
+}
+
+impl std::error::Error for DispatchError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		None
+	}
+}
+
+impl std::fmt::Display for DispatchError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}: ", self.member)?;
+		if self.hresult == co::HRESULT::DISP_E_EXCEPTION {
+			if !self.source.is_empty() {
+				write!(f, "[{}] ", self.source)?;
+			}
+			if self.description.is_empty() {
+				write!(f, "{}", self.scode)?;
+			} else {
+				write!(f, "{}", self.description.trim_end())?;
+			}
+		} else {
+			write!(f, "{}", self.hresult)?;
+		}
+		if let Some(arg_index) = self.arg_index {
+			write!(f, " (argument {})", arg_index)?;
+		}
+		Ok(())
+	}
+}
+impl std::fmt::Debug for DispatchError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		std::fmt::Display::fmt(self, f)
+	}
+}
+
+impl From<DispatchError> for co::HRESULT {
+	/// Returns the error code of the exception, if any, otherwise the error
+	/// returned by the call.
+	fn from(err: DispatchError) -> Self {
+		if err.hresult == co::HRESULT::DISP_E_EXCEPTION && err.scode != co::HRESULT::S_OK {
+			err.scode
+		} else {
+			err.hresult
+		}
+	}
+}
+
+impl DispatchError {
+	/// Creates a new error without exception information.
+	#[must_use]
+	pub(in crate::oleaut) fn new(member: impl Into<String>, hresult: co::HRESULT) -> Self {
+		Self {
+			member: member.into(),
+			hresult,
+			code: 0,
+			scode: co::HRESULT::S_OK,
+			source: String::new(),
+			description: String::new(),
+			help_file: String::new(),
+			help_context: 0,
+			arg_index: None,
+		}
+	}
+}
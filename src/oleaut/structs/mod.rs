@@ -1,16 +1,21 @@
 mod bstr;
+mod dispatch_error;
 mod others;
 mod propvariant;
+mod safearray;
 mod variant;
 mod variant_traits;
 
 pub mod decl {
 	pub use super::bstr::BSTR;
+	pub use super::dispatch_error::DispatchError;
 	pub use super::others::*;
 	pub use super::propvariant::PROPVARIANT;
+	pub use super::safearray::SAFEARRAY;
 	pub use super::variant::VARIANT;
 }
 
 pub mod traits {
+	pub use super::safearray::SafeArrayElement;
 	pub use super::variant_traits::*;
 }
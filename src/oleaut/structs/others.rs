@@ -1,6 +1,37 @@
+#![allow(non_snake_case)]
+
+use crate::co;
 use crate::decl::*;
+use crate::kernel::ffi_types::*;
 use crate::oleaut::privs::*;
 
+/// [`DISPPARAMS`](https://learn.microsoft.com/en-us/windows/win32/api/oaidl/ns-oaidl-dispparams)
+/// struct.
+#[repr(C)]
+pub(crate) struct DISPPARAMS {
+	pub(crate) rgvarg: *mut VARIANT,
+	pub(crate) rgdispidNamedArgs: *mut i32,
+	pub(crate) cArgs: u32,
+	pub(crate) cNamedArgs: u32,
+}
+
+/// [`EXCEPINFO`](https://learn.microsoft.com/en-us/windows/win32/api/oaidl/ns-oaidl-excepinfo)
+/// struct.
+#[repr(C)]
+pub(crate) struct EXCEPINFO {
+	pub(crate) wCode: u16,
+	pub(crate) wReserved: u16,
+	pub(crate) bstrSource: BSTR,
+	pub(crate) bstrDescription: BSTR,
+	pub(crate) bstrHelpFile: BSTR,
+	pub(crate) dwHelpContext: u32,
+	pub(crate) pvReserved: PVOID,
+	pub(crate) pfnDeferredFillIn: PVOID,
+	pub(crate) scode: co::HRESULT,
+}
+
+impl_default!(EXCEPINFO);
+
 /// [`PROPERTYKEY`](https://learn.microsoft.com/en-us/windows/win32/api/wtypes/ns-wtypes-propertykey)
 /// struct.
 #[repr(C)]
@@ -20,3 +51,12 @@ impl PROPERTYKEY {
 		Self { fmtid, pid: PID_FIRST_USABLE }
 	}
 }
+
+/// [`SAFEARRAYBOUND`](https://learn.microsoft.com/en-us/windows/win32/api/oaidl/ns-oaidl-safearraybound)
+/// struct.
+#[repr(C)]
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub struct SAFEARRAYBOUND {
+	pub cElements: u32,
+	pub lLbound: i32,
+}
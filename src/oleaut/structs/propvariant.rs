@@ -1,6 +1,7 @@
 #![allow(non_snake_case)]
 
 use crate::co;
+use crate::decl::*;
 use crate::oleaut::ffi;
 use crate::prelude::*;
 
@@ -55,6 +56,36 @@ impl oleaut_Variant for PROPVARIANT {
 	}
 }
 
+/// Implements the `new_vector_*` and `vector_*` methods of
+/// [`PROPVARIANT`](crate::PROPVARIANT) for plain `Copy` element types.
+macro_rules! pub_fn_vector {
+	(
+		$( $newfn:ident, $getfn:ident, $ntype:ty, $vt:ident )*
+	) => {
+		$(
+			#[doc = concat!("Creates a new object holding a vector of `", stringify!($ntype), "` values, that is, ")]
+			#[doc = concat!("[`co::VT::VECTOR`](crate::co::VT::VECTOR) | [`co::VT::", stringify!($vt), "`](crate::co::VT::", stringify!($vt), ").")]
+			#[must_use]
+			pub fn $newfn(vals: &[$ntype]) -> HrResult<Self> {
+				let pelems = Self::alloc_vector(std::mem::size_of_val(vals))?;
+				if !vals.is_empty() {
+					unsafe {
+						std::ptr::copy_nonoverlapping(vals.as_ptr(), pelems as *mut $ntype, vals.len());
+					}
+				}
+				Ok(unsafe { Self::new_vector(co::VT::$vt, vals.len(), pelems) })
+			}
+
+			#[doc = concat!("If the object holds a vector of `", stringify!($ntype), "` values, returns them, otherwise `None`.")]
+			#[must_use]
+			pub fn $getfn(&self) -> Option<Vec<$ntype>> {
+				unsafe { self.vector_slice::<$ntype>(co::VT::$vt) }
+					.map(|elems| elems.to_vec())
+			}
+		)*
+	};
+}
+
 impl PROPVARIANT {
 	/// Creates a new object holding an `i64` value.
 	#[must_use]
@@ -87,4 +118,106 @@ impl PROPVARIANT {
 			None
 		}
 	}
+
+	pub_fn_vector! {
+		new_vector_i8, vector_i8, i8, I1
+		new_vector_u8, vector_u8, u8, UI1
+		new_vector_i16, vector_i16, i16, I2
+		new_vector_u16, vector_u16, u16, UI2
+		new_vector_i32, vector_i32, i32, I4
+		new_vector_u32, vector_u32, u32, UI4
+		new_vector_i64, vector_i64, i64, I8
+		new_vector_u64, vector_u64, u64, UI8
+		new_vector_f32, vector_f32, f32, R4
+		new_vector_f64, vector_f64, f64, R8
+		new_vector_guid, vector_guid, GUID, CLSID
+		new_vector_filetime, vector_filetime, FILETIME, FILETIME
+	}
+
+	/// Creates a new object holding a vector of strings, that is,
+	/// [`co::VT::VECTOR`](crate::co::VT::VECTOR) |
+	/// [`co::VT::LPWSTR`](crate::co::VT::LPWSTR).
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*};
+	///
+	/// let pv = w::PROPVARIANT::new_vector_lpwstr(&["foo", "bar"])?;
+	/// let strs = pv.vector_lpwstr().unwrap();
+	/// # w::HrResult::Ok(())
+	/// ```
+	#[must_use]
+	pub fn new_vector_lpwstr(vals: &[impl AsRef<str>]) -> HrResult<Self> {
+		let pelems = Self::alloc_vector(vals.len() * std::mem::size_of::<usize>())? as *mut usize;
+		for idx in 0..vals.len() {
+			unsafe { *pelems.add(idx) = 0; }
+		}
+		let new_self = unsafe { Self::new_vector(co::VT::LPWSTR, vals.len(), pelems as _) }; // if we fail, strings allocated so far are freed
+
+		for (idx, val) in vals.iter().enumerate() {
+			let wstr = WString::from_str(val.as_ref());
+			let num_chars = wstr.str_len() + 1; // include terminating null
+			let pstr = Self::alloc_vector(num_chars * std::mem::size_of::<u16>())?;
+			unsafe {
+				std::ptr::copy_nonoverlapping(wstr.as_ptr(), pstr as *mut u16, num_chars);
+				*pelems.add(idx) = pstr as _;
+			}
+		}
+		Ok(new_self)
+	}
+
+	/// If the object holds a vector of strings, returns them, otherwise
+	/// `None`.
+	#[must_use]
+	pub fn vector_lpwstr(&self) -> Option<Vec<String>> {
+		unsafe { self.vector_slice::<usize>(co::VT::LPWSTR) }
+			.map(|elems| {
+				elems.iter()
+					.map(|pstr| unsafe { WString::from_wchars_nullt(*pstr as _) }.to_string())
+					.collect()
+			})
+	}
+
+	/// Allocates the memory of a vector element with
+	/// [`CoTaskMemAlloc`](crate::CoTaskMemAlloc), so it can be freed by
+	/// [`PropVariantClear`](https://learn.microsoft.com/en-us/windows/win32/api/combaseapi/nf-combaseapi-propvariantclear).
+	fn alloc_vector(num_bytes: usize) -> HrResult<*mut std::ffi::c_void> {
+		if num_bytes == 0 {
+			Ok(std::ptr::null_mut())
+		} else {
+			let mut mem = CoTaskMemAlloc(num_bytes)?;
+			Ok(mem.leak().0)
+		}
+	}
+
+	/// Creates the object with a `CA*` counted array struct.
+	unsafe fn new_vector(
+		vt: co::VT,
+		num_elems: usize,
+		pelems: *mut std::ffi::c_void,
+	) -> Self
+	{
+		let ptr_off = std::mem::size_of::<usize>(); // pointer is aligned after the u32 count
+		let mut data = [0u8; 16];
+		data[..4].copy_from_slice(&(num_elems as u32).to_ne_bytes());
+		data[ptr_off..ptr_off * 2].copy_from_slice(&(pelems as usize).to_ne_bytes());
+		Self::from_raw(co::VT::VECTOR | vt, &data)
+	}
+
+	/// Returns the elements of the `CA*` counted array struct, if the object
+	/// holds a vector of the given type.
+	unsafe fn vector_slice<T>(&self, vt: co::VT) -> Option<&[T]> {
+		if self.vt() != co::VT::VECTOR | vt {
+			return None;
+		}
+		let ptr_off = std::mem::size_of::<usize>();
+		let num_elems = u32::from_ne_bytes(self.raw()[..4].try_into().unwrap()) as usize;
+		let pelems = usize::from_ne_bytes(self.raw()[ptr_off..ptr_off * 2].try_into().unwrap()) as *const T;
+		if num_elems == 0 || pelems.is_null() {
+			Some(&[])
+		} else {
+			Some(std::slice::from_raw_parts(pelems, num_elems))
+		}
+	}
 }
@@ -0,0 +1,486 @@
+#![allow(non_snake_case)]
+
+use crate::co;
+use crate::decl::*;
+use crate::ole::privs::*;
+use crate::oleaut::ffi;
+
+/// [`SAFEARRAY`](https://learn.microsoft.com/en-us/windows/win32/api/oaidl/ns-oaidl-safearray)
+/// struct.
+///
+/// Automatically calls
+/// [`SafeArrayDestroy`](https://learn.microsoft.com/en-us/windows/win32/api/oleauto/nf-oleauto-safearraydestroy)
+/// when the object goes out of scope.
+///
+/// The elements are accessed through the
+/// [`SafeArrayElement`](crate::prelude::SafeArrayElement) trait, which is
+/// implemented for the numeric types, `bool`, `String` and
+/// [`VARIANT`](crate::VARIANT).
+///
+/// # Examples
+///
+/// Creating a 2×3 array of `i32`, and setting its last element:
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*, co};
+///
+/// let mut arr = w::SAFEARRAY::SafeArrayCreate(
+///     co::VT::I4,
+///     &[
+///         w::SAFEARRAYBOUND { cElements: 2, lLbound: 0 }, // first dimension
+///         w::SAFEARRAYBOUND { cElements: 3, lLbound: 0 }, // second dimension
+///     ],
+/// )?;
+///
+/// arr.SafeArrayPutElement(&[1, 2], &42i32)?; // indices in dimension order
+/// let n: i32 = arr.SafeArrayGetElement(&[1, 2])?;
+///
+/// let all = arr.to_vec::<i32>()?; // 6 elements
+/// assert_eq!(all[5], 42);
+/// # w::HrResult::Ok(())
+/// ```
+#[repr(transparent)]
+pub struct SAFEARRAY(*mut std::ffi::c_void);
+
+impl Drop for SAFEARRAY {
+	fn drop(&mut self) {
+		if !self.0.is_null() {
+			unsafe { ffi::SafeArrayDestroy(self.0); } // ignore errors
+		}
+	}
+}
+
+impl std::fmt::Debug for SAFEARRAY {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "SAFEARRAY {:#010x}", self.0 as usize)
+	}
+}
+
+impl SAFEARRAY {
+	/// [`SafeArrayCreate`](https://learn.microsoft.com/en-us/windows/win32/api/oleauto/nf-oleauto-safearraycreate)
+	/// function.
+	///
+	/// Each [`SAFEARRAYBOUND`](crate::SAFEARRAYBOUND) describes one dimension,
+	/// starting from the first one.
+	#[must_use]
+	pub fn SafeArrayCreate(
+		vt: co::VT,
+		bounds: &[SAFEARRAYBOUND],
+	) -> HrResult<Self>
+	{
+		let ptr = unsafe {
+			ffi::SafeArrayCreate(vt.raw(), bounds.len() as _, bounds.as_ptr() as _)
+		};
+		if ptr.is_null() {
+			Err(co::HRESULT::E_OUTOFMEMORY)
+		} else {
+			Ok(Self(ptr))
+		}
+	}
+
+	/// [`SafeArrayCreateVector`](https://learn.microsoft.com/en-us/windows/win32/api/oleauto/nf-oleauto-safearraycreatevector)
+	/// function.
+	#[must_use]
+	pub fn SafeArrayCreateVector(
+		vt: co::VT,
+		lower_bound: i32,
+		num_elements: u32,
+	) -> HrResult<Self>
+	{
+		let ptr = unsafe {
+			ffi::SafeArrayCreateVector(vt.raw(), lower_bound, num_elements)
+		};
+		if ptr.is_null() {
+			Err(co::HRESULT::E_OUTOFMEMORY)
+		} else {
+			Ok(Self(ptr))
+		}
+	}
+
+	/// Creates a new one-dimensional array, with lower bound zero, by copying
+	/// the given elements.
+	#[must_use]
+	pub fn from_slice<T>(vals: &[T]) -> HrResult<Self>
+		where T: SafeArrayElement,
+	{
+		let mut arr = Self::SafeArrayCreateVector(T::VT, 0, vals.len() as _)?;
+		for (idx, val) in vals.iter().enumerate() {
+			arr.SafeArrayPutElement(&[idx as _], val)?;
+		}
+		Ok(arr)
+	}
+
+	/// Creates a new `SAFEARRAY` by wrapping a pointer.
+	///
+	/// # Safety
+	///
+	/// Be sure the pointer has the correct type and isn't owned by anyone else,
+	/// otherwise you may cause memory access violations.
+	#[must_use]
+	pub const unsafe fn from_ptr(p: *mut std::ffi::c_void) -> Self {
+		Self(p)
+	}
+
+	/// Returns the underlying pointer.
+	#[must_use]
+	pub const fn as_ptr(&self) -> *mut std::ffi::c_void {
+		self.0
+	}
+
+	/// Ejects the underlying pointer leaving a null pointer in its place, so
+	/// that
+	/// [`SafeArrayDestroy`](https://learn.microsoft.com/en-us/windows/win32/api/oleauto/nf-oleauto-safearraydestroy)
+	/// won't be called.
+	///
+	/// Be sure to free the pointer, otherwise, as the name of this method
+	/// implies, you will cause a memory leak.
+	#[must_use]
+	pub fn leak(&mut self) -> *mut std::ffi::c_void {
+		std::mem::replace(&mut self.0, std::ptr::null_mut())
+	}
+
+	/// [`SafeArrayCopy`](https://learn.microsoft.com/en-us/windows/win32/api/oleauto/nf-oleauto-safearraycopy)
+	/// function.
+	#[must_use]
+	pub fn SafeArrayCopy(&self) -> HrResult<Self> {
+		let mut ptr = std::ptr::null_mut::<std::ffi::c_void>();
+		ok_to_hrresult(unsafe { ffi::SafeArrayCopy(self.0, &mut ptr) })
+			.map(|_| Self(ptr))
+	}
+
+	/// [`SafeArrayGetDim`](https://learn.microsoft.com/en-us/windows/win32/api/oleauto/nf-oleauto-safearraygetdim)
+	/// function.
+	#[must_use]
+	pub fn SafeArrayGetDim(&self) -> u32 {
+		unsafe { ffi::SafeArrayGetDim(self.0) }
+	}
+
+	/// [`SafeArrayGetElemsize`](https://learn.microsoft.com/en-us/windows/win32/api/oleauto/nf-oleauto-safearraygetelemsize)
+	/// function.
+	#[must_use]
+	pub fn SafeArrayGetElemsize(&self) -> u32 {
+		unsafe { ffi::SafeArrayGetElemsize(self.0) }
+	}
+
+	/// [`SafeArrayGetElement`](https://learn.microsoft.com/en-us/windows/win32/api/oleauto/nf-oleauto-safearraygetelement)
+	/// function.
+	///
+	/// `indices` must have one index for each dimension, in the same order of
+	/// [`bounds`](crate::SAFEARRAY::bounds), starting from the first one.
+	///
+	/// Returns
+	/// [`co::HRESULT::DISP_E_TYPEMISMATCH`](crate::co::HRESULT::DISP_E_TYPEMISMATCH)
+	/// if the array doesn't hold elements of type `T`, and
+	/// [`co::HRESULT::DISP_E_BADINDEX`](crate::co::HRESULT::DISP_E_BADINDEX)
+	/// if an index is out of bounds.
+	#[must_use]
+	pub fn SafeArrayGetElement<T>(&self, indices: &[i32]) -> HrResult<T>
+		where T: SafeArrayElement,
+	{
+		self.check_indices::<T>(indices)?;
+		let mut buf = vec![0u8; self.SafeArrayGetElemsize() as _];
+		ok_to_hrresult(
+			unsafe {
+				ffi::SafeArrayGetElement(self.0, indices.as_ptr(), buf.as_mut_ptr() as _)
+			},
+		).map(|_| unsafe { T::from_element(&buf) })
+	}
+
+	/// [`SafeArrayGetLBound`](https://learn.microsoft.com/en-us/windows/win32/api/oleauto/nf-oleauto-safearraygetlbound)
+	/// function.
+	///
+	/// Note that `dim` is one-based.
+	#[must_use]
+	pub fn SafeArrayGetLBound(&self, dim: u32) -> HrResult<i32> {
+		let mut bound = i32::default();
+		ok_to_hrresult(unsafe { ffi::SafeArrayGetLBound(self.0, dim, &mut bound) })
+			.map(|_| bound)
+	}
+
+	/// [`SafeArrayGetUBound`](https://learn.microsoft.com/en-us/windows/win32/api/oleauto/nf-oleauto-safearraygetubound)
+	/// function.
+	///
+	/// Note that `dim` is one-based.
+	#[must_use]
+	pub fn SafeArrayGetUBound(&self, dim: u32) -> HrResult<i32> {
+		let mut bound = i32::default();
+		ok_to_hrresult(unsafe { ffi::SafeArrayGetUBound(self.0, dim, &mut bound) })
+			.map(|_| bound)
+	}
+
+	/// [`SafeArrayGetVartype`](https://learn.microsoft.com/en-us/windows/win32/api/oleauto/nf-oleauto-safearraygetvartype)
+	/// function.
+	#[must_use]
+	pub fn SafeArrayGetVartype(&self) -> HrResult<co::VT> {
+		let mut vt = u16::default();
+		ok_to_hrresult(unsafe { ffi::SafeArrayGetVartype(self.0, &mut vt) })
+			.map(|_| unsafe { co::VT::from_raw(vt) })
+	}
+
+	/// [`SafeArrayPutElement`](https://learn.microsoft.com/en-us/windows/win32/api/oleauto/nf-oleauto-safearrayputelement)
+	/// function.
+	///
+	/// `indices` must have one index for each dimension, in the same order of
+	/// [`bounds`](crate::SAFEARRAY::bounds), starting from the first one. The
+	/// value is copied into the array.
+	///
+	/// Returns
+	/// [`co::HRESULT::DISP_E_TYPEMISMATCH`](crate::co::HRESULT::DISP_E_TYPEMISMATCH)
+	/// if the array doesn't hold elements of type `T`, and
+	/// [`co::HRESULT::DISP_E_BADINDEX`](crate::co::HRESULT::DISP_E_BADINDEX)
+	/// if an index is out of bounds.
+	pub fn SafeArrayPutElement<T>(&mut self, indices: &[i32], val: &T) -> HrResult<()>
+		where T: SafeArrayElement,
+	{
+		self.check_indices::<T>(indices)?;
+		val.with_element(|pv| ok_to_hrresult(
+			unsafe { ffi::SafeArrayPutElement(self.0, indices.as_ptr(), pv) },
+		))?
+	}
+
+	/// Returns the bounds of each dimension, starting from the first one, which
+	/// is the leftmost one, in the same order given to
+	/// [`SafeArrayCreate`](crate::SAFEARRAY::SafeArrayCreate).
+	#[must_use]
+	pub fn bounds(&self) -> HrResult<Vec<SAFEARRAYBOUND>> {
+		(1..=self.SafeArrayGetDim())
+			.map(|dim| {
+				let lower = self.SafeArrayGetLBound(dim)?;
+				let upper = self.SafeArrayGetUBound(dim)?;
+				Ok(SAFEARRAYBOUND {
+					cElements: (upper - lower + 1) as _,
+					lLbound: lower,
+				})
+			})
+			.collect()
+	}
+
+	/// Returns all the elements, in the order they're stored – that is, the
+	/// first dimension varies fastest.
+	///
+	/// Returns
+	/// [`co::HRESULT::DISP_E_TYPEMISMATCH`](crate::co::HRESULT::DISP_E_TYPEMISMATCH)
+	/// if the array doesn't hold elements of type `T`.
+	#[must_use]
+	pub fn to_vec<T>(&self) -> HrResult<Vec<T>>
+		where T: SafeArrayElement,
+	{
+		let bounds = self.bounds()?;
+		let total = bounds.iter()
+			.map(|b| b.cElements as usize)
+			.product::<usize>();
+		if bounds.is_empty() || total == 0 {
+			return Ok(Vec::new());
+		}
+
+		let mut indices = bounds.iter()
+			.map(|b| b.lLbound)
+			.collect::<Vec<_>>();
+		let mut vals = Vec::with_capacity(total);
+		for _ in 0..total {
+			vals.push(self.SafeArrayGetElement::<T>(&indices)?);
+			next_indices(&mut indices, &bounds);
+		}
+		Ok(vals)
+	}
+
+	/// Validates the indices, which are passed to the functions as they are.
+	fn check_indices<T>(&self, indices: &[i32]) -> HrResult<()>
+		where T: SafeArrayElement,
+	{
+		if indices.len() != self.SafeArrayGetDim() as usize {
+			Err(co::HRESULT::E_INVALIDARG) // the function would read past the indices
+		} else if self.SafeArrayGetVartype()? != T::VT {
+			Err(co::HRESULT::DISP_E_TYPEMISMATCH)
+		} else {
+			check_bounds(&self.bounds()?, indices)
+		}
+	}
+}
+
+/// Checks the indices, given in dimension order, against the bounds.
+fn check_bounds(bounds: &[SAFEARRAYBOUND], indices: &[i32]) -> HrResult<()> {
+	if indices.len() != bounds.len() {
+		return Err(co::HRESULT::E_INVALIDARG);
+	}
+	if indices.iter().zip(bounds.iter())
+		.any(|(idx, b)| *idx < b.lLbound || (*idx - b.lLbound) as i64 >= b.cElements as i64)
+	{
+		return Err(co::HRESULT::DISP_E_BADINDEX);
+	}
+	Ok(())
+}
+
+/// Advances the indices, given in dimension order, to the next element in the
+/// storage order, where the first dimension varies fastest.
+fn next_indices(indices: &mut [i32], bounds: &[SAFEARRAYBOUND]) {
+	for (idx, bound) in indices.iter_mut().zip(bounds.iter()) {
+		*idx += 1;
+		if *idx < bound.lLbound + bound.cElements as i32 {
+			break;
+		}
+		*idx = bound.lLbound; // wrap around, carry to the next dimension
+	}
+}
+
+/// This trait is enabled with the `oleaut` feature, and is implemented by the
+/// types which can be stored in a [`SAFEARRAY`](crate::SAFEARRAY).
+///
+/// Prefer importing this trait through the prelude:
+///
+/// ```no_run
+/// use winsafe::prelude::*;
+/// ```
+pub trait SafeArrayElement: Sized {
+	/// The [`co::VT`](crate::co::VT) variant type of the array elements.
+	const VT: co::VT;
+
+	/// Creates the value from the raw bytes of an element, as returned by
+	/// [`SafeArrayGetElement`](https://learn.microsoft.com/en-us/windows/win32/api/oleauto/nf-oleauto-safearraygetelement).
+	///
+	/// # Safety
+	///
+	/// The bytes must be a copy of an element of type
+	/// [`VT`](crate::prelude::SafeArrayElement::VT). Any resource held by the
+	/// element, like a [`BSTR`](crate::BSTR), will be owned by the returned
+	/// value.
+	#[must_use]
+	unsafe fn from_element(data: &[u8]) -> Self;
+
+	/// Calls `func` with the pointer to be passed to
+	/// [`SafeArrayPutElement`](https://learn.microsoft.com/en-us/windows/win32/api/oleauto/nf-oleauto-safearrayputelement),
+	/// which copies the element.
+	fn with_element<R>(&self, func: impl FnOnce(*const std::ffi::c_void) -> R) -> HrResult<R>;
+}
+
+macro_rules! impl_safearray_element_num {
+	( $( $ntype:ty, $vt:ident )* ) => {
+		$(
+			impl SafeArrayElement for $ntype {
+				const VT: co::VT = co::VT::$vt;
+
+				unsafe fn from_element(data: &[u8]) -> Self {
+					Self::from_ne_bytes(data[..std::mem::size_of::<Self>()].try_into().unwrap())
+				}
+
+				fn with_element<R>(&self, func: impl FnOnce(*const std::ffi::c_void) -> R) -> HrResult<R> {
+					Ok(func(self as *const _ as _))
+				}
+			}
+		)*
+	};
+}
+
+impl_safearray_element_num! {
+	i8, I1
+	u8, UI1
+	i16, I2
+	u16, UI2
+	i32, I4
+	u32, UI4
+	i64, I8
+	u64, UI8
+	f32, R4
+	f64, R8
+}
+
+impl SafeArrayElement for bool {
+	const VT: co::VT = co::VT::BOOL;
+
+	unsafe fn from_element(data: &[u8]) -> Self {
+		i16::from_ne_bytes([data[0], data[1]]) != 0 // VARIANT_BOOL
+	}
+
+	fn with_element<R>(&self, func: impl FnOnce(*const std::ffi::c_void) -> R) -> HrResult<R> {
+		let val16: i16 = if *self { -1 } else { 0 };
+		Ok(func(&val16 as *const _ as _))
+	}
+}
+
+impl SafeArrayElement for String {
+	const VT: co::VT = co::VT::BSTR;
+
+	unsafe fn from_element(data: &[u8]) -> Self {
+		let ptr = usize::from_ne_bytes(data[..std::mem::size_of::<usize>()].try_into().unwrap());
+		BSTR::from_ptr(ptr as _).to_string() // the copied BSTR is freed here
+	}
+
+	fn with_element<R>(&self, func: impl FnOnce(*const std::ffi::c_void) -> R) -> HrResult<R> {
+		let bstr = BSTR::SysAllocString(self)?;
+		Ok(func(bstr.as_ptr() as _)) // BSTR elements are passed directly, not by pointer
+	}
+}
+
+impl SafeArrayElement for VARIANT {
+	const VT: co::VT = co::VT::VARIANT;
+
+	unsafe fn from_element(data: &[u8]) -> Self {
+		std::ptr::read_unaligned(data.as_ptr() as *const Self)
+	}
+
+	fn with_element<R>(&self, func: impl FnOnce(*const std::ffi::c_void) -> R) -> HrResult<R> {
+		Ok(func(self as *const _ as _))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::kernel::ffi_types::*;
+	use super::*;
+
+	extern_sys! { "oleaut32"; // used only to check the memory layout
+		SafeArrayAccessData(PVOID, *mut PVOID) -> HRES
+		SafeArrayUnaccessData(PVOID) -> HRES
+	}
+
+	const BOUNDS_2X3: [SAFEARRAYBOUND; 2] = [
+		SAFEARRAYBOUND { cElements: 2, lLbound: 0 },
+		SAFEARRAYBOUND { cElements: 3, lLbound: 1 },
+	];
+
+	#[test]
+	fn check_bounds_2x3() {
+		assert!(check_bounds(&BOUNDS_2X3, &[1, 3]).is_ok());
+		assert!(check_bounds(&BOUNDS_2X3, &[0, 1]).is_ok());
+
+		assert_eq!(check_bounds(&BOUNDS_2X3, &[2, 1]).unwrap_err(), co::HRESULT::DISP_E_BADINDEX);
+		assert_eq!(check_bounds(&BOUNDS_2X3, &[0, 0]).unwrap_err(), co::HRESULT::DISP_E_BADINDEX);
+		assert_eq!(check_bounds(&BOUNDS_2X3, &[0, 4]).unwrap_err(), co::HRESULT::DISP_E_BADINDEX);
+		assert_eq!(check_bounds(&BOUNDS_2X3, &[0]).unwrap_err(), co::HRESULT::E_INVALIDARG);
+	}
+
+	#[test]
+	fn storage_order_2x3() {
+		let mut indices = BOUNDS_2X3.iter().map(|b| b.lLbound).collect::<Vec<_>>();
+		let mut all = Vec::new();
+		for _ in 0..6 {
+			all.push(indices.clone());
+			next_indices(&mut indices, &BOUNDS_2X3);
+		}
+		assert_eq!(all, [[0, 1], [1, 1], [0, 2], [1, 2], [0, 3], [1, 3]]); // first dimension varies fastest
+		assert_eq!(indices, [0, 1]); // wrapped around
+	}
+
+	#[test]
+	fn memory_layout_2x3() {
+		let mut arr = SAFEARRAY::SafeArrayCreate(co::VT::I4, &BOUNDS_2X3).unwrap();
+		assert!(arr.bounds().unwrap() == BOUNDS_2X3);
+
+		for i in 0..2 {
+			for j in 1..4 {
+				arr.SafeArrayPutElement(&[i, j], &(i * 10 + j)).unwrap();
+			}
+		}
+
+		let mut pv = std::ptr::null_mut();
+		ok_to_hrresult(unsafe { SafeArrayAccessData(arr.0, &mut pv) }).unwrap();
+		let raw = unsafe { std::slice::from_raw_parts(pv as *const i32, 6) }.to_vec();
+		ok_to_hrresult(unsafe { SafeArrayUnaccessData(arr.0) }).unwrap();
+		assert_eq!(raw, [1, 11, 2, 12, 3, 13]); // column-major
+
+		assert_eq!(arr.to_vec::<i32>().unwrap(), raw);
+		assert_eq!(arr.SafeArrayGetElement::<i32>(&[1, 3]).unwrap(), 13);
+		assert_eq!(arr.SafeArrayGetElement::<i32>(&[2, 1]).unwrap_err(), co::HRESULT::DISP_E_BADINDEX);
+		assert_eq!(arr.SafeArrayGetElement::<u32>(&[0, 1]).unwrap_err(), co::HRESULT::DISP_E_TYPEMISMATCH);
+	}
+}
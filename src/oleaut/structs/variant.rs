@@ -3,6 +3,7 @@
 use std::mem::ManuallyDrop;
 
 use crate::co;
+use crate::decl::*;
 use crate::oleaut::ffi;
 use crate::prelude::*;
 
@@ -124,4 +125,59 @@ impl VARIANT {
 			None
 		}
 	}
+
+	/// Creates a new object holding a [`SAFEARRAY`](crate::SAFEARRAY), whose
+	/// ownership is transferred to the `VARIANT`.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*};
+	///
+	/// let arr = w::SAFEARRAY::from_slice(&["foo".to_owned(), "bar".to_owned()])?;
+	/// let var = w::VARIANT::new_array(arr)?;
+	///
+	/// let strs = var.array::<String>().unwrap();
+	/// # w::HrResult::Ok(())
+	/// ```
+	#[must_use]
+	pub fn new_array(mut arr: SAFEARRAY) -> HrResult<Self> {
+		let vt = arr.SafeArrayGetVartype()?;
+		let ptr = arr.leak() as usize;
+		Ok(unsafe { Self::from_raw(co::VT::ARRAY | vt, &ptr.to_ne_bytes()) })
+	}
+
+	/// If the object holds a [`SAFEARRAY`](crate::SAFEARRAY) of `T` elements,
+	/// returns them, otherwise `None`.
+	///
+	/// See [`SAFEARRAY::to_vec`](crate::SAFEARRAY::to_vec) for the order of
+	/// the elements in multi-dimensional arrays.
+	#[must_use]
+	pub fn array<T>(&self) -> Option<Vec<T>>
+		where T: SafeArrayElement,
+	{
+		if self.vt() == co::VT::ARRAY | T::VT {
+			let arr = ManuallyDrop::new(unsafe { self.safearray_ref() }); // won't destroy the stored array
+			arr.to_vec().ok()
+		} else {
+			None
+		}
+	}
+
+	/// If the object holds a [`SAFEARRAY`](crate::SAFEARRAY) of any type,
+	/// returns a copy of it, otherwise `None`.
+	#[must_use]
+	pub fn safearray(&self) -> Option<SAFEARRAY> {
+		if self.vt().has(co::VT::ARRAY) && !self.vt().has(co::VT::BYREF) {
+			let arr = ManuallyDrop::new(unsafe { self.safearray_ref() }); // won't destroy the stored array
+			arr.SafeArrayCopy().ok()
+		} else {
+			None
+		}
+	}
+
+	unsafe fn safearray_ref(&self) -> SAFEARRAY {
+		let ptr = usize::from_ne_bytes(self.raw()[..std::mem::size_of::<usize>()].try_into().unwrap());
+		SAFEARRAY::from_ptr(ptr as _)
+	}
 }
@@ -27,15 +27,54 @@ impl std::fmt::Display for CDERR {
 }
 impl std::fmt::Debug for CDERR {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.name() {
+			Some(name) => write!(f, "CDERR::{}", name)?,
+			None => write!(f, "CDERR")?,
+		}
 		if self.0 > 0xffff {
-			write!(f, "CDERR({:#010x} {}) Common dialog error.", self.0, self.0)
+			write!(f, "({:#010x} {}) Common dialog error.", self.0, self.0)
 		} else {
-			write!(f, "CDERR({:#06x} {}) Common dialog error.", self.0, self.0)
+			write!(f, "({:#06x} {}) Common dialog error.", self.0, self.0)
 		}
 	}
 }
 
-const_values_pub! { CDERR;
+impl std::str::FromStr for CDERR {
+	type Err = crate::co::ERROR;
+
+	/// Parses the name of a constant, like `NOHOOK` or `co::CDERR::NOHOOK`, or
+	/// a number.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let names = CDERR_NAMES.iter()
+			.map(|(name, v)| (*name, v.0 as u64))
+			.collect::<Vec<_>>();
+		crate::kernel::privs::const_parse("CDERR", &names, std::mem::size_of::<u32>(), s, false)
+			.map(|v| unsafe { Self::from_raw(v as _) })
+	}
+}
+
+impl CDERR {
+	/// Returns the name of the constant, taken from a built-in table.
+	///
+	/// Returns `None` if the value is not declared in this library.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let err = co::CDERR::PD_NODEFAULTPRN;
+	/// assert_eq!(err.name(), Some("PD_NODEFAULTPRN"));
+	/// ```
+	#[must_use]
+	pub fn name(self) -> Option<&'static str> {
+		CDERR_NAMES.iter()
+			.find(|(_, v)| *v == self)
+			.map(|(name, _)| *name)
+	}
+}
+
+const_values_pub_named! { CDERR, CDERR_NAMES;
 	/// None of the actual values (zero).
 	NoValue 0
 	DIALOGFAILURE 0xffff
@@ -70,3 +109,28 @@ const_values_pub! { CDERR;
 	FN_SUBCLASSFAILURE 0x3001
 	FR_BUFFERLENGTHZERO 0x4001
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn names() {
+		assert_eq!(CDERR::FN_BUFFERTOOSMALL.name(), Some("FN_BUFFERTOOSMALL"));
+		assert_eq!(unsafe { CDERR::from_raw(0x5000) }.name(), None);
+		assert_eq!(format!("{:?}", CDERR::NOHOOK), "CDERR::NOHOOK(0x000b 11) Common dialog error.");
+		assert_eq!(format!("{:?}", unsafe { CDERR::from_raw(0x5000) }), "CDERR(0x5000 20480) Common dialog error.");
+	}
+
+	#[test]
+	fn from_str() {
+		for err in [CDERR::NOHOOK, CDERR::FN_BUFFERTOOSMALL, CDERR::DIALOGFAILURE] {
+			let name = err.name().unwrap();
+			assert_eq!(name.parse::<CDERR>().unwrap(), err);
+			assert_eq!(format!("CDERR::{}", name).parse::<CDERR>().unwrap(), err);
+		}
+		assert_eq!("0x5000".parse::<CDERR>().unwrap(), unsafe { CDERR::from_raw(0x5000) });
+		assert_eq!("CDERR::NOT_A_NAME".parse::<CDERR>(), Err(crate::co::ERROR::INVALID_DATA));
+		assert_eq!("NOHOOK | NOTEMPLATE".parse::<CDERR>(), Err(crate::co::ERROR::INVALID_DATA));
+	}
+}
@@ -235,6 +235,8 @@ const_wm! { CB;
 	SETDROPPEDWIDTH 0x0160
 	INITSTORAGE 0x0161
 	GETCOMBOBOXINFO 0x0164
+	;
+	#[cfg(feature = "comctl")] crate::co::CB_NAMES_COMCTL
 }
 
 const_cmd! { CBN;
@@ -1069,6 +1071,30 @@ const_ordinary! { DPI_HOSTING_BEHAVIOR: u32;
 	MIXED 1
 }
 
+const_ws! { DS: u32;
+	/// Dialog box
+	/// [styles](https://learn.microsoft.com/en-us/windows/win32/dlgbox/dialog-box-styles)
+	/// (`u32`).
+	=>
+	/// None of the actual values (zero).
+	NoValue 0
+	ABSALIGN 0x0001
+	SYSMODAL 0x0002
+	FIXEDSYS 0x0008
+	NOFAILCREATE 0x0010
+	LOCALEDIT 0x0020
+	SETFONT 0x0040
+	MODALFRAME 0x0080
+	NOIDLEMSG 0x0100
+	SETFOREGROUND 0x0200
+	CONTROL 0x0400
+	CENTER 0x0800
+	CENTERMOUSE 0x1000
+	CONTEXTHELP 0x2000
+	SHELLFONT 0x0040 | 0x0008
+	USEPIXELS 0x8000
+}
+
 const_bitflag! { DT: u32;
 	/// [`HDC::DrawText`](crate::prelude::user_Hdc::DrawText) `format` (`u32`).
 	=>
@@ -1173,6 +1199,8 @@ const_wm! { EM;
 	SETIMESTATUS 0x00d8
 	GETIMESTATUS 0x00d9
 	ENABLEFEATURE 0x00da
+	;
+	#[cfg(feature = "comctl")] crate::co::EM_NAMES_COMCTL
 }
 
 const_cmd! { EN;
@@ -3766,3 +3794,23 @@ const_bitflag! { WVR: u32;
 	REDRAW Self::HREDRAW.0 | Self::VREDRAW.0
 	VALIDRECTS 0x0400
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn debug_aliases() {
+		assert_eq!(format!("{:?}", WS::OVERLAPPEDWINDOW | WS::VISIBLE), "WS::OVERLAPPEDWINDOW | WS::VISIBLE");
+		assert_eq!(format!("{:?}", WS::TILEDWINDOW), "WS::OVERLAPPEDWINDOW");
+		assert_eq!(format!("{:?}", WS::SIZEBOX), "WS::THICKFRAME");
+		assert_eq!("WS::TILEDWINDOW".parse::<WS>().unwrap(), WS::OVERLAPPEDWINDOW);
+	}
+
+	#[test]
+	fn debug_flags() {
+		assert_eq!(format!("{:?}", WS::CHILD | WS::VISIBLE | WS::BORDER), "WS::CHILD | WS::VISIBLE | WS::BORDER");
+		assert_eq!(format!("{:?}", WS::POPUPWINDOW | WS::CAPTION), "WS::POPUPWINDOW | WS::DLGFRAME"); // BORDER is already in POPUPWINDOW
+		assert_eq!(format!("{:?}", unsafe { SWP::from_raw(0x0001 | 0x8000_0000) }), "SWP::NOSIZE | 0x80000000");
+	}
+}
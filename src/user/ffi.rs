@@ -46,6 +46,7 @@ extern_sys! { "user32";
 	CreateAcceleratorTableW(PCVOID, i32) -> HANDLE
 	CreateDesktopExW(PCSTR, PCSTR, PCVOID, u32, u32, PVOID, u32, PVOID) -> HANDLE
 	CreateDesktopW(PCSTR, PCSTR, PCVOID, u32, u32, PVOID) -> HANDLE
+	CreateDialogIndirectParamW(HANDLE, PCVOID, HANDLE, PFUNC, isize) -> HANDLE
 	CreateDialogParamW(HANDLE, PCSTR, HANDLE, PFUNC, isize) -> HANDLE
 	CreateMenu() -> HANDLE
 	CreatePopupMenu() -> HANDLE
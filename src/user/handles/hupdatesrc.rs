@@ -0,0 +1,154 @@
+#![allow(non_camel_case_types)]
+
+use crate::co;
+use crate::decl::*;
+use crate::prelude::*;
+
+impl user_Hupdatersrc for HUPDATERSRC {}
+
+/// This trait is enabled with the `user` feature, and provides methods for
+/// [`HUPDATERSRC`](crate::HUPDATERSRC).
+///
+/// Prefer importing this trait through the prelude:
+///
+/// ```no_run
+/// use winsafe::prelude::*;
+/// ```
+pub trait user_Hupdatersrc: kernel_Hupdatersrc {
+	/// Adds or replaces a dialog resource, from a
+	/// [`DLGTEMPLATE`](crate::DLGTEMPLATE) followed by its
+	/// [`DLGITEMTEMPLATE`](crate::DLGITEMTEMPLATE) items, which are encoded
+	/// by [`DialogTemplateBuilder`](crate::DialogTemplateBuilder) – use it
+	/// directly, along with
+	/// [`UpdateResource`](crate::prelude::kernel_Hupdatersrc::UpdateResource),
+	/// to set the fields which are not available here.
+	///
+	/// Each item is given along with its window class name – like `"BUTTON"`
+	/// or `"EDIT"` – and its text. The `cdit` field of the template is ignored,
+	/// and the number of items is used instead. If `font` is given, with the
+	/// point size and the typeface name, the
+	/// [`co::DS::SETFONT`](crate::co::DS::SETFONT) style is added.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let mut dlg = w::DLGTEMPLATE::default();
+	/// dlg.style = co::WS::POPUP | co::WS::CAPTION | co::WS::SYSMENU;
+	/// dlg.cx = 200;
+	/// dlg.cy = 80;
+	///
+	/// let mut btn = w::DLGITEMTEMPLATE::default();
+	/// btn.style = co::WS::CHILD | co::WS::VISIBLE | co::WS::TABSTOP;
+	/// (btn.x, btn.y, btn.cx, btn.cy, btn.id) = (140, 60, 50, 14, 1);
+	///
+	/// let hupd = w::HUPDATERSRC::BeginUpdateResource("C:\\Temp\\foo.exe", false)?;
+	/// hupd.update_dialog(
+	///     w::IdStr::Id(101),
+	///     w::LANGID::new(co::LANG::NEUTRAL, co::SUBLANG::NEUTRAL),
+	///     &dlg,
+	///     "About",
+	///     Some((9, "Segoe UI")),
+	///     &[(btn, "BUTTON", "OK")],
+	/// )?;
+	/// # w::SysResult::Ok(())
+	/// ```
+	fn update_dialog(&self,
+		dialog_id: IdStr,
+		language: LANGID,
+		template: &DLGTEMPLATE,
+		title: &str,
+		font: Option<(u16, &str)>,
+		items: &[(DLGITEMTEMPLATE, &str, &str)],
+	) -> SysResult<()>
+	{
+		self.UpdateResource(
+			RtStr::Rt(co::RT::DIALOG),
+			dialog_id,
+			language,
+			&dialog_builder(template, title, font, items).build()?,
+		)
+	}
+}
+
+/// Converts the fixed dialog structs into a builder, which encodes the
+/// variable-length data which follows them.
+fn dialog_builder(
+	template: &DLGTEMPLATE,
+	title: &str,
+	font: Option<(u16, &str)>,
+	items: &[(DLGITEMTEMPLATE, &str, &str)],
+) -> DialogTemplateBuilder
+{
+	DialogTemplateBuilder {
+		help_id: 0,
+		ex_style: template.dwExtendedStyle,
+		style: template.style,
+		x: template.x,
+		y: template.y,
+		cx: template.cx,
+		cy: template.cy,
+		menu: None,
+		class: None,
+		title: title.to_owned(),
+		font: font.map(|(point_size, typeface)| DialogTemplateFont::new(point_size, typeface)),
+		items: items.iter()
+			.map(|(item, class_name, text)| DialogTemplateItem {
+				help_id: 0,
+				ex_style: item.dwExtendedStyle,
+				style: item.style,
+				x: item.x,
+				y: item.y,
+				cx: item.cx,
+				cy: item.cy,
+				id: item.id as _,
+				class: IdStr::Str(WString::from_str(class_name)),
+				title: IdStr::Str(WString::from_str(text)),
+				creation_data: Vec::new(),
+			})
+			.collect(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn dialog() {
+		let dlg = DLGTEMPLATE {
+			style: co::WS::POPUP,
+			x: 1, y: 2, cx: 100, cy: 50,
+			..Default::default()
+		};
+		let btn = DLGITEMTEMPLATE {
+			style: co::WS::CHILD,
+			x: 5, y: 6, cx: 40, cy: 14,
+			id: 1,
+			..Default::default()
+		};
+
+		let bytes = dialog_builder(&dlg, "Title", Some((9, "Tahoma")), &[(btn, "BUTTON", "OK")])
+			.build().unwrap();
+		let parsed = DialogTemplateBuilder::parse(&bytes).unwrap();
+		assert_eq!(parsed.style, co::WS::POPUP | co::DS::SETFONT.into());
+		assert_eq!((parsed.x, parsed.y, parsed.cx, parsed.cy), (1, 2, 100, 50));
+		assert_eq!(parsed.title, "Title");
+		let font = parsed.font.unwrap();
+		assert_eq!((font.point_size, font.typeface.as_str()), (9, "Tahoma"));
+
+		assert_eq!(parsed.items.len(), 1);
+		let item = &parsed.items[0];
+		assert_eq!(item.style, co::WS::CHILD);
+		assert_eq!((item.x, item.y, item.cx, item.cy, item.id), (5, 6, 40, 14, 1));
+		assert!(matches!(&item.class, IdStr::Str(s) if s.to_string() == "BUTTON"));
+		assert!(matches!(&item.title, IdStr::Str(s) if s.to_string() == "OK"));
+
+		let no_font = dialog_builder(&dlg, "", None, &[]).build().unwrap();
+		let parsed = DialogTemplateBuilder::parse(&no_font).unwrap();
+		assert_eq!(parsed.style, co::WS::POPUP);
+		assert!(parsed.font.is_none());
+		assert!(parsed.items.is_empty());
+	}
+}
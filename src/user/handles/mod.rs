@@ -9,6 +9,7 @@ mod hinstance;
 mod hmenu;
 mod hmonitor;
 mod hprocess;
+mod hupdatesrc;
 mod hwnd;
 
 pub mod decl {
@@ -57,5 +58,6 @@ pub mod traits {
 	pub use super::hmenu::user_Hmenu;
 	pub use super::hmonitor::user_Hmonitor;
 	pub use super::hprocess::user_Hprocess;
+	pub use super::hupdatesrc::user_Hupdatersrc;
 	pub use super::hwnd::user_Hwnd;
 }
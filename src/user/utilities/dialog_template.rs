@@ -0,0 +1,617 @@
+use crate::co;
+use crate::decl::*;
+
+/// Font of a [`DialogTemplateBuilder`](crate::DialogTemplateBuilder).
+#[derive(Clone)]
+pub struct DialogTemplateFont {
+	/// Point size.
+	pub point_size: u16,
+	/// Weight, like `400` for normal or `700` for bold.
+	pub weight: u16,
+	/// Whether the font is italic.
+	pub italic: bool,
+	/// Character set.
+	pub char_set: u8,
+	/// Typeface name.
+	pub typeface: String,
+}
+
+impl DialogTemplateFont {
+	/// Creates a new font with normal weight, not italic, and default
+	/// character set.
+	#[must_use]
+	pub fn new(point_size: u16, typeface: &str) -> Self {
+		Self {
+			point_size,
+			weight: 400, // FW_NORMAL
+			italic: false,
+			char_set: 1, // DEFAULT_CHARSET
+			typeface: typeface.to_owned(),
+		}
+	}
+}
+
+/// A control of a [`DialogTemplateBuilder`](crate::DialogTemplateBuilder),
+/// which corresponds to a
+/// [`DLGITEMTEMPLATEEX`](https://learn.microsoft.com/en-us/windows/win32/dlgbox/dlgitemtemplateex)
+/// struct.
+#[derive(Clone)]
+pub struct DialogTemplateItem {
+	/// Help context identifier.
+	pub help_id: u32,
+	/// Extended window styles.
+	pub ex_style: co::WS_EX,
+	/// Window styles, including the control-specific ones.
+	pub style: co::WS,
+	/// Horizontal position, in dialog units.
+	pub x: i16,
+	/// Vertical position, in dialog units.
+	pub y: i16,
+	/// Width, in dialog units.
+	pub cx: i16,
+	/// Height, in dialog units.
+	pub cy: i16,
+	/// Control ID.
+	pub id: u32,
+	/// Window class, either a predefined class ordinal – like
+	/// [`BUTTON`](crate::DialogTemplateItem::BUTTON) – or a class name.
+	pub class: IdStr,
+	/// Text of the control, or the ordinal of a resource, like an icon of a
+	/// static control.
+	pub title: IdStr,
+	/// Creation data passed to the control in `WM_CREATE`.
+	pub creation_data: Vec<u8>,
+}
+
+impl DialogTemplateItem {
+	/// Ordinal of the predefined `Button` class.
+	pub const BUTTON: u16 = 0x0080;
+	/// Ordinal of the predefined `Edit` class.
+	pub const EDIT: u16 = 0x0081;
+	/// Ordinal of the predefined `Static` class.
+	pub const STATIC: u16 = 0x0082;
+	/// Ordinal of the predefined `ListBox` class.
+	pub const LIST_BOX: u16 = 0x0083;
+	/// Ordinal of the predefined `ScrollBar` class.
+	pub const SCROLL_BAR: u16 = 0x0084;
+	/// Ordinal of the predefined `ComboBox` class.
+	pub const COMBO_BOX: u16 = 0x0085;
+
+	/// Creates a new item with
+	/// [`co::WS::CHILD`](crate::co::WS::CHILD) and
+	/// [`co::WS::VISIBLE`](crate::co::WS::VISIBLE) styles.
+	#[must_use]
+	pub fn new(
+		class: IdStr,
+		title: &str,
+		id: u32,
+		x: i16, y: i16, cx: i16, cy: i16,
+	) -> Self
+	{
+		Self {
+			help_id: 0,
+			ex_style: co::WS_EX::NoValue,
+			style: co::WS::CHILD | co::WS::VISIBLE,
+			x, y, cx, cy,
+			id,
+			class,
+			title: IdStr::Str(WString::from_str(title)),
+			creation_data: Vec::new(),
+		}
+	}
+
+	/// Creates a new push button, with
+	/// [`co::WS::TABSTOP`](crate::co::WS::TABSTOP) style.
+	#[must_use]
+	pub fn button(text: &str, id: u32, x: i16, y: i16, cx: i16, cy: i16) -> Self {
+		Self::new(IdStr::Id(Self::BUTTON), text, id, x, y, cx, cy)
+			.style(co::WS::CHILD | co::WS::VISIBLE | co::WS::TABSTOP | co::BS::PUSHBUTTON.into())
+	}
+
+	/// Creates a new single-line edit, with
+	/// [`co::WS::TABSTOP`](crate::co::WS::TABSTOP) and
+	/// [`co::WS::BORDER`](crate::co::WS::BORDER) styles.
+	#[must_use]
+	pub fn edit(text: &str, id: u32, x: i16, y: i16, cx: i16, cy: i16) -> Self {
+		Self::new(IdStr::Id(Self::EDIT), text, id, x, y, cx, cy)
+			.style(co::WS::CHILD | co::WS::VISIBLE | co::WS::TABSTOP | co::WS::BORDER
+				| co::ES::AUTOHSCROLL.into())
+	}
+
+	/// Creates a new left-aligned static text.
+	#[must_use]
+	pub fn label(text: &str, id: u32, x: i16, y: i16, cx: i16, cy: i16) -> Self {
+		Self::new(IdStr::Id(Self::STATIC), text, id, x, y, cx, cy)
+			.style(co::WS::CHILD | co::WS::VISIBLE | co::SS::LEFT.into())
+	}
+
+	/// Sets the window styles, replacing the current ones.
+	#[must_use]
+	pub fn style(mut self, style: co::WS) -> Self {
+		self.style = style;
+		self
+	}
+
+	/// Sets the extended window styles, replacing the current ones.
+	#[must_use]
+	pub fn ex_style(mut self, ex_style: co::WS_EX) -> Self {
+		self.ex_style = ex_style;
+		self
+	}
+}
+
+/// Builds and parses in-memory dialog templates, in the
+/// [`DLGTEMPLATEEX`](https://learn.microsoft.com/en-us/windows/win32/dlgbox/dlgtemplateex)
+/// format, with all its variable-length data.
+///
+/// The template can be used with
+/// [`HINSTANCE::DialogBoxIndirectParam`](crate::prelude::user_Hinstance::DialogBoxIndirectParam),
+/// written as a resource with
+/// [`HUPDATERSRC::UpdateResource`](crate::prelude::kernel_Hupdatersrc::UpdateResource),
+/// or passed to `gui::WindowMain::new_dlg_template` and
+/// `gui::WindowModal::new_dlg_template`, when the `gui` feature is enabled.
+///
+/// All positions and sizes are in dialog units.
+///
+/// # Examples
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*, co};
+///
+/// let template = w::DialogTemplateBuilder::new("Login", 180, 70)
+///     .style(co::WS::POPUP | co::WS::CAPTION | co::WS::SYSMENU
+///         | co::DS::MODALFRAME.into() | co::DS::CENTER.into())
+///     .item(w::DialogTemplateItem::label("User:", 0xffff, 7, 9, 40, 8))
+///     .item(w::DialogTemplateItem::edit("", 1001, 50, 7, 123, 14))
+///     .item(w::DialogTemplateItem::button("OK", 1, 123, 49, 50, 14));
+///
+/// let bytes = template.build()?;
+/// let parsed = w::DialogTemplateBuilder::parse(&bytes)?;
+/// assert_eq!(parsed.items.len(), 3);
+/// # w::SysResult::Ok(())
+/// ```
+#[derive(Clone)]
+pub struct DialogTemplateBuilder {
+	/// Help context identifier.
+	pub help_id: u32,
+	/// Extended window styles.
+	pub ex_style: co::WS_EX,
+	/// Window styles, including the [`co::DS`](crate::co::DS) dialog styles.
+	///
+	/// The [`co::DS::SETFONT`](crate::co::DS::SETFONT) style is set or cleared
+	/// automatically, according to [`font`](crate::DialogTemplateBuilder::font).
+	pub style: co::WS,
+	/// Horizontal position.
+	pub x: i16,
+	/// Vertical position.
+	pub y: i16,
+	/// Width.
+	pub cx: i16,
+	/// Height.
+	pub cy: i16,
+	/// Menu resource, if any.
+	pub menu: Option<IdStr>,
+	/// Window class, if not the predefined dialog class.
+	pub class: Option<IdStr>,
+	/// Title of the dialog.
+	pub title: String,
+	/// Font of the dialog and its controls, if any.
+	pub font: Option<DialogTemplateFont>,
+	/// The controls.
+	pub items: Vec<DialogTemplateItem>,
+}
+
+impl DialogTemplateBuilder {
+	/// Creates a new, empty template with
+	/// [`co::WS::POPUP`](crate::co::WS::POPUP),
+	/// [`co::WS::CAPTION`](crate::co::WS::CAPTION),
+	/// [`co::WS::SYSMENU`](crate::co::WS::SYSMENU) and
+	/// [`co::DS::MODALFRAME`](crate::co::DS::MODALFRAME) styles, and 9-point
+	/// "MS Shell Dlg" font.
+	#[must_use]
+	pub fn new(title: &str, cx: i16, cy: i16) -> Self {
+		Self {
+			help_id: 0,
+			ex_style: co::WS_EX::NoValue,
+			style: co::WS::POPUP | co::WS::CAPTION | co::WS::SYSMENU
+				| co::DS::MODALFRAME.into(),
+			x: 0,
+			y: 0,
+			cx,
+			cy,
+			menu: None,
+			class: None,
+			title: title.to_owned(),
+			font: Some(DialogTemplateFont::new(9, "MS Shell Dlg")),
+			items: Vec::new(),
+		}
+	}
+
+	/// Sets the window styles, replacing the current ones.
+	#[must_use]
+	pub fn style(mut self, style: co::WS) -> Self {
+		self.style = style;
+		self
+	}
+
+	/// Sets the extended window styles, replacing the current ones.
+	#[must_use]
+	pub fn ex_style(mut self, ex_style: co::WS_EX) -> Self {
+		self.ex_style = ex_style;
+		self
+	}
+
+	/// Sets the position.
+	#[must_use]
+	pub fn pos(mut self, x: i16, y: i16) -> Self {
+		self.x = x;
+		self.y = y;
+		self
+	}
+
+	/// Sets the menu resource.
+	#[must_use]
+	pub fn menu(mut self, menu: IdStr) -> Self {
+		self.menu = Some(menu);
+		self
+	}
+
+	/// Sets the font, or removes it if `None`.
+	#[must_use]
+	pub fn font(mut self, font: Option<DialogTemplateFont>) -> Self {
+		self.font = font;
+		self
+	}
+
+	/// Adds a control.
+	#[must_use]
+	pub fn item(mut self, item: DialogTemplateItem) -> Self {
+		self.items.push(item);
+		self
+	}
+
+	/// Serializes the template into `DLGTEMPLATEEX` bytes.
+	///
+	/// Returns [`co::ERROR::INVALID_DATA`](crate::co::ERROR::INVALID_DATA) if
+	/// there are more than 65,535 items.
+	#[must_use]
+	pub fn build(&self) -> SysResult<Vec<u8>> {
+		let num_items = u16::try_from(self.items.len())
+			.map_err(|_| co::ERROR::INVALID_DATA)?;
+		let style = if self.font.is_some() {
+			self.style | co::DS::SETFONT.into()
+		} else {
+			unsafe { co::WS::from_raw(self.style.raw() & !co::DS::SETFONT.raw()) }
+		};
+
+		let mut buf = Vec::<u8>::new();
+		push_u16(&mut buf, 1); // dlgVer
+		push_u16(&mut buf, 0xffff); // signature
+		push_u32(&mut buf, self.help_id);
+		push_u32(&mut buf, self.ex_style.raw());
+		push_u32(&mut buf, style.raw());
+		push_u16(&mut buf, num_items);
+		[self.x, self.y, self.cx, self.cy].iter()
+			.for_each(|n| push_u16(&mut buf, *n as _));
+		push_sz_or_ord(&mut buf, self.menu.as_ref());
+		push_sz_or_ord(&mut buf, self.class.as_ref());
+		push_sz(&mut buf, &self.title);
+
+		if let Some(font) = &self.font {
+			push_u16(&mut buf, font.point_size);
+			push_u16(&mut buf, font.weight);
+			buf.push(font.italic as _);
+			buf.push(font.char_set);
+			push_sz(&mut buf, &font.typeface);
+		}
+
+		for item in self.items.iter() {
+			let creation_sz = u16::try_from(item.creation_data.len())
+				.map_err(|_| co::ERROR::INVALID_DATA)?;
+			buf.resize((buf.len() + 3) & !3, 0x00); // each item is DWORD-aligned
+			push_u32(&mut buf, item.help_id);
+			push_u32(&mut buf, item.ex_style.raw());
+			push_u32(&mut buf, item.style.raw());
+			[item.x, item.y, item.cx, item.cy].iter()
+				.for_each(|n| push_u16(&mut buf, *n as _));
+			push_u32(&mut buf, item.id);
+			push_sz_or_ord(&mut buf, Some(&item.class));
+			push_sz_or_ord(&mut buf, Some(&item.title));
+			push_u16(&mut buf, creation_sz);
+			buf.extend_from_slice(&item.creation_data);
+		}
+
+		Ok(buf)
+	}
+
+	/// Parses a dialog template, either in the `DLGTEMPLATEEX` format, or in
+	/// the older [`DLGTEMPLATE`](crate::DLGTEMPLATE) format – in this case,
+	/// the help IDs are zero, and the font has normal weight.
+	///
+	/// Returns [`co::ERROR::INVALID_DATA`](crate::co::ERROR::INVALID_DATA) if
+	/// the data is malformed.
+	#[must_use]
+	pub fn parse(data: &[u8]) -> SysResult<Self> {
+		let mut rd = Reader { data, off: 0 };
+		let is_ex = data.len() >= 4 && rd.peek_u32()? == 0xffff_0001;
+
+		let (help_id, ex_style, style) = if is_ex {
+			rd.u32()?; // dlgVer and signature
+			let help_id = rd.u32()?;
+			let ex_style = rd.u32()?;
+			(help_id, ex_style, rd.u32()?)
+		} else {
+			let style = rd.u32()?;
+			(0, rd.u32()?, style)
+		};
+		let num_items = rd.u16()?;
+		let (x, y, cx, cy) = (rd.u16()? as _, rd.u16()? as _, rd.u16()? as _, rd.u16()? as _);
+		let menu = rd.sz_or_ord()?;
+		let class = rd.sz_or_ord()?;
+		let title = rd.sz()?;
+
+		let font = if style & co::DS::SETFONT.raw() != 0 {
+			let point_size = rd.u16()?;
+			let (weight, italic, char_set) = if is_ex {
+				(rd.u16()?, rd.u8()? != 0, rd.u8()?)
+			} else {
+				(400, false, 1)
+			};
+			Some(DialogTemplateFont { point_size, weight, italic, char_set, typeface: rd.sz()? })
+		} else {
+			None
+		};
+
+		let mut items = Vec::with_capacity(num_items as _);
+		for _ in 0..num_items {
+			rd.align4();
+			let (help_id, ex_style, style) = if is_ex {
+				(rd.u32()?, rd.u32()?, rd.u32()?)
+			} else {
+				let style = rd.u32()?;
+				(0, rd.u32()?, style)
+			};
+			let (x, y, cx, cy) = (rd.u16()? as _, rd.u16()? as _, rd.u16()? as _, rd.u16()? as _);
+			let id = if is_ex { rd.u32()? } else { rd.u16()? as _ };
+			let class = rd.sz_or_ord()?.ok_or(co::ERROR::INVALID_DATA)?;
+			let title = rd.sz_or_ord()?.unwrap_or_else(|| IdStr::Str(WString::new()));
+			let creation_sz = rd.u16()? as usize;
+			let creation_data = rd.bytes(creation_sz)?.to_vec();
+
+			items.push(unsafe {
+				DialogTemplateItem {
+					help_id,
+					ex_style: co::WS_EX::from_raw(ex_style),
+					style: co::WS::from_raw(style),
+					x, y, cx, cy,
+					id,
+					class,
+					title,
+					creation_data,
+				}
+			});
+		}
+
+		Ok(unsafe {
+			Self {
+				help_id,
+				ex_style: co::WS_EX::from_raw(ex_style),
+				style: co::WS::from_raw(style),
+				x, y, cx, cy,
+				menu,
+				class,
+				title,
+				font,
+				items,
+			}
+		})
+	}
+}
+
+fn push_u16(buf: &mut Vec<u8>, n: u16) {
+	buf.extend_from_slice(&n.to_le_bytes());
+}
+
+fn push_u32(buf: &mut Vec<u8>, n: u32) {
+	buf.extend_from_slice(&n.to_le_bytes());
+}
+
+fn push_sz(buf: &mut Vec<u8>, s: &str) {
+	s.encode_utf16()
+		.chain(std::iter::once(0x0000))
+		.for_each(|ch| push_u16(buf, ch));
+}
+
+/// Writes an `sz_Or_Ord` field: zero, an ordinal, or a null-terminated string.
+fn push_sz_or_ord(buf: &mut Vec<u8>, val: Option<&IdStr>) {
+	match val {
+		None => push_u16(buf, 0x0000),
+		Some(IdStr::Id(id)) => {
+			push_u16(buf, 0xffff);
+			push_u16(buf, *id);
+		},
+		Some(IdStr::Str(s)) => push_sz(buf, &s.to_string()),
+	}
+}
+
+/// Reads little-endian values from a dialog template.
+struct Reader<'a> {
+	data: &'a [u8],
+	off: usize,
+}
+
+impl<'a> Reader<'a> {
+	fn bytes(&mut self, n: usize) -> SysResult<&'a [u8]> {
+		let slice = self.data.get(self.off..self.off + n)
+			.ok_or(co::ERROR::INVALID_DATA)?;
+		self.off += n;
+		Ok(slice)
+	}
+
+	fn u8(&mut self) -> SysResult<u8> {
+		self.bytes(1).map(|b| b[0])
+	}
+
+	fn u16(&mut self) -> SysResult<u16> {
+		self.bytes(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+	}
+
+	fn u32(&mut self) -> SysResult<u32> {
+		self.bytes(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+	}
+
+	fn peek_u32(&self) -> SysResult<u32> {
+		Reader { data: self.data, off: self.off }.u32()
+	}
+
+	fn align4(&mut self) {
+		self.off = (self.off + 3) & !3;
+	}
+
+	fn sz(&mut self) -> SysResult<String> {
+		let mut chars = Vec::new();
+		loop {
+			match self.u16()? {
+				0x0000 => break,
+				ch => chars.push(ch),
+			}
+		}
+		Ok(String::from_utf16_lossy(&chars))
+	}
+
+	/// Reads an `sz_Or_Ord` field, returning `None` if empty.
+	fn sz_or_ord(&mut self) -> SysResult<Option<IdStr>> {
+		match u16::from_le_bytes([
+			*self.data.get(self.off).ok_or(co::ERROR::INVALID_DATA)?,
+			*self.data.get(self.off + 1).ok_or(co::ERROR::INVALID_DATA)?,
+		]) {
+			0x0000 => {
+				self.off += 2;
+				Ok(None)
+			},
+			0xffff => {
+				self.off += 2;
+				Ok(Some(IdStr::Id(self.u16()?)))
+			},
+			_ => Ok(Some(IdStr::Str(WString::from_str(self.sz()?)))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn id_str(val: &IdStr) -> String {
+		match val {
+			IdStr::Id(id) => format!("#{}", id),
+			IdStr::Str(s) => s.to_string(),
+		}
+	}
+
+	#[test]
+	fn build_parse_round_trip() {
+		let mut font = DialogTemplateFont::new(10, "Segoe UI");
+		(font.weight, font.italic, font.char_set) = (700, true, 0);
+
+		let mut icon = DialogTemplateItem::new(
+			IdStr::Id(DialogTemplateItem::STATIC), "", 0xffff, 7, 7, 21, 20)
+			.style(co::WS::CHILD | co::WS::VISIBLE | co::SS::ICON.into());
+		icon.title = IdStr::Id(101);
+		icon.creation_data = vec![1, 2, 3];
+		icon.help_id = 42;
+
+		let template = DialogTemplateBuilder::new("Login", 180, 70)
+			.pos(10, 20)
+			.ex_style(co::WS_EX::TOOLWINDOW)
+			.menu(IdStr::Str(WString::from_str("MAINMENU")))
+			.font(Some(font))
+			.item(icon)
+			.item(DialogTemplateItem::label("User:", 0xffff, 7, 9, 40, 8))
+			.item(DialogTemplateItem::edit("", 1001, 50, 7, 123, 14))
+			.item(DialogTemplateItem::button("OK", 1, 123, 49, 50, 14));
+
+		let bytes = template.build().unwrap();
+		assert_eq!(&bytes[..4], &[1, 0, 0xff, 0xff]); // dlgVer and signature
+		let parsed = DialogTemplateBuilder::parse(&bytes).unwrap();
+
+		assert_eq!(parsed.ex_style, co::WS_EX::TOOLWINDOW);
+		assert_eq!(parsed.style, template.style | co::DS::SETFONT.into());
+		assert_eq!((parsed.x, parsed.y, parsed.cx, parsed.cy), (10, 20, 180, 70));
+		assert_eq!(parsed.menu.as_ref().map(id_str).as_deref(), Some("MAINMENU"));
+		assert!(parsed.class.is_none());
+		assert_eq!(parsed.title, "Login");
+
+		let font = parsed.font.as_ref().unwrap();
+		assert_eq!(
+			(font.point_size, font.weight, font.italic, font.char_set, font.typeface.as_str()),
+			(10, 700, true, 0, "Segoe UI"),
+		);
+
+		assert_eq!(parsed.items.len(), template.items.len());
+		for (p, t) in parsed.items.iter().zip(template.items.iter()) {
+			assert_eq!(
+				(p.help_id, p.ex_style, p.style, p.x, p.y, p.cx, p.cy, p.id),
+				(t.help_id, t.ex_style, t.style, t.x, t.y, t.cx, t.cy, t.id),
+			);
+			assert_eq!(id_str(&p.class), id_str(&t.class));
+			assert_eq!(id_str(&p.title), id_str(&t.title));
+			assert_eq!(p.creation_data, t.creation_data);
+		}
+		assert_eq!(id_str(&parsed.items[0].title), "#101");
+
+		assert_eq!(parsed.build().unwrap(), bytes);
+	}
+
+	#[test]
+	fn build_without_font() {
+		let template = DialogTemplateBuilder::new("", 10, 10)
+			.style(co::WS::POPUP | co::DS::SETFONT.into())
+			.font(None);
+		let parsed = DialogTemplateBuilder::parse(&template.build().unwrap()).unwrap();
+		assert_eq!(parsed.style, co::WS::POPUP);
+		assert!(parsed.font.is_none());
+	}
+
+	#[test]
+	fn parse_dlgtemplate() {
+		let mut data = Vec::<u8>::new();
+		data.extend_from_slice(&(co::WS::POPUP.raw() | co::DS::SETFONT.raw()).to_le_bytes());
+		data.extend_from_slice(&[0, 0, 0, 0]); // extended style
+		data.extend_from_slice(&[1, 0, 1, 0, 2, 0, 100, 0, 50, 0]); // items, x, y, cx, cy
+		data.extend_from_slice(&[0, 0, 0, 0]); // menu, class
+		data.extend_from_slice(&[b'T', 0, b'i', 0, 0, 0]);
+		data.extend_from_slice(&[9, 0, b'F', 0, 0, 0]); // font
+		data.extend_from_slice(&[0, 0]); // DWORD padding
+		data.extend_from_slice(&co::WS::CHILD.raw().to_le_bytes());
+		data.extend_from_slice(&[0, 0, 0, 0]); // extended style
+		data.extend_from_slice(&[5, 0, 6, 0, 40, 0, 14, 0, 1, 0]); // x, y, cx, cy, id
+		data.extend_from_slice(&[0xff, 0xff, 0x80, 0, b'O', 0, 0, 0]); // class, text
+		data.extend_from_slice(&[0, 0]); // creation data
+
+		let parsed = DialogTemplateBuilder::parse(&data).unwrap();
+		assert_eq!(parsed.help_id, 0);
+		assert_eq!(parsed.style, co::WS::POPUP | co::DS::SETFONT.into());
+		assert_eq!((parsed.x, parsed.y, parsed.cx, parsed.cy), (1, 2, 100, 50));
+		assert_eq!(parsed.title, "Ti");
+		let font = parsed.font.as_ref().unwrap();
+		assert_eq!((font.point_size, font.weight, font.typeface.as_str()), (9, 400, "F"));
+
+		let item = &parsed.items[0];
+		assert_eq!(item.style, co::WS::CHILD);
+		assert_eq!((item.x, item.y, item.cx, item.cy, item.id), (5, 6, 40, 14, 1));
+		assert_eq!(id_str(&item.class), format!("#{}", DialogTemplateItem::BUTTON));
+		assert_eq!(id_str(&item.title), "O");
+	}
+
+	#[test]
+	fn parse_malformed() {
+		let bytes = DialogTemplateBuilder::new("Title", 10, 10)
+			.item(DialogTemplateItem::button("OK", 1, 0, 0, 10, 10))
+			.build()
+			.unwrap();
+		for len in [0, 3, 10, 30, bytes.len() - 1] {
+			assert!(DialogTemplateBuilder::parse(&bytes[..len]).is_err(), "{}", len);
+		}
+	}
+}
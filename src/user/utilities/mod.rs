@@ -0,0 +1,3 @@
+mod dialog_template;
+
+pub use dialog_template::{DialogTemplateBuilder, DialogTemplateFont, DialogTemplateItem};
@@ -0,0 +1,52 @@
+#![allow(non_camel_case_types)]
+
+use crate::co;
+use crate::decl::*;
+use crate::prelude::*;
+
+impl version_Hupdatersrc for HUPDATERSRC {}
+
+/// This trait is enabled with the `version` feature, and provides methods for
+/// [`HUPDATERSRC`](crate::HUPDATERSRC).
+///
+/// Prefer importing this trait through the prelude:
+///
+/// ```no_run
+/// use winsafe::prelude::*;
+/// ```
+pub trait version_Hupdatersrc: kernel_Hupdatersrc {
+	/// Adds or replaces the version resource, with ID `1`, by serializing the
+	/// given [`VersionInfo`](crate::VersionInfo).
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use winsafe::{self as w, prelude::*, co};
+	///
+	/// let lang = w::LANGID::new(co::LANG::ENGLISH, co::SUBLANG::ENGLISH_US);
+	///
+	/// let mut ver = w::VersionInfo::default();
+	/// if let Some(fixed) = ver.fixed.as_mut() {
+	///     fixed.set_dwFileVersion([1, 2, 0, 0]);
+	///     fixed.set_dwProductVersion([1, 2, 0, 0]);
+	/// }
+	/// ver.set_str_val(lang, co::CP::UTF16, "FileVersion", "1.2.0.0")
+	///     .set_str_val(lang, co::CP::UTF16, "ProductName", "Foo");
+	///
+	/// let hupd = w::HUPDATERSRC::BeginUpdateResource("C:\\Temp\\foo.exe", false)?;
+	/// hupd.update_version_info(lang, &ver)?;
+	/// # w::SysResult::Ok(())
+	/// ```
+	fn update_version_info(&self,
+		language: LANGID,
+		info: &VersionInfo,
+	) -> SysResult<()>
+	{
+		self.UpdateResource(
+			RtStr::Rt(co::RT::VERSION),
+			IdStr::Id(1), // VS_VERSION_INFO
+			language,
+			&info.serialize()?,
+		)
+	}
+}
@@ -6,6 +6,7 @@ use crate::decl::*;
 /// [`VS_FIXEDFILEINFO`](https://learn.microsoft.com/en-us/windows/win32/api/verrsrc/ns-verrsrc-vs_fixedfileinfo)
 /// struct.
 #[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct VS_FIXEDFILEINFO {
 	dwSignature: u32,
 	pub dwStrucVersion: u32,
@@ -68,4 +69,61 @@ impl VS_FIXEDFILEINFO {
 		self.dwFileDateLS = LODWORD(val);
 		self.dwFileDateMS = HIDWORD(val);
 	}
+
+	/// Reads the struct from its little-endian binary representation, as
+	/// stored in a version resource. Fails if the signature is wrong.
+	pub(in crate::version) fn from_le_bytes(data: &[u8]) -> SysResult<Self> {
+		if data.len() < std::mem::size_of::<Self>() {
+			return Err(co::ERROR::INVALID_DATA);
+		}
+		let mut dwords = data.chunks_exact(4)
+			.map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+		let mut next = || dwords.next().unwrap();
+
+		let new_self = unsafe {
+			Self {
+				dwSignature: next(),
+				dwStrucVersion: next(),
+				dwFileVersionMS: next(),
+				dwFileVersionLS: next(),
+				dwProductVersionMS: next(),
+				dwProductVersionLS: next(),
+				dwFileFlagsMask: co::VS_FF::from_raw(next()),
+				dwFileFlags: co::VS_FF::from_raw(next()),
+				dwFileOS: co::VOS::from_raw(next()),
+				dwFileType: co::VFT::from_raw(next()),
+				dwFileSubtype: co::VFT2::from_raw(next()),
+				dwFileDateMS: next(),
+				dwFileDateLS: next(),
+			}
+		};
+		if new_self.dwSignature == Self::default().dwSignature {
+			Ok(new_self)
+		} else {
+			Err(co::ERROR::INVALID_DATA)
+		}
+	}
+
+	/// Returns the little-endian binary representation of the struct, as
+	/// stored in a version resource.
+	#[must_use]
+	pub(in crate::version) fn to_le_bytes(self) -> Vec<u8> {
+		[
+			self.dwSignature,
+			self.dwStrucVersion,
+			self.dwFileVersionMS,
+			self.dwFileVersionLS,
+			self.dwProductVersionMS,
+			self.dwProductVersionLS,
+			self.dwFileFlagsMask.raw(),
+			self.dwFileFlags.raw(),
+			self.dwFileOS.raw(),
+			self.dwFileType.raw(),
+			self.dwFileSubtype.raw(),
+			self.dwFileDateMS,
+			self.dwFileDateLS,
+		].iter()
+			.flat_map(|dword| dword.to_le_bytes())
+			.collect()
+	}
 }
@@ -0,0 +1,488 @@
+use crate::co;
+use crate::decl::*;
+
+/// A string table of a [`VersionInfo`](crate::VersionInfo), which holds the
+/// string values of a given language and code page.
+#[derive(Clone, PartialEq, Eq)]
+pub struct VersionStringTable {
+	/// Language of the strings.
+	pub lang_id: LANGID,
+	/// Code page of the strings.
+	pub code_page: co::CP,
+	/// Pairs of name and value, in the order they're stored.
+	pub strings: Vec<(String, String)>,
+}
+
+impl VersionStringTable {
+	/// Creates a new, empty string table.
+	#[must_use]
+	pub const fn new(lang_id: LANGID, code_page: co::CP) -> Self {
+		Self { lang_id, code_page, strings: Vec::new() }
+	}
+
+	/// Returns the value with the given name, if any. The name is
+	/// case-sensitive.
+	#[must_use]
+	pub fn get(&self, name: &str) -> Option<&str> {
+		self.strings.iter()
+			.find(|(n, _)| n == name)
+			.map(|(_, v)| v.as_str())
+	}
+
+	/// Sets the value with the given name, replacing the existing one, or
+	/// adding a new one at the end.
+	pub fn set(&mut self, name: &str, val: &str) -> &mut Self {
+		match self.strings.iter_mut().find(|(n, _)| n == name) {
+			Some((_, v)) => *v = val.to_owned(),
+			None => self.strings.push((name.to_owned(), val.to_owned())),
+		}
+		self
+	}
+
+	/// Removes the value with the given name, returning it.
+	pub fn remove(&mut self, name: &str) -> Option<String> {
+		self.strings.iter()
+			.position(|(n, _)| n == name)
+			.map(|idx| self.strings.remove(idx).1)
+	}
+}
+
+/// The contents of a
+/// [`VS_VERSIONINFO`](https://learn.microsoft.com/en-us/windows/win32/menurc/vs-versioninfo)
+/// resource, which can be parsed from and serialized to its binary
+/// representation, without calling any Windows function.
+///
+/// Unlike [`HVERSIONINFO`](crate::HVERSIONINFO), which reads the block
+/// returned by
+/// [`HVERSIONINFO::GetFileVersionInfo`](crate::prelude::version_Hversioninfo::GetFileVersionInfo),
+/// this struct can also be used to build a version resource to be written with
+/// [`HUPDATERSRC::UpdateResource`](crate::prelude::kernel_Hupdatersrc::UpdateResource).
+///
+/// # Examples
+///
+/// Changing the file version of an executable:
+///
+/// ```no_run
+/// use winsafe::{self as w, prelude::*, co};
+///
+/// let mut ver = {
+///     let hexe = w::HINSTANCE::LoadLibrary("C:\\Temp\\foo.exe")?;
+///     let hres = hexe.FindResource(
+///         w::IdStr::Id(1),
+///         w::RtStr::Rt(co::RT::VERSION),
+///     )?;
+///     let hres_load = hexe.LoadResource(&hres)?;
+///     w::VersionInfo::parse(hexe.LockResource(&hres, &hres_load)?)?
+/// }; // FreeLibrary() called automatically
+///
+/// if let Some(fixed) = ver.fixed.as_mut() {
+///     fixed.set_dwFileVersion([2, 0, 0, 1]);
+/// }
+/// let (lang, cp) = ver.translations[0];
+/// ver.set_str_val(lang, cp, "FileVersion", "2.0.0.1");
+///
+/// let hupd = w::HUPDATERSRC::BeginUpdateResource("C:\\Temp\\foo.exe", false)?;
+/// hupd.UpdateResource(
+///     w::RtStr::Rt(co::RT::VERSION),
+///     w::IdStr::Id(1),
+///     lang,
+///     &ver.serialize()?,
+/// )?;
+/// # w::SysResult::Ok(())
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct VersionInfo {
+	/// The fixed version information, if any.
+	pub fixed: Option<VS_FIXEDFILEINFO>,
+	/// The `StringFileInfo` tables, one for each language and code page.
+	pub string_tables: Vec<VersionStringTable>,
+	/// The `Translation` value of `VarFileInfo`, with the languages and code
+	/// pages supported by the file.
+	pub translations: Vec<(LANGID, co::CP)>,
+}
+
+impl Default for VersionInfo {
+	fn default() -> Self {
+		Self {
+			fixed: Some(VS_FIXEDFILEINFO::default()),
+			string_tables: Vec::new(),
+			translations: Vec::new(),
+		}
+	}
+}
+
+impl VersionInfo {
+	/// Parses the binary representation of a `VS_VERSIONINFO` resource.
+	///
+	/// Any trailing data after the root block, like the one appended by
+	/// [`HVERSIONINFO::GetFileVersionInfo`](crate::prelude::version_Hversioninfo::GetFileVersionInfo),
+	/// is ignored.
+	///
+	/// Returns [`co::ERROR::INVALID_DATA`](crate::co::ERROR::INVALID_DATA) if
+	/// the data is malformed.
+	#[must_use]
+	pub fn parse(data: &[u8]) -> SysResult<Self> {
+		let (root, _) = Node::read(data)?;
+		if root.key != "VS_VERSION_INFO" {
+			return Err(co::ERROR::INVALID_DATA);
+		}
+
+		let mut new_self = Self {
+			fixed: if root.value.is_empty() {
+				None
+			} else {
+				Some(VS_FIXEDFILEINFO::from_le_bytes(root.value)?)
+			},
+			string_tables: Vec::new(),
+			translations: Vec::new(),
+		};
+
+		for child in Node::read_all(root.children)? {
+			match child.key.as_str() {
+				"StringFileInfo" => for table in Node::read_all(child.children)? {
+					new_self.string_tables.push(Self::parse_string_table(&table)?);
+				},
+				"VarFileInfo" => for var in Node::read_all(child.children)? {
+					if var.key == "Translation" {
+						new_self.translations.extend(
+							var.value.chunks_exact(4)
+								.map(|chunk| unsafe {(
+									LANGID::from_raw(u16::from_le_bytes([chunk[0], chunk[1]])),
+									co::CP::from_raw(u16::from_le_bytes([chunk[2], chunk[3]])),
+								)}),
+						);
+					}
+				},
+				_ => {}, // unknown blocks are ignored
+			}
+		}
+		Ok(new_self)
+	}
+
+	fn parse_string_table(table: &Node) -> SysResult<VersionStringTable> {
+		let (lang, cp) = match (
+			table.key.get(0..4).and_then(|s| u16::from_str_radix(s, 16).ok()),
+			table.key.get(4..8).and_then(|s| u16::from_str_radix(s, 16).ok()),
+		) {
+			(Some(lang), Some(cp)) if table.key.len() == 8 => (lang, cp),
+			_ => return Err(co::ERROR::INVALID_DATA),
+		};
+
+		let mut new_table = unsafe {
+			VersionStringTable::new(LANGID::from_raw(lang), co::CP::from_raw(cp))
+		};
+		for string in Node::read_all(table.children)? {
+			let chars = string.after_key.chunks_exact(2) // wValueLength is unreliable, so we look for the null
+				.map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+				.take_while(|ch| *ch != 0x0000)
+				.collect::<Vec<_>>();
+			new_table.strings.push((string.key, String::from_utf16_lossy(&chars)));
+		}
+		Ok(new_table)
+	}
+
+	/// Serializes the contents into the binary representation of a
+	/// `VS_VERSIONINFO` resource.
+	///
+	/// Returns [`co::ERROR::INVALID_DATA`](crate::co::ERROR::INVALID_DATA) if
+	/// any block exceeds the 64 KB limit of the format.
+	#[must_use]
+	pub fn serialize(&self) -> SysResult<Vec<u8>> {
+		let mut buf = Vec::<u8>::new();
+		let fixed = self.fixed.map(|f| f.to_le_bytes()).unwrap_or_default();
+
+		Node::write(&mut buf, "VS_VERSION_INFO", false, &fixed, fixed.len(), |buf| {
+			if !self.string_tables.is_empty() {
+				Node::write(buf, "StringFileInfo", true, &[], 0, |buf| {
+					for table in self.string_tables.iter() {
+						let key = format!("{:04x}{:04x}",
+							u16::from(table.lang_id), u16::from(table.code_page));
+						Node::write(buf, &key, true, &[], 0, |buf| {
+							for (name, val) in table.strings.iter() {
+								let chars = val.encode_utf16()
+									.chain(std::iter::once(0x0000))
+									.collect::<Vec<_>>();
+								let bytes = chars.iter()
+									.flat_map(|ch| ch.to_le_bytes())
+									.collect::<Vec<_>>();
+								Node::write(buf, name, true, &bytes, chars.len(), |_| Ok(()))?;
+							}
+							Ok(())
+						})?;
+					}
+					Ok(())
+				})?;
+			}
+
+			if !self.translations.is_empty() {
+				Node::write(buf, "VarFileInfo", true, &[], 0, |buf| {
+					let bytes = self.translations.iter()
+						.flat_map(|(lang, cp)| {
+							let mut pair = [0u8; 4];
+							pair[..2].copy_from_slice(&u16::from(*lang).to_le_bytes());
+							pair[2..].copy_from_slice(&u16::from(*cp).to_le_bytes());
+							pair
+						})
+						.collect::<Vec<_>>();
+					Node::write(buf, "Translation", false, &bytes, bytes.len(), |_| Ok(()))
+				})?;
+			}
+			Ok(())
+		})?;
+
+		Ok(buf)
+	}
+
+	/// Returns the string table of the given language and code page, if any.
+	#[must_use]
+	pub fn string_table(&self,
+		lang_id: LANGID,
+		code_page: co::CP,
+	) -> Option<&VersionStringTable>
+	{
+		self.string_tables.iter()
+			.find(|t| t.lang_id == lang_id && t.code_page == code_page)
+	}
+
+	/// Returns a string value, if any.
+	///
+	/// This is the equivalent of
+	/// [`HVERSIONINFO::str_val`](crate::prelude::version_Hversioninfo::str_val).
+	#[must_use]
+	pub fn str_val(&self,
+		lang_id: LANGID,
+		code_page: co::CP,
+		name: &str,
+	) -> Option<&str>
+	{
+		self.string_table(lang_id, code_page)
+			.and_then(|t| t.get(name))
+	}
+
+	/// Sets a string value.
+	///
+	/// If there is no string table for the given language and code page, a new
+	/// one is created, and the pair is also added to the
+	/// [`translations`](crate::VersionInfo::translations).
+	pub fn set_str_val(&mut self,
+		lang_id: LANGID,
+		code_page: co::CP,
+		name: &str,
+		val: &str,
+	) -> &mut Self
+	{
+		let idx = match self.string_tables.iter()
+			.position(|t| t.lang_id == lang_id && t.code_page == code_page)
+		{
+			Some(idx) => idx,
+			None => {
+				self.string_tables.push(VersionStringTable::new(lang_id, code_page));
+				if !self.translations.contains(&(lang_id, code_page)) {
+					self.translations.push((lang_id, code_page));
+				}
+				self.string_tables.len() - 1
+			},
+		};
+		self.string_tables[idx].set(name, val);
+		self
+	}
+}
+
+/// A generic block of the `VS_VERSIONINFO` tree.
+struct Node<'a> {
+	key: String,
+	value: &'a [u8],
+	after_key: &'a [u8],
+	children: &'a [u8],
+}
+
+impl<'a> Node<'a> {
+	/// Reads a block, returning it along with the offset of the next sibling.
+	fn read(data: &'a [u8]) -> SysResult<(Self, usize)> {
+		let word_at = |off: usize| u16::from_le_bytes([data[off], data[off + 1]]) as usize;
+
+		if data.len() < 6 {
+			return Err(co::ERROR::INVALID_DATA);
+		}
+		let len = word_at(0);
+		if len < 6 || len > data.len() {
+			return Err(co::ERROR::INVALID_DATA);
+		}
+		let value_len = word_at(2);
+		let is_text = word_at(4) == 1;
+		let block = &data[..len];
+
+		let key_chars = block[6..].chunks_exact(2)
+			.map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+			.take_while(|ch| *ch != 0x0000)
+			.collect::<Vec<_>>();
+		let key = String::from_utf16(&key_chars)
+			.map_err(|_| co::ERROR::INVALID_DATA)?;
+
+		let value_off = align4(6 + (key_chars.len() + 1) * 2).min(len);
+		let value_end = (value_off + if is_text { value_len * 2 } else { value_len }).min(len);
+		let children_off = align4(value_end).min(len);
+
+		Ok((
+			Self {
+				key,
+				value: &block[value_off..value_end],
+				after_key: &block[value_off..],
+				children: &block[children_off..],
+			},
+			align4(len).min(data.len()),
+		))
+	}
+
+	/// Reads all the sibling blocks.
+	fn read_all(mut data: &'a [u8]) -> SysResult<Vec<Self>> {
+		let mut nodes = Vec::new();
+		while data.iter().any(|b| *b != 0x00) { // trailing padding is ignored
+			let (node, next_off) = Self::read(data)?;
+			nodes.push(node);
+			data = &data[next_off..];
+		}
+		Ok(nodes)
+	}
+
+	/// Writes a block, including its children, which are written by `children`.
+	fn write(
+		buf: &mut Vec<u8>,
+		key: &str,
+		is_text: bool,
+		value: &[u8],
+		value_len: usize,
+		children: impl FnOnce(&mut Vec<u8>) -> SysResult<()>,
+	) -> SysResult<()>
+	{
+		buf.resize(align4(buf.len()), 0x00); // blocks start at 32-bit boundaries
+		let start = buf.len();
+
+		buf.extend_from_slice(&[0x00, 0x00]); // length, written at the end
+		buf.extend_from_slice(&u16::try_from(value_len)
+			.map_err(|_| co::ERROR::INVALID_DATA)?
+			.to_le_bytes());
+		buf.extend_from_slice(&(is_text as u16).to_le_bytes());
+		key.encode_utf16()
+			.chain(std::iter::once(0x0000))
+			.for_each(|ch| buf.extend_from_slice(&ch.to_le_bytes()));
+
+		buf.resize(align4(buf.len()), 0x00);
+		buf.extend_from_slice(value);
+		children(buf)?;
+
+		let len = u16::try_from(buf.len() - start)
+			.map_err(|_| co::ERROR::INVALID_DATA)?;
+		buf[start..start + 2].copy_from_slice(&len.to_le_bytes());
+		Ok(())
+	}
+}
+
+/// Rounds the offset up to the next 32-bit boundary.
+const fn align4(off: usize) -> usize {
+	(off + 3) & !3
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EN_US: LANGID = LANGID::new(co::LANG::ENGLISH, co::SUBLANG::ENGLISH_US);
+	const PT_BR: LANGID = LANGID::new(co::LANG::PORTUGUESE, co::SUBLANG::PORTUGUESE_BRAZILIAN);
+
+	fn sample() -> VersionInfo {
+		let mut ver = VersionInfo::default();
+		let fixed = ver.fixed.as_mut().unwrap();
+		fixed.set_dwFileVersion([1, 2, 3, 4]);
+		fixed.set_dwProductVersion([5, 6, 7, 8]);
+		fixed.dwFileType = co::VFT::APP;
+
+		ver.set_str_val(EN_US, co::CP::UTF16, "FileVersion", "1.2.3.4")
+			.set_str_val(EN_US, co::CP::UTF16, "ProductName", "Foo")
+			.set_str_val(PT_BR, co::CP::WINDOWS_1252, "ProductName", "Fóo")
+			.set_str_val(PT_BR, co::CP::WINDOWS_1252, "Comments", "");
+		ver
+	}
+
+	fn word_at(data: &[u8], off: usize) -> u16 {
+		u16::from_le_bytes([data[off], data[off + 1]])
+	}
+
+	#[test]
+	fn round_trip() {
+		let ver = sample();
+		let data = ver.serialize().unwrap();
+		let parsed = VersionInfo::parse(&data).unwrap();
+		assert!(parsed == ver);
+
+		let fixed = parsed.fixed.unwrap();
+		assert_eq!(fixed.dwFileVersion(), [1, 2, 3, 4]);
+		assert_eq!(fixed.dwProductVersion(), [5, 6, 7, 8]);
+		assert_eq!(parsed.translations, [(EN_US, co::CP::UTF16), (PT_BR, co::CP::WINDOWS_1252)]);
+		assert_eq!(parsed.str_val(EN_US, co::CP::UTF16, "ProductName"), Some("Foo"));
+		assert_eq!(parsed.str_val(PT_BR, co::CP::WINDOWS_1252, "ProductName"), Some("Fóo"));
+		assert_eq!(parsed.str_val(PT_BR, co::CP::WINDOWS_1252, "Comments"), Some(""));
+		assert_eq!(parsed.str_val(PT_BR, co::CP::UTF16, "ProductName"), None);
+
+		let no_fixed = VersionInfo { fixed: None, ..VersionInfo::default() };
+		assert!(VersionInfo::parse(&no_fixed.serialize().unwrap()).unwrap() == no_fixed);
+	}
+
+	#[test]
+	fn layout() {
+		let data = sample().serialize().unwrap();
+		assert_eq!(word_at(&data, 0) as usize, data.len()); // wLength
+		assert_eq!(word_at(&data, 2), 52); // wValueLength, size of VS_FIXEDFILEINFO
+		assert_eq!(word_at(&data, 4), 0); // wType, binary
+		assert_eq!(
+			String::from_utf16(&(0..15).map(|i| word_at(&data, 6 + i * 2)).collect::<Vec<_>>()).unwrap(),
+			"VS_VERSION_INFO",
+		);
+		assert_eq!(&data[40..44], &0xfeef_04bd_u32.to_le_bytes()); // value is DWORD-aligned after the key
+		assert_eq!(data.len() % 4, 0);
+	}
+
+	#[test]
+	fn parse_trailing_data() {
+		let mut data = sample().serialize().unwrap();
+		data.extend_from_slice(b"FE2X\x00\x00\x00\x00"); // appended by GetFileVersionInfo
+		assert!(VersionInfo::parse(&data).unwrap() == sample());
+	}
+
+	#[test]
+	fn parse_malformed() {
+		let data = sample().serialize().unwrap();
+
+		let mut bad_len = data.clone();
+		bad_len[..2].copy_from_slice(&(data.len() as u16 + 4).to_le_bytes());
+		let mut bad_key = data.clone();
+		bad_key[6] = b'X';
+		let mut bad_signature = data.clone();
+		bad_signature[40] = 0x00;
+		let mut bad_table = data.clone();
+		let table_key = "040904b0".encode_utf16().flat_map(|ch| ch.to_le_bytes()).collect::<Vec<_>>();
+		let off = data.windows(table_key.len()).position(|w| w == table_key).unwrap();
+		bad_table[off] = b'x';
+
+		for bad in [&data[..4], &data[..20], &bad_len, &bad_key, &bad_signature, &bad_table] {
+			assert_eq!(VersionInfo::parse(bad).err(), Some(co::ERROR::INVALID_DATA));
+		}
+	}
+
+	#[test]
+	fn string_tables() {
+		let mut ver = VersionInfo::default();
+		ver.set_str_val(EN_US, co::CP::UTF16, "A", "1")
+			.set_str_val(EN_US, co::CP::UTF16, "B", "2")
+			.set_str_val(EN_US, co::CP::UTF16, "A", "3");
+		assert_eq!(ver.string_tables.len(), 1);
+		assert_eq!(ver.translations, [(EN_US, co::CP::UTF16)]);
+
+		let table = &mut ver.string_tables[0];
+		assert_eq!(table.strings, [("A".to_owned(), "3".to_owned()), ("B".to_owned(), "2".to_owned())]);
+		assert_eq!(table.remove("A").as_deref(), Some("3"));
+		assert_eq!(table.remove("A"), None);
+		assert_eq!(table.get("a"), None); // case-sensitive
+		assert_eq!(table.get("B"), Some("2"));
+	}
+}